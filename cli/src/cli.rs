@@ -17,6 +17,7 @@
 //! Polkadot CLI library.
 
 use clap::Parser;
+use std::path::PathBuf;
 
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
@@ -50,6 +51,20 @@ pub enum Subcommand {
 	#[clap(name = "execute-worker", hide = true)]
 	PvfExecuteWorker(ValidationWorkerCommand),
 
+	/// Validate a candidate offline, by running it through the same PVF preparation and execution
+	/// pipeline a validator uses.
+	ValidateCandidate(ValidateCandidateCmd),
+
+	/// Export the `AvailableData` of a candidate from the availability store to a file.
+	ExportAvailableData(ExportAvailableDataCmd),
+
+	/// Dump the decoded entries of the parachains database as JSON, without running a node.
+	InspectParachainsDb(InspectParachainsDbCmd),
+
+	/// Downgrade the parachains database to an older version, e.g. to roll back to an older
+	/// release without deleting it.
+	DowngradeParachainsDb(DowngradeParachainsDbCmd),
+
 	/// Sub-commands concerned with benchmarking.
 	/// The pallet benchmarking moved to the `pallet` sub-command.
 	#[clap(subcommand)]
@@ -82,6 +97,143 @@ pub struct ValidationWorkerCommand {
 	pub socket_path: String,
 }
 
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct ValidateCandidateCmd {
+	/// The path to the validation code blob. The blob may be compressed.
+	#[clap(long, parse(from_os_str))]
+	pub validation_code: PathBuf,
+
+	/// The path to the SCALE-encoded `ValidationParams`.
+	///
+	/// Either this or both `--pov` and `--persisted-validation-data` must be specified.
+	#[clap(
+		long,
+		parse(from_os_str),
+		required_unless_present = "pov",
+		conflicts_with_all = &["pov", "persisted_validation_data"],
+	)]
+	pub params: Option<PathBuf>,
+
+	/// The path to the SCALE-encoded `PoV`.
+	#[clap(long, parse(from_os_str), requires = "persisted_validation_data")]
+	pub pov: Option<PathBuf>,
+
+	/// The path to the SCALE-encoded `PersistedValidationData`.
+	#[clap(long, parse(from_os_str), requires = "pov")]
+	pub persisted_validation_data: Option<PathBuf>,
+
+	/// The execution timeout, in seconds.
+	#[clap(long, default_value = "6")]
+	pub execution_timeout: u64,
+
+	/// The directory to keep the prepared artifacts in.
+	///
+	/// A temporary directory is used if not specified.
+	#[clap(long, parse(from_os_str))]
+	pub cache_path: Option<PathBuf>,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct ExportAvailableDataCmd {
+	/// The hash of the candidate to export the available data of.
+	#[clap(long)]
+	pub candidate_hash: polkadot_primitives::v2::Hash,
+
+	/// The file to write the SCALE-encoded `AvailableData` to.
+	#[clap(long, parse(from_os_str))]
+	pub output: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for ExportAvailableDataCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct InspectParachainsDbCmd {
+	/// Only include entries concerning the block with this hash.
+	///
+	/// Candidates in the availability store are only matched while they are unfinalized.
+	#[clap(long)]
+	pub block_hash: Option<polkadot_primitives::v2::Hash>,
+
+	/// Only include entries concerning the candidate with this hash.
+	///
+	/// Excludes the chain selection leaves, which do not record candidates.
+	#[clap(long)]
+	pub candidate_hash: Option<polkadot_primitives::v2::Hash>,
+
+	/// Only include entries of this session.
+	///
+	/// Excludes the availability store and chain selection entries, which do not record sessions.
+	#[clap(long)]
+	pub session: Option<polkadot_primitives::v2::SessionIndex>,
+
+	/// The file to write the JSON to. Printed to stdout if not specified.
+	#[clap(long, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for InspectParachainsDbCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct DowngradeParachainsDbCmd {
+	/// The version to downgrade the parachains database to.
+	#[clap(long, value_name = "VERSION")]
+	pub to_version: u32,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for DowngradeParachainsDbCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
 #[cfg_attr(feature = "malus", derive(Clone))]
@@ -144,6 +296,79 @@ pub struct RunCmd {
 	/// **Dangerous!** Do not touch unless explicitly adviced to.
 	#[clap(long)]
 	pub overseer_channel_capacity_override: Option<usize>,
+
+	/// How long, in seconds, to keep the availability data of candidates that did not get
+	/// included.
+	#[clap(long, value_name = "SECONDS")]
+	pub availability_keep_unavailable_for: Option<u64>,
+
+	/// How long, in seconds, to keep the availability data of finalized candidates.
+	#[clap(long, value_name = "SECONDS", conflicts_with = "availability_archive")]
+	pub availability_keep_finalized_for: Option<u64>,
+
+	/// How often, in seconds, to prune the availability store.
+	#[clap(long, value_name = "SECONDS")]
+	pub availability_pruning_interval: Option<u64>,
+
+	/// Never prune the availability data of finalized candidates.
+	///
+	/// The availability store will grow without bounds.
+	#[clap(long)]
+	pub availability_archive: bool,
+
+	/// Only accept collations for a para from the given collator, as `PARA_ID:COLLATOR` with the
+	/// collator id SS58 encoded.
+	///
+	/// May be given multiple times. All collators of paras without an allowlist or denylist are
+	/// accepted.
+	#[clap(long, value_name = "PARA_ID:COLLATOR")]
+	pub collator_allowlist: Vec<String>,
+
+	/// Never accept collations for a para from the given collator, as `PARA_ID:COLLATOR` with the
+	/// collator id SS58 encoded.
+	///
+	/// May be given multiple times, but not for a para that has an allowlist.
+	#[clap(long, value_name = "PARA_ID:COLLATOR")]
+	pub collator_denylist: Vec<String>,
+
+	/// Keep the reputations of collators in the database directory across restarts.
+	#[clap(long)]
+	pub persist_collator_reputation: bool,
+
+	/// Limit the incoming notifications of a kind per peer, as `KIND:BURST:PER_SECOND`.
+	///
+	/// `KIND` is one of `bitfield-distribution`, `statement-distribution`,
+	/// `approval-distribution` or `collator-protocol`. May be given multiple times, replacing the
+	/// default limit of the kind.
+	#[clap(long, value_name = "KIND:BURST:PER_SECOND")]
+	pub network_rate_limit: Vec<String>,
+
+	/// Do not rate limit incoming notifications, except for the kinds given with
+	/// `--network-rate-limit`.
+	#[clap(long)]
+	pub no_default_network_rate_limits: bool,
+
+	/// Record the messages and signals delivered to a subsystem into the file given with
+	/// `--record-subsystem-path`, to replay them later.
+	#[clap(long, arg_enum, ignore_case = true, requires = "record_subsystem_path")]
+	pub record_subsystem: Option<RecordSubsystem>,
+
+	/// The file to record the subsystem given with `--record-subsystem` into.
+	///
+	/// The file is created, or truncated if it exists.
+	#[clap(long, parse(from_os_str), requires = "record_subsystem")]
+	pub record_subsystem_path: Option<PathBuf>,
+}
+
+/// A subsystem which can be recorded.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum RecordSubsystem {
+	/// The chain API subsystem.
+	ChainApi,
+	/// The dispute coordinator subsystem.
+	DisputeCoordinator,
+	/// The approval voting subsystem.
+	ApprovalVoting,
 }
 
 #[allow(missing_docs)]
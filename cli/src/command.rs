@@ -14,18 +14,24 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{Cli, Subcommand};
+use crate::cli::{Cli, RecordSubsystem, Subcommand};
 use frame_benchmarking_cli::{BenchmarkCmd, ExtrinsicFactory, SUBSTRATE_REFERENCE_HARDWARE};
 use futures::future::TryFutureExt;
 use log::info;
+use parity_scale_codec::Encode;
 use polkadot_client::benchmarking::{
 	benchmark_inherent_data, ExistentialDepositProvider, RemarkBuilder, TransferKeepAliveBuilder,
 };
+use polkadot_primitives::v2::{CandidateHash, CollatorId, Id as ParaId};
 use sc_cli::{RuntimeVersion, SubstrateCli};
 use service::{self, HeaderBackend, IdentifyVariant};
-use sp_core::crypto::Ss58AddressFormatRegistry;
+use sp_core::crypto::{Ss58AddressFormatRegistry, Ss58Codec};
 use sp_keyring::Sr25519Keyring;
-use std::net::ToSocketAddrs;
+use std::{
+	collections::{HashMap, HashSet},
+	net::ToSocketAddrs,
+	time::Duration,
+};
 
 pub use crate::{error::Error, service::BlockId};
 pub use polkadot_performance_test::PerfCheckError;
@@ -327,6 +333,9 @@ where
 		None
 	};
 
+	let collator_filters = collator_filters(&cli.run)?;
+	let network_rate_limits = network_rate_limits(&cli.run)?;
+
 	runner.run_node_until_exit(move |config| async move {
 		let hwbench = if !cli.run.no_hardware_benchmarks {
 			config.database.path().map(|database_path| {
@@ -337,6 +346,15 @@ where
 			None
 		};
 
+		let collator_policy = service::CollatorPolicy {
+			filters: collator_filters,
+			reputation_path: if cli.run.persist_collator_reputation {
+				config.database.path().map(|path| path.join("collator-reputation"))
+			} else {
+				None
+			},
+		};
+
 		service::build_full(
 			config,
 			service::IsCollator::No,
@@ -347,6 +365,10 @@ where
 			false,
 			overseer_gen,
 			cli.run.overseer_channel_capacity_override,
+			availability_pruning_config(&cli.run),
+			Default::default(),
+			collator_policy,
+			network_rate_limits,
 			maybe_malus_finality_delay,
 			hwbench,
 		)
@@ -355,6 +377,112 @@ where
 	})
 }
 
+/// Builds the availability store pruning configuration from the CLI arguments, falling back to the
+/// defaults for anything not specified.
+fn availability_pruning_config(run: &crate::cli::RunCmd) -> service::AvailabilityPruningConfig {
+	let mut pruning_config = if run.availability_archive {
+		service::AvailabilityPruningConfig::archive()
+	} else {
+		service::AvailabilityPruningConfig::default()
+	};
+
+	if let Some(secs) = run.availability_keep_unavailable_for {
+		pruning_config.keep_unavailable_for = Duration::from_secs(secs);
+	}
+	if let Some(secs) = run.availability_keep_finalized_for {
+		pruning_config.keep_finalized_for = Some(Duration::from_secs(secs));
+	}
+	if let Some(secs) = run.availability_pruning_interval {
+		pruning_config.pruning_interval = Duration::from_secs(secs);
+	}
+
+	pruning_config
+}
+
+/// Builds the collators accepted per para from the CLI arguments.
+fn collator_filters(run: &crate::cli::RunCmd) -> Result<HashMap<ParaId, service::CollatorFilter>> {
+	let mut filters = HashMap::new();
+
+	for (entries, allow) in [(&run.collator_allowlist, true), (&run.collator_denylist, false)] {
+		for entry in entries {
+			let (para_id, collator_id) = parse_para_collator(entry)?;
+			let filter = filters.entry(para_id).or_insert_with(|| {
+				if allow {
+					service::CollatorFilter::Allow(HashSet::new())
+				} else {
+					service::CollatorFilter::Deny(HashSet::new())
+				}
+			});
+
+			match (filter, allow) {
+				(service::CollatorFilter::Allow(collators), true) |
+				(service::CollatorFilter::Deny(collators), false) => {
+					collators.insert(collator_id);
+				},
+				_ =>
+					return Err(Error::Other(format!(
+						"Para {} is given both an allowlist and a denylist of collators",
+						para_id,
+					))),
+			}
+		}
+	}
+
+	Ok(filters)
+}
+
+/// Parses a `PARA_ID:COLLATOR` pair, with the collator id SS58 encoded.
+fn parse_para_collator(entry: &str) -> Result<(ParaId, CollatorId)> {
+	let invalid =
+		|| Error::Other(format!("Invalid collator `{}`, expected `PARA_ID:COLLATOR`", entry));
+
+	let (para_id, collator_id) = entry.split_once(':').ok_or_else(invalid)?;
+	let para_id = para_id.parse::<u32>().map_err(|_| invalid())?;
+	let collator_id =
+		sp_core::sr25519::Public::from_ss58check(collator_id).map_err(|_| invalid())?;
+
+	Ok((para_id.into(), collator_id.into()))
+}
+
+/// Builds the per-peer rate limits of incoming notifications from the CLI arguments.
+fn network_rate_limits(run: &crate::cli::RunCmd) -> Result<service::RateLimits> {
+	let mut rate_limits = if run.no_default_network_rate_limits {
+		service::RateLimits::unlimited()
+	} else {
+		service::RateLimits::default()
+	};
+
+	for entry in &run.network_rate_limit {
+		let (kind, limit) = parse_network_rate_limit(entry)?;
+		rate_limits = rate_limits.with_limit(kind.peer_set(), kind, limit);
+	}
+
+	Ok(rate_limits)
+}
+
+/// Parses a `KIND:BURST:PER_SECOND` rate limit.
+fn parse_network_rate_limit(
+	entry: &str,
+) -> Result<(service::NotificationKind, service::RateLimit)> {
+	let invalid = || {
+		Error::Other(format!("Invalid rate limit `{}`, expected `KIND:BURST:PER_SECOND`", entry))
+	};
+
+	let mut parts = entry.split(':');
+	let (kind, burst, per_second) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+		(Some(kind), Some(burst), Some(per_second), None) => (kind, burst, per_second),
+		_ => return Err(invalid()),
+	};
+	let kind = service::NotificationKind::ALL
+		.into_iter()
+		.find(|k| k.label() == kind)
+		.ok_or_else(invalid)?;
+	let burst = burst.parse().map_err(|_| invalid())?;
+	let per_second = per_second.parse().map_err(|_| invalid())?;
+
+	Ok((kind, service::RateLimit { burst, per_second }))
+}
+
 /// Parses polkadot specific CLI arguments and run the service.
 pub fn run() -> Result<()> {
 	let cli: Cli = Cli::from_args();
@@ -385,12 +513,28 @@ pub fn run() -> Result<()> {
 	}
 
 	match &cli.subcommand {
-		None => run_node_inner(
-			cli,
-			service::RealOverseerGen,
-			None,
-			polkadot_node_metrics::logger_hook(),
-		),
+		None => match (cli.run.record_subsystem, cli.run.record_subsystem_path.clone()) {
+			(Some(subsystem), Some(path)) => {
+				let subsystem = match subsystem {
+					RecordSubsystem::ChainApi => service::RecordedSubsystem::ChainApi,
+					RecordSubsystem::DisputeCoordinator =>
+						service::RecordedSubsystem::DisputeCoordinator,
+					RecordSubsystem::ApprovalVoting => service::RecordedSubsystem::ApprovalVoting,
+				};
+				run_node_inner(
+					cli,
+					service::RecordingOverseerGen { subsystem, path },
+					None,
+					polkadot_node_metrics::logger_hook(),
+				)
+			},
+			_ => run_node_inner(
+				cli,
+				service::RealOverseerGen,
+				None,
+				polkadot_node_metrics::logger_hook(),
+			),
+		},
 		Some(Subcommand::BuildSpec(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			Ok(runner.sync_run(|config| cmd.run(config.chain_spec, config.network))?)
@@ -507,6 +651,72 @@ pub fn run() -> Result<()> {
 				Ok(())
 			}
 		},
+		Some(Subcommand::ValidateCandidate(cmd)) => {
+			let mut builder = sc_cli::LoggerBuilder::new("");
+			builder.with_colors(true);
+			builder.init()?;
+
+			#[cfg(target_os = "android")]
+			{
+				return Err(sc_cli::Error::Input(
+					"PVF validation is not supported under this platform".into(),
+				)
+				.into())
+			}
+
+			#[cfg(not(target_os = "android"))]
+			{
+				crate::validate_candidate::validate_candidate(cmd)
+			}
+		},
+		Some(Subcommand::ExportAvailableData(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| {
+				let candidate_hash = CandidateHash(cmd.candidate_hash);
+				let available_data = service::read_available_data(
+					&config.database,
+					&candidate_hash,
+				)?
+				.ok_or_else(|| {
+					Error::Other(format!("No available data for candidate {:?}", candidate_hash))
+				})?;
+
+				std::fs::write(&cmd.output, available_data.encode())
+					.map_err(|e| Error::Other(format!("Failed to write the output file: {}", e)))?;
+				info!(
+					"Exported the available data of candidate {:?} to {:?}",
+					candidate_hash, cmd.output
+				);
+				Ok::<_, Error>(())
+			})?)
+		},
+		Some(Subcommand::InspectParachainsDb(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| {
+				let filter = service::ParachainsDbFilter {
+					block_hash: cmd.block_hash,
+					candidate_hash: cmd.candidate_hash.map(CandidateHash),
+					session: cmd.session,
+				};
+				let json = service::dump_parachains_db(&config.database, &filter)?.to_json();
+
+				match &cmd.output {
+					Some(output) => std::fs::write(output, json).map_err(|e| {
+						Error::Other(format!("Failed to write the output file: {}", e))
+					})?,
+					None => println!("{}", json),
+				}
+				Ok::<_, Error>(())
+			})?)
+		},
+		Some(Subcommand::DowngradeParachainsDb(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| {
+				service::downgrade_parachains_db(&config.database, cmd.to_version)?;
+				info!("Downgraded the parachains database to version {}", cmd.to_version);
+				Ok::<_, Error>(())
+			})?)
+		},
 		Some(Subcommand::Benchmark(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			let chain_spec = &runner.config().chain_spec;
@@ -689,3 +899,76 @@ pub fn run() -> Result<()> {
 	}
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+	use service::NotificationKind;
+
+	#[test]
+	fn network_rate_limits_are_parsed() {
+		let cli = Cli::try_parse_from([
+			"polkadot",
+			"--no-default-network-rate-limits",
+			"--network-rate-limit",
+			"statement-distribution:10:2",
+		])
+		.unwrap();
+		let rate_limits = network_rate_limits(&cli.run).unwrap();
+
+		let kind = NotificationKind::StatementDistribution;
+		assert_eq!(
+			rate_limits.get(kind.peer_set(), kind),
+			Some(service::RateLimit { burst: 10, per_second: 2 }),
+		);
+		assert_eq!(rate_limits.iter().count(), 1);
+
+		let cli = Cli::try_parse_from(["polkadot"]).unwrap();
+		let rate_limits = network_rate_limits(&cli.run).unwrap();
+		let kind = NotificationKind::BitfieldDistribution;
+		assert!(rate_limits.get(kind.peer_set(), kind).is_some());
+	}
+
+	#[test]
+	fn invalid_network_rate_limits_are_rejected() {
+		for entry in ["statements:10:2", "statement-distribution:10", "bitfield-distribution:a:1"] {
+			assert!(parse_network_rate_limit(entry).is_err());
+		}
+	}
+
+	#[test]
+	fn recording_a_subsystem_requires_a_path() {
+		let cli = Cli::try_parse_from([
+			"polkadot",
+			"--record-subsystem",
+			"dispute-coordinator",
+			"--record-subsystem-path",
+			"disputes.rec",
+		])
+		.unwrap();
+		assert_eq!(cli.run.record_subsystem, Some(RecordSubsystem::DisputeCoordinator));
+		assert_eq!(cli.run.record_subsystem_path, Some("disputes.rec".into()));
+
+		assert!(Cli::try_parse_from(["polkadot", "--record-subsystem", "chain-api"]).is_err());
+		assert!(Cli::try_parse_from(["polkadot", "--record-subsystem-path", "a.rec"]).is_err());
+	}
+
+	#[test]
+	fn validate_candidate_requires_params_or_pov_and_persisted_validation_data() {
+		let parse = |args: &[&str]| {
+			let mut argv = vec!["polkadot", "validate-candidate", "--validation-code", "code"];
+			argv.extend_from_slice(args);
+			Cli::try_parse_from(argv)
+		};
+
+		assert!(parse(&["--params", "params"]).is_ok());
+		assert!(parse(&["--pov", "pov", "--persisted-validation-data", "pvd"]).is_ok());
+
+		assert!(parse(&[]).is_err());
+		assert!(parse(&["--pov", "pov"]).is_err());
+		assert!(parse(&["--persisted-validation-data", "pvd"]).is_err());
+		assert!(parse(&["--params", "params", "--pov", "pov"]).is_err());
+		assert!(parse(&["--params", "params", "--persisted-validation-data", "pvd"]).is_err());
+	}
+}
@@ -31,6 +31,9 @@ pub enum Error {
 	#[error(transparent)]
 	PerfCheck(#[from] polkadot_performance_test::PerfCheckError),
 
+	#[error("Candidate validation failed: {0}")]
+	ValidateCandidate(String),
+
 	#[cfg(not(feature = "pyroscope"))]
 	#[error("Binary was not compiled with `--feature=pyroscope`")]
 	PyroscopeNotCompiledIn,
@@ -26,6 +26,8 @@ mod command;
 mod error;
 #[cfg(all(feature = "hostperfcheck", build_type = "release"))]
 mod host_perf_check;
+#[cfg(feature = "cli")]
+mod validate_candidate;
 
 #[cfg(feature = "full-node")]
 pub use service::RuntimeApiCollection;
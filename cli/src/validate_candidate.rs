@@ -0,0 +1,182 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline validation of a candidate, used for replaying PVF executions, e.g. of disputed
+//! candidates.
+
+use crate::{cli::ValidateCandidateCmd, error::Error};
+use futures::{channel::oneshot, FutureExt as _};
+use log::info;
+use parity_scale_codec::{Decode, Encode};
+use polkadot_node_core_pvf::{
+	sp_maybe_compressed_blob, Config, Metrics, PrepareResult, Priority, Pvf, ValidationError,
+	ValidationHost,
+};
+use polkadot_node_primitives::{BlockData, PoV, POV_BOMB_LIMIT, VALIDATION_CODE_BOMB_LIMIT};
+use polkadot_parachain::primitives::{ValidationParams, ValidationResult};
+use polkadot_primitives::v2::PersistedValidationData;
+use std::{
+	path::Path,
+	time::{Duration, Instant},
+};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Runs the candidate described by the command through the PVF preparation and execution pipeline
+/// and prints the outcome.
+pub fn validate_candidate(cmd: &ValidateCandidateCmd) -> Result<()> {
+	let code = read_file(&cmd.validation_code)?;
+	let code = sp_maybe_compressed_blob::decompress(&code, VALIDATION_CODE_BOMB_LIMIT)
+		.map_err(|e| Error::ValidateCandidate(format!("cannot decompress the code: {:?}", e)))?
+		.into_owned();
+	let params = validation_params(cmd)?;
+
+	let (cache_path, is_temporary) = match cmd.cache_path {
+		Some(ref cache_path) => (cache_path.clone(), false),
+		None => (
+			std::env::temp_dir()
+				.join(format!("polkadot-validate-candidate-{}", std::process::id())),
+			true,
+		),
+	};
+	let program_path = std::env::current_exe().map_err(|e| {
+		Error::ValidateCandidate(format!("cannot determine the worker program path: {}", e))
+	})?;
+
+	let (mut validation_host, task) = polkadot_node_core_pvf::start(
+		Config::new(cache_path.clone(), program_path),
+		Metrics::default(),
+	);
+
+	let result = futures::executor::block_on(async move {
+		let validate = validate(
+			&mut validation_host,
+			Pvf::from_code(code),
+			params,
+			Duration::from_secs(cmd.execution_timeout),
+		)
+		.fuse();
+		let task = task.fuse();
+		futures::pin_mut!(validate, task);
+
+		futures::select! {
+			result = validate => result,
+			() = task => Err(Error::ValidateCandidate(
+				"the validation host has stopped unexpectedly".into(),
+			)),
+		}
+	});
+
+	if is_temporary {
+		let _ = std::fs::remove_dir_all(&cache_path);
+	}
+
+	result
+}
+
+/// Builds the validation parameters either from the encoded parameters or from the PoV and the
+/// persisted validation data.
+fn validation_params(cmd: &ValidateCandidateCmd) -> Result<ValidationParams> {
+	match (&cmd.params, &cmd.pov, &cmd.persisted_validation_data) {
+		(Some(params), None, None) => decode_file(params),
+		(None, Some(pov), Some(persisted_validation_data)) => {
+			let pov: PoV = decode_file(pov)?;
+			let persisted_validation_data: PersistedValidationData =
+				decode_file(persisted_validation_data)?;
+
+			let block_data =
+				sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT).map_err(
+					|e| Error::ValidateCandidate(format!("cannot decompress the PoV: {:?}", e)),
+				)?;
+
+			Ok(ValidationParams {
+				parent_head: persisted_validation_data.parent_head,
+				block_data: BlockData(block_data.into_owned()),
+				relay_parent_number: persisted_validation_data.relay_parent_number,
+				relay_parent_storage_root: persisted_validation_data.relay_parent_storage_root,
+			})
+		},
+		_ => Err(Error::ValidateCandidate(
+			"either `--params` or both `--pov` and `--persisted-validation-data` must be specified"
+				.into(),
+		)),
+	}
+}
+
+async fn validate(
+	validation_host: &mut ValidationHost,
+	pvf: Pvf,
+	params: ValidationParams,
+	execution_timeout: Duration,
+) -> Result<()> {
+	// Prepare the artifact upfront, so that the preparation and the execution are timed separately.
+	let started_at = Instant::now();
+	let (result_tx, result_rx) = oneshot::channel();
+	validation_host
+		.precheck_pvf(pvf.clone(), result_tx)
+		.await
+		.map_err(Error::ValidateCandidate)?;
+	let prepare_result: PrepareResult = result_rx
+		.await
+		.map_err(|_| Error::ValidateCandidate("the preparation was canceled".into()))?;
+	let prepare_time = started_at.elapsed();
+
+	match prepare_result {
+		Ok(()) => info!("Preparation succeeded in {:?}", prepare_time),
+		Err(err) => {
+			info!("Preparation failed in {:?}: {:?}", prepare_time, err);
+			let err = ValidationError::from(err);
+			println!("{:?}", err);
+			return Err(Error::ValidateCandidate(format!("preparation failed: {:?}", err)))
+		},
+	}
+
+	let started_at = Instant::now();
+	let (result_tx, result_rx) = oneshot::channel();
+	validation_host
+		.execute_pvf(pvf, execution_timeout, params.encode(), Priority::Critical, result_tx)
+		.await
+		.map_err(Error::ValidateCandidate)?;
+	let result: std::result::Result<ValidationResult, ValidationError> = result_rx
+		.await
+		.map_err(|_| Error::ValidateCandidate("the execution was canceled".into()))?;
+	let execution_time = started_at.elapsed();
+
+	match result {
+		Ok(validation_result) => {
+			info!("Execution succeeded in {:?}", execution_time);
+			println!("{:?}", validation_result);
+		},
+		Err(err) => {
+			info!("Execution failed in {:?}", execution_time);
+			println!("{:?}", err);
+			return Err(Error::ValidateCandidate(format!("execution failed: {:?}", err)))
+		},
+	}
+
+	Ok(())
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+	std::fs::read(path)
+		.map_err(|e| Error::ValidateCandidate(format!("cannot read {}: {}", path.display(), e)))
+}
+
+fn decode_file<T: Decode>(path: &Path) -> Result<T> {
+	let bytes = read_file(path)?;
+	T::decode(&mut bytes.as_slice())
+		.map_err(|e| Error::ValidateCandidate(format!("cannot decode {}: {}", path.display(), e)))
+}
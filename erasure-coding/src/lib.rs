@@ -96,6 +96,18 @@ pub const fn recovery_threshold(n_validators: usize) -> Result<usize, Error> {
 	Ok(needed + 1)
 }
 
+/// Obtain the number of systematic chunks, which hold the encoded data as is.
+///
+/// The systematic chunks are the ones with the lowest indices, up to the returned threshold. Having
+/// all of them is enough to recover the data without running the decoder.
+pub fn systematic_recovery_threshold(n_validators: usize) -> Result<usize, Error> {
+	let threshold = recovery_threshold(n_validators)?;
+
+	// The code operates on a power of two number of data shards, which is the largest one not
+	// exceeding the recovery threshold.
+	Ok(if threshold.is_power_of_two() { threshold } else { threshold.next_power_of_two() / 2 })
+}
+
 fn code_params(n_validators: usize) -> Result<CodeParams, Error> {
 	// we need to be able to reconstruct from 1/3 - eps
 
@@ -204,6 +216,59 @@ where
 	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
 }
 
+/// Reconstruct the v1 available data from the systematic chunks.
+///
+/// See [`reconstruct_from_systematic`] for details.
+pub fn reconstruct_from_systematic_v1<'a, I: 'a>(
+	n_validators: usize,
+	chunks: I,
+) -> Result<AvailableData, Error>
+where
+	I: IntoIterator<Item = &'a [u8]>,
+{
+	reconstruct_from_systematic(n_validators, chunks)
+}
+
+/// Reconstruct decodable data from the systematic chunks, without running the decoder.
+///
+/// Provide an iterator yielding the chunks with indices
+/// `0..systematic_recovery_threshold(n_validators)`, in order. Any further chunks are ignored.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn reconstruct_from_systematic<'a, I: 'a, T: Decode>(
+	n_validators: usize,
+	chunks: I,
+) -> Result<T, Error>
+where
+	I: IntoIterator<Item = &'a [u8]>,
+{
+	let threshold = systematic_recovery_threshold(n_validators)?;
+	let chunks: Vec<&[u8]> = chunks.into_iter().take(threshold).collect();
+
+	if chunks.len() < threshold {
+		return Err(Error::NotEnoughChunks)
+	}
+
+	let shard_len = chunks[0].len();
+	if shard_len % 2 != 0 {
+		return Err(Error::UnevenLength)
+	}
+	if shard_len == 0 || chunks.iter().any(|chunk| chunk.len() != shard_len) {
+		return Err(Error::NonUniformChunks)
+	}
+
+	// Every chunk holds one 16-bit symbol of each codeword, and the data symbols of a codeword are
+	// spread over the systematic chunks in order.
+	let mut payload_bytes = Vec::with_capacity(shard_len * threshold);
+	for offset in (0..shard_len).step_by(2) {
+		for chunk in &chunks {
+			payload_bytes.extend_from_slice(&chunk[offset..offset + 2]);
+		}
+	}
+
+	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
+}
+
 /// An iterator that yields merkle branches and chunk data for all chunks to
 /// be sent to other validators.
 pub struct Branches<'a, I> {
@@ -376,6 +441,40 @@ mod tests {
 		assert_eq!(reconstructed, available_data);
 	}
 
+	#[test]
+	fn systematic_round_trip_works() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+
+		for n_validators in [2, 3, 4, 10, 100, 1000] {
+			let chunks = obtain_chunks(n_validators, &available_data).unwrap();
+			let threshold = systematic_recovery_threshold(n_validators).unwrap();
+
+			assert!(threshold <= recovery_threshold(n_validators).unwrap());
+
+			let reconstructed: AvailableData = reconstruct_from_systematic(
+				n_validators,
+				chunks.iter().take(threshold).map(|chunk| &chunk[..]),
+			)
+			.unwrap();
+
+			assert_eq!(reconstructed, available_data);
+		}
+	}
+
+	#[test]
+	fn systematic_reconstruction_needs_all_systematic_chunks() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+		let chunks = obtain_chunks(10, &available_data).unwrap();
+
+		assert_eq!(systematic_recovery_threshold(10), Ok(4));
+
+		let reconstructed: Result<AvailableData, _> =
+			reconstruct_from_systematic(10, chunks.iter().take(3).map(|chunk| &chunk[..]));
+		assert_eq!(reconstructed, Err(Error::NotEnoughChunks));
+	}
+
 	#[test]
 	fn reconstruct_does_not_panic_on_low_validator_count() {
 		let reconstructed = reconstruct_v1(1, [].iter().cloned());
@@ -25,6 +25,8 @@ pub enum Error {
 	#[error(transparent)]
 	Runtime(#[from] polkadot_node_subsystem::errors::RuntimeApiError),
 	#[error(transparent)]
+	ChainApi(#[from] polkadot_node_subsystem::errors::ChainApiError),
+	#[error(transparent)]
 	Util(#[from] polkadot_node_subsystem_util::Error),
 	#[error(transparent)]
 	Erasure(#[from] polkadot_erasure_coding::Error),
@@ -39,6 +39,7 @@ use sp_core::crypto::Pair;
 use std::sync::Arc;
 
 mod error;
+mod lifecycle;
 
 #[cfg(test)]
 mod tests;
@@ -80,11 +81,26 @@ impl CollationGenerationSubsystem {
 		// to the overseer here, via the context.
 		let (sender, receiver) = mpsc::channel(0);
 
+		// Following the candidates through the relay chain may need to walk a long ancestry, so
+		// it happens in a task of its own fed with the relevant signals.
+		let (lifecycle_updates, lifecycle_updates_rx) = mpsc::unbounded();
+		if let Err(err) = ctx.spawn(
+			"candidate-lifecycles",
+			lifecycle::run(ctx.sender().clone(), lifecycle_updates_rx).boxed(),
+		) {
+			gum::warn!(target: LOG_TARGET, err = ?err, "failed to spawn candidate lifecycle task");
+		}
+
 		let mut receiver = receiver.fuse();
 		loop {
 			select! {
 				incoming = ctx.recv().fuse() => {
-					if self.handle_incoming::<Context>(incoming, &mut ctx, &sender).await {
+					if self.handle_incoming::<Context>(
+						incoming,
+						&mut ctx,
+						&sender,
+						&lifecycle_updates,
+					).await {
 						break;
 					}
 				},
@@ -106,12 +122,15 @@ impl CollationGenerationSubsystem {
 		incoming: SubsystemResult<FromOrchestra<<Context as SubsystemContext>::Message>>,
 		ctx: &mut Context,
 		sender: &mpsc::Sender<overseer::CollationGenerationOutgoingMessages>,
+		lifecycle_updates: &mpsc::UnboundedSender<lifecycle::Update>,
 	) -> bool {
 		match incoming {
 			Ok(FromOrchestra::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
 				activated,
 				..
 			}))) => {
+				let activated_leaf = activated.as_ref().map(|leaf| (leaf.hash, leaf.number));
+
 				// follow the procedure from the guide
 				if let Some(config) = &self.config {
 					let metrics = self.metrics.clone();
@@ -126,6 +145,15 @@ impl CollationGenerationSubsystem {
 					{
 						gum::warn!(target: LOG_TARGET, err = ?err, "failed to handle new activations");
 					}
+
+					if let Some((hash, number)) = activated_leaf {
+						let collator = config.key.public();
+						let _ = lifecycle_updates.unbounded_send(lifecycle::Update::NewLeaf {
+							collator,
+							hash,
+							number,
+						});
+					}
 				}
 
 				false
@@ -141,7 +169,16 @@ impl CollationGenerationSubsystem {
 				}
 				false
 			},
-			Ok(FromOrchestra::Signal(OverseerSignal::BlockFinalized(..))) => false,
+			Ok(FromOrchestra::Communication {
+				msg: CollationGenerationMessage::SubscribeCandidateLifecycle(subscriber),
+			}) => {
+				let _ = lifecycle_updates.unbounded_send(lifecycle::Update::Subscribe(subscriber));
+				false
+			},
+			Ok(FromOrchestra::Signal(OverseerSignal::BlockFinalized(_, number))) => {
+				let _ = lifecycle_updates.unbounded_send(lifecycle::Update::Finalized(number));
+				false
+			},
 			Err(err) => {
 				gum::error!(
 					target: LOG_TARGET,
@@ -0,0 +1,301 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Following the candidates built by this collator through the relay chain.
+
+use futures::{
+	channel::{mpsc, oneshot},
+	StreamExt,
+};
+use lru::LruCache;
+use std::collections::{HashMap, HashSet};
+
+use polkadot_node_primitives::{CandidateLifecycleEvent, MAX_FINALITY_LAG};
+use polkadot_node_subsystem::{messages::ChainApiMessage, overseer};
+use polkadot_node_subsystem_util::{request_candidate_events, request_on_chain_votes};
+use polkadot_primitives::v2::{BlockNumber, CandidateEvent, CandidateHash, CollatorId, Hash};
+
+use crate::LOG_TARGET;
+
+/// Number of processed relay chain blocks to remember.
+///
+/// When traversing the ancestry of a new leaf we stop at the first block found in here, so this
+/// should at least be as large as the number of expected forks.
+const LRU_OBSERVED_BLOCKS_CAPACITY: usize = 20;
+
+/// Limits the number of ancestors received for a single request.
+const ANCESTRY_CHUNK_SIZE: u32 = 10;
+
+/// Limits the overall number of ancestors walked through for a new leaf.
+const ANCESTRY_SIZE_LIMIT: u32 = MAX_FINALITY_LAG;
+
+/// The subscribers to candidate lifecycle events, together with the candidates that may still be
+/// disputed.
+pub(crate) struct CandidateLifecycles {
+	subscribers: Vec<mpsc::UnboundedSender<CandidateLifecycleEvent>>,
+	/// Our backed candidates, by the number of the block they were last backed or included in.
+	///
+	/// They are forgotten once that block got finalized or they timed out.
+	backed: HashMap<CandidateHash, BlockNumber>,
+	/// Our backed candidates which were reported as disputed already.
+	disputed: HashSet<CandidateHash>,
+	/// Latest relay chain blocks processed.
+	///
+	/// We assume that the ancestors of these blocks were processed as well.
+	last_observed_blocks: LruCache<Hash, ()>,
+}
+
+impl Default for CandidateLifecycles {
+	fn default() -> Self {
+		Self {
+			subscribers: Vec::new(),
+			backed: HashMap::new(),
+			disputed: HashSet::new(),
+			last_observed_blocks: LruCache::new(LRU_OBSERVED_BLOCKS_CAPACITY),
+		}
+	}
+}
+
+impl CandidateLifecycles {
+	/// Add a subscriber to the lifecycle events.
+	pub(crate) fn subscribe(&mut self, subscriber: mpsc::UnboundedSender<CandidateLifecycleEvent>) {
+		self.subscribers.push(subscriber);
+	}
+
+	/// Whether anyone is still interested in the lifecycle events.
+	pub(crate) fn has_subscribers(&self) -> bool {
+		self.subscribers.iter().any(|subscriber| !subscriber.is_closed())
+	}
+
+	/// Report what happened to the candidates of `collator` in a relay chain block.
+	pub(crate) fn process_block(
+		&mut self,
+		collator: &CollatorId,
+		relay_block: Hash,
+		number: BlockNumber,
+		candidate_events: Vec<CandidateEvent>,
+		disputed: impl IntoIterator<Item = CandidateHash>,
+	) {
+		for event in candidate_events {
+			let event = match event {
+				CandidateEvent::CandidateBacked(receipt, ..)
+					if &receipt.descriptor.collator == collator =>
+				{
+					let candidate_hash = receipt.hash();
+					self.backed.insert(candidate_hash, number);
+					CandidateLifecycleEvent::Backed { candidate_hash, relay_block }
+				},
+				CandidateEvent::CandidateIncluded(receipt, ..)
+					if &receipt.descriptor.collator == collator =>
+				{
+					let candidate_hash = receipt.hash();
+					self.backed.insert(candidate_hash, number);
+					CandidateLifecycleEvent::Included { candidate_hash, relay_block }
+				},
+				CandidateEvent::CandidateTimedOut(receipt, ..)
+					if &receipt.descriptor.collator == collator =>
+				{
+					let candidate_hash = receipt.hash();
+					self.backed.remove(&candidate_hash);
+					self.disputed.remove(&candidate_hash);
+					CandidateLifecycleEvent::TimedOut { candidate_hash, relay_block }
+				},
+				_ => continue,
+			};
+			self.notify(event);
+		}
+
+		// Votes keep getting included in later blocks, only report the first one.
+		for candidate_hash in disputed {
+			if self.backed.contains_key(&candidate_hash) && self.disputed.insert(candidate_hash) {
+				self.notify(CandidateLifecycleEvent::Disputed { candidate_hash, relay_block });
+			}
+		}
+	}
+
+	/// Forget about the candidates last backed or included in finalized blocks.
+	pub(crate) fn note_finalized(&mut self, number: BlockNumber) {
+		self.backed.retain(|_, backed_in| *backed_in > number);
+		let backed = &self.backed;
+		self.disputed.retain(|candidate_hash| backed.contains_key(candidate_hash));
+	}
+
+	fn notify(&mut self, event: CandidateLifecycleEvent) {
+		gum::trace!(target: LOG_TARGET, ?event, "candidate lifecycle event");
+		self.subscribers
+			.retain(|subscriber| subscriber.unbounded_send(event.clone()).is_ok());
+	}
+}
+
+/// What the candidate lifecycle task gets fed with.
+pub(crate) enum Update {
+	/// A subscriber to the lifecycle events.
+	Subscribe(mpsc::UnboundedSender<CandidateLifecycleEvent>),
+	/// A newly activated relay chain block.
+	NewLeaf {
+		/// The collator to report the events of the candidates of.
+		collator: CollatorId,
+		/// The hash of the block.
+		hash: Hash,
+		/// The number of the block.
+		number: BlockNumber,
+	},
+	/// The number of the latest finalized block.
+	Finalized(BlockNumber),
+}
+
+/// Follow the candidates through the relay chain, until the subsystem stops sending updates.
+pub(crate) async fn run(
+	mut sender: impl overseer::CollationGenerationSenderTrait,
+	mut updates: mpsc::UnboundedReceiver<Update>,
+) {
+	let mut lifecycles = CandidateLifecycles::default();
+	while let Some(update) = updates.next().await {
+		match update {
+			Update::Subscribe(subscriber) => lifecycles.subscribe(subscriber),
+			Update::NewLeaf { collator, hash, number } if lifecycles.has_subscribers() =>
+				if let Err(err) =
+					process_new_leaf(&mut sender, &mut lifecycles, &collator, hash, number).await
+				{
+					gum::warn!(
+						target: LOG_TARGET,
+						err = ?err,
+						"failed to report candidate lifecycle events",
+					);
+				},
+			Update::NewLeaf { .. } => {},
+			Update::Finalized(number) => lifecycles.note_finalized(number),
+		}
+	}
+}
+
+/// Fetch the candidate events and disputes of a newly activated relay chain block, and of its
+/// ancestors not processed yet, and report those concerning the candidates of `collator`.
+pub(crate) async fn process_new_leaf(
+	sender: &mut impl overseer::CollationGenerationSenderTrait,
+	lifecycles: &mut CandidateLifecycles,
+	collator: &CollatorId,
+	leaf: Hash,
+	leaf_number: BlockNumber,
+) -> crate::error::Result<()> {
+	let ancestors = get_unobserved_ancestors(sender, lifecycles, leaf, leaf_number).await?;
+
+	// Process the oldest ancestor first, such that events are reported in order.
+	let blocks = ancestors.into_iter().rev().chain(std::iter::once((leaf, leaf_number)));
+	for (relay_block, number) in blocks {
+		process_relay_block(sender, lifecycles, collator, relay_block, number).await?;
+		lifecycles.last_observed_blocks.put(relay_block, ());
+	}
+
+	Ok(())
+}
+
+async fn process_relay_block(
+	sender: &mut impl overseer::CollationGenerationSenderTrait,
+	lifecycles: &mut CandidateLifecycles,
+	collator: &CollatorId,
+	relay_block: Hash,
+	number: BlockNumber,
+) -> crate::error::Result<()> {
+	let candidate_events = request_candidate_events(relay_block, sender).await.await??;
+
+	let disputed = match request_on_chain_votes(relay_block, sender).await.await? {
+		Ok(votes) => votes
+			.map(|votes| votes.disputes.into_iter().map(|dispute| dispute.candidate_hash).collect())
+			.unwrap_or_default(),
+		Err(error) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				?relay_block,
+				?error,
+				"failed to fetch on-chain votes, not reporting disputes",
+			);
+			Vec::new()
+		},
+	};
+
+	lifecycles.process_block(collator, relay_block, number, candidate_events, disputed);
+
+	Ok(())
+}
+
+/// Returns the ancestors of `head` with their numbers in descending order, stopping either at a
+/// block processed already or at the last finalized block.
+///
+/// Both `head` and the latest finalized block are not included in the result.
+async fn get_unobserved_ancestors(
+	sender: &mut impl overseer::CollationGenerationSenderTrait,
+	lifecycles: &mut CandidateLifecycles,
+	mut head: Hash,
+	mut head_number: BlockNumber,
+) -> crate::error::Result<Vec<(Hash, BlockNumber)>> {
+	let (tx, rx) = oneshot::channel();
+	sender.send_message(ChainApiMessage::FinalizedBlockNumber(tx)).await;
+	let target_ancestor = rx.await??;
+
+	let mut ancestors = Vec::new();
+
+	// If head_number <= target_ancestor + 1 the ancestry will be empty.
+	if lifecycles.last_observed_blocks.get(&head).is_some() || head_number <= target_ancestor + 1 {
+		return Ok(ancestors)
+	}
+
+	loop {
+		let (tx, rx) = oneshot::channel();
+		sender
+			.send_message(ChainApiMessage::Ancestors {
+				hash: head,
+				k: ANCESTRY_CHUNK_SIZE as usize,
+				response_channel: tx,
+			})
+			.await;
+		let hashes = rx.await??;
+
+		let earliest_block_number = match head_number.checked_sub(hashes.len() as u32) {
+			Some(number) => number,
+			None => {
+				gum::warn!(
+					target: LOG_TARGET,
+					"Received {} ancestors for block number {} from Chain API",
+					hashes.len(),
+					head_number,
+				);
+				return Ok(ancestors)
+			},
+		};
+		// The reversed order is parent, grandparent, etc. excluding the head.
+		let block_numbers = (earliest_block_number..head_number).rev();
+
+		for (block_number, hash) in block_numbers.zip(&hashes) {
+			if lifecycles.last_observed_blocks.get(hash).is_some() ||
+				block_number <= target_ancestor ||
+				ancestors.len() >= ANCESTRY_SIZE_LIMIT as usize
+			{
+				return Ok(ancestors)
+			}
+
+			ancestors.push((*hash, block_number));
+		}
+
+		match hashes.last() {
+			Some(last_hash) => {
+				head = *last_hash;
+				head_number = earliest_block_number;
+			},
+			None => return Ok(ancestors),
+		}
+	}
+}
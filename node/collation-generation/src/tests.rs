@@ -483,3 +483,235 @@ mod handle_new_activations {
 		}
 	}
 }
+
+mod candidate_lifecycles {
+	use super::super::{lifecycle::*, *};
+	use ::test_helpers::{dummy_candidate_receipt, dummy_hash, dummy_head_data};
+	use polkadot_node_primitives::CandidateLifecycleEvent;
+	use polkadot_node_subsystem::messages::{
+		AllMessages, ChainApiMessage, RuntimeApiMessage, RuntimeApiRequest,
+	};
+	use polkadot_node_subsystem_test_helpers::{
+		subsystem_test_harness, TestSubsystemContextHandle,
+	};
+	use polkadot_primitives::v2::{
+		CandidateEvent, CandidateReceipt, CollatorId, CollatorPair, CoreIndex, DisputeStatementSet,
+		GroupIndex, ScrapedOnChainVotes,
+	};
+
+	fn receipt_of(collator: &CollatorId, seed: u8) -> CandidateReceipt {
+		let mut receipt = dummy_candidate_receipt(Hash::repeat_byte(seed));
+		receipt.descriptor.collator = collator.clone();
+		receipt
+	}
+
+	fn backed(receipt: &CandidateReceipt) -> CandidateEvent {
+		CandidateEvent::CandidateBacked(
+			receipt.clone(),
+			dummy_head_data(),
+			CoreIndex(0),
+			GroupIndex(0),
+		)
+	}
+
+	fn timed_out(receipt: &CandidateReceipt) -> CandidateEvent {
+		CandidateEvent::CandidateTimedOut(receipt.clone(), dummy_head_data(), CoreIndex(0))
+	}
+
+	#[test]
+	fn reports_events_of_own_candidates_only() {
+		let ours = CollatorPair::generate().0.public();
+		let theirs = CollatorPair::generate().0.public();
+		let (own_receipt, other_receipt) = (receipt_of(&ours, 1), receipt_of(&theirs, 2));
+		let (own_hash, other_hash) = (own_receipt.hash(), other_receipt.hash());
+
+		let mut lifecycles = CandidateLifecycles::default();
+		let (tx, mut rx) = mpsc::unbounded();
+		lifecycles.subscribe(tx);
+		assert!(lifecycles.has_subscribers());
+
+		let block = Hash::repeat_byte(10);
+		lifecycles.process_block(
+			&ours,
+			block,
+			1,
+			vec![backed(&own_receipt), backed(&other_receipt)],
+			vec![other_hash],
+		);
+		assert_eq!(
+			rx.try_next().unwrap(),
+			Some(CandidateLifecycleEvent::Backed { candidate_hash: own_hash, relay_block: block })
+		);
+		assert!(rx.try_next().is_err());
+
+		// Disputes are only reported for candidates that got backed.
+		let block = Hash::repeat_byte(11);
+		lifecycles.process_block(&ours, block, 2, vec![], vec![own_hash]);
+		assert_eq!(
+			rx.try_next().unwrap(),
+			Some(CandidateLifecycleEvent::Disputed {
+				candidate_hash: own_hash,
+				relay_block: block
+			})
+		);
+
+		// Timed out candidates are forgotten.
+		let block = Hash::repeat_byte(12);
+		lifecycles.process_block(&ours, block, 3, vec![timed_out(&own_receipt)], vec![own_hash]);
+		assert_eq!(
+			rx.try_next().unwrap(),
+			Some(CandidateLifecycleEvent::TimedOut {
+				candidate_hash: own_hash,
+				relay_block: block
+			})
+		);
+		assert!(rx.try_next().is_err());
+
+		drop(rx);
+		assert!(!lifecycles.has_subscribers());
+	}
+
+	#[test]
+	fn forgets_candidates_once_finalized() {
+		let ours = CollatorPair::generate().0.public();
+		let (first, second) = (receipt_of(&ours, 1), receipt_of(&ours, 2));
+
+		let mut lifecycles = CandidateLifecycles::default();
+		let (tx, mut rx) = mpsc::unbounded();
+		lifecycles.subscribe(tx);
+
+		lifecycles.process_block(&ours, dummy_hash(), 5, vec![backed(&first)], vec![]);
+		lifecycles.process_block(&ours, dummy_hash(), 6, vec![backed(&second)], vec![]);
+		assert!(rx.try_next().unwrap().is_some());
+		assert!(rx.try_next().unwrap().is_some());
+
+		lifecycles.note_finalized(5);
+		let block = Hash::repeat_byte(7);
+		lifecycles.process_block(&ours, block, 7, vec![], vec![first.hash(), second.hash()]);
+		assert_eq!(
+			rx.try_next().unwrap(),
+			Some(CandidateLifecycleEvent::Disputed {
+				candidate_hash: second.hash(),
+				relay_block: block
+			})
+		);
+		assert!(rx.try_next().is_err());
+	}
+
+	#[test]
+	fn reports_disputes_once() {
+		let ours = CollatorPair::generate().0.public();
+		let receipt = receipt_of(&ours, 1);
+		let candidate_hash = receipt.hash();
+
+		let mut lifecycles = CandidateLifecycles::default();
+		let (tx, mut rx) = mpsc::unbounded();
+		lifecycles.subscribe(tx);
+
+		lifecycles.process_block(&ours, dummy_hash(), 1, vec![backed(&receipt)], vec![]);
+		assert!(rx.try_next().unwrap().is_some());
+
+		let block = Hash::repeat_byte(2);
+		lifecycles.process_block(&ours, block, 2, vec![], vec![candidate_hash]);
+		assert_eq!(
+			rx.try_next().unwrap(),
+			Some(CandidateLifecycleEvent::Disputed { candidate_hash, relay_block: block })
+		);
+
+		// Later votes of the same dispute are not reported again.
+		lifecycles.process_block(&ours, Hash::repeat_byte(3), 3, vec![], vec![candidate_hash]);
+		assert!(rx.try_next().is_err());
+	}
+
+	#[test]
+	fn new_leaf_reports_candidate_events_and_disputes_of_unobserved_ancestors() {
+		let ours = CollatorPair::generate().0.public();
+		let receipt = receipt_of(&ours, 1);
+		let candidate_hash = receipt.hash();
+		// F <- B1 <- B2 <- L3 <- L4
+		let (finalized, b1, b2, leaf, next_leaf) = (
+			Hash::repeat_byte(40),
+			Hash::repeat_byte(41),
+			Hash::repeat_byte(42),
+			Hash::repeat_byte(43),
+			Hash::repeat_byte(44),
+		);
+
+		let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+			let mut processed = Vec::new();
+			loop {
+				match handle.try_recv().await {
+					None => break,
+					Some(AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(tx))) => {
+						tx.send(Ok(0)).unwrap();
+					},
+					Some(AllMessages::ChainApi(ChainApiMessage::Ancestors {
+						hash,
+						k: _,
+						response_channel,
+					})) => {
+						let ancestors = if hash == leaf {
+							vec![b2, b1, finalized]
+						} else {
+							assert_eq!(hash, next_leaf);
+							vec![leaf, b2, b1, finalized]
+						};
+						response_channel.send(Ok(ancestors)).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						hash,
+						RuntimeApiRequest::CandidateEvents(tx),
+					))) => {
+						processed.push(hash);
+						let events = if hash == b1 { vec![backed(&receipt)] } else { vec![] };
+						tx.send(Ok(events)).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						hash,
+						RuntimeApiRequest::FetchOnChainVotes(tx),
+					))) => {
+						// The dispute votes are included in every block after `B1`.
+						let disputes = if hash == b1 {
+							Vec::new()
+						} else {
+							vec![DisputeStatementSet {
+								candidate_hash,
+								session: 1,
+								statements: Vec::new(),
+							}]
+						};
+						tx.send(Ok(Some(ScrapedOnChainVotes {
+							session: 1,
+							backing_validators_per_candidate: Vec::new(),
+							disputes,
+						})))
+						.unwrap();
+					},
+					Some(msg) => panic!("didn't expect any other overseer requests; got {:?}", msg),
+				}
+			}
+			assert_eq!(processed, vec![b1, b2, leaf, next_leaf]);
+		};
+
+		subsystem_test_harness(overseer, |mut ctx| async move {
+			let mut lifecycles = CandidateLifecycles::default();
+			let (tx, rx) = mpsc::unbounded();
+			lifecycles.subscribe(tx);
+
+			process_new_leaf(ctx.sender(), &mut lifecycles, &ours, leaf, 3).await.unwrap();
+			process_new_leaf(ctx.sender(), &mut lifecycles, &ours, next_leaf, 4)
+				.await
+				.unwrap();
+			drop(lifecycles);
+
+			let events: Vec<_> = rx.collect().await;
+			assert_eq!(
+				events,
+				vec![
+					CandidateLifecycleEvent::Backed { candidate_hash, relay_block: b1 },
+					CandidateLifecycleEvent::Disputed { candidate_hash, relay_block: b2 },
+				]
+			);
+		});
+	}
+}
@@ -18,6 +18,7 @@
 
 use bitvec::{order::Lsb0 as BitOrderLsb0, slice::BitSlice};
 use polkadot_node_primitives::approval::DelayTranche;
+use polkadot_node_subsystem::messages;
 use polkadot_primitives::v2::ValidatorIndex;
 
 use crate::{
@@ -63,6 +64,36 @@ pub enum RequiredTranches {
 	},
 }
 
+impl From<RequiredTranches> for messages::RequiredTranches {
+	fn from(required: RequiredTranches) -> Self {
+		match required {
+			RequiredTranches::All => messages::RequiredTranches::All,
+			RequiredTranches::Pending {
+				considered,
+				next_no_show,
+				maximum_broadcast,
+				clock_drift,
+			} => messages::RequiredTranches::Pending {
+				considered,
+				next_no_show,
+				maximum_broadcast,
+				clock_drift,
+			},
+			RequiredTranches::Exact {
+				needed,
+				tolerated_missing,
+				next_no_show,
+				last_assignment_tick,
+			} => messages::RequiredTranches::Exact {
+				needed,
+				tolerated_missing,
+				next_no_show,
+				last_assignment_tick,
+			},
+		}
+	}
+}
+
 /// The result of a check.
 #[derive(Debug, Clone, Copy)]
 pub enum Check {
@@ -331,6 +362,7 @@ fn count_no_shows(
 	block_tick: Tick,
 	no_show_duration: Tick,
 	drifted_tick_now: Tick,
+	note_no_show: &mut impl FnMut(ValidatorIndex),
 ) -> (usize, Option<u64>) {
 	let mut next_no_show = None;
 	let no_shows = assignments
@@ -356,6 +388,9 @@ fn count_no_shows(
 				// the clock drift will be removed again to do the comparison above.
 				next_no_show = super::min_prefer_some(next_no_show, Some(no_show_at + clock_drift));
 			}
+			if is_no_show {
+				note_no_show(*v_index);
+			}
 
 			is_no_show
 		})
@@ -372,6 +407,49 @@ pub fn tranches_to_approve(
 	block_tick: Tick,
 	no_show_duration: Tick,
 	needed_approvals: usize,
+) -> RequiredTranches {
+	tranches_to_approve_noting_no_shows(
+		approval_entry,
+		approvals,
+		tranche_now,
+		block_tick,
+		no_show_duration,
+		needed_approvals,
+		|_| {},
+	)
+}
+
+/// Determine the validators which [`tranches_to_approve`] considers no-shows, with the clock
+/// drift of the depth their tranche is considered at.
+pub fn no_shows(
+	approval_entry: &ApprovalEntry,
+	approvals: &BitSlice<u8, BitOrderLsb0>,
+	tranche_now: DelayTranche,
+	block_tick: Tick,
+	no_show_duration: Tick,
+	needed_approvals: usize,
+) -> Vec<ValidatorIndex> {
+	let mut no_shows = Vec::new();
+	tranches_to_approve_noting_no_shows(
+		approval_entry,
+		approvals,
+		tranche_now,
+		block_tick,
+		no_show_duration,
+		needed_approvals,
+		|validator| no_shows.push(validator),
+	);
+	no_shows
+}
+
+fn tranches_to_approve_noting_no_shows(
+	approval_entry: &ApprovalEntry,
+	approvals: &BitSlice<u8, BitOrderLsb0>,
+	tranche_now: DelayTranche,
+	block_tick: Tick,
+	no_show_duration: Tick,
+	needed_approvals: usize,
+	mut note_no_show: impl FnMut(ValidatorIndex),
 ) -> RequiredTranches {
 	let tick_now = tranche_now as Tick + block_tick;
 	let n_validators = approval_entry.n_validators();
@@ -429,6 +507,7 @@ pub fn tranches_to_approve(
 				block_tick,
 				no_show_duration,
 				drifted_tick_now,
+				&mut note_no_show,
 			);
 
 			let s = s.advance(n_assignments, no_shows, next_no_show, last_assignment_tick);
@@ -1023,6 +1102,58 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn no_shows_are_only_reported_in_considered_tranches() {
+		let block_tick = 20;
+		let no_show_duration = 10;
+		let needed_approvals = 1;
+		let n_validators = 8;
+
+		let mut approval_entry: ApprovalEntry = approval_db::v1::ApprovalEntry {
+			tranches: Vec::new(),
+			assignments: bitvec![u8, BitOrderLsb0; 0; n_validators],
+			our_assignment: None,
+			our_approval_sig: None,
+			backing_group: GroupIndex(0),
+			approved: false,
+		}
+		.into();
+
+		approval_entry.import_assignment(0, ValidatorIndex(0), block_tick);
+		approval_entry.import_assignment(1, ValidatorIndex(1), block_tick + 1);
+
+		let mut approvals = bitvec![u8, BitOrderLsb0; 0; n_validators];
+		approvals.set(0, true);
+
+		// Tranche 1 is not needed, so its assignment can't be a no-show.
+		let tranche_now = 2 * no_show_duration as DelayTranche;
+		assert_eq!(
+			no_shows(
+				&approval_entry,
+				&approvals,
+				tranche_now,
+				block_tick,
+				no_show_duration,
+				needed_approvals,
+			),
+			Vec::new(),
+		);
+
+		// Covering the no-show of tranche 0 considers tranche 1 as well.
+		approvals.set(0, false);
+		assert_eq!(
+			no_shows(
+				&approval_entry,
+				&approvals,
+				tranche_now,
+				block_tick,
+				no_show_duration,
+				needed_approvals,
+			),
+			vec![ValidatorIndex(0), ValidatorIndex(1)],
+		);
+	}
+
 	#[test]
 	fn validator_indexes_out_of_range_are_ignored_in_assignments() {
 		let block_tick = 20;
@@ -1149,6 +1280,7 @@ mod tests {
 			block_tick,
 			test.no_show_duration,
 			test.drifted_tick_now,
+			&mut |_| {},
 		);
 		assert_eq!(no_shows, test.exp_no_shows, "for test: {:?}", test);
 		assert_eq!(next_no_show, test.exp_next_no_show, "for test {:?}", test);
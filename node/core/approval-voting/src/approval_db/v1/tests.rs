@@ -138,6 +138,71 @@ fn read_write() {
 		.is_none());
 }
 
+#[test]
+fn read_block_entries_decodes_stored_blocks() {
+	let (mut db, store) = make_db();
+
+	let block_hash = Hash::repeat_byte(1);
+	let candidate = make_candidate(ParaId::from(5_u32), block_hash);
+	let candidate_hash = candidate.hash();
+
+	let candidate_entry = CandidateEntry {
+		candidate,
+		session: 1,
+		block_assignments: vec![(
+			block_hash,
+			ApprovalEntry {
+				tranches: vec![TrancheEntry {
+					tranche: 0,
+					assignments: vec![(ValidatorIndex(2), Tick(10))],
+				}],
+				backing_group: GroupIndex(1),
+				our_assignment: None,
+				our_approval_sig: None,
+				assignments: make_bitvec(4),
+				approved: false,
+			},
+		)]
+		.into_iter()
+		.collect(),
+		approvals: bitvec::bitvec![u8, BitOrderLsb0; 0, 0, 1, 0],
+	};
+
+	let mut overlay_db = OverlayedBackend::new(&db);
+	overlay_db.write_stored_block_range(StoredBlockRange(1, 2));
+	overlay_db.write_blocks_at_height(1, vec![block_hash]);
+	overlay_db.write_block_entry(
+		make_block_entry(block_hash, Hash::repeat_byte(0), 1, vec![(CoreIndex(3), candidate_hash)])
+			.into(),
+	);
+	overlay_db.write_candidate_entry(candidate_entry.into());
+	let write_ops = overlay_db.into_write_ops();
+	db.write(write_ops).unwrap();
+
+	let config = crate::Config { col_data: DATA_COL, slot_duration_millis: 6_000 };
+	let blocks = crate::read_block_entries(store, &config).unwrap();
+
+	assert_eq!(blocks.len(), 1);
+	assert_eq!(blocks[0].block_hash, block_hash);
+	assert_eq!(blocks[0].block_number, 1);
+	assert_eq!(blocks[0].session, 1);
+	assert!(!blocks[0].approved);
+	assert_eq!(
+		blocks[0].candidates,
+		vec![crate::StoredCandidate {
+			candidate_hash,
+			para_id: ParaId::from(5_u32),
+			core_index: CoreIndex(3),
+			approved: false,
+			tranches: vec![polkadot_node_subsystem::messages::TrancheAssignments {
+				tranche: 0,
+				assignments: vec![(ValidatorIndex(2), 10)],
+			}],
+			approvals: vec![ValidatorIndex(2)],
+		}],
+	);
+}
+
 #[test]
 fn add_block_entry_works() {
 	let (mut db, store) = make_db();
@@ -26,16 +26,17 @@ use polkadot_node_primitives::{
 	approval::{
 		BlockApprovalMeta, DelayTranche, IndirectAssignmentCert, IndirectSignedApprovalVote,
 	},
-	ValidationResult, APPROVAL_EXECUTION_TIMEOUT,
+	PvfExecKind, ValidationResult, APPROVAL_EXECUTION_TIMEOUT,
 };
 use polkadot_node_subsystem::{
 	errors::RecoveryError,
 	messages::{
 		ApprovalCheckError, ApprovalCheckResult, ApprovalDistributionMessage,
 		ApprovalVotingMessage, AssignmentCheckError, AssignmentCheckResult,
-		AvailabilityRecoveryMessage, BlockDescription, CandidateValidationMessage, ChainApiMessage,
+		AvailabilityRecoveryMessage, BlockApprovalStatus, BlockDescription,
+		CandidateApprovalStatus, CandidateValidationMessage, ChainApiMessage,
 		ChainSelectionMessage, DisputeCoordinatorMessage, HighestApprovedAncestorBlock,
-		RuntimeApiMessage, RuntimeApiRequest,
+		RuntimeApiMessage, RuntimeApiRequest, TrancheAssignments,
 	},
 	overseer, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError, SubsystemResult,
 	SubsystemSender,
@@ -50,9 +51,9 @@ use polkadot_node_subsystem_util::{
 	TimeoutExt,
 };
 use polkadot_primitives::v2::{
-	ApprovalVote, BlockNumber, CandidateHash, CandidateIndex, CandidateReceipt, DisputeStatement,
-	GroupIndex, Hash, SessionIndex, SessionInfo, ValidDisputeStatementKind, ValidatorId,
-	ValidatorIndex, ValidatorPair, ValidatorSignature,
+	ApprovalVote, BlockNumber, CandidateHash, CandidateIndex, CandidateReceipt, CoreIndex,
+	DisputeStatement, GroupIndex, Hash, Id as ParaId, SessionIndex, SessionInfo,
+	ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
 };
 use sc_keystore::LocalKeystore;
 use sp_application_crypto::Pair;
@@ -119,6 +120,105 @@ pub struct Config {
 	pub slot_duration_millis: u64,
 }
 
+/// A candidate included in a block, as read by [`read_block_entries`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredCandidate {
+	/// The hash of the candidate.
+	pub candidate_hash: CandidateHash,
+	/// The para the candidate is for.
+	pub para_id: ParaId,
+	/// The core the candidate occupied.
+	pub core_index: CoreIndex,
+	/// Whether the candidate is approved in the context of the block.
+	pub approved: bool,
+	/// The assignments received for the candidate in the context of the block, per tranche.
+	pub tranches: Vec<TrancheAssignments>,
+	/// The validators which approved the candidate.
+	pub approvals: Vec<ValidatorIndex>,
+}
+
+/// A block tracked by approval voting, as read by [`read_block_entries`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredBlock {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// The hash of the parent of the block.
+	pub parent_hash: Hash,
+	/// The session the block is in.
+	pub session: SessionIndex,
+	/// The slot of the block.
+	pub slot: Slot,
+	/// Whether all candidates included in the block are approved.
+	pub approved: bool,
+	/// The candidates included in the block.
+	pub candidates: Vec<StoredCandidate>,
+}
+
+/// Load all blocks tracked by approval voting straight from its database, ascending by height.
+///
+/// This is meant for offline inspection of a node's database.
+pub fn read_block_entries(
+	db: Arc<dyn Database>,
+	config: &Config,
+) -> SubsystemResult<Vec<StoredBlock>> {
+	let backend = DbBackend::new(db, DatabaseConfig { col_data: config.col_data });
+
+	let mut blocks = Vec::new();
+	for block_hash in backend.load_all_blocks()? {
+		let block_entry = match backend.load_block_entry(&block_hash)? {
+			None => continue,
+			Some(e) => e,
+		};
+
+		let mut candidates = Vec::with_capacity(block_entry.candidates().len());
+		for (core_index, candidate_hash) in block_entry.candidates() {
+			let candidate_entry = match backend.load_candidate_entry(candidate_hash)? {
+				None => continue,
+				Some(e) => e,
+			};
+
+			candidates.push(StoredCandidate {
+				candidate_hash: *candidate_hash,
+				para_id: candidate_entry.candidate_receipt().descriptor.para_id,
+				core_index: *core_index,
+				approved: block_entry.is_candidate_approved(candidate_hash),
+				tranches: candidate_entry
+					.approval_entry(&block_hash)
+					.map(|approval_entry| {
+						approval_entry
+							.tranches()
+							.iter()
+							.map(|tranche| TrancheAssignments {
+								tranche: tranche.tranche(),
+								assignments: tranche.assignments().to_vec(),
+							})
+							.collect()
+					})
+					.unwrap_or_default(),
+				approvals: candidate_entry
+					.approvals()
+					.iter_ones()
+					.map(|i| ValidatorIndex(i as _))
+					.collect(),
+			});
+		}
+
+		blocks.push(StoredBlock {
+			block_hash,
+			block_number: block_entry.block_number(),
+			parent_hash: block_entry.parent_hash(),
+			session: block_entry.session(),
+			slot: block_entry.slot(),
+			approved: block_entry.is_fully_approved(),
+			candidates,
+		});
+	}
+
+	Ok(blocks)
+}
+
 // The mode of the approval voting subsystem. It should start in a `Syncing` mode when it first
 // starts, and then once it's reached the head of the chain it should move into the `Active` mode.
 //
@@ -1193,12 +1293,96 @@ async fn handle_from_overseer<Context>(
 				get_approval_signatures_for_candidate(ctx, db, candidate_hash, tx).await?;
 				Vec::new()
 			},
+			ApprovalVotingMessage::InspectBlock(block_hash, tx) => {
+				let _ = tx.send(inspect_block(state, db, block_hash)?);
+				Vec::new()
+			},
 		},
 	};
 
 	Ok(actions)
 }
 
+/// Inspect the approval state of all candidates included in a block, as computed at the
+/// current tick.
+fn inspect_block(
+	state: &State,
+	db: &OverlayedBackend<'_, impl Backend>,
+	block_hash: Hash,
+) -> SubsystemResult<Option<BlockApprovalStatus>> {
+	let block_entry = match db.load_block_entry(&block_hash)? {
+		None => return Ok(None),
+		Some(e) => e,
+	};
+
+	let session_info = match state.session_info(block_entry.session()) {
+		None => return Ok(None),
+		Some(s) => s,
+	};
+	let no_show_duration = slot_number_to_tick(
+		state.slot_duration_millis,
+		Slot::from(u64::from(session_info.no_show_slots)),
+	);
+
+	let mut candidates = Vec::with_capacity(block_entry.candidates().len());
+	for (core_index, candidate_hash) in block_entry.candidates() {
+		let candidate_entry = match db.load_candidate_entry(candidate_hash)? {
+			None => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					?block_hash,
+					"Missing candidate entry for inspected block."
+				);
+				continue
+			},
+			Some(e) => e,
+		};
+
+		let (approval_entry, status) = match state.approval_status(&block_entry, &candidate_entry) {
+			None => continue,
+			Some(s) => s,
+		};
+
+		let approvals = candidate_entry.approvals();
+		let no_shows = approval_checking::no_shows(
+			approval_entry,
+			approvals,
+			status.tranche_now,
+			status.block_tick,
+			no_show_duration,
+			session_info.needed_approvals as _,
+		);
+
+		candidates.push(CandidateApprovalStatus {
+			candidate_hash: *candidate_hash,
+			para_id: candidate_entry.candidate_receipt().descriptor.para_id,
+			core_index: *core_index,
+			approved: block_entry.is_candidate_approved(candidate_hash),
+			required_tranches: status.required_tranches.into(),
+			tranches: approval_entry
+				.tranches()
+				.iter()
+				.map(|tranche| TrancheAssignments {
+					tranche: tranche.tranche(),
+					assignments: tranche.assignments().to_vec(),
+				})
+				.collect(),
+			approvals: approvals.iter_ones().map(|i| ValidatorIndex(i as _)).collect(),
+			no_shows,
+		});
+	}
+
+	Ok(Some(BlockApprovalStatus {
+		block_hash,
+		block_number: block_entry.block_number(),
+		session: block_entry.session(),
+		tranche_now: state.clock.tranche_now(state.slot_duration_millis, block_entry.slot()),
+		approved: block_entry.is_fully_approved(),
+		candidates,
+	}))
+}
+
 /// Retrieve approval signatures.
 ///
 /// This involves an unbounded message send to approval-distribution, the caller has to ensure that
@@ -2341,6 +2525,7 @@ async fn launch_approval<Context>(
 				candidate.clone(),
 				available_data.pov,
 				APPROVAL_EXECUTION_TIMEOUT,
+				PvfExecKind::Approval,
 				val_tx,
 			))
 			.await;
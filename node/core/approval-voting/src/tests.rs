@@ -1482,6 +1482,103 @@ fn subsystem_accepts_and_imports_approval_after_assignment() {
 	});
 }
 
+async fn inspect_block(
+	overseer: &mut VirtualOverseer,
+	block_hash: Hash,
+) -> Option<BlockApprovalStatus> {
+	let (tx, rx) = oneshot::channel();
+	overseer_send(
+		overseer,
+		FromOrchestra::Communication { msg: ApprovalVotingMessage::InspectBlock(block_hash, tx) },
+	)
+	.await;
+	rx.await.unwrap()
+}
+
+#[test]
+fn subsystem_inspect_block_reports_assignments_and_approvals() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+
+		let candidate_hash = {
+			let mut candidate_receipt =
+				dummy_candidate_receipt_bad_sig(block_hash, Some(Default::default()));
+			candidate_receipt.descriptor.para_id = ParaId::from(0_u32);
+			candidate_receipt.descriptor.relay_parent = block_hash;
+			candidate_receipt.hash()
+		};
+
+		let candidate_index = 0;
+		let validator = ValidatorIndex(0);
+		let session_index = 1;
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig { slot: Slot::from(1), candidates: None, session_info: None },
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		assert_eq!(inspect_block(&mut virtual_overseer, Hash::repeat_byte(0x02)).await, None);
+
+		let rx = check_and_import_assignment(
+			&mut virtual_overseer,
+			block_hash,
+			candidate_index,
+			validator,
+		)
+		.await;
+		assert_eq!(rx.await, Ok(AssignmentCheckResult::Accepted));
+
+		let status = inspect_block(&mut virtual_overseer, block_hash).await.unwrap();
+		assert_eq!(status.block_number, 1);
+		assert_eq!(status.session, session_index);
+		assert!(!status.approved);
+		assert_eq!(status.candidates.len(), 1);
+
+		let candidate = &status.candidates[0];
+		assert_eq!(candidate.candidate_hash, candidate_hash);
+		assert!(!candidate.approved);
+		assert_eq!(candidate.tranches.len(), 1);
+		assert_eq!(candidate.tranches[0].tranche, 0);
+		assert_eq!(candidate.tranches[0].assignments[0].0, validator);
+		assert!(candidate.approvals.is_empty());
+		assert!(candidate.no_shows.is_empty());
+
+		let rx = check_and_import_approval(
+			&mut virtual_overseer,
+			block_hash,
+			candidate_index,
+			validator,
+			candidate_hash,
+			session_index,
+			true,
+			None,
+		)
+		.await;
+		assert_eq!(rx.await, Ok(ApprovalCheckResult::Accepted));
+
+		let status = inspect_block(&mut virtual_overseer, block_hash).await.unwrap();
+		assert!(status.approved);
+		assert!(status.candidates[0].approved);
+		assert_eq!(status.candidates[0].approvals, vec![validator]);
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn subsystem_second_approval_import_only_schedules_wakeups() {
 	test_harness(HarnessConfig::default(), |test_harness| async move {
@@ -2389,7 +2486,15 @@ pub async fn handle_double_assignment_import(
 				assert_eq!(candidate_index, c_index);
 			},
 			AllMessages::CandidateValidation(
-				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx),
+				CandidateValidationMessage::ValidateFromExhaustive(
+					_,
+					_,
+					_,
+					_,
+					timeout,
+					PvfExecKind::Approval,
+					tx,
+				),
 			) if timeout == APPROVAL_EXECUTION_TIMEOUT => {
 				tx.send(Ok(ValidationResult::Valid(Default::default(), Default::default())))
 					.unwrap();
@@ -63,13 +63,13 @@ const PRUNE_BY_TIME_PREFIX: &[u8; 13] = b"prune_by_time";
 // rocksdb doesn't support empty values.
 const TOMBSTONE_VALUE: &[u8] = &*b" ";
 
-/// Unavailable blocks are kept for 1 hour.
+/// Unavailable blocks are kept for 1 hour by default.
 const KEEP_UNAVAILABLE_FOR: Duration = Duration::from_secs(60 * 60);
 
-/// Finalized data is kept for 25 hours.
+/// Finalized data is kept for 25 hours by default.
 const KEEP_FINALIZED_FOR: Duration = Duration::from_secs(25 * 60 * 60);
 
-/// The pruning interval.
+/// The default pruning interval.
 const PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 5);
 
 /// Unix time wrapper with big-endian encoding.
@@ -189,6 +189,82 @@ fn load_available_data(
 	query_inner(db, config.col_data, &key)
 }
 
+/// Load the `AvailableData` of a candidate straight from the availability store's database.
+///
+/// This is meant for offline inspection of a node's database, e.g. to export the data of a
+/// candidate. Returns `None` if the data is not stored.
+pub fn read_available_data(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	candidate_hash: &CandidateHash,
+) -> Result<Option<AvailableData>, Error> {
+	load_available_data(db, config, candidate_hash)
+}
+
+/// The state of a candidate in the availability store, as read by [`read_candidate_meta`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CandidateState {
+	/// Not available in any block since the given time since the unix epoch.
+	Unavailable(Duration),
+	/// First observed at the given time and included in the given unfinalized blocks.
+	Unfinalized(Duration, Vec<(BlockNumber, Hash)>),
+	/// Included in a finalized block at the given time.
+	Finalized(Duration),
+}
+
+/// The meta information the availability store keeps about a candidate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateMetaInfo {
+	/// The hash of the candidate.
+	pub candidate_hash: CandidateHash,
+	/// The state of the candidate.
+	pub state: CandidateState,
+	/// Whether the full `AvailableData` is stored.
+	pub data_available: bool,
+	/// The validator indices of the stored chunks.
+	pub chunks_stored: Vec<ValidatorIndex>,
+}
+
+impl CandidateMetaInfo {
+	fn new(candidate_hash: CandidateHash, meta: CandidateMeta) -> Self {
+		let state = match meta.state {
+			State::Unavailable(at) => CandidateState::Unavailable(at.into()),
+			State::Unfinalized(at, blocks) => CandidateState::Unfinalized(
+				at.into(),
+				blocks.into_iter().map(|(number, hash)| (number.0, hash)).collect(),
+			),
+			State::Finalized(at) => CandidateState::Finalized(at.into()),
+		};
+
+		CandidateMetaInfo {
+			candidate_hash,
+			state,
+			data_available: meta.data_available,
+			chunks_stored: meta
+				.chunks_stored
+				.iter_ones()
+				.map(|index| ValidatorIndex(index as _))
+				.collect(),
+		}
+	}
+}
+
+/// Load the meta information of all candidates straight from the availability store's database.
+///
+/// Like [`read_available_data`], this is meant for offline inspection of a node's database.
+pub fn read_candidate_meta(
+	db: &Arc<dyn Database>,
+	config: &Config,
+) -> Result<Vec<CandidateMetaInfo>, Error> {
+	db.iter_with_prefix(config.col_meta, META_PREFIX)
+		.map(|(key, value)| -> Result<_, Error> {
+			let candidate_hash = CandidateHash::decode(&mut &key[META_PREFIX.len()..])?;
+			let meta = CandidateMeta::decode(&mut &value[..])?;
+			Ok(CandidateMetaInfo::new(candidate_hash, meta))
+		})
+		.collect()
+}
+
 fn delete_available_data(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
 	let key = (AVAILABLE_PREFIX, hash).encode();
 
@@ -396,25 +472,38 @@ impl Error {
 }
 
 /// Struct holding pruning timing configuration.
-/// The only purpose of this structure is to use different timing
-/// configurations in production and in testing.
-#[derive(Clone)]
-struct PruningConfig {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruningConfig {
 	/// How long unavailable data should be kept.
-	keep_unavailable_for: Duration,
+	pub keep_unavailable_for: Duration,
 
 	/// How long finalized data should be kept.
-	keep_finalized_for: Duration,
+	///
+	/// `None` enables archive mode: finalized data is never pruned. Data that was finalized
+	/// before archive mode was enabled is still pruned on schedule.
+	pub keep_finalized_for: Option<Duration>,
 
 	/// How often to perform data pruning.
-	pruning_interval: Duration,
+	pub pruning_interval: Duration,
+}
+
+impl PruningConfig {
+	/// A configuration that never prunes finalized data.
+	pub fn archive() -> Self {
+		Self { keep_finalized_for: None, ..Default::default() }
+	}
+
+	/// Whether finalized data is kept forever.
+	pub fn is_archive(&self) -> bool {
+		self.keep_finalized_for.is_none()
+	}
 }
 
 impl Default for PruningConfig {
 	fn default() -> Self {
 		Self {
 			keep_unavailable_for: KEEP_UNAVAILABLE_FOR,
-			keep_finalized_for: KEEP_FINALIZED_FOR,
+			keep_finalized_for: Some(KEEP_FINALIZED_FOR),
 			pruning_interval: PRUNING_INTERVAL,
 		}
 	}
@@ -427,6 +516,8 @@ pub struct Config {
 	pub col_data: u32,
 	/// The column family for availability store meta information.
 	pub col_meta: u32,
+	/// When to prune stored data.
+	pub pruning: PruningConfig,
 }
 
 trait Clock: Send + Sync {
@@ -456,25 +547,18 @@ pub struct AvailabilityStoreSubsystem {
 impl AvailabilityStoreSubsystem {
 	/// Create a new `AvailabilityStoreSubsystem` with a given config on disk.
 	pub fn new(db: Arc<dyn Database>, config: Config, metrics: Metrics) -> Self {
-		Self::with_pruning_config_and_clock(
-			db,
-			config,
-			PruningConfig::default(),
-			Box::new(SystemClock),
-			metrics,
-		)
+		Self::with_clock(db, config, Box::new(SystemClock), metrics)
 	}
 
-	/// Create a new `AvailabilityStoreSubsystem` with a given config on disk.
-	fn with_pruning_config_and_clock(
+	/// Create a new `AvailabilityStoreSubsystem` with a given config on disk and a custom clock.
+	fn with_clock(
 		db: Arc<dyn Database>,
 		config: Config,
-		pruning_config: PruningConfig,
 		clock: Box<dyn Clock>,
 		metrics: Metrics,
 	) -> Self {
 		Self {
-			pruning_config,
+			pruning_config: config.pruning,
 			config,
 			db,
 			metrics,
@@ -968,14 +1052,16 @@ fn update_blocks_at_finalized_height(
 
 			meta.state = State::Finalized(now.into());
 
-			// Write the meta and a pruning record.
+			// Write the meta and a pruning record, unless finalized data is kept forever.
 			write_meta(db_transaction, &subsystem.config, &candidate_hash, &meta);
-			write_pruning_key(
-				db_transaction,
-				&subsystem.config,
-				now + subsystem.pruning_config.keep_finalized_for,
-				&candidate_hash,
-			);
+			if let Some(keep_finalized_for) = subsystem.pruning_config.keep_finalized_for {
+				write_pruning_key(
+					db_transaction,
+					&subsystem.config,
+					now + keep_finalized_for,
+					&candidate_hash,
+				);
+			}
 		} else {
 			meta.state = match meta.state {
 				State::Finalized(_) => continue,   // sanity.
@@ -42,7 +42,15 @@ mod columns {
 	pub const NUM_COLUMNS: u32 = 2;
 }
 
-const TEST_CONFIG: Config = Config { col_data: columns::DATA, col_meta: columns::META };
+const TEST_CONFIG: Config = Config {
+	col_data: columns::DATA,
+	col_meta: columns::META,
+	pruning: PruningConfig {
+		keep_unavailable_for: KEEP_UNAVAILABLE_FOR,
+		keep_finalized_for: Some(KEEP_FINALIZED_FOR),
+		pruning_interval: PRUNING_INTERVAL,
+	},
+};
 
 type VirtualOverseer = test_helpers::TestSubsystemContextHandle<AvailabilityStoreMessage>;
 
@@ -93,7 +101,7 @@ impl Default for TestState {
 
 		let pruning_config = PruningConfig {
 			keep_unavailable_for: Duration::from_secs(1),
-			keep_finalized_for: Duration::from_secs(2),
+			keep_finalized_for: Some(Duration::from_secs(2)),
 			pruning_interval: Duration::from_millis(250),
 		};
 
@@ -117,10 +125,9 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 	let pool = sp_core::testing::TaskExecutor::new();
 	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
 
-	let subsystem = AvailabilityStoreSubsystem::with_pruning_config_and_clock(
+	let subsystem = AvailabilityStoreSubsystem::with_clock(
 		store,
-		TEST_CONFIG,
-		state.pruning_config.clone(),
+		Config { pruning: state.pruning_config, ..TEST_CONFIG },
 		Box::new(state.clock),
 		Metrics::default(),
 	);
@@ -322,6 +329,55 @@ fn store_chunk_works() {
 	});
 }
 
+#[test]
+fn candidate_meta_can_be_read_from_the_database() {
+	let store = test_store();
+	let unavailable = CandidateHash(Hash::repeat_byte(1));
+	let unfinalized = CandidateHash(Hash::repeat_byte(2));
+	let block_hash = Hash::repeat_byte(3);
+
+	with_tx(&store, |tx| {
+		super::write_meta(
+			tx,
+			&TEST_CONFIG,
+			&unavailable,
+			&CandidateMeta {
+				data_available: false,
+				chunks_stored: bitvec::bitvec![u8, BitOrderLsb0; 0, 1, 0, 1],
+				state: State::Unavailable(BETimestamp(10)),
+			},
+		);
+		super::write_meta(
+			tx,
+			&TEST_CONFIG,
+			&unfinalized,
+			&CandidateMeta {
+				data_available: true,
+				chunks_stored: bitvec::bitvec![u8, BitOrderLsb0; 0; 4],
+				state: State::Unfinalized(BETimestamp(20), vec![(BEBlockNumber(5), block_hash)]),
+			},
+		);
+	});
+
+	assert_eq!(
+		read_candidate_meta(&store, &TEST_CONFIG).unwrap(),
+		vec![
+			CandidateMetaInfo {
+				candidate_hash: unavailable,
+				state: CandidateState::Unavailable(Duration::from_secs(10)),
+				data_available: false,
+				chunks_stored: vec![ValidatorIndex(1), ValidatorIndex(3)],
+			},
+			CandidateMetaInfo {
+				candidate_hash: unfinalized,
+				state: CandidateState::Unfinalized(Duration::from_secs(20), vec![(5, block_hash)],),
+				data_available: true,
+				chunks_stored: Vec::new(),
+			},
+		],
+	);
+}
+
 #[test]
 fn store_chunk_does_nothing_if_no_entry_already() {
 	let store = test_store();
@@ -703,7 +759,7 @@ fn stored_data_kept_until_finalized() {
 		.await;
 
 		// Wait until unavailable data would definitely be pruned.
-		test_state.clock.inc(test_state.pruning_config.keep_finalized_for / 2);
+		test_state.clock.inc(test_state.pruning_config.keep_finalized_for.unwrap() / 2);
 		test_state.wait_for_pruning().await;
 
 		// At this point data should _still_ be in the store.
@@ -715,7 +771,7 @@ fn stored_data_kept_until_finalized() {
 		assert!(has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators, true).await);
 
 		// Wait until it definitely should be gone.
-		test_state.clock.inc(test_state.pruning_config.keep_finalized_for);
+		test_state.clock.inc(test_state.pruning_config.keep_finalized_for.unwrap());
 		test_state.wait_for_pruning().await;
 
 		// At this point data should be gone from the store.
@@ -726,6 +782,75 @@ fn stored_data_kept_until_finalized() {
 	});
 }
 
+#[test]
+fn finalized_data_is_never_pruned_in_archive_mode() {
+	let store = test_store();
+	let mut test_state = TestState::default();
+	test_state.pruning_config.keep_finalized_for = None;
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let n_validators = 10;
+
+		let pov = PoV { block_data: BlockData(vec![4, 5, 6]) };
+
+		let candidate = TestCandidateBuilder { pov_hash: pov.hash(), ..Default::default() }.build();
+
+		let candidate_hash = candidate.hash();
+
+		let available_data = AvailableData {
+			pov: Arc::new(pov),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+
+		let (tx, rx) = oneshot::channel();
+		let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+			candidate_hash,
+			n_validators,
+			available_data: available_data.clone(),
+			tx,
+		};
+
+		virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+
+		rx.await.unwrap().unwrap();
+
+		let block_number = 10;
+		let new_leaf = import_leaf(
+			&mut virtual_overseer,
+			Hash::repeat_byte(2),
+			block_number,
+			vec![candidate_included(candidate)],
+			(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect(),
+		)
+		.await;
+
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::BlockFinalized(new_leaf, block_number),
+		)
+		.await;
+
+		// Wait well past the default retention period.
+		test_state.clock.inc(KEEP_FINALIZED_FOR * 10);
+		test_state.wait_for_pruning().await;
+
+		// At this point data should _still_ be in the store.
+		assert_eq!(
+			query_available_data(&mut virtual_overseer, candidate_hash).await.unwrap(),
+			available_data,
+		);
+
+		assert!(has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators, true).await);
+
+		// And it can be read straight from the database.
+		assert_eq!(
+			read_available_data(&store, &TEST_CONFIG, &candidate_hash).unwrap(),
+			Some(available_data),
+		);
+		virtual_overseer
+	});
+}
+
 #[test]
 fn we_dont_miss_anything_if_import_notifications_are_missed() {
 	let store = test_store();
@@ -981,7 +1106,7 @@ fn forkfullness_works() {
 		assert!(has_all_chunks(&mut virtual_overseer, candidate_2_hash, n_validators, false).await);
 
 		// Wait for longer than finalized blocks should be kept for
-		test_state.clock.inc(test_state.pruning_config.keep_finalized_for);
+		test_state.clock.inc(test_state.pruning_config.keep_finalized_for.unwrap());
 		test_state.wait_for_pruning().await;
 
 		// Everything should be pruned now.
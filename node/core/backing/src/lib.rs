@@ -31,8 +31,8 @@ use futures::{
 
 use error::{Error, FatalResult};
 use polkadot_node_primitives::{
-	AvailableData, InvalidCandidate, PoV, SignedFullStatement, Statement, ValidationResult,
-	BACKING_EXECUTION_TIMEOUT,
+	AvailableData, InvalidCandidate, PoV, PvfExecKind, SignedFullStatement, Statement,
+	ValidationResult, BACKING_EXECUTION_TIMEOUT,
 };
 use polkadot_node_subsystem::{
 	jaeger,
@@ -651,6 +651,7 @@ async fn request_candidate_validation(
 			candidate_receipt,
 			pov,
 			BACKING_EXECUTION_TIMEOUT,
+			PvfExecKind::Backing,
 			tx,
 		))
 		.await;
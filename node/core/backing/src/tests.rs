@@ -305,6 +305,7 @@ fn backing_second_works() {
 					candidate_receipt,
 					pov,
 					timeout,
+					PvfExecKind::Backing,
 					tx,
 				)
 			) if pov == pov && &candidate_receipt.descriptor == candidate.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT &&  candidate.commitments.hash() == candidate_receipt.commitments_hash => {
@@ -451,6 +452,7 @@ fn backing_works() {
 					c,
 					pov,
 					timeout,
+					PvfExecKind::Backing,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_a.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && c.commitments_hash == candidate_a_commitments_hash=> {
@@ -623,6 +625,7 @@ fn backing_works_while_validation_ongoing() {
 					c,
 					pov,
 					timeout,
+					PvfExecKind::Backing,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_a.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && candidate_a_commitments_hash == c.commitments_hash => {
@@ -775,6 +778,7 @@ fn backing_misbehavior_works() {
 					c,
 					pov,
 					timeout,
+					PvfExecKind::Backing,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_a.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && candidate_a_commitments_hash == c.commitments_hash => {
@@ -919,6 +923,7 @@ fn backing_dont_second_invalid() {
 					c,
 					pov,
 					timeout,
+					PvfExecKind::Backing,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_a.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT => {
@@ -948,6 +953,7 @@ fn backing_dont_second_invalid() {
 					c,
 					pov,
 					timeout,
+					PvfExecKind::Backing,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate_b.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT => {
@@ -1063,6 +1069,7 @@ fn backing_second_after_first_fails_works() {
 					c,
 					pov,
 					timeout,
+					PvfExecKind::Backing,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && c.commitments_hash == candidate.commitments.hash() => {
@@ -1112,6 +1119,7 @@ fn backing_second_after_first_fails_works() {
 					pov,
 					_,
 					_,
+					_,
 				)
 			) => {
 				assert_eq!(&*pov, &pov_to_second);
@@ -1189,6 +1197,7 @@ fn backing_works_after_failed_validation() {
 					c,
 					pov,
 					timeout,
+					PvfExecKind::Backing,
 					tx,
 				)
 			) if pov == pov && c.descriptor() == candidate.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && c.commitments_hash == candidate.commitments.hash() => {
@@ -1542,6 +1551,7 @@ fn retry_works() {
 					c,
 					pov,
 					timeout,
+					PvfExecKind::Backing,
 					_tx,
 				)
 			) if pov == pov && c.descriptor() == candidate.descriptor() && timeout == BACKING_EXECUTION_TIMEOUT && c.commitments_hash == candidate.commitments.hash()
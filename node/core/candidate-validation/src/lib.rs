@@ -24,10 +24,12 @@
 #![warn(missing_docs)]
 
 use polkadot_node_core_pvf::{
-	InvalidCandidate as WasmInvalidCandidate, PrepareError, Pvf, ValidationError, ValidationHost,
+	InvalidCandidate as WasmInvalidCandidate, PrepareError, Priority, Pvf, ValidationError,
+	ValidationHost,
 };
 use polkadot_node_primitives::{
-	BlockData, InvalidCandidate, PoV, ValidationResult, POV_BOMB_LIMIT, VALIDATION_CODE_BOMB_LIMIT,
+	BlockData, InvalidCandidate, PoV, PvfExecKind, ValidationResult, POV_BOMB_LIMIT,
+	VALIDATION_CODE_BOMB_LIMIT,
 };
 use polkadot_node_subsystem::{
 	errors::RuntimeApiError,
@@ -133,6 +135,7 @@ async fn run<Context>(
 					candidate_receipt,
 					pov,
 					timeout,
+					exec_kind,
 					response_sender,
 				) => {
 					let bg = {
@@ -148,6 +151,7 @@ async fn run<Context>(
 								candidate_receipt,
 								pov,
 								timeout,
+								exec_kind,
 								&metrics,
 							)
 							.await;
@@ -165,6 +169,7 @@ async fn run<Context>(
 					candidate_receipt,
 					pov,
 					timeout,
+					exec_kind,
 					response_sender,
 				) => {
 					let bg = {
@@ -180,6 +185,7 @@ async fn run<Context>(
 								candidate_receipt,
 								pov,
 								timeout,
+								exec_kind,
 								&metrics,
 							)
 							.await;
@@ -317,7 +323,8 @@ where
 		Err(prepare_err) => match prepare_err {
 			PrepareError::Prevalidation(_) |
 			PrepareError::Preparation(_) |
-			PrepareError::Panic(_) => PreCheckOutcome::Invalid,
+			PrepareError::Panic(_) |
+			PrepareError::MemoryLimitExceeded => PreCheckOutcome::Invalid,
 			PrepareError::TimedOut | PrepareError::DidNotMakeIt => PreCheckOutcome::Failed,
 		},
 	}
@@ -437,6 +444,7 @@ async fn validate_from_chain_state<Sender>(
 	candidate_receipt: CandidateReceipt,
 	pov: Arc<PoV>,
 	timeout: Duration,
+	exec_kind: PvfExecKind,
 	metrics: &Metrics,
 ) -> Result<ValidationResult, ValidationFailed>
 where
@@ -456,6 +464,7 @@ where
 		candidate_receipt.clone(),
 		pov,
 		timeout,
+		exec_kind,
 		metrics,
 	)
 	.await;
@@ -496,6 +505,7 @@ async fn validate_candidate_exhaustive(
 	candidate_receipt: CandidateReceipt,
 	pov: Arc<PoV>,
 	timeout: Duration,
+	exec_kind: PvfExecKind,
 	metrics: &Metrics,
 ) -> Result<ValidationResult, ValidationFailed> {
 	let _timer = metrics.time_validate_candidate_exhaustive();
@@ -549,7 +559,7 @@ async fn validate_candidate_exhaustive(
 	};
 
 	let result = validation_backend
-		.validate_candidate(raw_validation_code.to_vec(), timeout, params)
+		.validate_candidate(raw_validation_code.to_vec(), timeout, exec_kind, params)
 		.await;
 
 	if let Err(ref e) = result {
@@ -573,6 +583,10 @@ async fn validate_candidate_exhaustive(
 			))),
 		Err(ValidationError::InvalidCandidate(WasmInvalidCandidate::PrepareError(e))) =>
 			Ok(ValidationResult::Invalid(InvalidCandidate::ExecutionError(e))),
+		Err(ValidationError::InvalidCandidate(WasmInvalidCandidate::MemoryLimitExceeded)) =>
+			Ok(ValidationResult::Invalid(InvalidCandidate::ExecutionError(
+				"memory limit exceeded".to_string(),
+			))),
 
 		Ok(res) =>
 			if res.head_data.hash() != candidate_receipt.descriptor.para_head {
@@ -602,18 +616,29 @@ trait ValidationBackend {
 		&mut self,
 		raw_validation_code: Vec<u8>,
 		timeout: Duration,
+		exec_kind: PvfExecKind,
 		params: ValidationParams,
 	) -> Result<WasmValidationResult, ValidationError>;
 
 	async fn precheck_pvf(&mut self, pvf: Pvf) -> Result<(), PrepareError>;
 }
 
+/// The priority with which the validation host executes the PVF for the given kind of work.
+fn exec_priority(exec_kind: PvfExecKind) -> Priority {
+	match exec_kind {
+		PvfExecKind::Backing => Priority::Critical,
+		PvfExecKind::Approval => Priority::Normal,
+		PvfExecKind::Dispute => Priority::Background,
+	}
+}
+
 #[async_trait]
 impl ValidationBackend for ValidationHost {
 	async fn validate_candidate(
 		&mut self,
 		raw_validation_code: Vec<u8>,
 		timeout: Duration,
+		exec_kind: PvfExecKind,
 		params: ValidationParams,
 	) -> Result<WasmValidationResult, ValidationError> {
 		let (tx, rx) = oneshot::channel();
@@ -622,7 +647,7 @@ impl ValidationBackend for ValidationHost {
 				Pvf::from_code(raw_validation_code),
 				timeout,
 				params.encode(),
-				polkadot_node_core_pvf::Priority::Normal,
+				exec_priority(exec_kind),
 				tx,
 			)
 			.await
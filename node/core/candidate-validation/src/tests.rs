@@ -360,6 +360,7 @@ impl ValidationBackend for MockValidateCandidateBackend {
 		&mut self,
 		_raw_validation_code: Vec<u8>,
 		_timeout: Duration,
+		_exec_kind: PvfExecKind,
 		_params: ValidationParams,
 	) -> Result<WasmValidationResult, ValidationError> {
 		self.result.clone()
@@ -424,6 +425,7 @@ fn candidate_validation_ok_is_ok() {
 		candidate_receipt,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		PvfExecKind::Backing,
 		&Default::default(),
 	))
 	.unwrap();
@@ -475,6 +477,7 @@ fn candidate_validation_bad_return_is_invalid() {
 		candidate_receipt,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		PvfExecKind::Backing,
 		&Default::default(),
 	))
 	.unwrap();
@@ -519,6 +522,7 @@ fn candidate_validation_timeout_is_internal_error() {
 		candidate_receipt,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		PvfExecKind::Backing,
 		&Default::default(),
 	));
 
@@ -563,6 +567,7 @@ fn candidate_validation_commitment_hash_mismatch_is_invalid() {
 		candidate_receipt,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		PvfExecKind::Backing,
 		&Default::default(),
 	))
 	.unwrap();
@@ -608,6 +613,7 @@ fn candidate_validation_code_mismatch_is_invalid() {
 		candidate_receipt,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		PvfExecKind::Backing,
 		&Default::default(),
 	))
 	.unwrap();
@@ -664,6 +670,7 @@ fn compressed_code_works() {
 		candidate_receipt,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		PvfExecKind::Backing,
 		&Default::default(),
 	));
 
@@ -711,6 +718,7 @@ fn code_decompression_failure_is_invalid() {
 		candidate_receipt,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		PvfExecKind::Backing,
 		&Default::default(),
 	));
 
@@ -759,6 +767,7 @@ fn pov_decompression_failure_is_invalid() {
 		candidate_receipt,
 		Arc::new(pov),
 		Duration::from_secs(0),
+		PvfExecKind::Backing,
 		&Default::default(),
 	));
 
@@ -781,6 +790,7 @@ impl ValidationBackend for MockPreCheckBackend {
 		&mut self,
 		_raw_validation_code: Vec<u8>,
 		_timeout: Duration,
+		_exec_kind: PvfExecKind,
 		_params: ValidationParams,
 	) -> Result<WasmValidationResult, ValidationError> {
 		unreachable!()
@@ -923,6 +933,7 @@ fn precheck_properly_classifies_outcomes() {
 	inner(Err(PrepareError::Prevalidation("foo".to_owned())), PreCheckOutcome::Invalid);
 	inner(Err(PrepareError::Preparation("bar".to_owned())), PreCheckOutcome::Invalid);
 	inner(Err(PrepareError::Panic("baz".to_owned())), PreCheckOutcome::Invalid);
+	inner(Err(PrepareError::MemoryLimitExceeded), PreCheckOutcome::Invalid);
 
 	inner(Err(PrepareError::TimedOut), PreCheckOutcome::Failed);
 	inner(Err(PrepareError::DidNotMakeIt), PreCheckOutcome::Failed);
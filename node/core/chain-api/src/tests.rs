@@ -5,7 +5,13 @@ use parity_scale_codec::Encode;
 use std::collections::BTreeMap;
 
 use polkadot_node_primitives::BlockWeight;
-use polkadot_node_subsystem_test_helpers::{make_subsystem_context, TestSubsystemContextHandle};
+use polkadot_node_subsystem::overseer::{
+	recorder::{Recording, StreamRecorder},
+	MessageRecorder,
+};
+use polkadot_node_subsystem_test_helpers::{
+	make_subsystem_context, replay::replay_recording, TestSubsystemContextHandle,
+};
 use polkadot_primitives::v2::{BlockId, BlockNumber, Hash, Header};
 use sp_blockchain::Info as BlockInfo;
 use sp_core::testing::TaskExecutor;
@@ -356,3 +362,59 @@ fn request_ancestors() {
 		.boxed()
 	})
 }
+
+#[test]
+fn recorded_requests_replay_with_the_recorded_responses() {
+	let mut recorder = StreamRecorder::new(Vec::new(), "chain-api").unwrap();
+	let written = recorder.written();
+
+	test_harness(move |_client, mut sender| {
+		async move {
+			let (tx, rx) = oneshot::channel();
+			let message =
+				FromOrchestra::Communication { msg: ChainApiMessage::BlockNumber(TWO, tx) };
+			sender.send(recorder.record(message)).await;
+			assert_eq!(rx.await.unwrap().unwrap(), Some(2));
+
+			// Errors are recorded as well.
+			let (tx, rx) = oneshot::channel();
+			let message = FromOrchestra::Communication {
+				msg: ChainApiMessage::BlockHeader(Hash::zero(), tx),
+			};
+			sender.send(recorder.record(message)).await;
+			assert!(rx.await.unwrap().is_err());
+
+			let (tx, rx) = oneshot::channel();
+			let message = FromOrchestra::Communication {
+				msg: ChainApiMessage::Ancestors { hash: THREE, k: 4, response_channel: tx },
+			};
+			sender.send(recorder.record(message)).await;
+			assert_eq!(rx.await.unwrap().unwrap(), vec![TWO, ONE, GENESIS]);
+
+			sender
+				.send(recorder.record(FromOrchestra::Signal(OverseerSignal::Conclude)))
+				.await;
+		}
+		.boxed()
+	});
+
+	let written = futures::executor::block_on(written).unwrap();
+	let recording = || Recording::<ChainApiMessage>::decode(&written).unwrap();
+	let recorded = recording();
+	assert_eq!(recorded.messages.len(), 4);
+	assert!(recorded.messages[..3].iter().all(|message| message.response.is_some()));
+
+	let subsystem = ChainApiSubsystem::new(Arc::new(TestClient::default()), Metrics(None));
+	let outcome = replay_recording(subsystem, recorded, |_| {});
+	assert!(outcome.result.is_ok());
+	assert_eq!(outcome.responses.len(), 3);
+	assert_eq!(outcome.diverging_responses().count(), 0);
+
+	// A client not knowing the block anymore responds differently.
+	let mut client = TestClient::default();
+	assert!(client.blocks.remove(&TWO).is_some());
+	let subsystem = ChainApiSubsystem::new(Arc::new(client), Metrics(None));
+	let outcome = replay_recording(subsystem, recording(), |_| {});
+	let diverging: Vec<_> = outcome.diverging_responses().map(|response| response.index).collect();
+	assert_eq!(diverging, vec![0]);
+}
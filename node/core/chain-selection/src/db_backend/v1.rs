@@ -58,8 +58,11 @@ enum Approval {
 	Approved,
 	#[codec(index = 1)]
 	Unapproved,
+	// Stagnant, without the time it became stagnant at.
 	#[codec(index = 2)]
 	Stagnant,
+	#[codec(index = 3)]
+	StagnantSince(Timestamp),
 }
 
 impl From<crate::Approval> for Approval {
@@ -67,7 +70,8 @@ impl From<crate::Approval> for Approval {
 		match x {
 			crate::Approval::Approved => Approval::Approved,
 			crate::Approval::Unapproved => Approval::Unapproved,
-			crate::Approval::Stagnant => Approval::Stagnant,
+			crate::Approval::Stagnant(None) => Approval::Stagnant,
+			crate::Approval::Stagnant(Some(since)) => Approval::StagnantSince(since),
 		}
 	}
 }
@@ -77,22 +81,41 @@ impl From<Approval> for crate::Approval {
 		match x {
 			Approval::Approved => crate::Approval::Approved,
 			Approval::Unapproved => crate::Approval::Unapproved,
-			Approval::Stagnant => crate::Approval::Stagnant,
+			Approval::Stagnant => crate::Approval::Stagnant(None),
+			Approval::StagnantSince(since) => crate::Approval::Stagnant(Some(since)),
 		}
 	}
 }
 
+// Encodes the same as a `bool` for entries written before the reverting block was recorded.
+#[derive(Debug, Encode, Decode, Clone, PartialEq)]
+enum Reversion {
+	#[codec(index = 0)]
+	NotReverted,
+	// Reverted, without the reverting block.
+	#[codec(index = 1)]
+	Reverted,
+	#[codec(index = 2)]
+	RevertedBy(Hash),
+}
+
 #[derive(Debug, Encode, Decode, Clone, PartialEq)]
 struct ViabilityCriteria {
-	explicitly_reverted: bool,
+	explicitly_reverted: Reversion,
 	approval: Approval,
 	earliest_unviable_ancestor: Option<Hash>,
 }
 
 impl From<crate::ViabilityCriteria> for ViabilityCriteria {
 	fn from(x: crate::ViabilityCriteria) -> Self {
+		let explicitly_reverted = match (x.explicitly_reverted, x.reverted_by) {
+			(false, _) => Reversion::NotReverted,
+			(true, None) => Reversion::Reverted,
+			(true, Some(by)) => Reversion::RevertedBy(by),
+		};
+
 		ViabilityCriteria {
-			explicitly_reverted: x.explicitly_reverted,
+			explicitly_reverted,
 			approval: x.approval.into(),
 			earliest_unviable_ancestor: x.earliest_unviable_ancestor,
 		}
@@ -101,8 +124,15 @@ impl From<crate::ViabilityCriteria> for ViabilityCriteria {
 
 impl From<ViabilityCriteria> for crate::ViabilityCriteria {
 	fn from(x: ViabilityCriteria) -> crate::ViabilityCriteria {
+		let (explicitly_reverted, reverted_by) = match x.explicitly_reverted {
+			Reversion::NotReverted => (false, None),
+			Reversion::Reverted => (true, None),
+			Reversion::RevertedBy(by) => (true, Some(by)),
+		};
+
 		crate::ViabilityCriteria {
-			explicitly_reverted: x.explicitly_reverted,
+			explicitly_reverted,
+			reverted_by,
 			approval: x.approval.into(),
 			earliest_unviable_ancestor: x.earliest_unviable_ancestor,
 		}
@@ -328,6 +358,34 @@ impl Backend for DbBackend {
 	}
 }
 
+/// The block entries to overwrite such that they decode with versions which neither record the
+/// reverting block nor the time a block became stagnant at. These details are dropped.
+pub(crate) fn viability_details_downgrade(
+	db: &dyn Database,
+	col_data: u32,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Error> {
+	let mut overwrites = Vec::new();
+	for (key, value) in db.iter_with_prefix(col_data, &BLOCK_ENTRY_PREFIX[..]) {
+		let mut entry = BlockEntry::decode(&mut &value[..])?;
+		let mut changed = false;
+
+		if let Reversion::RevertedBy(_) = entry.viability.explicitly_reverted {
+			entry.viability.explicitly_reverted = Reversion::Reverted;
+			changed = true;
+		}
+		if let Approval::StagnantSince(_) = entry.viability.approval {
+			entry.viability.approval = Approval::Stagnant;
+			changed = true;
+		}
+
+		if changed {
+			overwrites.push((key.to_vec(), entry.encode()));
+		}
+	}
+
+	Ok(overwrites)
+}
+
 fn load_decode<D: Decode>(
 	db: &dyn Database,
 	col_data: u32,
@@ -447,7 +505,7 @@ mod tests {
 			children: vec![],
 			viability: ViabilityCriteria {
 				earliest_unviable_ancestor: None,
-				explicitly_reverted: false,
+				explicitly_reverted: Reversion::NotReverted,
 				approval: Approval::Unapproved,
 			},
 			weight: 100,
@@ -463,6 +521,100 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn block_entry_without_viability_details_decodes() {
+		// The layout of block entries written before the stagnation time and the reverting
+		// block were recorded.
+		#[derive(Encode)]
+		struct LegacyViabilityCriteria {
+			explicitly_reverted: bool,
+			approval: u8,
+			earliest_unviable_ancestor: Option<Hash>,
+		}
+
+		#[derive(Encode)]
+		struct LegacyBlockEntry {
+			block_hash: Hash,
+			block_number: BlockNumber,
+			parent_hash: Hash,
+			children: Vec<Hash>,
+			viability: LegacyViabilityCriteria,
+			weight: BlockWeight,
+		}
+
+		let legacy = LegacyBlockEntry {
+			block_hash: Hash::repeat_byte(1),
+			block_number: 1,
+			parent_hash: Hash::repeat_byte(0),
+			children: vec![Hash::repeat_byte(2)],
+			viability: LegacyViabilityCriteria {
+				explicitly_reverted: true,
+				approval: 2,
+				earliest_unviable_ancestor: None,
+			},
+			weight: 100,
+		};
+
+		let decoded = BlockEntry::decode(&mut &legacy.encode()[..]).unwrap();
+		assert_eq!(decoded.viability.explicitly_reverted, Reversion::Reverted);
+		assert_eq!(decoded.viability.approval, Approval::Stagnant);
+		assert_eq!(decoded.children, vec![Hash::repeat_byte(2)]);
+		assert_eq!(decoded.weight, 100);
+
+		let decoded: crate::BlockEntry = decoded.into();
+		assert!(decoded.viability.explicitly_reverted);
+		assert_eq!(decoded.viability.reverted_by, None);
+		assert!(matches!(decoded.viability.approval, crate::Approval::Stagnant(None)));
+	}
+
+	#[test]
+	fn viability_details_downgrade_drops_details() {
+		let db = test_db();
+		let config = Config { col_data: 0 };
+
+		let mut backend = DbBackend::new(db.clone(), config);
+
+		let entry = |byte, explicitly_reverted, approval| BlockEntry {
+			block_hash: Hash::repeat_byte(byte),
+			block_number: 1,
+			parent_hash: Hash::repeat_byte(0),
+			children: vec![],
+			viability: ViabilityCriteria {
+				earliest_unviable_ancestor: None,
+				explicitly_reverted,
+				approval,
+			},
+			weight: 100,
+		};
+
+		let unchanged = entry(1, Reversion::Reverted, Approval::Stagnant);
+		let reverted_by = entry(2, Reversion::RevertedBy(Hash::repeat_byte(9)), Approval::Approved);
+		let stagnant_since = entry(3, Reversion::NotReverted, Approval::StagnantSince(5));
+
+		backend
+			.write(vec![
+				BackendWriteOp::WriteBlockEntry(unchanged.clone().into()),
+				BackendWriteOp::WriteBlockEntry(reverted_by.clone().into()),
+				BackendWriteOp::WriteBlockEntry(stagnant_since.clone().into()),
+			])
+			.unwrap();
+
+		let overwrites = viability_details_downgrade(&*db, 0).unwrap();
+		assert_eq!(
+			overwrites,
+			vec![
+				(
+					block_entry_key(&reverted_by.block_hash).to_vec(),
+					entry(2, Reversion::Reverted, Approval::Approved).encode(),
+				),
+				(
+					block_entry_key(&stagnant_since.block_hash).to_vec(),
+					entry(3, Reversion::NotReverted, Approval::Stagnant).encode(),
+				),
+			],
+		);
+	}
+
 	#[test]
 	fn delete_block_entry() {
 		let db = test_db();
@@ -477,7 +629,7 @@ mod tests {
 			children: vec![],
 			viability: ViabilityCriteria {
 				earliest_unviable_ancestor: None,
-				explicitly_reverted: false,
+				explicitly_reverted: Reversion::NotReverted,
 				approval: Approval::Unapproved,
 			},
 			weight: 100,
@@ -19,7 +19,10 @@
 use polkadot_node_primitives::BlockWeight;
 use polkadot_node_subsystem::{
 	errors::ChainApiError,
-	messages::{ChainApiMessage, ChainSelectionMessage},
+	messages::{
+		BlockApprovalState, BlockViability, ChainApiMessage, ChainSelectionMessage,
+		ViabilityReport, ViableLeaf,
+	},
 	overseer::{self, SubsystemSender},
 	FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
 };
@@ -54,6 +57,8 @@ const STAGNANT_TIMEOUT: Timestamp = 120;
 const STAGNANT_PRUNE_DELAY: Timestamp = 25 * 60 * 60;
 // Maximum number of stagnant entries cleaned during one `STAGNANT_TIMEOUT` iteration
 const MAX_STAGNANT_ENTRIES: usize = 1000;
+// Maximum number of ancestors inspected to explain the viability of a block
+const MAX_VIABILITY_ANCESTRY: usize = 512;
 
 #[derive(Debug, Clone)]
 enum Approval {
@@ -61,13 +66,13 @@ enum Approval {
 	Approved,
 	// Unapproved but not stagnant
 	Unapproved,
-	// Unapproved and stagnant.
-	Stagnant,
+	// Unapproved and stagnant, since the given timestamp if known.
+	Stagnant(Option<Timestamp>),
 }
 
 impl Approval {
 	fn is_stagnant(&self) -> bool {
-		matches!(*self, Approval::Stagnant)
+		matches!(*self, Approval::Stagnant(_))
 	}
 }
 
@@ -75,6 +80,8 @@ impl Approval {
 struct ViabilityCriteria {
 	// Whether this block has been explicitly reverted by one of its descendants.
 	explicitly_reverted: bool,
+	// The descendant which first reverted this block, if known.
+	reverted_by: Option<Hash>,
 	// The approval state of this block specifically.
 	approval: Approval,
 	// The earliest unviable ancestor - the hash of the earliest unfinalized
@@ -346,6 +353,23 @@ impl ChainSelectionSubsystem {
 
 		backend.write(ops)
 	}
+
+	/// Load the leaves of the block-tree, ordered by descending weight.
+	pub fn leaves(&self) -> Result<Vec<ViableLeaf>, Error> {
+		let config = db_backend::v1::Config { col_data: self.config.col_data };
+		let backend = db_backend::v1::DbBackend::new(self.db.clone(), config);
+
+		load_viable_leaves(&backend)
+	}
+}
+
+/// The `(key, value)` pairs to overwrite in the data column when downgrading a database to a
+/// version which neither recorded the reverting block nor the time blocks became stagnant at.
+pub fn viability_details_downgrade(
+	db: &dyn Database,
+	col_data: u32,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Error> {
+	db_backend::v1::viability_details_downgrade(db, col_data)
 }
 
 #[overseer::subsystem(ChainSelection, error = SubsystemError, prefix = self::overseer)]
@@ -465,6 +489,10 @@ where
 
 							let _ = tx.send(best_containing);
 						}
+						ChainSelectionMessage::Viability(hash, tx) => {
+							let report = load_viability(&*backend, hash)?;
+							let _ = tx.send(report);
+						}
 					}
 				}
 			}
@@ -719,3 +747,79 @@ async fn load_leaves(
 		Ok(leaves)
 	}
 }
+
+// Load the leaves from the backend, ordered by descending weight.
+fn load_viable_leaves(backend: &impl Backend) -> Result<Vec<ViableLeaf>, Error> {
+	let leaves = backend
+		.load_leaves()?
+		.inner
+		.into_iter()
+		.map(|leaf| ViableLeaf {
+			block_hash: leaf.block_hash,
+			block_number: leaf.block_number,
+			weight: leaf.weight,
+		})
+		.collect();
+
+	Ok(leaves)
+}
+
+// Explain the viability of the given block and report the viable leaves.
+fn load_viability(backend: &impl Backend, block_hash: Hash) -> Result<ViabilityReport, Error> {
+	let leaves = load_viable_leaves(backend)?;
+
+	let block = match backend.load_block_entry(&block_hash)? {
+		None => None,
+		Some(entry) => {
+			let earliest_unviable_ancestor = match entry.viability.earliest_unviable_ancestor {
+				None => None,
+				Some(ancestor_hash) => match backend.load_block_entry(&ancestor_hash)? {
+					None => None,
+					Some(ancestor) => Some(Box::new(block_viability(backend, ancestor, None)?)),
+				},
+			};
+
+			Some(block_viability(backend, entry, earliest_unviable_ancestor)?)
+		},
+	};
+
+	Ok(ViabilityReport { block, leaves })
+}
+
+fn block_viability(
+	backend: &impl Backend,
+	entry: BlockEntry,
+	earliest_unviable_ancestor: Option<Box<BlockViability>>,
+) -> Result<BlockViability, Error> {
+	// Walk the ancestry back to the finalized block, which is not stored, but not further than
+	// `MAX_VIABILITY_ANCESTRY` blocks, as finality might be lagging far behind.
+	let mut earliest_unapproved_ancestor = None;
+	let mut ancestor_hash = entry.parent_hash;
+	for _ in 0..MAX_VIABILITY_ANCESTRY {
+		let ancestor = match backend.load_block_entry(&ancestor_hash)? {
+			None => break,
+			Some(ancestor) => ancestor,
+		};
+		if !matches!(ancestor.viability.approval, Approval::Approved) {
+			earliest_unapproved_ancestor = Some(ancestor.block_hash);
+		}
+		ancestor_hash = ancestor.parent_hash;
+	}
+
+	let approval = match entry.viability.approval {
+		Approval::Approved => BlockApprovalState::Approved,
+		Approval::Unapproved => BlockApprovalState::Unapproved,
+		Approval::Stagnant(since) => BlockApprovalState::Stagnant { since },
+	};
+
+	Ok(BlockViability {
+		block_hash: entry.block_hash,
+		block_number: entry.block_number,
+		viable: entry.viability.is_viable(),
+		approval,
+		explicitly_reverted: entry.viability.explicitly_reverted,
+		reverted_by: entry.viability.reverted_by,
+		earliest_unviable_ancestor,
+		earliest_unapproved_ancestor,
+	})
+}
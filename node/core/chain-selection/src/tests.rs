@@ -624,6 +624,15 @@ async fn best_leaf_containing(
 	rx.await.unwrap()
 }
 
+async fn viability(virtual_overseer: &mut VirtualOverseer, hash: Hash) -> ViabilityReport {
+	let (tx, rx) = oneshot::channel();
+	virtual_overseer
+		.send(FromOrchestra::Communication { msg: ChainSelectionMessage::Viability(hash, tx) })
+		.await;
+
+	rx.await.unwrap()
+}
+
 async fn approve_block(
 	virtual_overseer: &mut VirtualOverseer,
 	backend: &TestBackend,
@@ -1810,7 +1819,7 @@ fn detects_stagnant() {
 
 		assert_matches!(
 			backend.load_block_entry(&a1_hash).unwrap().unwrap().viability.approval,
-			Approval::Stagnant
+			Approval::Stagnant(Some(_))
 		);
 
 		assert_leaves(&backend, vec![]);
@@ -1860,7 +1869,7 @@ fn finalize_stagnant_unlocks_subtree() {
 
 		assert_matches!(
 			backend.load_block_entry(&a1_hash).unwrap().unwrap().viability.approval,
-			Approval::Stagnant
+			Approval::Stagnant(Some(_))
 		);
 
 		assert_leaves(&backend, vec![]);
@@ -2014,3 +2023,103 @@ fn stagnant_makes_childless_parent_leaf() {
 		virtual_overseer
 	})
 }
+
+#[test]
+fn viability_explains_reversion() {
+	test_harness(|backend, _, mut virtual_overseer| async move {
+		let finalized_number = 0;
+		let finalized_hash = Hash::repeat_byte(0);
+
+		// F <- A1 <- A2 <- A3.
+		//
+		// A3 reverts A2
+
+		let (a3_hash, chain_a) =
+			construct_chain_on_base(vec![1, 2, 3], finalized_number, finalized_hash, |h| {
+				if h.number == 3 {
+					add_reversions(h, Some(2))
+				}
+			});
+
+		let (_, a1_hash, a1_weight) = extract_info_from_chain(0, &chain_a);
+		let (_, a2_hash, _) = extract_info_from_chain(1, &chain_a);
+
+		import_blocks_into(
+			&mut virtual_overseer,
+			&backend,
+			Some((finalized_number, finalized_hash)),
+			chain_a.clone(),
+		)
+		.await;
+
+		let report = viability(&mut virtual_overseer, a3_hash).await;
+		assert_eq!(
+			report.leaves,
+			vec![ViableLeaf { block_hash: a1_hash, block_number: 1, weight: a1_weight }],
+		);
+
+		let block = report.block.unwrap();
+		assert!(!block.viable);
+		assert!(!block.explicitly_reverted);
+		assert_eq!(block.approval, BlockApprovalState::Unapproved);
+		assert_eq!(block.earliest_unapproved_ancestor, Some(a1_hash));
+
+		let ancestor = block.earliest_unviable_ancestor.unwrap();
+		assert_eq!(ancestor.block_hash, a2_hash);
+		assert!(ancestor.explicitly_reverted);
+		assert_eq!(ancestor.reverted_by, Some(a3_hash));
+		assert_eq!(ancestor.earliest_unviable_ancestor, None);
+
+		approve_block(&mut virtual_overseer, &backend, a1_hash).await;
+		let report = viability(&mut virtual_overseer, a3_hash).await;
+		assert_eq!(report.block.unwrap().earliest_unapproved_ancestor, Some(a2_hash));
+
+		// Unknown blocks are not explained.
+		let report = viability(&mut virtual_overseer, finalized_hash).await;
+		assert_eq!(report.block, None);
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn viability_explains_stagnation() {
+	test_harness(|backend, clock, mut virtual_overseer| async move {
+		let finalized_number = 0;
+		let finalized_hash = Hash::repeat_byte(0);
+
+		// F <- A1
+
+		let (a1_hash, chain_a) =
+			construct_chain_on_base(vec![1], finalized_number, finalized_hash, |h| {
+				salt_header(h, b"a");
+			});
+
+		import_chains_into_empty(
+			&mut virtual_overseer,
+			&backend,
+			finalized_number,
+			finalized_hash,
+			vec![chain_a.clone()],
+		)
+		.await;
+
+		{
+			let (_, write_rx) = backend.await_next_write();
+			clock.inc_by(STAGNANT_TIMEOUT);
+
+			write_rx.await.unwrap();
+		}
+
+		let report = viability(&mut virtual_overseer, a1_hash).await;
+		assert!(report.leaves.is_empty());
+
+		let block = report.block.unwrap();
+		assert!(!block.viable);
+		assert_eq!(block.approval, BlockApprovalState::Stagnant { since: Some(STAGNANT_TIMEOUT) });
+		assert_eq!(block.earliest_unviable_ancestor, None);
+		assert_eq!(block.earliest_unapproved_ancestor, None);
+
+		virtual_overseer
+	})
+}
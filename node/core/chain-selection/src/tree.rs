@@ -316,6 +316,7 @@ fn add_block(
 		viability: ViabilityCriteria {
 			earliest_unviable_ancestor: inherited_viability,
 			explicitly_reverted: false,
+			reverted_by: None,
 			approval: Approval::Unapproved,
 		},
 		weight,
@@ -388,6 +389,7 @@ fn apply_reversions(
 			};
 
 		ancestor_entry.viability.explicitly_reverted = true;
+		ancestor_entry.viability.reverted_by.get_or_insert(block_hash);
 		propagate_viability_update(backend, ancestor_entry)?;
 	}
 
@@ -563,7 +565,7 @@ pub(super) fn detect_stagnant<'a, B: 'a + Backend>(
 			if let Some(mut entry) = backend.load_block_entry(&block_hash)? {
 				let was_viable = entry.viability.is_viable();
 				if let Approval::Unapproved = entry.viability.approval {
-					entry.viability.approval = Approval::Stagnant;
+					entry.viability.approval = Approval::Stagnant(Some(timestamp));
 				}
 				let is_viable = entry.viability.is_viable();
 				gum::trace!(
@@ -675,6 +677,7 @@ pub(super) fn revert_to<'a, B: Backend + 'a>(
 			let block_number = first_number.saturating_sub(1);
 			let viability = ViabilityCriteria {
 				explicitly_reverted: false,
+				reverted_by: None,
 				approval: Approval::Approved,
 				earliest_unviable_ancestor: None,
 			};
@@ -26,7 +26,7 @@ use polkadot_primitives::v2::{CandidateHash, SessionIndex};
 
 use std::collections::HashMap;
 
-use super::db::v1::{CandidateVotes, RecentDisputes};
+use super::db::v1::{CandidateVotes, ParticipationQueue, QueuedParticipation, RecentDisputes};
 use crate::error::FatalResult;
 
 #[derive(Debug)]
@@ -35,6 +35,8 @@ pub enum BackendWriteOp {
 	WriteRecentDisputes(RecentDisputes),
 	WriteCandidateVotes(SessionIndex, CandidateHash, CandidateVotes),
 	DeleteCandidateVotes(SessionIndex, CandidateHash),
+	WriteParticipation(QueuedParticipation),
+	DeleteParticipation(ParticipationQueue, CandidateHash),
 }
 
 /// An abstraction over backend storage for the logic of this subsystem.
@@ -52,6 +54,10 @@ pub trait Backend {
 		candidate_hash: &CandidateHash,
 	) -> SubsystemResult<Option<CandidateVotes>>;
 
+	/// Load all persisted participations, running ones first, followed by the priority and then
+	/// the best effort queue.
+	fn load_participations(&self) -> SubsystemResult<Vec<QueuedParticipation>>;
+
 	/// Atomically writes the list of operations, with later operations taking precedence over
 	/// prior.
 	fn write<I>(&mut self, ops: I) -> FatalResult<()>
@@ -73,6 +79,8 @@ pub struct OverlayedBackend<'a, B: 'a> {
 	recent_disputes: Option<RecentDisputes>,
 	// `None` means deleted, missing means query inner.
 	candidate_votes: HashMap<(SessionIndex, CandidateHash), Option<CandidateVotes>>,
+	// `None` means deleted, missing means query inner.
+	participations: HashMap<(ParticipationQueue, CandidateHash), Option<QueuedParticipation>>,
 }
 
 impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
@@ -82,6 +90,7 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 			earliest_session: None,
 			recent_disputes: None,
 			candidate_votes: HashMap::new(),
+			participations: HashMap::new(),
 		}
 	}
 
@@ -89,7 +98,8 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 	pub fn is_empty(&self) -> bool {
 		self.earliest_session.is_none() &&
 			self.recent_disputes.is_none() &&
-			self.candidate_votes.is_empty()
+			self.candidate_votes.is_empty() &&
+			self.participations.is_empty()
 	}
 
 	/// Load the earliest session, if any.
@@ -123,6 +133,25 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 		self.inner.load_candidate_votes(session, candidate_hash)
 	}
 
+	/// Load all persisted participations, running ones first, followed by the priority and then
+	/// the best effort queue.
+	pub fn load_participations(&self) -> SubsystemResult<Vec<QueuedParticipation>> {
+		let mut participations: Vec<_> = self
+			.inner
+			.load_participations()?
+			.into_iter()
+			.filter(|participation| {
+				let key = (participation.kind.queue(), participation.candidate_receipt.hash());
+				!self.participations.contains_key(&key)
+			})
+			.collect();
+		participations.extend(self.participations.values().flatten().cloned());
+		// Stable, so the order within each queue is kept:
+		participations.sort_by_key(|participation| participation.kind.queue());
+
+		Ok(participations)
+	}
+
 	/// Prepare a write to the "earliest session" field of the DB.
 	///
 	/// Later calls to this function will override earlier ones.
@@ -149,6 +178,23 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 		self.candidate_votes.insert((session, candidate_hash), Some(votes));
 	}
 
+	/// Prepare a write of a participation to the queue of its kind.
+	///
+	/// Later calls to this function for the same queue and candidate will override earlier ones.
+	pub fn write_participation(&mut self, participation: QueuedParticipation) {
+		let key = (participation.kind.queue(), participation.candidate_receipt.hash());
+		self.participations.insert(key, Some(participation));
+	}
+
+	/// Prepare a deletion of the participation for a candidate from a queue.
+	pub fn delete_participation(
+		&mut self,
+		queue: ParticipationQueue,
+		candidate_hash: CandidateHash,
+	) {
+		self.participations.insert((queue, candidate_hash), None);
+	}
+
 	/// Transform this backend into a set of write-ops to be written to the inner backend.
 	pub fn into_write_ops(self) -> impl Iterator<Item = BackendWriteOp> {
 		let earliest_session_ops = self
@@ -167,6 +213,17 @@ impl<'a, B: 'a + Backend> OverlayedBackend<'a, B> {
 					None => BackendWriteOp::DeleteCandidateVotes(session, candidate),
 				});
 
-		earliest_session_ops.chain(recent_dispute_ops).chain(candidate_vote_ops)
+		let participation_ops =
+			self.participations.into_iter().map(|((queue, candidate), participation)| {
+				match participation {
+					Some(participation) => BackendWriteOp::WriteParticipation(participation),
+					None => BackendWriteOp::DeleteParticipation(queue, candidate),
+				}
+			});
+
+		earliest_session_ops
+			.chain(recent_dispute_ops)
+			.chain(candidate_vote_ops)
+			.chain(participation_ops)
 	}
 }
@@ -19,7 +19,7 @@
 use polkadot_node_subsystem::{SubsystemError, SubsystemResult};
 use polkadot_node_subsystem_util::database::{DBTransaction, Database};
 use polkadot_primitives::v2::{
-	CandidateHash, CandidateReceipt, Hash, InvalidDisputeStatementKind, SessionIndex,
+	BlockNumber, CandidateHash, CandidateReceipt, Hash, InvalidDisputeStatementKind, SessionIndex,
 	ValidDisputeStatementKind, ValidatorIndex, ValidatorSignature,
 };
 
@@ -40,6 +40,10 @@ const EARLIEST_SESSION_KEY: &[u8; 16] = b"earliest-session";
 const CANDIDATE_VOTES_SUBKEY: &[u8; 15] = b"candidate-votes";
 /// Until what session have votes been cleaned up already?
 const CLEANED_VOTES_WATERMARK_KEY: &[u8; 23] = b"cleaned-votes-watermark";
+/// Participations are stored under one of these prefixes, followed by the candidate hash.
+const RUNNING_PARTICIPATION_PREFIX: &[u8; 21] = b"running-participation";
+const PRIORITY_PARTICIPATION_PREFIX: &[u8; 22] = b"priority-participation";
+const BEST_EFFORT_PARTICIPATION_PREFIX: &[u8; 25] = b"best-effort-participation";
 
 /// Restrict number of cleanup operations.
 ///
@@ -127,6 +131,11 @@ impl Backend for DbBackend {
 		load_candidate_votes(&*self.inner, &self.config, session, candidate_hash)
 	}
 
+	/// Load all persisted participations.
+	fn load_participations(&self) -> SubsystemResult<Vec<QueuedParticipation>> {
+		load_participations(&*self.inner, &self.config)
+	}
+
 	/// Atomically writes the list of operations, with later operations taking precedence over
 	/// prior.
 	///
@@ -164,6 +173,19 @@ impl Backend for DbBackend {
 				BackendWriteOp::DeleteCandidateVotes(session, candidate_hash) => {
 					tx.delete(self.config.col_data, &candidate_votes_key(session, &candidate_hash));
 				},
+				BackendWriteOp::WriteParticipation(participation) => {
+					tx.put_vec(
+						self.config.col_data,
+						&participation_key(
+							participation.kind.queue(),
+							&participation.candidate_receipt.hash(),
+						),
+						participation.encode(),
+					);
+				},
+				BackendWriteOp::DeleteParticipation(queue, candidate_hash) => {
+					tx.delete(self.config.col_data, &participation_key(queue, &candidate_hash));
+				},
 			}
 		}
 
@@ -182,6 +204,12 @@ fn candidate_votes_key(session: SessionIndex, candidate_hash: &CandidateHash) ->
 	buf
 }
 
+fn participation_key(queue: ParticipationQueue, candidate_hash: &CandidateHash) -> Vec<u8> {
+	let mut key = queue.key_prefix().to_vec();
+	candidate_hash.using_encoded(|s| key.extend_from_slice(s));
+	key
+}
+
 fn candidate_votes_session_prefix(session: SessionIndex) -> [u8; 15 + 4] {
 	let mut buf = [0u8; 15 + 4];
 	buf[..15].copy_from_slice(CANDIDATE_VOTES_SUBKEY);
@@ -236,6 +264,77 @@ impl From<polkadot_node_primitives::CandidateVotes> for CandidateVotes {
 /// The mapping for recent disputes; any which have not yet been pruned for being ancient.
 pub type RecentDisputes = std::collections::BTreeMap<(SessionIndex, CandidateHash), DisputeStatus>;
 
+/// A dispute participation which was either queued or already running.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct QueuedParticipation {
+	/// The receipt of the disputed candidate.
+	pub candidate_receipt: CandidateReceipt,
+	/// The session of the dispute.
+	pub session: SessionIndex,
+	/// The number of validators in that session.
+	pub n_validators: u32,
+	/// Where the participation was queued.
+	pub kind: QueuedParticipationKind,
+}
+
+/// Where a `QueuedParticipation` was queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum QueuedParticipationKind {
+	/// The participation was already running and has to be resumed first.
+	Running,
+	/// Priority queue, with the block number of the candidate's relay parent.
+	Priority(BlockNumber),
+	/// Best effort queue, with the number of times the participation was added to it.
+	BestEffort(u32),
+}
+
+impl QueuedParticipationKind {
+	/// The queue participations of this kind are persisted in.
+	pub fn queue(&self) -> ParticipationQueue {
+		match self {
+			Self::Running => ParticipationQueue::Running,
+			Self::Priority(_) => ParticipationQueue::Priority,
+			Self::BestEffort(_) => ParticipationQueue::BestEffort,
+		}
+	}
+}
+
+/// The queues participations are persisted in, with running participations in a queue of their
+/// own.
+///
+/// A candidate can be in several queues at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParticipationQueue {
+	/// Running participations.
+	Running,
+	/// The priority queue.
+	Priority,
+	/// The best effort queue.
+	BestEffort,
+}
+
+impl ParticipationQueue {
+	/// All queues, in the order they are processed in.
+	const ALL: [Self; 3] = [Self::Running, Self::Priority, Self::BestEffort];
+
+	fn key_prefix(&self) -> &'static [u8] {
+		match self {
+			Self::Running => RUNNING_PARTICIPATION_PREFIX,
+			Self::Priority => PRIORITY_PARTICIPATION_PREFIX,
+			Self::BestEffort => BEST_EFFORT_PARTICIPATION_PREFIX,
+		}
+	}
+}
+
+/// A change to the persisted participations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParticipationChange {
+	/// Add a participation to, or update it in, the queue of its kind.
+	Write(QueuedParticipation),
+	/// Remove the participation for the candidate from a queue.
+	Delete(ParticipationQueue, CandidateHash),
+}
+
 /// Errors while accessing things from the DB.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -293,6 +392,33 @@ pub(crate) fn load_recent_disputes(
 		.map_err(|e| SubsystemError::with_origin("dispute-coordinator", e))
 }
 
+/// Load all persisted participations, running ones first, followed by the priority and then the
+/// best effort queue.
+pub(crate) fn load_participations(
+	db: &dyn Database,
+	config: &ColumnConfiguration,
+) -> SubsystemResult<Vec<QueuedParticipation>> {
+	let mut participations = Vec::new();
+	for queue in ParticipationQueue::ALL {
+		for (_, value) in db.iter_with_prefix(config.col_data, queue.key_prefix()) {
+			let participation = QueuedParticipation::decode(&mut &value[..])
+				.map_err(|e| SubsystemError::with_origin("dispute-coordinator", Error::from(e)))?;
+			participations.push(participation);
+		}
+	}
+	Ok(participations)
+}
+
+/// The keys to delete from the data column when downgrading a database to a version which did
+/// not persist participations yet.
+pub(crate) fn participation_queue_downgrade(db: &dyn Database, col_data: u32) -> Vec<Vec<u8>> {
+	ParticipationQueue::ALL
+		.iter()
+		.flat_map(|queue| db.iter_with_prefix(col_data, queue.key_prefix()))
+		.map(|(key, _)| key.to_vec())
+		.collect()
+}
+
 /// Maybe prune data in the DB based on the provided session index.
 ///
 /// This is intended to be called on every block, and as such will be used to populate the DB on
@@ -581,6 +707,98 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn participations_are_persisted_per_queue() {
+		let mut backend = make_db();
+
+		let running = QueuedParticipation {
+			candidate_receipt: dummy_candidate_receipt(Hash::repeat_byte(1)),
+			session: 1,
+			n_validators: 10,
+			kind: QueuedParticipationKind::Running,
+		};
+		let priority = QueuedParticipation {
+			candidate_receipt: dummy_candidate_receipt(Hash::repeat_byte(2)),
+			session: 2,
+			n_validators: 10,
+			kind: QueuedParticipationKind::Priority(5),
+		};
+		let best_effort = QueuedParticipation {
+			candidate_receipt: dummy_candidate_receipt(Hash::repeat_byte(2)),
+			session: 2,
+			n_validators: 10,
+			kind: QueuedParticipationKind::BestEffort(3),
+		};
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		assert_eq!(overlay_db.load_participations().unwrap(), Vec::new());
+		overlay_db.write_participation(best_effort.clone());
+		overlay_db.write_participation(priority.clone());
+		overlay_db.write_participation(running.clone());
+		// Running participations are loaded first.
+		let all = vec![running.clone(), priority.clone(), best_effort.clone()];
+		assert_eq!(overlay_db.load_participations().unwrap(), all);
+
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+		assert_eq!(backend.load_participations().unwrap(), all);
+
+		// Only the entry of the given queue is removed.
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.delete_participation(
+			ParticipationQueue::BestEffort,
+			priority.candidate_receipt.hash(),
+		);
+		assert_eq!(
+			overlay_db.load_participations().unwrap(),
+			vec![running.clone(), priority.clone()]
+		);
+
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+		assert_eq!(backend.load_participations().unwrap(), vec![running, priority]);
+
+		assert_eq!(participation_queue_downgrade(&*backend.inner, 0).len(), 2);
+	}
+
+	#[test]
+	fn read_disputes_includes_votes() {
+		let mut backend = make_db();
+
+		let hash_a = CandidateHash(Hash::repeat_byte(0x0a));
+		let hash_b = CandidateHash(Hash::repeat_byte(0x0b));
+		let receipt = dummy_candidate_receipt(dummy_hash());
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_recent_disputes(
+			vec![((1, hash_a), DisputeStatus::Active), ((2, hash_b), DisputeStatus::Confirmed)]
+				.into_iter()
+				.collect(),
+		);
+		overlay_db.write_candidate_votes(
+			1,
+			hash_a,
+			CandidateVotes {
+				candidate_receipt: receipt.clone(),
+				valid: Vec::new(),
+				invalid: Vec::new(),
+			},
+		);
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		let disputes =
+			crate::read_disputes(backend.inner.clone(), &crate::Config { col_data: 0 }).unwrap();
+
+		assert_eq!(disputes.len(), 2);
+		assert_eq!((disputes[0].session, disputes[0].candidate_hash), (1, hash_a));
+		assert_eq!(disputes[0].status, DisputeStatus::Active);
+		assert_eq!(disputes[0].votes.as_ref().unwrap().candidate_receipt, receipt);
+		assert_eq!((disputes[1].session, disputes[1].candidate_hash), (2, hash_b));
+		assert_eq!(disputes[1].status, DisputeStatus::Confirmed);
+		assert!(disputes[1].votes.is_none());
+	}
+
 	#[test]
 	fn note_current_session_prunes_old() {
 		let mut backend = make_db();
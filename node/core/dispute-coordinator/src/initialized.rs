@@ -92,11 +92,12 @@ impl Initialized {
 		rolling_session_window: RollingSessionWindow,
 		spam_slots: SpamSlots,
 		scraper: ChainScraper,
+		participation_queue: Vec<db::v1::QueuedParticipation>,
 	) -> Self {
 		let DisputeCoordinatorSubsystem { config: _, store: _, keystore, metrics } = subsystem;
 
 		let (participation_sender, participation_receiver) = mpsc::channel(1);
-		let participation = Participation::new(participation_sender);
+		let participation = Participation::new(participation_sender, participation_queue);
 		let highest_session = rolling_session_window.latest_session();
 
 		Self {
@@ -252,6 +253,16 @@ impl Initialized {
 					},
 				};
 
+			// Persist participations, so they can be resumed after a restart.
+			for change in self.participation.take_queue_updates() {
+				match change {
+					db::v1::ParticipationChange::Write(participation) =>
+						overlay_db.write_participation(participation),
+					db::v1::ParticipationChange::Delete(queue, candidate_hash) =>
+						overlay_db.delete_participation(queue, candidate_hash),
+				}
+			}
+
 			if !overlay_db.is_empty() {
 				let ops = overlay_db.into_write_ops();
 				backend.write(ops)?;
@@ -599,7 +610,14 @@ impl Initialized {
 				};
 				gum::trace!(target: LOG_TARGET, "Loaded recent disputes from db");
 
-				let _ = tx.send(recent_disputes.keys().cloned().collect());
+				let _ = tx.send(
+					recent_disputes
+						.into_iter()
+						.map(|((session, candidate_hash), status)| {
+							(session, candidate_hash, status)
+						})
+						.collect(),
+				);
 			},
 			DisputeCoordinatorMessage::ActiveDisputes(tx) => {
 				// Return error if session information is missing.
@@ -30,14 +30,17 @@ use futures::FutureExt;
 
 use sc_keystore::LocalKeystore;
 
-use polkadot_node_primitives::{CandidateVotes, DISPUTE_WINDOW};
+use polkadot_node_primitives::{CandidateVotes, DisputeStatus, DISPUTE_WINDOW};
 use polkadot_node_subsystem::{
 	overseer, ActivatedLeaf, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
+	SubsystemResult,
 };
 use polkadot_node_subsystem_util::{
 	database::Database, rolling_session_window::RollingSessionWindow,
 };
-use polkadot_primitives::v2::{ScrapedOnChainVotes, ValidatorIndex, ValidatorPair};
+use polkadot_primitives::v2::{
+	CandidateHash, ScrapedOnChainVotes, SessionIndex, ValidatorIndex, ValidatorPair,
+};
 
 use crate::{
 	error::{FatalResult, JfyiError, Result},
@@ -45,7 +48,7 @@ use crate::{
 	status::{get_active_with_status, SystemClock},
 };
 use backend::{Backend, OverlayedBackend};
-use db::v1::DbBackend;
+use db::v1::{DbBackend, QueuedParticipation};
 use fatality::Split;
 
 use self::{
@@ -126,6 +129,45 @@ impl Config {
 	}
 }
 
+/// The keys to delete from the data column `col_data` of the dispute coordinator when downgrading
+/// a database to a version which did not persist participations yet.
+pub fn participation_queue_downgrade(db: &dyn Database, col_data: u32) -> Vec<Vec<u8>> {
+	db::v1::participation_queue_downgrade(db, col_data)
+}
+
+/// A dispute recorded by the dispute coordinator, as read by [`read_disputes`].
+#[derive(Debug, Clone)]
+pub struct StoredDispute {
+	/// The session the disputed candidate was included in.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: CandidateHash,
+	/// The status of the dispute.
+	pub status: DisputeStatus,
+	/// The votes on the disputed candidate, if they are still stored.
+	pub votes: Option<CandidateVotes>,
+}
+
+/// Load all recent disputes straight from the dispute coordinator's database.
+///
+/// This is meant for offline inspection of a node's database.
+pub fn read_disputes(
+	db: Arc<dyn Database>,
+	config: &Config,
+) -> SubsystemResult<Vec<StoredDispute>> {
+	let backend = DbBackend::new(db, config.column_config(), Metrics::default());
+
+	backend
+		.load_recent_disputes()?
+		.unwrap_or_default()
+		.into_iter()
+		.map(|((session, candidate_hash), status)| {
+			let votes = backend.load_candidate_votes(session, &candidate_hash)?.map(Into::into);
+			Ok(StoredDispute { session, candidate_hash, status, votes })
+		})
+		.collect()
+}
+
 #[overseer::subsystem(DisputeCoordinator, error=SubsystemError, prefix=self::overseer)]
 impl<Context: Send> DisputeCoordinatorSubsystem {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
@@ -212,22 +254,23 @@ impl DisputeCoordinatorSubsystem {
 			};
 
 			let mut overlay_db = OverlayedBackend::new(&mut backend);
-			let (participations, votes, spam_slots, ordering_provider) = match self
-				.handle_startup(
-					ctx,
-					first_leaf.clone(),
-					&rolling_session_window,
-					&mut overlay_db,
-					clock,
-				)
-				.await
-			{
-				Ok(v) => v,
-				Err(e) => {
-					e.split()?.log();
-					continue
-				},
-			};
+			let (participations, votes, spam_slots, ordering_provider, participation_queue) =
+				match self
+					.handle_startup(
+						ctx,
+						first_leaf.clone(),
+						&rolling_session_window,
+						&mut overlay_db,
+						clock,
+					)
+					.await
+				{
+					Ok(v) => v,
+					Err(e) => {
+						e.split()?.log();
+						continue
+					},
+				};
 			if !overlay_db.is_empty() {
 				let ops = overlay_db.into_write_ops();
 				backend.write(ops)?;
@@ -237,7 +280,13 @@ impl DisputeCoordinatorSubsystem {
 				participations,
 				votes,
 				first_leaf,
-				Initialized::new(self, rolling_session_window, spam_slots, ordering_provider),
+				Initialized::new(
+					self,
+					rolling_session_window,
+					spam_slots,
+					ordering_provider,
+					participation_queue,
+				),
 				backend,
 			)))
 		}
@@ -248,6 +297,7 @@ impl DisputeCoordinatorSubsystem {
 	// - Prune any old disputes.
 	// - Find disputes we need to participate in.
 	// - Initialize spam slots & OrderingProvider.
+	// - Load the persisted participations.
 	async fn handle_startup<Context>(
 		&self,
 		ctx: &mut Context,
@@ -260,6 +310,7 @@ impl DisputeCoordinatorSubsystem {
 		Vec<ScrapedOnChainVotes>,
 		SpamSlots,
 		ChainScraper,
+		Vec<QueuedParticipation>,
 	)> {
 		// Prune obsolete disputes:
 		db::v1::note_current_session(overlay_db, rolling_session_window.latest_session())?;
@@ -344,11 +395,21 @@ impl DisputeCoordinatorSubsystem {
 			}
 		}
 
+		// Participations of pruned sessions are obsolete:
+		let (participation_queue, obsolete): (Vec<_>, Vec<_>) = overlay_db
+			.load_participations()?
+			.into_iter()
+			.partition(|queued| queued.session >= rolling_session_window.earliest_session());
+		for queued in obsolete {
+			overlay_db.delete_participation(queued.kind.queue(), queued.candidate_receipt.hash());
+		}
+
 		Ok((
 			participation_requests,
 			votes,
 			SpamSlots::recover_from_state(unconfirmed_disputes),
 			scraper,
+			participation_queue,
 		))
 	}
 }
@@ -25,7 +25,7 @@ use futures::{
 #[cfg(test)]
 use futures_timer::Delay;
 
-use polkadot_node_primitives::{ValidationResult, APPROVAL_EXECUTION_TIMEOUT};
+use polkadot_node_primitives::{PvfExecKind, ValidationResult, APPROVAL_EXECUTION_TIMEOUT};
 use polkadot_node_subsystem::{
 	messages::{AvailabilityRecoveryMessage, AvailabilityStoreMessage, CandidateValidationMessage},
 	overseer, ActiveLeavesUpdate, RecoveryError,
@@ -33,7 +33,12 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_util::runtime::get_validation_code_by_hash;
 use polkadot_primitives::v2::{BlockNumber, CandidateHash, CandidateReceipt, Hash, SessionIndex};
 
-use crate::LOG_TARGET;
+use crate::{
+	db::v1::{
+		ParticipationChange, ParticipationQueue, QueuedParticipation, QueuedParticipationKind,
+	},
+	LOG_TARGET,
+};
 
 use crate::error::{FatalError, FatalResult, Result};
 
@@ -57,11 +62,14 @@ const MAX_PARALLEL_PARTICIPATIONS: usize = 3;
 ///
 /// - Prioritize and queue participations
 /// - Dequeue participation requests in order and launch participation worker.
+/// - Keep track of changes to the participations for persisting them.
 pub struct Participation {
 	/// Participations currently being processed.
 	running_participations: HashSet<CandidateHash>,
 	/// Priority and best effort queues.
 	queue: Queues,
+	/// Changes to the running participations since they were last persisted.
+	changes: Vec<ParticipationChange>,
 	/// Sender to be passed to worker tasks.
 	worker_sender: WorkerMessageSender,
 	/// Some recent block for retrieving validation code from chain.
@@ -130,15 +138,31 @@ impl Participation {
 	/// The passed in sender will be used by background workers to communicate back their results.
 	/// The calling context should make sure to call `Participation::on_worker_message()` for the
 	/// received messages.
-	pub fn new(sender: WorkerMessageSender) -> Self {
+	///
+	/// `persisted` are the participations persisted before a restart, as obtained from
+	/// `take_queue_updates`. Participations which were running are launched again first, all
+	/// others are queued in the order they were persisted in.
+	pub fn new(sender: WorkerMessageSender, persisted: Vec<QueuedParticipation>) -> Self {
 		Self {
 			running_participations: HashSet::new(),
-			queue: Queues::new(),
+			queue: Queues::restore(persisted),
+			changes: Vec::new(),
 			worker_sender: sender,
 			recent_block: None,
 		}
 	}
 
+	/// Get the changes to the running and queued participations since the last call.
+	///
+	/// The changes should be written to the database, so participations survive restarts.
+	pub fn take_queue_updates(&mut self) -> Vec<ParticipationChange> {
+		// Running participations are persisted separately from the queues, so the order between
+		// the two does not matter.
+		let mut changes = self.queue.take_changes();
+		changes.append(&mut self.changes);
+		changes
+	}
+
 	/// Queue a dispute for the node to participate in.
 	///
 	/// If capacity is available right now and we already got some relay chain head via
@@ -182,6 +206,10 @@ impl Participation {
 	) -> FatalResult<ParticipationStatement> {
 		let WorkerMessage(statement) = msg;
 		self.running_participations.remove(&statement.candidate_hash);
+		self.changes.push(ParticipationChange::Delete(
+			ParticipationQueue::Running,
+			statement.candidate_hash,
+		));
 		let recent_block = self.recent_block.expect("We never ever reset recent_block to `None` and we already received a result, so it must have been set before. qed.");
 		self.dequeue_until_capacity(ctx, recent_block.1).await?;
 		Ok(statement)
@@ -237,6 +265,9 @@ impl Participation {
 		recent_head: Hash,
 	) -> FatalResult<()> {
 		if self.running_participations.insert(req.candidate_hash().clone()) {
+			self.changes.push(ParticipationChange::Write(
+				req.to_persisted(QueuedParticipationKind::Running),
+			));
 			let sender = ctx.sender().clone();
 			ctx.spawn(
 				"participation-worker",
@@ -366,6 +397,7 @@ async fn participate(
 			req.candidate_receipt().clone(),
 			available_data.pov,
 			APPROVAL_EXECUTION_TIMEOUT,
+			PvfExecKind::Dispute,
 			validation_tx,
 		))
 		.await;
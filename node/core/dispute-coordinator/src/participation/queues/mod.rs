@@ -16,7 +16,7 @@
 
 use std::{
 	cmp::Ordering,
-	collections::{BTreeMap, HashMap},
+	collections::{BTreeMap, HashMap, VecDeque},
 };
 
 use futures::channel::oneshot;
@@ -24,6 +24,9 @@ use polkadot_node_subsystem::{messages::ChainApiMessage, overseer};
 use polkadot_primitives::v2::{BlockNumber, CandidateHash, CandidateReceipt, Hash, SessionIndex};
 
 use crate::{
+	db::v1::{
+		ParticipationChange, ParticipationQueue, QueuedParticipation, QueuedParticipationKind,
+	},
 	error::{FatalError, FatalResult, Result},
 	LOG_TARGET,
 };
@@ -55,6 +58,11 @@ type BestEffortCount = u32;
 
 /// Queues for dispute participation.
 pub struct Queues {
+	/// Participations which were already running before a restart.
+	///
+	/// They are resumed before anything else, in the order they were restored in.
+	resumed: VecDeque<ParticipationRequest>,
+
 	/// Set of best effort participation requests.
 	///
 	/// Note that as size is limited to `BEST_EFFORT_QUEUE_SIZE` we simply do a linear search for
@@ -70,6 +78,9 @@ pub struct Queues {
 	/// In the priority queue, we have a strict ordering of candidates and participation will
 	/// happen in that order.
 	priority: BTreeMap<CandidateComparator, ParticipationRequest>,
+
+	/// Changes to the queues since they were last persisted.
+	changes: Vec<ParticipationChange>,
 }
 
 /// A dispute participation request that can be queued.
@@ -146,12 +157,93 @@ impl ParticipationRequest {
 		let Self { candidate_hash, candidate_receipt, .. } = self;
 		(candidate_hash, candidate_receipt)
 	}
+	/// Get the representation of this request for the database.
+	pub fn to_persisted(&self, kind: QueuedParticipationKind) -> QueuedParticipation {
+		QueuedParticipation {
+			candidate_receipt: self.candidate_receipt.clone(),
+			session: self.session,
+			n_validators: self.n_validators as u32,
+			kind,
+		}
+	}
 }
 
 impl Queues {
 	/// Create new `Queues`.
 	pub fn new() -> Self {
-		Self { best_effort: HashMap::new(), priority: BTreeMap::new() }
+		Self {
+			resumed: VecDeque::new(),
+			best_effort: HashMap::new(),
+			priority: BTreeMap::new(),
+			changes: Vec::new(),
+		}
+	}
+
+	/// Create `Queues` from persisted participations.
+	///
+	/// Participations which were running already are queued to be resumed first. Persisted
+	/// participations which can't be queued anymore are removed with the next changes.
+	pub fn restore(persisted: impl IntoIterator<Item = QueuedParticipation>) -> Self {
+		let mut queues = Self::new();
+		let mut dropped = Vec::new();
+		for QueuedParticipation { candidate_receipt, session, n_validators, kind } in persisted {
+			let req = ParticipationRequest::new(candidate_receipt, session, n_validators as usize);
+			let candidate_hash = req.candidate_hash;
+			let res = match kind {
+				QueuedParticipationKind::Running => {
+					queues.resumed.push_back(req);
+					Ok(())
+				},
+				QueuedParticipationKind::Priority(relay_parent_block_number) => queues
+					.queue_with_comparator(
+						Some(CandidateComparator { relay_parent_block_number, candidate_hash }),
+						req,
+					),
+				QueuedParticipationKind::BestEffort(added_count) => queues
+					.queue_with_comparator(None, req)
+					.map(|()| queues.set_best_effort_count(&candidate_hash, added_count)),
+			};
+			if let Err(err) = res {
+				gum::warn!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					?err,
+					"Dropping persisted participation request"
+				);
+				dropped.push(ParticipationChange::Delete(kind.queue(), candidate_hash));
+			}
+		}
+		// Everything else is persisted already.
+		queues.changes = dropped;
+		queues
+	}
+
+	/// Get the changes to the queues since the last call.
+	pub fn take_changes(&mut self) -> Vec<ParticipationChange> {
+		std::mem::take(&mut self.changes)
+	}
+
+	/// All queued requests, in the order they would be dequeued in.
+	///
+	/// The order of best effort requests with the same `added_count` is arbitrary.
+	#[cfg(test)]
+	pub fn persisted(&self) -> impl Iterator<Item = QueuedParticipation> + '_ {
+		let resumed = self
+			.resumed
+			.iter()
+			.map(|req| req.to_persisted(QueuedParticipationKind::Running));
+		let priority = self.priority.iter().map(|(comparator, req)| {
+			req.to_persisted(QueuedParticipationKind::Priority(
+				comparator.relay_parent_block_number,
+			))
+		});
+		let mut best_effort: Vec<_> = self.best_effort.values().collect();
+		best_effort.sort_by(|a, b| b.added_count.cmp(&a.added_count));
+		let best_effort = best_effort.into_iter().map(|entry| {
+			entry.req.to_persisted(QueuedParticipationKind::BestEffort(entry.added_count))
+		});
+
+		resumed.chain(priority).chain(best_effort)
 	}
 
 	/// Will put message in queue, either priority or best effort depending on priority.
@@ -181,10 +273,13 @@ impl Queues {
 	/// if any.  Priority queue is always considered first, then the best effort queue based on
 	/// `added_count`.
 	pub fn dequeue(&mut self) -> Option<ParticipationRequest> {
+		if let Some(req) = self.resumed.pop_front() {
+			return Some(req)
+		}
 		if let Some(req) = self.pop_priority() {
 			// In case a candidate became best effort over time, we might have it also queued in
 			// the best effort queue - get rid of any such entry:
-			self.best_effort.remove(req.candidate_hash());
+			self.remove_best_effort(req.candidate_hash());
 			return Some(req)
 		}
 		self.pop_best_effort()
@@ -195,12 +290,19 @@ impl Queues {
 		comparator: Option<CandidateComparator>,
 		req: ParticipationRequest,
 	) -> std::result::Result<(), QueueError> {
+		// Going to be resumed first anyway:
+		if self.resumed.iter().any(|resumed| resumed.candidate_hash == req.candidate_hash) {
+			return Ok(())
+		}
 		if let Some(comparator) = comparator {
 			if self.priority.len() >= PRIORITY_QUEUE_SIZE {
 				return Err(QueueError::PriorityFull)
 			}
 			// Remove any best effort entry:
-			self.best_effort.remove(&req.candidate_hash);
+			self.remove_best_effort(&req.candidate_hash);
+			self.changes.push(ParticipationChange::Write(req.to_persisted(
+				QueuedParticipationKind::Priority(comparator.relay_parent_block_number),
+			)));
 			self.priority.insert(comparator, req);
 		} else {
 			if self.best_effort.len() >= BEST_EFFORT_QUEUE_SIZE {
@@ -208,14 +310,37 @@ impl Queues {
 			}
 			// Note: The request might have been added to priority in a previous call already, we
 			// take care of that case in `dequeue` (more efficient).
-			self.best_effort
+			let entry = self
+				.best_effort
 				.entry(req.candidate_hash)
-				.or_insert(BestEffortEntry { req, added_count: 0 })
-				.added_count += 1;
+				.or_insert(BestEffortEntry { req, added_count: 0 });
+			entry.added_count += 1;
+			self.changes.push(ParticipationChange::Write(
+				entry.req.to_persisted(QueuedParticipationKind::BestEffort(entry.added_count)),
+			));
 		}
 		Ok(())
 	}
 
+	/// Remove a request from the best effort queue, if present.
+	fn remove_best_effort(&mut self, candidate_hash: &CandidateHash) -> Option<BestEffortEntry> {
+		let entry = self.best_effort.remove(candidate_hash)?;
+		self.changes
+			.push(ParticipationChange::Delete(ParticipationQueue::BestEffort, *candidate_hash));
+		Some(entry)
+	}
+
+	/// Overwrite the `added_count` of a best effort entry.
+	fn set_best_effort_count(
+		&mut self,
+		candidate_hash: &CandidateHash,
+		added_count: BestEffortCount,
+	) {
+		if let Some(entry) = self.best_effort.get_mut(candidate_hash) {
+			entry.added_count = added_count;
+		}
+	}
+
 	/// Get the next best from the best effort queue.
 	///
 	/// If there are multiple best - just pick one.
@@ -229,7 +354,7 @@ impl Queues {
 		});
 		if let Some((best_hash, _)) = best {
 			let best_hash = best_hash.clone();
-			self.best_effort.remove(&best_hash).map(|e| e.req)
+			self.remove_best_effort(&best_hash).map(|e| e.req)
 		} else {
 			None
 		}
@@ -241,6 +366,10 @@ impl Queues {
 		// priority.pop_first().
 		if let Some((comparator, _)) = self.priority.iter().next() {
 			let comparator = comparator.clone();
+			self.changes.push(ParticipationChange::Delete(
+				ParticipationQueue::Priority,
+				comparator.candidate_hash,
+			));
 			self.priority.remove(&comparator)
 		} else {
 			None
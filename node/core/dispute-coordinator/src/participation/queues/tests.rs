@@ -14,11 +14,16 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
+
 use ::test_helpers::{dummy_candidate_receipt, dummy_hash};
 use assert_matches::assert_matches;
-use polkadot_primitives::v2::{BlockNumber, Hash};
+use polkadot_primitives::v2::{BlockNumber, CandidateHash, Hash};
 
 use super::{CandidateComparator, ParticipationRequest, QueueError, Queues};
+use crate::db::v1::{
+	ParticipationChange, ParticipationQueue, QueuedParticipation, QueuedParticipationKind,
+};
 
 /// Make a `ParticipationRequest` based on the given commitments hash.
 fn make_participation_request(hash: Hash) -> ParticipationRequest {
@@ -28,6 +33,24 @@ fn make_participation_request(hash: Hash) -> ParticipationRequest {
 	ParticipationRequest::new(receipt, 1, 100)
 }
 
+/// Apply the changes recorded by `queue` to `persisted`, like the database does.
+fn apply_changes(
+	queue: &mut Queues,
+	persisted: &mut BTreeMap<(ParticipationQueue, CandidateHash), QueuedParticipation>,
+) {
+	for change in queue.take_changes() {
+		match change {
+			ParticipationChange::Write(participation) => {
+				let key = (participation.kind.queue(), participation.candidate_receipt.hash());
+				persisted.insert(key, participation);
+			},
+			ParticipationChange::Delete(queue, candidate_hash) => {
+				persisted.remove(&(queue, candidate_hash));
+			},
+		}
+	}
+}
+
 /// Make dummy comparator for request, based on the given block number.
 fn make_dummy_comparator(
 	req: &ParticipationRequest,
@@ -128,3 +151,100 @@ fn candidate_is_only_dequeued_once() {
 	assert_eq!(queue.dequeue(), Some(req1));
 	assert_eq!(queue.dequeue(), None);
 }
+
+/// Persisted queues are restored in the same order, with running participations first.
+#[test]
+fn persisted_queues_are_restored_in_order() {
+	let mut queue = Queues::new();
+	let req_running = make_participation_request(Hash::repeat_byte(0x01));
+	let req_prio = make_participation_request(Hash::repeat_byte(0x02));
+	let req_prio_2 = make_participation_request(Hash::repeat_byte(0x03));
+	let req_best_effort = make_participation_request(Hash::repeat_byte(0x04));
+	let req_best_effort_2 = make_participation_request(Hash::repeat_byte(0x05));
+
+	queue
+		.queue_with_comparator(Some(make_dummy_comparator(&req_prio_2, 2)), req_prio_2.clone())
+		.unwrap();
+	queue
+		.queue_with_comparator(Some(make_dummy_comparator(&req_prio, 1)), req_prio.clone())
+		.unwrap();
+	queue.queue_with_comparator(None, req_best_effort_2.clone()).unwrap();
+	queue.queue_with_comparator(None, req_best_effort.clone()).unwrap();
+	queue.queue_with_comparator(None, req_best_effort.clone()).unwrap();
+
+	let persisted: Vec<_> =
+		std::iter::once(req_running.to_persisted(QueuedParticipationKind::Running))
+			.chain(queue.persisted())
+			.collect();
+	assert_eq!(
+		persisted.iter().map(|p| p.kind).collect::<Vec<_>>(),
+		vec![
+			QueuedParticipationKind::Running,
+			QueuedParticipationKind::Priority(1),
+			QueuedParticipationKind::Priority(2),
+			QueuedParticipationKind::BestEffort(2),
+			QueuedParticipationKind::BestEffort(1),
+		],
+	);
+
+	let mut restored = Queues::restore(persisted.clone());
+	assert_eq!(restored.persisted().collect::<Vec<_>>(), persisted);
+
+	// Already going to be resumed:
+	restored.queue_with_comparator(None, req_running.clone()).unwrap();
+
+	assert_eq!(restored.dequeue(), Some(req_running));
+	assert_eq!(restored.dequeue(), Some(req_prio));
+	assert_eq!(restored.dequeue(), Some(req_prio_2));
+	assert_eq!(restored.dequeue(), Some(req_best_effort));
+	assert_eq!(restored.dequeue(), Some(req_best_effort_2));
+	assert_eq!(restored.dequeue(), None);
+}
+
+/// Check that the recorded changes keep the persisted participations in sync with the queues.
+#[test]
+fn changes_keep_persisted_participations_in_sync() {
+	let mut queue = Queues::new();
+	let mut persisted = BTreeMap::new();
+	let req_prio = make_participation_request(Hash::repeat_byte(0x01));
+	let req_best_effort = make_participation_request(Hash::repeat_byte(0x02));
+	let req_moved = make_participation_request(Hash::repeat_byte(0x03));
+	let req_best_effort_2 = make_participation_request(Hash::repeat_byte(0x04));
+
+	let assert_in_sync = |queue: &Queues, persisted: &BTreeMap<_, QueuedParticipation>| {
+		let mut expected: Vec<_> = queue.persisted().collect();
+		expected.sort_by_key(|p| (p.kind.queue(), p.candidate_receipt.hash()));
+		assert_eq!(persisted.values().cloned().collect::<Vec<_>>(), expected);
+	};
+
+	queue
+		.queue_with_comparator(Some(make_dummy_comparator(&req_prio, 1)), req_prio.clone())
+		.unwrap();
+	queue.queue_with_comparator(None, req_best_effort.clone()).unwrap();
+	queue.queue_with_comparator(None, req_best_effort.clone()).unwrap();
+	queue.queue_with_comparator(None, req_moved.clone()).unwrap();
+	queue
+		.queue_with_comparator(Some(make_dummy_comparator(&req_moved, 2)), req_moved.clone())
+		.unwrap();
+	queue.queue_with_comparator(None, req_best_effort_2.clone()).unwrap();
+	// Now in both queues:
+	queue.queue_with_comparator(None, req_moved.clone()).unwrap();
+	apply_changes(&mut queue, &mut persisted);
+	assert_in_sync(&queue, &persisted);
+	assert_eq!(persisted.len(), 5);
+
+	assert_eq!(queue.dequeue(), Some(req_prio));
+	apply_changes(&mut queue, &mut persisted);
+	assert_in_sync(&queue, &persisted);
+
+	let mut restored = Queues::restore(persisted.values().cloned());
+	assert!(restored.take_changes().is_empty());
+	assert_in_sync(&restored, &persisted);
+
+	assert_eq!(restored.dequeue(), Some(req_moved));
+	assert_eq!(restored.dequeue(), Some(req_best_effort));
+	assert_eq!(restored.dequeue(), Some(req_best_effort_2));
+	assert_eq!(restored.dequeue(), None);
+	apply_changes(&mut restored, &mut persisted);
+	assert!(persisted.is_empty());
+}
@@ -34,8 +34,8 @@ use polkadot_node_subsystem_util::database::Database;
 use polkadot_node_primitives::{SignedDisputeStatement, SignedFullStatement, Statement};
 use polkadot_node_subsystem::{
 	messages::{
-		ApprovalVotingMessage, ChainApiMessage, DisputeCoordinatorMessage,
-		DisputeDistributionMessage, ImportStatementsResult,
+		ApprovalVotingMessage, AvailabilityRecoveryMessage, ChainApiMessage,
+		DisputeCoordinatorMessage, DisputeDistributionMessage, ImportStatementsResult,
 	},
 	overseer::FromOrchestra,
 	OverseerSignal,
@@ -66,11 +66,11 @@ use crate::{
 	backend::Backend,
 	metrics::Metrics,
 	participation::{participation_full_happy_path, participation_missing_availability},
-	status::{Clock, Timestamp, ACTIVE_DURATION_SECS},
+	status::{Clock, DisputeStatus, Timestamp, ACTIVE_DURATION_SECS},
 	Config, DisputeCoordinatorSubsystem,
 };
 
-use super::db::v1::DbBackend;
+use super::db::v1::{DbBackend, QueuedParticipation, QueuedParticipationKind};
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(2);
 
@@ -458,6 +458,12 @@ impl TestState {
 		)
 	}
 
+	fn load_participations(&self) -> Vec<QueuedParticipation> {
+		DbBackend::new(self.db.clone(), self.config.column_config(), Metrics::default())
+			.load_participations()
+			.unwrap()
+	}
+
 	fn resume<F>(mut self, test: F) -> Self
 	where
 		F: FnOnce(TestState, VirtualOverseer) -> BoxFuture<'static, TestState>,
@@ -1846,7 +1852,10 @@ fn concluded_supermajority_for_non_active_after_time() {
 					})
 					.await;
 
-				assert_eq!(rx.await.unwrap().len(), 1);
+				assert_matches!(
+					rx.await.unwrap().as_slice(),
+					[(s, c, DisputeStatus::ConcludedFor(_))] if *s == session && *c == candidate_hash
+				);
 			}
 
 			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
@@ -1968,7 +1977,10 @@ fn concluded_supermajority_against_non_active_after_time() {
 					})
 					.await;
 
-				assert_eq!(rx.await.unwrap().len(), 1);
+				assert_matches!(
+					rx.await.unwrap().as_slice(),
+					[(s, c, DisputeStatus::ConcludedAgainst(_))] if *s == session && *c == candidate_hash
+				);
 			}
 
 			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
@@ -2162,6 +2174,113 @@ fn resume_dispute_without_local_statement() {
 	});
 }
 
+#[test]
+fn participation_is_resumed_after_crash() {
+	let session = 1;
+
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt = make_valid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+
+			test_state.activate_leaf_at_session(&mut virtual_overseer, session, 1).await;
+
+			let valid_vote = test_state
+				.issue_explicit_statement_with_index(
+					ValidatorIndex(1),
+					candidate_hash,
+					session,
+					true,
+				)
+				.await;
+
+			let invalid_vote = test_state
+				.issue_explicit_statement_with_index(
+					ValidatorIndex(2),
+					candidate_hash,
+					session,
+					false,
+				)
+				.await;
+
+			let (pending_confirmation, confirmation_rx) = oneshot::channel();
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ImportStatements {
+						candidate_receipt: candidate_receipt.clone(),
+						session,
+						statements: vec![
+							(valid_vote, ValidatorIndex(1)),
+							(invalid_vote, ValidatorIndex(2)),
+						],
+						pending_confirmation: Some(pending_confirmation),
+					},
+				})
+				.await;
+			handle_approval_vote_request(&mut virtual_overseer, &candidate_hash, HashMap::new())
+				.await;
+
+			// Participation gets started, but never finishes:
+			let _recovery_tx = assert_matches!(
+				overseer_recv(&mut virtual_overseer).await,
+				AllMessages::AvailabilityRecovery(
+					AvailabilityRecoveryMessage::RecoverAvailableData(_, _, _, tx)
+				) => tx
+			);
+
+			assert_eq!(confirmation_rx.await, Ok(ImportStatementsResult::ValidImport));
+
+			let queue = test_state.load_participations();
+			assert_eq!(queue.len(), 1);
+			assert_eq!(queue[0].candidate_receipt, candidate_receipt);
+			assert_eq!(queue[0].kind, QueuedParticipationKind::Running);
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	})
+	// The interrupted participation is resumed on restart, exactly once.
+	.resume(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt = make_valid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+
+			participation_with_distribution(
+				&mut virtual_overseer,
+				&candidate_hash,
+				candidate_receipt.commitments_hash,
+			)
+			.await;
+
+			{
+				let (tx, rx) = oneshot::channel();
+
+				virtual_overseer
+					.send(FromOrchestra::Communication {
+						msg: DisputeCoordinatorMessage::ActiveDisputes(tx),
+					})
+					.await;
+
+				assert_eq!(rx.await.unwrap().len(), 1);
+			}
+
+			// The finished participation is no longer persisted:
+			assert_eq!(test_state.load_participations(), Vec::new());
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
 #[test]
 fn resume_dispute_with_local_statement() {
 	let session = 1;
@@ -690,22 +690,32 @@ async fn request_disputes(
 	sender: &mut impl overseer::ProvisionerSenderTrait,
 	active_or_recent: RequestType,
 ) -> Vec<(SessionIndex, CandidateHash)> {
-	let (tx, rx) = oneshot::channel();
-	let msg = match active_or_recent {
-		RequestType::Recent => DisputeCoordinatorMessage::RecentDisputes(tx),
-		RequestType::Active => DisputeCoordinatorMessage::ActiveDisputes(tx),
-	};
 	// Bounded by block production - `ProvisionerMessage::RequestInherentData`.
-	sender.send_unbounded_message(msg);
+	let disputes = match active_or_recent {
+		RequestType::Recent => {
+			let (tx, rx) = oneshot::channel();
+			sender.send_unbounded_message(DisputeCoordinatorMessage::RecentDisputes(tx));
+			rx.await.map(|disputes| {
+				disputes
+					.into_iter()
+					.map(|(session, candidate_hash, _status)| (session, candidate_hash))
+					.collect()
+			})
+		},
+		RequestType::Active => {
+			let (tx, rx) = oneshot::channel();
+			sender.send_unbounded_message(DisputeCoordinatorMessage::ActiveDisputes(tx));
+			rx.await
+		},
+	};
 
-	let recent_disputes = match rx.await {
+	match disputes {
 		Ok(r) => r,
 		Err(oneshot::Canceled) => {
 			gum::warn!(target: LOG_TARGET, "Unable to gather {:?} disputes", active_or_recent);
 			Vec::new()
 		},
-	};
-	recent_disputes
+	}
 }
 
 /// Request the relevant dispute statements for a set of disputes identified by `CandidateHash` and the `SessionIndex`.
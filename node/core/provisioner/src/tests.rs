@@ -501,6 +501,7 @@ mod select_candidates {
 mod select_disputes {
 	use super::{super::*, common::test_harness};
 	use futures::channel::mpsc;
+	use polkadot_node_primitives::DisputeStatus;
 	use polkadot_node_subsystem::{
 		messages::{AllMessages, DisputeCoordinatorMessage, RuntimeApiMessage, RuntimeApiRequest},
 		RuntimeApiError,
@@ -558,7 +559,14 @@ mod select_disputes {
 				AllMessages::DisputeCoordinator(DisputeCoordinatorMessage::RecentDisputes(
 					sender,
 				)) => {
-					let _ = sender.send(recent_disputes.clone());
+					let _ = sender.send(
+						recent_disputes
+							.iter()
+							.map(|(session, candidate_hash)| {
+								(*session, *candidate_hash, DisputeStatus::Active)
+							})
+							.collect(),
+					);
 				},
 				AllMessages::DisputeCoordinator(DisputeCoordinatorMessage::ActiveDisputes(
 					sender,
@@ -42,10 +42,9 @@ mod runtime_api;
 #[cfg(test)]
 mod tests;
 
-use self::{
-	interest_view::{InterestView, Judgement},
-	metrics::Metrics,
-};
+use self::interest_view::{InterestView, Judgement};
+
+pub use self::metrics::Metrics;
 
 /// PVF pre-checking subsystem.
 pub struct PvfCheckerSubsystem {
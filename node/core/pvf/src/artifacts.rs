@@ -14,15 +14,25 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{error::PrepareError, host::PrepareResultSender};
+use crate::{error::PrepareError, host::PrepareResultSender, LOG_TARGET, NODE_VERSION};
 use always_assert::always;
 use async_std::path::{Path, PathBuf};
+use futures::StreamExt as _;
+use parity_scale_codec::{Decode, Encode};
 use polkadot_parachain::primitives::ValidationCodeHash;
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
 	time::{Duration, SystemTime},
 };
 
+/// The extension of the file that holds the [`ArtifactHeader`] of an artifact. The header file
+/// lives next to the artifact file and shares its name.
+const HEADER_EXTENSION: &str = ".header";
+
+/// The extension of the file that records a [`PreparationFailure`] for an artifact. The file is
+/// placed where the artifact would be and shares its name.
+const FAILURE_EXTENSION: &str = ".failed";
+
 pub struct CompiledArtifact(Vec<u8>);
 
 impl CompiledArtifact {
@@ -53,7 +63,6 @@ impl ArtifactId {
 	}
 
 	/// Tries to recover the artifact id from the given file name.
-	#[cfg(test)]
 	pub fn from_file_name(file_name: &str) -> Option<Self> {
 		use polkadot_core_primitives::Hash;
 		use std::str::FromStr as _;
@@ -71,6 +80,168 @@ impl ArtifactId {
 	}
 }
 
+/// Returns the path of the header file that accompanies the artifact at the given path.
+pub fn header_path(artifact_path: &Path) -> PathBuf {
+	let mut header_path = artifact_path.as_os_str().to_owned();
+	header_path.push(HEADER_EXTENSION);
+	PathBuf::from(header_path)
+}
+
+/// Returns the path of the file that records the preparation failure of the artifact at the given
+/// path.
+pub fn failure_path(artifact_path: &Path) -> PathBuf {
+	let mut failure_path = artifact_path.as_os_str().to_owned();
+	failure_path.push(FAILURE_EXTENSION);
+	PathBuf::from(failure_path)
+}
+
+/// The header that is written next to every successfully prepared artifact.
+///
+/// The compiled artifact is loaded by the execute workers directly from disk, so it is only safe to
+/// reuse an artifact left over from a previous run if it was produced by the same node version with
+/// the same executor configuration and it was not altered since. The header records all of that.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ArtifactHeader {
+	/// The version of the node that produced the artifact.
+	node_version: String,
+	/// The fingerprint of the executor configuration used to produce the artifact.
+	config_fingerprint: [u8; 32],
+	/// The checksum of the artifact contents.
+	checksum: [u8; 32],
+}
+
+impl ArtifactHeader {
+	/// Creates a header for the given artifact contents produced by this node.
+	pub fn for_artifact(artifact: &[u8]) -> Self {
+		Self {
+			node_version: NODE_VERSION.to_owned(),
+			config_fingerprint: crate::executor_intf::config_fingerprint(),
+			checksum: sp_core::hashing::blake2_256(artifact),
+		}
+	}
+
+	/// Computes the header for the artifact at the given path and writes it next to the artifact.
+	///
+	/// The artifact must be fully written at this point.
+	pub async fn write_for(artifact_path: &Path) -> std::io::Result<()> {
+		let artifact = async_std::fs::read(artifact_path).await?;
+		let header = Self::for_artifact(&artifact);
+		async_std::fs::write(header_path(artifact_path), header.encode()).await
+	}
+
+	/// Checks that the artifact at the given path is described by a valid header, i.e. it was
+	/// produced by this node version with the same executor configuration and was not modified.
+	async fn validate(artifact_path: &Path) -> Result<(), String> {
+		let header_bytes = async_std::fs::read(header_path(artifact_path))
+			.await
+			.map_err(|err| format!("cannot read header: {}", err))?;
+		let header = Self::decode(&mut header_bytes.as_slice())
+			.map_err(|err| format!("cannot decode header: {}", err))?;
+
+		if header.node_version != NODE_VERSION {
+			return Err(format!(
+				"produced by node version {}, current is {}",
+				header.node_version, NODE_VERSION,
+			))
+		}
+		if header.config_fingerprint != crate::executor_intf::config_fingerprint() {
+			return Err("produced with a different executor configuration".to_owned())
+		}
+
+		let artifact = async_std::fs::read(artifact_path)
+			.await
+			.map_err(|err| format!("cannot read artifact: {}", err))?;
+		if header.checksum != sp_core::hashing::blake2_256(&artifact) {
+			return Err("checksum mismatch".to_owned())
+		}
+
+		Ok(())
+	}
+}
+
+/// The record of a failed preparation that is persisted in the cache, so that the known-bad code is
+/// not compiled again after a restart.
+#[derive(Debug, Clone, Encode, Decode)]
+struct PreparationFailure {
+	/// The version of the node that attempted the preparation.
+	node_version: String,
+	/// The fingerprint of the executor configuration used for the preparation.
+	config_fingerprint: [u8; 32],
+	/// The error the preparation ended up with.
+	error: PrepareError,
+	/// The number of consecutive failed attempts.
+	num_failures: u32,
+	/// The time of the last failed attempt, in seconds since the Unix epoch.
+	last_time_failed: u64,
+}
+
+impl PreparationFailure {
+	/// Reads the failure record at the given path and converts it into an artifact state.
+	///
+	/// The records left by a different node version or executor configuration are rejected, since
+	/// the preparation might succeed now.
+	async fn read(path: &Path, now: SystemTime) -> Result<ArtifactState, String> {
+		let bytes = async_std::fs::read(path)
+			.await
+			.map_err(|err| format!("cannot read failure record: {}", err))?;
+		let failure = Self::decode(&mut bytes.as_slice())
+			.map_err(|err| format!("cannot decode failure record: {}", err))?;
+
+		if failure.node_version != NODE_VERSION ||
+			failure.config_fingerprint != crate::executor_intf::config_fingerprint()
+		{
+			return Err("recorded with a different node version or configuration".to_owned())
+		}
+
+		Ok(ArtifactState::FailedToProcess {
+			last_time_failed: SystemTime::UNIX_EPOCH +
+				Duration::from_secs(failure.last_time_failed),
+			last_time_needed: now,
+			num_failures: failure.num_failures,
+			error: failure.error,
+		})
+	}
+}
+
+/// Persists the failed preparation of the artifact with the given ID in the cache at the given
+/// path.
+pub async fn write_failure(
+	cache_path: &Path,
+	artifact_id: &ArtifactId,
+	error: &PrepareError,
+	num_failures: u32,
+	last_time_failed: SystemTime,
+) -> std::io::Result<()> {
+	let failure = PreparationFailure {
+		node_version: NODE_VERSION.to_owned(),
+		config_fingerprint: crate::executor_intf::config_fingerprint(),
+		error: error.clone(),
+		num_failures,
+		last_time_failed: last_time_failed
+			.duration_since(SystemTime::UNIX_EPOCH)
+			.map(|since_epoch| since_epoch.as_secs())
+			.unwrap_or(0),
+	};
+	async_std::fs::write(failure_path(&artifact_id.path(cache_path)), failure.encode()).await
+}
+
+/// Removes the record of a failed preparation of the artifact with the given ID from the cache at
+/// the given path, if any.
+pub async fn remove_failure(cache_path: &Path, artifact_id: &ArtifactId) {
+	match async_std::fs::remove_file(failure_path(&artifact_id.path(cache_path))).await {
+		Ok(()) => (),
+		Err(err) if err.kind() == std::io::ErrorKind::NotFound => (),
+		Err(err) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				validation_code_hash = ?artifact_id.code_hash,
+				"failed to remove the record of a preparation failure: {:?}",
+				err,
+			);
+		},
+	}
+}
+
 /// A bundle of the artifact ID and the path.
 ///
 /// Rationale for having this is two-fold:
@@ -103,10 +274,27 @@ pub enum ArtifactState {
 		last_time_needed: SystemTime,
 	},
 	/// A task to prepare this artifact is scheduled.
-	Preparing { waiting_for_response: Vec<PrepareResultSender> },
-	/// The code couldn't be compiled due to an error. Such artifacts
-	/// never reach the executor and stay in the host's memory.
-	FailedToProcess(PrepareError),
+	Preparing {
+		waiting_for_response: Vec<PrepareResultSender>,
+		/// The number of failed attempts to prepare this artifact preceding this one.
+		num_failures: u32,
+	},
+	/// The code couldn't be compiled due to an error. Such artifacts never reach the executor.
+	///
+	/// The failure is recorded in the cache, so it survives restarts. Requests for this artifact
+	/// are answered with the error right away. If the error is not deterministic, the preparation
+	/// is retried once the backoff elapses.
+	FailedToProcess {
+		/// The time of the last failed attempt.
+		last_time_failed: SystemTime,
+		/// The time the artifact was last needed. A deterministic failure is kept for as long as a
+		/// prepared artifact would be, so that the known-bad code is not prepared again.
+		last_time_needed: SystemTime,
+		/// The number of consecutive failed attempts.
+		num_failures: u32,
+		/// The error the last attempt ended up with.
+		error: PrepareError,
+	},
 }
 
 /// A container of all known artifact ids and their states.
@@ -115,17 +303,143 @@ pub struct Artifacts {
 }
 
 impl Artifacts {
-	/// Initialize a blank cache at the given path. This will clear everything present at the
-	/// given path, to be populated over time.
+	/// Initialize the cache at the given path, picking up the artifacts left over from a previous
+	/// run.
 	///
-	/// The recognized artifacts will be filled in the table and unrecognized will be removed.
+	/// The recognized artifacts that pass the validation against their [`ArtifactHeader`] will be
+	/// filled in the table as prepared and the recognized preparation failures will be filled in as
+	/// failed. Everything else will be removed.
 	pub async fn new(cache_path: &Path) -> Self {
 		// Make sure that the cache path directory and all it's parents are created.
-		// First delete the entire cache. Nodes are long-running so this should populate shortly.
-		let _ = async_std::fs::remove_dir_all(cache_path).await;
 		let _ = async_std::fs::create_dir_all(cache_path).await;
 
-		Self { artifacts: HashMap::new() }
+		let mut artifacts = HashMap::new();
+		let mut entries = match async_std::fs::read_dir(cache_path).await {
+			Ok(entries) => entries,
+			Err(err) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					"failed to read the artifact cache at {}: {:?}",
+					cache_path.display(),
+					err,
+				);
+				return Self { artifacts }
+			},
+		};
+
+		let now = SystemTime::now();
+		let mut headers = HashSet::new();
+		let mut failures = Vec::new();
+		let mut to_remove = Vec::new();
+		while let Some(entry) = entries.next().await {
+			let path = match entry {
+				Ok(entry) => entry.path(),
+				Err(_) => continue,
+			};
+			let file_name = match path.file_name().and_then(|name| name.to_str()) {
+				Some(file_name) => file_name.to_owned(),
+				None => {
+					to_remove.push(path);
+					continue
+				},
+			};
+
+			// Headers are only meaningful together with their artifacts, those are checked below.
+			if file_name.ends_with(HEADER_EXTENSION) {
+				headers.insert(path);
+				continue
+			}
+			// Failures are loaded after all the artifacts are known, see below.
+			if let Some(artifact_file_name) = file_name.strip_suffix(FAILURE_EXTENSION) {
+				match ArtifactId::from_file_name(artifact_file_name) {
+					Some(artifact_id) => failures.push((artifact_id, path)),
+					None => to_remove.push(path),
+				}
+				continue
+			}
+
+			let artifact_id = match ArtifactId::from_file_name(&file_name) {
+				Some(artifact_id) => artifact_id,
+				None => {
+					to_remove.push(path);
+					continue
+				},
+			};
+
+			match ArtifactHeader::validate(&path).await {
+				Ok(()) => {
+					artifacts
+						.insert(artifact_id, ArtifactState::Prepared { last_time_needed: now });
+				},
+				Err(reason) => {
+					gum::debug!(
+						target: LOG_TARGET,
+						validation_code_hash = ?artifact_id.code_hash,
+						"discarding the cached artifact: {}",
+						reason,
+					);
+					to_remove.push(path);
+				},
+			}
+		}
+
+		// A successfully prepared artifact takes precedence over a failure recorded for the same
+		// code.
+		for (artifact_id, path) in failures {
+			if artifacts.contains_key(&artifact_id) {
+				to_remove.push(path);
+				continue
+			}
+
+			match PreparationFailure::read(&path, now).await {
+				Ok(state) => {
+					artifacts.insert(artifact_id, state);
+				},
+				Err(reason) => {
+					gum::debug!(
+						target: LOG_TARGET,
+						validation_code_hash = ?artifact_id.code_hash,
+						"discarding the cached preparation failure: {}",
+						reason,
+					);
+					to_remove.push(path);
+				},
+			}
+		}
+
+		// Remove the headers that don't belong to any of the retained artifacts.
+		let retained_headers = artifacts
+			.iter()
+			.filter(|(_, state)| matches!(state, ArtifactState::Prepared { .. }))
+			.map(|(artifact_id, _)| artifact_id)
+			.map(|artifact_id| header_path(&artifact_id.path(cache_path)))
+			.collect::<HashSet<_>>();
+		to_remove.extend(headers.difference(&retained_headers).cloned());
+
+		for path in to_remove {
+			let result = if path.is_dir().await {
+				async_std::fs::remove_dir_all(&path).await
+			} else {
+				async_std::fs::remove_file(&path).await
+			};
+			if let Err(err) = result {
+				gum::warn!(
+					target: LOG_TARGET,
+					"failed to remove {} from the artifact cache: {:?}",
+					path.display(),
+					err,
+				);
+			}
+		}
+
+		gum::info!(
+			target: LOG_TARGET,
+			"Recovered {} prepared artifacts and preparation failures from the cache at {}",
+			artifacts.len(),
+			cache_path.display(),
+		);
+
+		Self { artifacts }
 	}
 
 	#[cfg(test)]
@@ -150,7 +464,7 @@ impl Artifacts {
 		// See the precondition.
 		always!(self
 			.artifacts
-			.insert(artifact_id, ArtifactState::Preparing { waiting_for_response })
+			.insert(artifact_id, ArtifactState::Preparing { waiting_for_response, num_failures: 0 })
 			.is_none());
 	}
 
@@ -167,20 +481,59 @@ impl Artifacts {
 			.is_none());
 	}
 
+	/// Insert an artifact with the given ID as "failed".
+	///
+	/// This function must be used only for brand-new artifacts and should never be used for
+	/// replacing existing ones.
+	#[cfg(test)]
+	pub fn insert_failed(
+		&mut self,
+		artifact_id: ArtifactId,
+		last_time_failed: SystemTime,
+		num_failures: u32,
+		error: PrepareError,
+	) {
+		// See the precondition.
+		always!(self
+			.artifacts
+			.insert(
+				artifact_id,
+				ArtifactState::FailedToProcess {
+					last_time_failed,
+					last_time_needed: last_time_failed,
+					num_failures,
+					error,
+				}
+			)
+			.is_none());
+	}
+
 	/// Remove and retrieve the artifacts from the table that are older than the supplied Time-To-Live.
+	///
+	/// This applies both to the prepared artifacts and the deterministic failures, which age since
+	/// they were last needed, and to the other failures, which age since the last failed attempt.
 	pub fn prune(&mut self, artifact_ttl: Duration) -> Vec<ArtifactId> {
 		let now = SystemTime::now();
 
 		let mut to_remove = vec![];
 		for (k, v) in self.artifacts.iter() {
-			if let ArtifactState::Prepared { last_time_needed, .. } = *v {
-				if now
-					.duration_since(last_time_needed)
-					.map(|age| age > artifact_ttl)
-					.unwrap_or(false)
-				{
-					to_remove.push(k.clone());
-				}
+			let last_time = match *v {
+				ArtifactState::Prepared { last_time_needed, .. } => last_time_needed,
+				ArtifactState::FailedToProcess {
+					last_time_failed,
+					last_time_needed,
+					ref error,
+					..
+				} =>
+					if error.is_deterministic() {
+						last_time_needed
+					} else {
+						last_time_failed
+					},
+				ArtifactState::Preparing { .. } => continue,
+			};
+			if now.duration_since(last_time).map(|age| age > artifact_ttl).unwrap_or(false) {
+				to_remove.push(k.clone());
 			}
 		}
 
@@ -194,10 +547,18 @@ impl Artifacts {
 
 #[cfg(test)]
 mod tests {
-	use super::{ArtifactId, Artifacts};
+	use super::{
+		failure_path, header_path, remove_failure, write_failure, ArtifactHeader, ArtifactId,
+		ArtifactState, Artifacts,
+	};
+	use crate::PrepareError;
 	use async_std::path::Path;
+	use parity_scale_codec::Encode;
 	use sp_core::H256;
-	use std::str::FromStr;
+	use std::{
+		str::FromStr,
+		time::{Duration, SystemTime},
+	};
 
 	#[test]
 	fn from_file_name() {
@@ -234,7 +595,79 @@ mod tests {
 	}
 
 	#[test]
-	fn artifacts_removes_cache_on_startup() {
+	fn header_path_is_next_to_artifact() {
+		assert_eq!(
+			header_path(Path::new("/test/wasmtime_0x1234")).to_str(),
+			Some("/test/wasmtime_0x1234.header"),
+		);
+	}
+
+	#[test]
+	fn artifacts_retains_valid_cache_on_startup() {
+		let fake_cache_path = async_std::task::block_on(async move {
+			crate::worker_common::tmpfile("test-cache").await.unwrap()
+		});
+		std::fs::create_dir_all(&fake_cache_path).unwrap();
+
+		let artifact_path = |hash: &str| {
+			let mut p = fake_cache_path.clone();
+			p.push(format!("wasmtime_0x{}", hash));
+			p
+		};
+		let valid_hash = "1234567890123456789012345678901234567890123456789012345678901234";
+		let tampered_hash = "2234567890123456789012345678901234567890123456789012345678901234";
+		let headerless_hash = "3234567890123456789012345678901234567890123456789012345678901234";
+
+		// A valid artifact with a matching header.
+		std::fs::write(artifact_path(valid_hash), b"valid").unwrap();
+		std::fs::write(
+			header_path(&artifact_path(valid_hash)),
+			ArtifactHeader::for_artifact(b"valid").encode(),
+		)
+		.unwrap();
+
+		// An artifact which contents doesn't match the checksum.
+		std::fs::write(artifact_path(tampered_hash), b"tampered").unwrap();
+		std::fs::write(
+			header_path(&artifact_path(tampered_hash)),
+			ArtifactHeader::for_artifact(b"original").encode(),
+		)
+		.unwrap();
+
+		// An artifact without a header, an orphaned header and junk.
+		std::fs::write(artifact_path(headerless_hash), b"headerless").unwrap();
+		std::fs::write(
+			header_path(&fake_cache_path.join("wasmtime_0x00")),
+			ArtifactHeader::for_artifact(b"").encode(),
+		)
+		.unwrap();
+		std::fs::write(fake_cache_path.join("prepare-artifact-junk"), b"junk").unwrap();
+
+		let p = &fake_cache_path;
+		let mut artifacts = async_std::task::block_on(async { Artifacts::new(p).await });
+
+		let valid_id = ArtifactId::from_file_name(&format!("wasmtime_0x{}", valid_hash)).unwrap();
+		assert_eq!(artifacts.artifacts.len(), 1);
+		assert_matches::assert_matches!(
+			artifacts.artifact_state_mut(&valid_id),
+			Some(ArtifactState::Prepared { .. })
+		);
+
+		let mut remaining = std::fs::read_dir(&fake_cache_path)
+			.unwrap()
+			.map(|entry| entry.unwrap().file_name().into_string().unwrap())
+			.collect::<Vec<_>>();
+		remaining.sort();
+		assert_eq!(
+			remaining,
+			vec![format!("wasmtime_0x{}", valid_hash), format!("wasmtime_0x{}.header", valid_hash)],
+		);
+
+		std::fs::remove_dir_all(fake_cache_path).unwrap();
+	}
+
+	#[test]
+	fn artifacts_removes_invalid_cache_on_startup() {
 		let fake_cache_path = async_std::task::block_on(async move {
 			crate::worker_common::tmpfile("test-cache").await.unwrap()
 		});
@@ -244,12 +677,12 @@ mod tests {
 			p
 		};
 
-		// create a tmp cache with 1 artifact.
+		// create a tmp cache with 1 artifact that lacks a header.
 
 		std::fs::create_dir_all(&fake_cache_path).unwrap();
 		std::fs::File::create(fake_artifact_path).unwrap();
 
-		// this should remove it and re-create.
+		// this should remove it.
 
 		let p = &fake_cache_path;
 		async_std::task::block_on(async { Artifacts::new(p).await });
@@ -258,4 +691,111 @@ mod tests {
 
 		std::fs::remove_dir_all(fake_cache_path).unwrap();
 	}
+
+	#[test]
+	fn artifacts_recovers_failures_on_startup() {
+		let fake_cache_path = async_std::task::block_on(async move {
+			crate::worker_common::tmpfile("test-cache").await.unwrap()
+		});
+		std::fs::create_dir_all(&fake_cache_path).unwrap();
+
+		let artifact_id = ArtifactId::from_file_name(
+			"wasmtime_0x1234567890123456789012345678901234567890123456789012345678901234",
+		)
+		.unwrap();
+
+		let p = &fake_cache_path;
+		let mut artifacts = async_std::task::block_on(async {
+			write_failure(
+				p,
+				&artifact_id,
+				&PrepareError::TimedOut,
+				2,
+				std::time::SystemTime::now(),
+			)
+			.await
+			.unwrap();
+			Artifacts::new(p).await
+		});
+
+		assert_matches::assert_matches!(
+			artifacts.artifact_state_mut(&artifact_id),
+			Some(ArtifactState::FailedToProcess {
+				num_failures: 2,
+				error: PrepareError::TimedOut,
+				..
+			})
+		);
+
+		std::fs::remove_dir_all(fake_cache_path).unwrap();
+	}
+
+	#[test]
+	fn removed_failures_are_not_recovered() {
+		let fake_cache_path = async_std::task::block_on(async move {
+			crate::worker_common::tmpfile("test-cache").await.unwrap()
+		});
+		std::fs::create_dir_all(&fake_cache_path).unwrap();
+
+		let artifact_id = ArtifactId::from_file_name(
+			"wasmtime_0x1234567890123456789012345678901234567890123456789012345678901234",
+		)
+		.unwrap();
+
+		let p = &fake_cache_path;
+		let mut artifacts = async_std::task::block_on(async {
+			write_failure(p, &artifact_id, &PrepareError::TimedOut, 1, SystemTime::now())
+				.await
+				.unwrap();
+			remove_failure(p, &artifact_id).await;
+			// Removing a failure that is not recorded is fine.
+			remove_failure(p, &artifact_id).await;
+			Artifacts::new(p).await
+		});
+
+		assert!(artifacts.artifact_state_mut(&artifact_id).is_none());
+		assert!(!std::path::Path::new(&failure_path(&artifact_id.path(p))).exists());
+
+		std::fs::remove_dir_all(fake_cache_path).unwrap();
+	}
+
+	#[test]
+	fn deterministic_failures_are_pruned_once_no_longer_needed() {
+		let artifact_id = |i: u8| ArtifactId::new(H256::repeat_byte(i).into());
+		let long_ago = SystemTime::now() - Duration::from_secs(3600);
+		let ttl = Duration::from_secs(60);
+
+		let mut artifacts = Artifacts::empty();
+		artifacts.insert_failed(
+			artifact_id(1),
+			long_ago,
+			1,
+			PrepareError::Preparation("compilation failed".to_owned()),
+		);
+		artifacts.insert_failed(
+			artifact_id(2),
+			long_ago,
+			1,
+			PrepareError::Preparation("compilation failed".to_owned()),
+		);
+		artifacts.insert_failed(artifact_id(3), long_ago, 1, PrepareError::TimedOut);
+
+		// The first failure was needed recently, while the other ones were not.
+		if let Some(ArtifactState::FailedToProcess { last_time_needed, .. }) =
+			artifacts.artifact_state_mut(&artifact_id(1))
+		{
+			*last_time_needed = SystemTime::now();
+		}
+		if let Some(ArtifactState::FailedToProcess { last_time_needed, .. }) =
+			artifacts.artifact_state_mut(&artifact_id(3))
+		{
+			*last_time_needed = SystemTime::now();
+		}
+
+		let mut pruned = artifacts.prune(ttl);
+		pruned.sort();
+		// The non-deterministic failure ages since the last attempt, even if it is still needed.
+		assert_eq!(pruned, vec![artifact_id(2), artifact_id(3)]);
+		assert!(artifacts.artifact_state_mut(&artifact_id(1)).is_some());
+	}
 }
@@ -34,6 +34,26 @@ pub enum PrepareError {
 	/// This state indicates that the process assigned to prepare the artifact wasn't responsible
 	/// or were killed. This state is reported by the validation host (not by the worker).
 	DidNotMakeIt,
+	/// The preparation of the PVF allocated more memory than the protocol allows.
+	MemoryLimitExceeded,
+}
+
+impl PrepareError {
+	/// Returns whether this is a deterministic error, i.e. one that should trigger reliably. Those
+	/// errors depend on the PVF itself and the sc-executor/wasmtime logic.
+	///
+	/// Non-deterministic errors can happen spuriously. Typically, they occur due to resource
+	/// starvation, e.g. under heavy load or memory pressure. Those errors are typically transient
+	/// but may persist e.g. if the node is run by overwhelmingly underpowered machine.
+	pub fn is_deterministic(&self) -> bool {
+		match self {
+			Self::Prevalidation(_) |
+			Self::Preparation(_) |
+			Self::Panic(_) |
+			Self::MemoryLimitExceeded => true,
+			Self::TimedOut | Self::DidNotMakeIt => false,
+		}
+	}
 }
 
 /// A error raised during validation of the candidate.
@@ -75,6 +95,8 @@ pub enum InvalidCandidate {
 	AmbiguousWorkerDeath,
 	/// PVF execution (compilation is not included) took more time than was allotted.
 	HardTimeout,
+	/// The preparation or the execution of the PVF allocated more memory than the protocol allows.
+	MemoryLimitExceeded,
 }
 
 impl From<PrepareError> for ValidationError {
@@ -103,6 +125,8 @@ impl From<PrepareError> for ValidationError {
 			PrepareError::Panic(err) => ValidationError::InvalidCandidate(
 				InvalidCandidate::PrepareError(format!("panic: {}", err)),
 			),
+			PrepareError::MemoryLimitExceeded =>
+				ValidationError::InvalidCandidate(InvalidCandidate::MemoryLimitExceeded),
 			PrepareError::TimedOut => ValidationError::InternalError("prepare: timeout".to_owned()),
 			PrepareError::DidNotMakeIt =>
 				ValidationError::InternalError("prepare: did not make it".to_owned()),
@@ -123,3 +147,17 @@ pub(crate) fn stringify_panic_payload(payload: Box<dyn Any + Send + 'static>) ->
 		},
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn prepare_error_encoding_is_stable() {
+		// The errors are persisted in the artifact cache and exchanged with the workers, so the
+		// variants must keep their indices.
+		assert_eq!(PrepareError::TimedOut.encode(), vec![3]);
+		assert_eq!(PrepareError::DidNotMakeIt.encode(), vec![4]);
+		assert_eq!(PrepareError::MemoryLimitExceeded.encode(), vec![5]);
+	}
+}
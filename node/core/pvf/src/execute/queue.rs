@@ -22,7 +22,7 @@ use crate::{
 	host::ResultSender,
 	metrics::Metrics,
 	worker_common::{IdleWorker, WorkerHandle},
-	InvalidCandidate, ValidationError, LOG_TARGET,
+	InvalidCandidate, Priority, PriorityClasses, ValidationError, LOG_TARGET,
 };
 use async_std::path::PathBuf;
 use futures::{
@@ -32,7 +32,11 @@ use futures::{
 	Future, FutureExt,
 };
 use slotmap::HopSlotMap;
-use std::{collections::VecDeque, fmt, time::Duration};
+use std::{
+	collections::VecDeque,
+	fmt,
+	time::{Duration, Instant},
+};
 
 slotmap::new_key_type! { struct Worker; }
 
@@ -42,6 +46,7 @@ pub enum ToQueue {
 		artifact: ArtifactPathId,
 		execution_timeout: Duration,
 		params: Vec<u8>,
+		priority: Priority,
 		result_tx: ResultSender,
 	},
 }
@@ -50,12 +55,17 @@ struct ExecuteJob {
 	artifact: ArtifactPathId,
 	execution_timeout: Duration,
 	params: Vec<u8>,
+	priority: Priority,
+	/// The time the job was enqueued at.
+	waiting_since: Instant,
 	result_tx: ResultSender,
 }
 
 struct WorkerData {
 	idle: Option<IdleWorker>,
 	handle: WorkerHandle,
+	/// The priority of the job the worker is busy with, if any.
+	job_priority: Option<Priority>,
 }
 
 impl fmt::Debug for WorkerData {
@@ -86,11 +96,98 @@ impl Workers {
 			.find_map(|d| if d.1.idle.is_some() { Some(d.0) } else { None })
 	}
 
-	/// Find the associated data by the worker token and extract it's [`IdleWorker`] token.
+	/// Find the associated data by the worker token and extract it's [`IdleWorker`] token, marking
+	/// the worker as busy with a job of the given priority.
 	///
 	/// Returns `None` if either worker is not recognized or idle token is absent.
-	fn claim_idle(&mut self, worker: Worker) -> Option<IdleWorker> {
-		self.running.get_mut(worker)?.idle.take()
+	fn claim_idle(&mut self, worker: Worker, priority: Priority) -> Option<IdleWorker> {
+		let data = self.running.get_mut(worker)?;
+		let idle = data.idle.take()?;
+		data.job_priority = Some(priority);
+		Some(idle)
+	}
+
+	/// The number of workers busy with the jobs of the given priority.
+	fn busy_with(&self, priority: Priority) -> usize {
+		self.running.values().filter(|data| data.job_priority == Some(priority)).count()
+	}
+}
+
+/// All priorities, from the highest to the lowest.
+const PRIORITIES: [Priority; 3] = [Priority::Critical, Priority::Normal, Priority::Background];
+
+/// The jobs that are waiting for a worker to pick up.
+///
+/// The next job is picked by smooth weighted round-robin between the priorities which have jobs
+/// waiting and may occupy one more worker. That is, every such priority earns credit by its weight
+/// on each pick and the one with the most credit is picked, paying back the sum of the weights.
+struct Unscheduled {
+	classes: PriorityClasses,
+	/// The maximum number of workers, against which the reservations are applied.
+	capacity: usize,
+	/// The jobs, indexed by their priority.
+	jobs: [VecDeque<ExecuteJob>; 3],
+	/// The credit of every priority, indexed by the priority.
+	credits: [i64; 3],
+}
+
+impl Unscheduled {
+	fn new(classes: PriorityClasses, capacity: usize) -> Self {
+		Self { classes, capacity, jobs: Default::default(), credits: [0; 3] }
+	}
+
+	fn add(&mut self, job: ExecuteJob) {
+		self.jobs[job.priority as usize].push_back(job);
+	}
+
+	fn is_empty(&self) -> bool {
+		self.jobs.iter().all(VecDeque::is_empty)
+	}
+
+	/// The number of workers the jobs of the given priority and the lower ones can occupy at once.
+	fn allowance(&self, priority: Priority) -> usize {
+		let reserved: usize = PRIORITIES
+			.iter()
+			.filter(|p| **p > priority)
+			.map(|p| self.classes.get(*p).reserved_workers)
+			.sum();
+		self.capacity.saturating_sub(reserved).max(1)
+	}
+
+	/// Picks the next job for an idle worker. `busy` returns the number of workers busy with the
+	/// jobs of the given priority.
+	///
+	/// Returns `None` if there are no jobs which may be started now.
+	fn next(&mut self, busy: impl Fn(Priority) -> usize) -> Option<ExecuteJob> {
+		let eligible: Vec<Priority> = PRIORITIES
+			.iter()
+			.copied()
+			.filter(|p| !self.jobs[*p as usize].is_empty())
+			.filter(|p| {
+				let occupied: usize =
+					PRIORITIES.iter().filter(|q| **q <= *p).map(|q| busy(*q)).sum();
+				occupied < self.allowance(*p)
+			})
+			.collect();
+
+		let mut total_weight = 0;
+		for priority in &eligible {
+			let weight = i64::from(self.classes.get(*priority).weight);
+			self.credits[*priority as usize] += weight;
+			total_weight += weight;
+		}
+
+		// Ties are resolved in favor of the higher priority.
+		let picked = eligible.into_iter().max_by_key(|p| (self.credits[*p as usize], *p))?;
+		self.credits[picked as usize] -= total_weight;
+
+		let jobs = &mut self.jobs[picked as usize];
+		let job = jobs.pop_front();
+		if jobs.is_empty() {
+			// Don't carry the credit over to the next time the priority has jobs waiting.
+			self.credits[picked as usize] = 0;
+		}
+		job
 	}
 }
 
@@ -111,7 +208,7 @@ struct Queue {
 	spawn_timeout: Duration,
 
 	/// The queue of jobs that are waiting for a worker to pick up.
-	queue: VecDeque<ExecuteJob>,
+	unscheduled: Unscheduled,
 	workers: Workers,
 	mux: Mux,
 }
@@ -121,6 +218,7 @@ impl Queue {
 		metrics: Metrics,
 		program_path: PathBuf,
 		worker_capacity: usize,
+		priority_classes: PriorityClasses,
 		spawn_timeout: Duration,
 		to_queue_rx: mpsc::Receiver<ToQueue>,
 	) -> Self {
@@ -129,7 +227,7 @@ impl Queue {
 			program_path,
 			spawn_timeout,
 			to_queue_rx,
-			queue: VecDeque::new(),
+			unscheduled: Unscheduled::new(priority_classes, worker_capacity),
 			mux: Mux::new(),
 			workers: Workers {
 				running: HopSlotMap::with_capacity_and_key(10),
@@ -173,22 +271,38 @@ async fn purge_dead(metrics: &Metrics, workers: &mut Workers) {
 }
 
 fn handle_to_queue(queue: &mut Queue, to_queue: ToQueue) {
-	let ToQueue::Enqueue { artifact, execution_timeout, params, result_tx } = to_queue;
+	let ToQueue::Enqueue { artifact, execution_timeout, params, priority, result_tx } = to_queue;
 	gum::debug!(
 		target: LOG_TARGET,
 		validation_code_hash = ?artifact.id.code_hash,
+		?priority,
 		"enqueueing an artifact for execution",
 	);
 	queue.metrics.execute_enqueued();
-	let job = ExecuteJob { artifact, execution_timeout, params, result_tx };
+	let job = ExecuteJob {
+		artifact,
+		execution_timeout,
+		params,
+		priority,
+		waiting_since: Instant::now(),
+		result_tx,
+	};
+	queue.unscheduled.add(job);
 
-	if let Some(available) = queue.workers.find_available() {
-		assign(queue, available, job);
-	} else {
-		if queue.workers.can_afford_one_more() {
-			spawn_extra_worker(queue);
+	if queue.workers.find_available().is_none() && queue.workers.can_afford_one_more() {
+		spawn_extra_worker(queue);
+	}
+	schedule(queue);
+}
+
+/// Assigns the waiting jobs to the available workers, as far as the reservations allow.
+fn schedule(queue: &mut Queue) {
+	while let Some(worker) = queue.workers.find_available() {
+		let workers = &queue.workers;
+		match queue.unscheduled.next(|priority| workers.busy_with(priority)) {
+			Some(job) => assign(queue, worker, job),
+			None => break,
 		}
-		queue.queue.push_back(job);
 	}
 }
 
@@ -206,17 +320,19 @@ async fn handle_mux(queue: &mut Queue, event: QueueEvent) {
 fn handle_worker_spawned(queue: &mut Queue, idle: IdleWorker, handle: WorkerHandle) {
 	queue.metrics.execute_worker().on_spawned();
 	queue.workers.spawn_inflight -= 1;
-	let worker = queue.workers.running.insert(WorkerData { idle: Some(idle), handle });
+	let worker =
+		queue
+			.workers
+			.running
+			.insert(WorkerData { idle: Some(idle), handle, job_priority: None });
 
 	gum::debug!(target: LOG_TARGET, ?worker, "execute worker spawned");
 
-	if let Some(job) = queue.queue.pop_front() {
-		assign(queue, worker, job);
-	}
+	schedule(queue);
 }
 
-/// If there are pending jobs in the queue, schedules the next of them onto the just freed up
-/// worker. Otherwise, puts back into the available workers list.
+/// Puts the just freed up worker back into the available workers list and schedules the pending
+/// jobs, if any.
 fn handle_job_finish(
 	queue: &mut Queue,
 	worker: Worker,
@@ -239,6 +355,8 @@ fn handle_job_finish(
 			(Some(idle_worker), Err(ValidationError::InternalError(err))),
 		Outcome::HardTimeout =>
 			(None, Err(ValidationError::InvalidCandidate(InvalidCandidate::HardTimeout))),
+		Outcome::MemoryLimitExceeded =>
+			(None, Err(ValidationError::InvalidCandidate(InvalidCandidate::MemoryLimitExceeded))),
 		Outcome::IoErr =>
 			(None, Err(ValidationError::InvalidCandidate(InvalidCandidate::AmbiguousWorkerDeath))),
 	};
@@ -266,10 +384,7 @@ fn handle_job_finish(
 	if let Some(idle_worker) = idle_worker {
 		if let Some(data) = queue.workers.running.get_mut(worker) {
 			data.idle = Some(idle_worker);
-
-			if let Some(job) = queue.queue.pop_front() {
-				assign(queue, worker, job);
-			}
+			data.job_priority = None;
 		}
 	} else {
 		// Note it's possible that the worker was purged already by `purge_dead`
@@ -277,13 +392,16 @@ fn handle_job_finish(
 			queue.metrics.execute_worker().on_retired();
 		}
 
-		if !queue.queue.is_empty() {
+		if !queue.unscheduled.is_empty() {
 			// The worker has died and we still have work we have to do. Request an extra worker.
 			//
 			// That can potentially overshoot, but that should be OK.
 			spawn_extra_worker(queue);
 		}
 	}
+
+	// Either way, a job of some priority is done, which might have unblocked the waiting ones.
+	schedule(queue);
 }
 
 fn spawn_extra_worker(queue: &mut Queue) {
@@ -320,10 +438,14 @@ fn assign(queue: &mut Queue, worker: Worker, job: ExecuteJob) {
 		target: LOG_TARGET,
 		validation_code_hash = ?job.artifact.id,
 		?worker,
+		priority = ?job.priority,
 		"assigning the execute worker",
 	);
 
-	let idle = queue.workers.claim_idle(worker).expect(
+	queue
+		.metrics
+		.observe_execution_queued_time(job.priority, job.waiting_since.elapsed());
+	let idle = queue.workers.claim_idle(worker, job.priority).expect(
 		"this caller must supply a worker which is idle and running;
 			thus claim_idle cannot return None;
 			qed.",
@@ -349,9 +471,100 @@ pub fn start(
 	metrics: Metrics,
 	program_path: PathBuf,
 	worker_capacity: usize,
+	priority_classes: PriorityClasses,
 	spawn_timeout: Duration,
 ) -> (mpsc::Sender<ToQueue>, impl Future<Output = ()>) {
 	let (to_queue_tx, to_queue_rx) = mpsc::channel(20);
-	let run = Queue::new(metrics, program_path, worker_capacity, spawn_timeout, to_queue_rx).run();
+	let run = Queue::new(
+		metrics,
+		program_path,
+		worker_capacity,
+		priority_classes,
+		spawn_timeout,
+		to_queue_rx,
+	)
+	.run();
 	(to_queue_tx, run)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{PriorityClass, Pvf};
+	use async_std::path::Path;
+	use futures::channel::oneshot;
+
+	fn job(priority: Priority) -> ExecuteJob {
+		let (result_tx, _) = oneshot::channel();
+		ExecuteJob {
+			artifact: ArtifactPathId::new(
+				Pvf::from_discriminator(1).as_artifact_id(),
+				Path::new("/tmp"),
+			),
+			execution_timeout: Duration::from_secs(1),
+			params: Vec::new(),
+			priority,
+			waiting_since: Instant::now(),
+			result_tx,
+		}
+	}
+
+	fn unscheduled(classes: PriorityClasses, capacity: usize, jobs: &[Priority]) -> Unscheduled {
+		let mut unscheduled = Unscheduled::new(classes, capacity);
+		for priority in jobs {
+			unscheduled.add(job(*priority));
+		}
+		unscheduled
+	}
+
+	#[test]
+	fn priorities_are_picked_by_weight() {
+		let jobs = [[Priority::Background, Priority::Normal, Priority::Critical]; 7].concat();
+		let mut unscheduled = unscheduled(PriorityClasses::default(), 10, &jobs);
+
+		let mut picked = Vec::new();
+		for _ in 0..7 {
+			picked.push(unscheduled.next(|_| 0).unwrap().priority);
+		}
+
+		let count = |priority: Priority| picked.iter().filter(|p| **p == priority).count();
+		assert_eq!(count(Priority::Critical), 4);
+		assert_eq!(count(Priority::Normal), 2);
+		assert_eq!(count(Priority::Background), 1);
+		// The highest priority goes first.
+		assert_eq!(picked[0], Priority::Critical);
+	}
+
+	#[test]
+	fn lower_priorities_do_not_take_reserved_workers() {
+		// By default, one worker is kept for the normal and critical jobs.
+		let mut unscheduled = unscheduled(
+			PriorityClasses::default(),
+			2,
+			&[Priority::Background, Priority::Background],
+		);
+
+		let busy_background = |p: Priority| if p == Priority::Background { 1 } else { 0 };
+		assert!(unscheduled.next(busy_background).is_none());
+
+		unscheduled.add(job(Priority::Normal));
+		let picked = unscheduled.next(busy_background).unwrap();
+		assert_eq!(picked.priority, Priority::Normal);
+
+		// Without busy workers, the background jobs may run again.
+		assert_eq!(unscheduled.next(|_| 0).unwrap().priority, Priority::Background);
+	}
+
+	#[test]
+	fn reservations_leave_at_least_one_worker() {
+		let classes = PriorityClasses {
+			critical: PriorityClass { weight: 1, reserved_workers: 5 },
+			..Default::default()
+		};
+		let mut unscheduled = unscheduled(classes, 2, &[Priority::Normal, Priority::Normal]);
+
+		assert_eq!(unscheduled.next(|_| 0).unwrap().priority, Priority::Normal);
+		let busy_normal = |p: Priority| if p == Priority::Normal { 1 } else { 0 };
+		assert!(unscheduled.next(busy_normal).is_none());
+	}
+}
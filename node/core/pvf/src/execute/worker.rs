@@ -18,8 +18,8 @@ use crate::{
 	artifacts::ArtifactPathId,
 	executor_intf::Executor,
 	worker_common::{
-		bytes_to_path, framed_recv, framed_send, path_to_bytes, spawn_with_program_path,
-		worker_event_loop, IdleWorker, SpawnErr, WorkerHandle,
+		bytes_to_path, framed_recv, framed_send, path_to_bytes, run_with_memory_limit,
+		spawn_with_program_path, worker_event_loop, IdleWorker, SpawnErr, WorkerHandle,
 	},
 	LOG_TARGET,
 };
@@ -32,7 +32,16 @@ use futures::FutureExt;
 use futures_timer::Delay;
 use parity_scale_codec::{Decode, Encode};
 use polkadot_parachain::primitives::ValidationResult;
-use std::time::{Duration, Instant};
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// The limit of the memory allocated by the execution of a PVF, in bytes.
+///
+/// The linear memory of the wasm instance is not included, the executor bounds it on its own.
+/// Exceeding the limit makes the candidate invalid, so it must be the same for all validators.
+const EXECUTE_MEMORY_LIMIT: u64 = 1024 * 1024 * 1024;
 
 /// Spawns a new worker with the given program path that acts as the worker and the spawn timeout.
 ///
@@ -57,6 +66,8 @@ pub enum Outcome {
 	InternalError { err: String, idle_worker: IdleWorker },
 	/// The execution time exceeded the hard limit. The worker is terminated.
 	HardTimeout,
+	/// The execution exceeded the memory limit. The worker is terminated.
+	MemoryLimitExceeded,
 	/// An I/O error happened during communication with the worker. This may mean that the worker
 	/// process already died. The token is not returned in any case.
 	IoErr,
@@ -125,6 +136,7 @@ pub async fn start_work(
 			Outcome::InvalidCandidate { err, idle_worker: IdleWorker { stream, pid } },
 		Response::InternalError(err) =>
 			Outcome::InternalError { err, idle_worker: IdleWorker { stream, pid } },
+		Response::MemoryLimitExceeded => Outcome::MemoryLimitExceeded,
 	}
 }
 
@@ -168,6 +180,7 @@ enum Response {
 	Ok { result_descriptor: ValidationResult, duration_ms: u64 },
 	InvalidCandidate(String),
 	InternalError(String),
+	MemoryLimitExceeded,
 }
 
 impl Response {
@@ -184,9 +197,9 @@ impl Response {
 /// the path to the socket used to communicate with the host.
 pub fn worker_entrypoint(socket_path: &str) {
 	worker_event_loop("execute", socket_path, |mut stream| async move {
-		let executor = Executor::new().map_err(|e| {
+		let executor = Arc::new(Executor::new().map_err(|e| {
 			io::Error::new(io::ErrorKind::Other, format!("cannot create executor: {}", e))
-		})?;
+		})?);
 		loop {
 			let (artifact_path, params) = recv_request(&mut stream).await?;
 			gum::debug!(
@@ -195,35 +208,107 @@ pub fn worker_entrypoint(socket_path: &str) {
 				"worker: validating artifact {}",
 				artifact_path.display(),
 			);
-			let response = validate_using_artifact(&artifact_path, &params, &executor).await;
+			let response =
+				validate_using_artifact(artifact_path, params, EXECUTE_MEMORY_LIMIT, &executor)
+					.await?;
+			let memory_limit_exceeded = matches!(response, Response::MemoryLimitExceeded);
 			send_response(&mut stream, response).await?;
+			if memory_limit_exceeded {
+				// The execution might still be running, so the worker must not be used anymore.
+				return Err(io::Error::new(io::ErrorKind::Other, "memory limit exceeded"))
+			}
 		}
 	});
 }
 
 async fn validate_using_artifact(
-	artifact_path: &Path,
-	params: &[u8],
-	executor: &Executor,
-) -> Response {
+	artifact_path: PathBuf,
+	params: Vec<u8>,
+	memory_limit: u64,
+	executor: &Arc<Executor>,
+) -> io::Result<Response> {
 	let validation_started_at = Instant::now();
-	let descriptor_bytes = match unsafe {
+	let executor = executor.clone();
+	let execution = run_with_memory_limit(memory_limit, move || unsafe {
 		// SAFETY: this should be safe since the compiled artifact passed here comes from the
 		//         file created by the prepare workers. These files are obtained by calling
 		//         [`executor_intf::prepare`].
-		executor.execute(artifact_path.as_ref(), params)
-	} {
-		Err(err) => return Response::format_invalid("execute", &err.to_string()),
-		Ok(d) => d,
+		executor.execute(artifact_path.as_ref(), &params)
+	});
+	let descriptor_bytes = match execution.await? {
+		None => return Ok(Response::MemoryLimitExceeded),
+		Some(Err(err)) => return Ok(Response::format_invalid("execute", &err.to_string())),
+		Some(Ok(d)) => d,
 	};
 
 	let duration_ms = validation_started_at.elapsed().as_millis() as u64;
 
 	let result_descriptor = match ValidationResult::decode(&mut &descriptor_bytes[..]) {
 		Err(err) =>
-			return Response::InvalidCandidate(format!("validation result decoding failed: {}", err)),
+			return Ok(Response::InvalidCandidate(format!(
+				"validation result decoding failed: {}",
+				err
+			))),
 		Ok(r) => r,
 	};
 
-	Response::Ok { result_descriptor, duration_ms }
+	Ok(Response::Ok { result_descriptor, duration_ms })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use adder::{hash_state, BlockData, HeadData};
+	use polkadot_parachain::primitives::{
+		BlockData as GenericBlockData, HeadData as GenericHeadData, ValidationParams,
+	};
+
+	fn adder_artifact(dir: &std::path::Path) -> PathBuf {
+		let code =
+			sp_maybe_compressed_blob::decompress(adder::wasm_binary_unwrap(), 10 * 1024 * 1024)
+				.unwrap();
+		let blob = crate::executor_intf::prevalidate(&code).unwrap();
+		let artifact = crate::executor_intf::prepare(blob).unwrap();
+		let artifact_path = dir.join("artifact");
+		std::fs::write(&artifact_path, &artifact).unwrap();
+		artifact_path.into()
+	}
+
+	fn adder_params() -> Vec<u8> {
+		let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+		let block_data = BlockData { state: 0, add: 512 };
+		ValidationParams {
+			parent_head: GenericHeadData(parent_head.encode()),
+			block_data: GenericBlockData(block_data.encode()),
+			relay_parent_number: 1,
+			relay_parent_storage_root: Default::default(),
+		}
+		.encode()
+	}
+
+	#[async_std::test]
+	async fn execution_within_memory_limit_succeeds() {
+		let dir = tempfile::tempdir().unwrap();
+		let executor = Arc::new(Executor::new().unwrap());
+		let response = validate_using_artifact(
+			adder_artifact(dir.path()),
+			adder_params(),
+			EXECUTE_MEMORY_LIMIT,
+			&executor,
+		)
+		.await
+		.unwrap();
+		assert!(matches!(response, Response::Ok { .. }));
+	}
+
+	#[async_std::test]
+	async fn execution_exceeding_memory_limit_is_invalid() {
+		let dir = tempfile::tempdir().unwrap();
+		let executor = Arc::new(Executor::new().unwrap());
+		let response =
+			validate_using_artifact(adder_artifact(dir.path()), adder_params(), 1024, &executor)
+				.await
+				.unwrap();
+		assert!(matches!(response, Response::MemoryLimitExceeded));
+	}
 }
@@ -85,6 +85,25 @@ const CONFIG: Config = Config {
 	},
 };
 
+/// Returns the fingerprint of the executor configuration that affects the compiled artifacts.
+///
+/// The artifacts prepared with a different configuration are not compatible and must not be reused.
+pub fn config_fingerprint() -> [u8; 32] {
+	let semantics = &CONFIG.semantics;
+	let stack_limit = semantics
+		.deterministic_stack_limit
+		.as_ref()
+		.map(|limit| (limit.logical_max, limit.native_stack_max));
+	let fingerprint = (
+		semantics.extra_heap_pages,
+		semantics.max_memory_size.map(|size| size as u64),
+		stack_limit,
+		semantics.canonicalize_nans,
+		semantics.parallel_compilation,
+	);
+	sp_core::hashing::blake2_256(&parity_scale_codec::Encode::encode(&fingerprint))
+}
+
 /// Runs the prevalidation on the given code. Returns a [`RuntimeBlob`] if it succeeds.
 pub fn prevalidate(code: &[u8]) -> Result<RuntimeBlob, sc_executor_common::error::WasmError> {
 	let blob = RuntimeBlob::new(code)?;
@@ -176,16 +195,20 @@ impl Executor {
 		params: &[u8],
 	) -> Result<Vec<u8>, String> {
 		let spawner = self.spawner.clone();
+		let job = crate::worker_common::current_job();
 		let mut result = None;
 		self.thread_pool.scope({
 			let result = &mut result;
 			move |s| {
 				s.spawn(move |_| {
 					// spawn does not return a value, so we need to use a variable to pass the result.
-					*result = Some(
+					//
+					// The execution happens on this thread on behalf of the job of the worker, so its
+					// allocations count towards the memory limit of the job.
+					*result = Some(crate::worker_common::track_memory(job, || {
 						do_execute(compiled_artifact_path, params, spawner)
-							.map_err(|err| format!("execute error: {:?}", err)),
-					);
+							.map_err(|err| format!("execute error: {:?}", err))
+					}));
 				});
 			}
 		});
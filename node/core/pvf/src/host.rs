@@ -21,10 +21,13 @@
 //! [`ValidationHost`], that allows communication with that event-loop.
 
 use crate::{
-	artifacts::{ArtifactId, ArtifactPathId, ArtifactState, Artifacts},
+	artifacts::{
+		self, failure_path, header_path, ArtifactId, ArtifactPathId, ArtifactState, Artifacts,
+	},
 	execute,
 	metrics::Metrics,
-	prepare, PrepareResult, Priority, Pvf, ValidationError, LOG_TARGET,
+	prepare, PrepareError, PrepareResult, Priority, PriorityClasses, Pvf, ValidationError,
+	LOG_TARGET,
 };
 use always_assert::never;
 use async_std::path::{Path, PathBuf};
@@ -140,6 +143,12 @@ pub struct Config {
 	pub execute_worker_spawn_timeout: Duration,
 	/// The maximum number of execute workers that can run at the same time.
 	pub execute_workers_max_num: usize,
+	/// How the execute workers are shared between the execution priorities.
+	pub execute_priority_classes: PriorityClasses,
+	/// The time after which the preparation that failed with a non-deterministic error is retried.
+	///
+	/// The backoff doubles with every consecutive failure of the same artifact.
+	pub prepare_failure_backoff: Duration,
 }
 
 impl Config {
@@ -158,6 +167,8 @@ impl Config {
 			execute_worker_program_path: program_path,
 			execute_worker_spawn_timeout: Duration::from_secs(3),
 			execute_workers_max_num: 2,
+			execute_priority_classes: PriorityClasses::default(),
+			prepare_failure_backoff: Duration::from_secs(60 * 10),
 		}
 	}
 }
@@ -195,6 +206,7 @@ pub fn start(config: Config, metrics: Metrics) -> (ValidationHost, impl Future<O
 		metrics.clone(),
 		config.execute_worker_program_path.to_owned(),
 		config.execute_workers_max_num,
+		config.execute_priority_classes,
 		config.execute_worker_spawn_timeout,
 	);
 
@@ -208,6 +220,7 @@ pub fn start(config: Config, metrics: Metrics) -> (ValidationHost, impl Future<O
 			cache_path: config.cache_path,
 			cleanup_pulse_interval: Duration::from_secs(3600),
 			artifact_ttl: Duration::from_secs(3600 * 24),
+			prepare_failure_backoff: config.prepare_failure_backoff,
 			artifacts,
 			to_host_rx,
 			to_prepare_queue_tx,
@@ -239,6 +252,7 @@ pub fn start(config: Config, metrics: Metrics) -> (ValidationHost, impl Future<O
 struct PendingExecutionRequest {
 	execution_timeout: Duration,
 	params: Vec<u8>,
+	priority: Priority,
 	result_tx: ResultSender,
 }
 
@@ -253,11 +267,13 @@ impl AwaitingPrepare {
 		artifact_id: ArtifactId,
 		execution_timeout: Duration,
 		params: Vec<u8>,
+		priority: Priority,
 		result_tx: ResultSender,
 	) {
 		self.0.entry(artifact_id).or_default().push(PendingExecutionRequest {
 			execution_timeout,
 			params,
+			priority,
 			result_tx,
 		});
 	}
@@ -271,6 +287,7 @@ struct Inner {
 	cache_path: PathBuf,
 	cleanup_pulse_interval: Duration,
 	artifact_ttl: Duration,
+	prepare_failure_backoff: Duration,
 	artifacts: Artifacts,
 
 	to_host_rx: mpsc::Receiver<ToHost>,
@@ -292,6 +309,7 @@ async fn run(
 		cache_path,
 		cleanup_pulse_interval,
 		artifact_ttl,
+		prepare_failure_backoff,
 		mut artifacts,
 		to_host_rx,
 		from_prepare_queue_rx,
@@ -351,6 +369,7 @@ async fn run(
 
 				break_if_fatal!(handle_to_host(
 					&cache_path,
+					prepare_failure_backoff,
 					&mut artifacts,
 					&mut to_prepare_queue_tx,
 					&mut to_execute_queue_tx,
@@ -385,6 +404,7 @@ async fn run(
 
 async fn handle_to_host(
 	cache_path: &Path,
+	prepare_failure_backoff: Duration,
 	artifacts: &mut Artifacts,
 	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
 	execute_queue: &mut mpsc::Sender<execute::ToQueue>,
@@ -393,11 +413,13 @@ async fn handle_to_host(
 ) -> Result<(), Fatal> {
 	match to_host {
 		ToHost::PrecheckPvf { pvf, result_tx } => {
-			handle_precheck_pvf(artifacts, prepare_queue, pvf, result_tx).await?;
+			handle_precheck_pvf(prepare_failure_backoff, artifacts, prepare_queue, pvf, result_tx)
+				.await?;
 		},
 		ToHost::ExecutePvf { pvf, execution_timeout, params, priority, result_tx } => {
 			handle_execute_pvf(
 				cache_path,
+				prepare_failure_backoff,
 				artifacts,
 				prepare_queue,
 				execute_queue,
@@ -411,7 +433,7 @@ async fn handle_to_host(
 			.await?;
 		},
 		ToHost::HeadsUp { active_pvfs } => {
-			handle_heads_up(artifacts, prepare_queue, active_pvfs).await?;
+			handle_heads_up(prepare_failure_backoff, artifacts, prepare_queue, active_pvfs).await?;
 		},
 	}
 
@@ -419,6 +441,7 @@ async fn handle_to_host(
 }
 
 async fn handle_precheck_pvf(
+	prepare_failure_backoff: Duration,
 	artifacts: &mut Artifacts,
 	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
 	pvf: Pvf,
@@ -432,11 +455,33 @@ async fn handle_precheck_pvf(
 				*last_time_needed = SystemTime::now();
 				let _ = result_sender.send(Ok(()));
 			},
-			ArtifactState::Preparing { waiting_for_response } =>
+			ArtifactState::Preparing { waiting_for_response, num_failures: _ } =>
 				waiting_for_response.push(result_sender),
-			ArtifactState::FailedToProcess(result) => {
-				let _ = result_sender.send(PrepareResult::Err(result.clone()));
-			},
+			ArtifactState::FailedToProcess {
+				last_time_failed,
+				last_time_needed,
+				num_failures,
+				error,
+			} =>
+				if can_retry_prepare_after_failure(
+					*last_time_failed,
+					*num_failures,
+					error,
+					prepare_failure_backoff,
+				) {
+					*state = ArtifactState::Preparing {
+						waiting_for_response: vec![result_sender],
+						num_failures: *num_failures,
+					};
+					send_prepare(
+						prepare_queue,
+						prepare::ToQueue::Enqueue { priority: Priority::Normal, pvf },
+					)
+					.await?;
+				} else {
+					*last_time_needed = SystemTime::now();
+					let _ = result_sender.send(PrepareResult::Err(error.clone()));
+				},
 		}
 	} else {
 		artifacts.insert_preparing(artifact_id, vec![result_sender]);
@@ -448,6 +493,7 @@ async fn handle_precheck_pvf(
 
 async fn handle_execute_pvf(
 	cache_path: &Path,
+	prepare_failure_backoff: Duration,
 	artifacts: &mut Artifacts,
 	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
 	execute_queue: &mut mpsc::Sender<execute::ToQueue>,
@@ -471,17 +517,45 @@ async fn handle_execute_pvf(
 						artifact: ArtifactPathId::new(artifact_id, cache_path),
 						execution_timeout,
 						params,
+						priority,
 						result_tx,
 					},
 				)
 				.await?;
 			},
-			ArtifactState::Preparing { waiting_for_response: _ } => {
-				awaiting_prepare.add(artifact_id, execution_timeout, params, result_tx);
-			},
-			ArtifactState::FailedToProcess(error) => {
-				let _ = result_tx.send(Err(ValidationError::from(error.clone())));
+			ArtifactState::Preparing { waiting_for_response: _, num_failures: _ } => {
+				awaiting_prepare.add(artifact_id, execution_timeout, params, priority, result_tx);
 			},
+			ArtifactState::FailedToProcess {
+				last_time_failed,
+				last_time_needed,
+				num_failures,
+				error,
+			} =>
+				if can_retry_prepare_after_failure(
+					*last_time_failed,
+					*num_failures,
+					error,
+					prepare_failure_backoff,
+				) {
+					*state = ArtifactState::Preparing {
+						waiting_for_response: Vec::new(),
+						num_failures: *num_failures,
+					};
+					send_prepare(prepare_queue, prepare::ToQueue::Enqueue { priority, pvf })
+						.await?;
+
+					awaiting_prepare.add(
+						artifact_id,
+						execution_timeout,
+						params,
+						priority,
+						result_tx,
+					);
+				} else {
+					*last_time_needed = SystemTime::now();
+					let _ = result_tx.send(Err(ValidationError::from(error.clone())));
+				},
 		}
 	} else {
 		// Artifact is unknown: register it and enqueue a job with the corresponding priority and
@@ -489,13 +563,14 @@ async fn handle_execute_pvf(
 		artifacts.insert_preparing(artifact_id.clone(), Vec::new());
 		send_prepare(prepare_queue, prepare::ToQueue::Enqueue { priority, pvf }).await?;
 
-		awaiting_prepare.add(artifact_id, execution_timeout, params, result_tx);
+		awaiting_prepare.add(artifact_id, execution_timeout, params, priority, result_tx);
 	}
 
 	return Ok(())
 }
 
 async fn handle_heads_up(
+	prepare_failure_backoff: Duration,
 	artifacts: &mut Artifacts,
 	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
 	active_pvfs: Vec<Pvf>,
@@ -509,10 +584,36 @@ async fn handle_heads_up(
 				ArtifactState::Prepared { last_time_needed, .. } => {
 					*last_time_needed = now;
 				},
-				ArtifactState::Preparing { waiting_for_response: _ } => {
+				ArtifactState::Preparing { waiting_for_response: _, num_failures: _ } => {
 					// The artifact is already being prepared, so we don't need to do anything.
 				},
-				ArtifactState::FailedToProcess(_) => {},
+				ArtifactState::FailedToProcess {
+					last_time_failed,
+					last_time_needed,
+					num_failures,
+					error,
+				} =>
+					if can_retry_prepare_after_failure(
+						*last_time_failed,
+						*num_failures,
+						error,
+						prepare_failure_backoff,
+					) {
+						*state = ArtifactState::Preparing {
+							waiting_for_response: Vec::new(),
+							num_failures: *num_failures,
+						};
+						send_prepare(
+							prepare_queue,
+							prepare::ToQueue::Enqueue {
+								priority: Priority::Normal,
+								pvf: active_pvf,
+							},
+						)
+						.await?;
+					} else {
+						*last_time_needed = now;
+					},
 			}
 		} else {
 			// It's not in the artifacts, so we need to enqueue a job to prepare it.
@@ -558,16 +659,21 @@ async fn handle_prepare_done(
 			never!("the artifact is already prepared: {:?}", artifact_id);
 			return Ok(())
 		},
-		Some(ArtifactState::FailedToProcess(_)) => {
+		Some(ArtifactState::FailedToProcess { .. }) => {
 			// The reasoning is similar to the above, the artifact cannot be
 			// processed at this point.
 			never!("the artifact is already processed unsuccessfully: {:?}", artifact_id);
 			return Ok(())
 		},
-		Some(state @ ArtifactState::Preparing { waiting_for_response: _ }) => state,
+		Some(state @ ArtifactState::Preparing { waiting_for_response: _, num_failures: _ }) =>
+			state,
 	};
 
-	if let ArtifactState::Preparing { waiting_for_response } = state {
+	let mut num_failures = 0;
+	if let ArtifactState::Preparing { waiting_for_response, num_failures: previous_failures } =
+		state
+	{
+		num_failures = *previous_failures;
 		for result_sender in waiting_for_response.drain(..) {
 			let _ = result_sender.send(result.clone());
 		}
@@ -576,7 +682,9 @@ async fn handle_prepare_done(
 	// It's finally time to dispatch all the execution requests that were waiting for this artifact
 	// to be prepared.
 	let pending_requests = awaiting_prepare.take(&artifact_id);
-	for PendingExecutionRequest { execution_timeout, params, result_tx } in pending_requests {
+	for PendingExecutionRequest { execution_timeout, params, priority, result_tx } in
+		pending_requests
+	{
 		if result_tx.is_canceled() {
 			// Preparation could've taken quite a bit of time and the requester may be not interested
 			// in execution anymore, in which case we just skip the request.
@@ -595,6 +703,7 @@ async fn handle_prepare_done(
 				artifact: ArtifactPathId::new(artifact_id.clone(), cache_path),
 				execution_timeout,
 				params,
+				priority,
 				result_tx,
 			},
 		)
@@ -602,13 +711,75 @@ async fn handle_prepare_done(
 	}
 
 	*state = match result {
-		Ok(()) => ArtifactState::Prepared { last_time_needed: SystemTime::now() },
-		Err(error) => ArtifactState::FailedToProcess(error.clone()),
+		Ok(()) => {
+			// The failure recorded by a previous attempt is stale now. It must not be picked up
+			// instead of the artifact upon restart, e.g. if the artifact gets discarded.
+			if num_failures > 0 {
+				artifacts::remove_failure(cache_path, &artifact_id).await;
+			}
+
+			ArtifactState::Prepared { last_time_needed: SystemTime::now() }
+		},
+		Err(error) => {
+			let last_time_failed = SystemTime::now();
+			let num_failures = num_failures.saturating_add(1);
+
+			// Record the failure so that it is not forgotten upon restart. This is best effort: if
+			// the failure is lost, the preparation will be just tried once again.
+			if let Err(err) = artifacts::write_failure(
+				cache_path,
+				&artifact_id,
+				&error,
+				num_failures,
+				last_time_failed,
+			)
+			.await
+			{
+				gum::warn!(
+					target: LOG_TARGET,
+					validation_code_hash = ?artifact_id.code_hash,
+					"failed to record the preparation failure: {:?}",
+					err,
+				);
+			}
+
+			ArtifactState::FailedToProcess {
+				last_time_failed,
+				last_time_needed: last_time_failed,
+				num_failures,
+				error,
+			}
+		},
 	};
 
 	Ok(())
 }
 
+/// Returns whether the preparation of an artifact that previously failed should be retried.
+///
+/// Deterministic errors are never retried. For non-deterministic errors the backoff doubles with
+/// every consecutive failure.
+fn can_retry_prepare_after_failure(
+	last_time_failed: SystemTime,
+	num_failures: u32,
+	error: &PrepareError,
+	prepare_failure_backoff: Duration,
+) -> bool {
+	/// The limit on the number of times the backoff is doubled.
+	const MAX_BACKOFF_EXPONENT: u32 = 5;
+
+	if error.is_deterministic() {
+		return false
+	}
+
+	let exponent = num_failures.saturating_sub(1).min(MAX_BACKOFF_EXPONENT);
+	let backoff = prepare_failure_backoff * 2u32.pow(exponent);
+	SystemTime::now()
+		.duration_since(last_time_failed)
+		.map(|elapsed| elapsed >= backoff)
+		.unwrap_or(false)
+}
+
 async fn send_prepare(
 	prepare_queue: &mut mpsc::Sender<prepare::ToQueue>,
 	to_queue: prepare::ToQueue,
@@ -642,6 +813,8 @@ async fn handle_cleanup_pulse(
 			"pruning artifact",
 		);
 		let artifact_path = artifact_id.path(cache_path);
+		sweeper_tx.send(failure_path(&artifact_path)).await.map_err(|_| Fatal)?;
+		sweeper_tx.send(header_path(&artifact_path)).await.map_err(|_| Fatal)?;
 		sweeper_tx.send(artifact_path).await.map_err(|_| Fatal)?;
 	}
 
@@ -712,6 +885,7 @@ mod tests {
 	struct Builder {
 		cleanup_pulse_interval: Duration,
 		artifact_ttl: Duration,
+		prepare_failure_backoff: Duration,
 		artifacts: Artifacts,
 	}
 
@@ -721,6 +895,7 @@ mod tests {
 				// these are selected high to not interfere in tests in which pruning is irrelevant.
 				cleanup_pulse_interval: Duration::from_secs(3600),
 				artifact_ttl: Duration::from_secs(3600),
+				prepare_failure_backoff: Duration::from_secs(3600),
 
 				artifacts: Artifacts::empty(),
 			}
@@ -743,7 +918,14 @@ mod tests {
 	}
 
 	impl Test {
-		fn new(Builder { cleanup_pulse_interval, artifact_ttl, artifacts }: Builder) -> Self {
+		fn new(
+			Builder {
+				cleanup_pulse_interval,
+				artifact_ttl,
+				prepare_failure_backoff,
+				artifacts,
+			}: Builder,
+		) -> Self {
 			let cache_path = PathBuf::from(std::env::temp_dir());
 
 			let (to_host_tx, to_host_rx) = mpsc::channel(10);
@@ -756,6 +938,7 @@ mod tests {
 				cache_path,
 				cleanup_pulse_interval,
 				artifact_ttl,
+				prepare_failure_backoff,
 				artifacts,
 				to_host_rx,
 				to_prepare_queue_tx,
@@ -793,6 +976,25 @@ mod tests {
 				.await
 		}
 
+		async fn poll_ensure_to_prepare_queue_is_empty(&mut self) {
+			use futures_timer::Delay;
+
+			let to_prepare_queue_rx = &mut self.to_prepare_queue_rx;
+			run_until(
+				&mut self.run,
+				async {
+					futures::select! {
+						_ = Delay::new(Duration::from_millis(500)).fuse() => (),
+						_ = to_prepare_queue_rx.next().fuse() => {
+							panic!("the prepare queue supposed to be empty")
+						}
+					}
+				}
+				.boxed(),
+			)
+			.await
+		}
+
 		async fn poll_ensure_to_execute_queue_is_empty(&mut self) {
 			use futures_timer::Delay;
 
@@ -887,6 +1089,8 @@ mod tests {
 		run_until(
 			&mut test.run,
 			async {
+				assert_eq!(to_sweeper_rx.next().await.unwrap(), failure_path(&artifact_path(2)));
+				assert_eq!(to_sweeper_rx.next().await.unwrap(), header_path(&artifact_path(2)));
 				assert_eq!(to_sweeper_rx.next().await.unwrap(), artifact_path(2));
 			}
 			.boxed(),
@@ -1157,4 +1361,104 @@ mod tests {
 
 		test.poll_ensure_to_execute_queue_is_empty().await;
 	}
+
+	#[async_std::test]
+	async fn deterministic_prepare_failure_is_not_retried() {
+		let mut builder = Builder::default();
+		builder.prepare_failure_backoff = Duration::from_millis(0);
+		builder.artifacts.insert_failed(
+			artifact_id(1),
+			SystemTime::now() - Duration::from_secs(60),
+			1,
+			PrepareError::Preparation("compilation failed".to_owned()),
+		);
+		let mut test = builder.build();
+		let mut host = test.host_handle();
+
+		let (result_tx, result_rx) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(1), result_tx).await.unwrap();
+		let (result_tx, result_rx_execute) = oneshot::channel();
+		host.execute_pvf(
+			Pvf::from_discriminator(1),
+			TEST_EXECUTION_TIMEOUT,
+			b"pvf1".to_vec(),
+			Priority::Normal,
+			result_tx,
+		)
+		.await
+		.unwrap();
+		host.heads_up(vec![Pvf::from_discriminator(1)]).await.unwrap();
+
+		// The known error is reported right away and nothing is compiled again.
+		test.poll_ensure_to_prepare_queue_is_empty().await;
+		assert_matches!(
+			result_rx.now_or_never().unwrap().unwrap(),
+			Err(PrepareError::Preparation(_))
+		);
+		assert_matches!(
+			result_rx_execute.now_or_never().unwrap().unwrap(),
+			Err(ValidationError::InvalidCandidate(InvalidCandidate::PrepareError(_)))
+		);
+	}
+
+	#[async_std::test]
+	async fn non_deterministic_prepare_failure_is_retried_after_backoff() {
+		let mut builder = Builder::default();
+		builder.prepare_failure_backoff = Duration::from_secs(60);
+		builder.artifacts.insert_failed(
+			artifact_id(1),
+			SystemTime::now(),
+			1,
+			PrepareError::TimedOut,
+		);
+		builder.artifacts.insert_failed(
+			artifact_id(2),
+			SystemTime::now() - Duration::from_secs(61),
+			1,
+			PrepareError::DidNotMakeIt,
+		);
+		// The backoff is doubled for the second consecutive failure.
+		builder.artifacts.insert_failed(
+			artifact_id(3),
+			SystemTime::now() - Duration::from_secs(61),
+			2,
+			PrepareError::TimedOut,
+		);
+		let mut test = builder.build();
+		let mut host = test.host_handle();
+
+		// The backoff hasn't elapsed yet, the error is reported right away.
+		let (result_tx, result_rx) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(1), result_tx).await.unwrap();
+		let (result_tx, result_rx_3) = oneshot::channel();
+		host.precheck_pvf(Pvf::from_discriminator(3), result_tx).await.unwrap();
+		test.poll_ensure_to_prepare_queue_is_empty().await;
+		assert_matches!(result_rx.now_or_never().unwrap().unwrap(), Err(PrepareError::TimedOut));
+		assert_matches!(result_rx_3.now_or_never().unwrap().unwrap(), Err(PrepareError::TimedOut));
+
+		// The backoff has elapsed, so the preparation is retried.
+		let (result_tx, result_rx) = oneshot::channel();
+		host.execute_pvf(
+			Pvf::from_discriminator(2),
+			TEST_EXECUTION_TIMEOUT,
+			b"pvf2".to_vec(),
+			Priority::Normal,
+			result_tx,
+		)
+		.await
+		.unwrap();
+		assert_matches!(
+			test.poll_and_recv_to_prepare_queue().await,
+			prepare::ToQueue::Enqueue { .. }
+		);
+		test.from_prepare_queue_tx
+			.send(prepare::FromQueue { artifact_id: artifact_id(2), result: Ok(()) })
+			.await
+			.unwrap();
+		assert_matches!(
+			test.poll_and_recv_to_execute_queue().await,
+			execute::ToQueue::Enqueue { .. }
+		);
+		drop(result_rx);
+	}
 }
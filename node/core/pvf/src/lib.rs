@@ -46,6 +46,11 @@
 //!
 //! Priority can never go down, only up.
 //!
+//! The execution queue picks the next job by weighted round-robin between the priorities that have
+//! jobs waiting, so that even a flood of low priority jobs cannot starve the higher priorities and
+//! vice versa. Additionally, some of the execute workers can be reserved for the higher priorities.
+//! See [`PriorityClasses`].
+//!
 //! # Under the hood
 //!
 //! Under the hood, the validation host is built using a bunch of communicating processes, not
@@ -61,9 +66,9 @@
 //! Artifact is a final product of preparation. If the preparation succeeded, then the artifact will
 //! contain the compiled code usable for quick execution by a worker later on.
 //!
-//! If the preparation failed, then the worker will still write the artifact with the error message.
-//! We save the artifact with the error so that we don't try to prepare the artifacts that are broken
-//! repeatedly.
+//! If the preparation failed, then the host records the error in the cache instead of the artifact.
+//! We save the error so that we don't try to prepare the artifacts that are broken repeatedly. Only
+//! the non-deterministic errors are retried, after a backoff.
 //!
 //! The artifact is saved on disk and is also tracked by an in memory table. This in memory table
 //! doesn't contain the artifact contents though, only a flag that the given artifact is compiled.
@@ -74,6 +79,14 @@
 //!
 //! Each fixed interval of time a pruning task will run. This task will remove all artifacts that
 //! weren't used or received a heads up signal for a while.
+//!
+//! Every compiled artifact is accompanied by a header that records the node version (including the
+//! commit hash), the executor configuration and the checksum of the artifact. The artifacts survive
+//! node restarts: upon startup the cache is scanned and the artifacts with a valid header are
+//! recognized as prepared, while the rest is removed.
+//!
+//! Workers announce their version upon connecting to the host, and are rejected unless they were
+//! built from the same commit as the node.
 
 mod artifacts;
 mod error;
@@ -93,7 +106,7 @@ pub mod testing;
 pub use sp_tracing;
 
 pub use error::{InvalidCandidate, PrepareError, PrepareResult, ValidationError};
-pub use priority::Priority;
+pub use priority::{Priority, PriorityClass, PriorityClasses};
 pub use pvf::Pvf;
 
 pub use host::{start, Config, ValidationHost};
@@ -103,8 +116,19 @@ pub use execute::worker_entrypoint as execute_worker_entrypoint;
 pub use prepare::worker_entrypoint as prepare_worker_entrypoint;
 
 pub use executor_intf::{prepare, prevalidate};
+pub use worker_common::TrackingAllocator;
 
 pub use sc_executor_common;
 pub use sp_maybe_compressed_blob;
 
 const LOG_TARGET: &str = "parachain::pvf";
+
+#[cfg(test)]
+#[global_allocator]
+static ALLOC: TrackingAllocator<std::alloc::System> = TrackingAllocator(std::alloc::System);
+
+/// The implementation version of the node, i.e. the crate version followed by the commit hash.
+///
+/// The workers and the artifacts are only used if they come from the very same build, which the
+/// crate version alone does not tell apart.
+const NODE_VERSION: &str = env!("SUBSTRATE_CLI_IMPL_VERSION");
@@ -16,7 +16,9 @@
 
 //! Prometheus metrics related to the validation host.
 
+use crate::Priority;
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
+use std::time::Duration;
 
 /// Validation host metrics.
 #[derive(Default, Clone)]
@@ -72,6 +74,16 @@ impl Metrics {
 	pub(crate) fn time_execution(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.execution_time.start_timer())
 	}
+
+	/// Time an execution request of the given priority waited in the queue for a worker.
+	pub(crate) fn observe_execution_queued_time(&self, priority: Priority, waited: Duration) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.execution_queued_time
+				.with_label_values(&[priority_label(priority)])
+				.observe(waited.as_secs_f64());
+		}
+	}
 }
 
 #[derive(Clone)]
@@ -85,6 +97,7 @@ struct MetricsInner {
 	execute_finished: prometheus::Counter<prometheus::U64>,
 	preparation_time: prometheus::Histogram,
 	execution_time: prometheus::Histogram,
+	execution_queued_time: prometheus::HistogramVec,
 }
 
 impl metrics::Metrics for Metrics {
@@ -191,11 +204,30 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			execution_queued_time: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_queued_time",
+						"Time spent by execution requests waiting in the queue for a worker in seconds",
+					)
+					.buckets(vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0]),
+					&["priority"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(inner)))
 	}
 }
 
+fn priority_label(priority: Priority) -> &'static str {
+	match priority {
+		Priority::Background => "background",
+		Priority::Normal => "normal",
+		Priority::Critical => "critical",
+	}
+}
+
 enum WorkerFlavor {
 	Prepare,
 	Execute,
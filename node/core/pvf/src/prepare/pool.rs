@@ -339,6 +339,20 @@ fn handle_mux(
 						)?;
 					}
 
+					Ok(())
+				},
+				Outcome::MemoryLimitExceeded => {
+					if attempt_retire(metrics, spawned, worker) {
+						reply(
+							from_pool,
+							FromPool::Concluded {
+								worker,
+								rip: true,
+								result: Err(PrepareError::MemoryLimitExceeded),
+							},
+						)?;
+					}
+
 					Ok(())
 				},
 			}
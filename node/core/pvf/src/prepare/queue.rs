@@ -94,6 +94,7 @@ impl WorkerData {
 ///  there is going to be a limited number of critical jobs and we don't really care if background starve.
 #[derive(Default)]
 struct Unscheduled {
+	background: VecDeque<Job>,
 	normal: VecDeque<Job>,
 	critical: VecDeque<Job>,
 }
@@ -101,6 +102,7 @@ struct Unscheduled {
 impl Unscheduled {
 	fn queue_mut(&mut self, prio: Priority) -> &mut VecDeque<Job> {
 		match prio {
+			Priority::Background => &mut self.background,
 			Priority::Normal => &mut self.normal,
 			Priority::Critical => &mut self.critical,
 		}
@@ -115,12 +117,14 @@ impl Unscheduled {
 	}
 
 	fn is_empty(&self) -> bool {
-		self.normal.is_empty() && self.critical.is_empty()
+		self.background.is_empty() && self.normal.is_empty() && self.critical.is_empty()
 	}
 
 	fn next(&mut self) -> Option<Job> {
 		let mut check = |prio: Priority| self.queue_mut(prio).pop_front();
-		check(Priority::Critical).or_else(|| check(Priority::Normal))
+		check(Priority::Critical)
+			.or_else(|| check(Priority::Normal))
+			.or_else(|| check(Priority::Background))
 	}
 }
 
@@ -15,11 +15,11 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	artifacts::CompiledArtifact,
+	artifacts::{header_path, ArtifactHeader, CompiledArtifact},
 	error::{PrepareError, PrepareResult},
 	worker_common::{
-		bytes_to_path, framed_recv, framed_send, path_to_bytes, spawn_with_program_path,
-		tmpfile_in, worker_event_loop, IdleWorker, SpawnErr, WorkerHandle,
+		bytes_to_path, framed_recv, framed_send, path_to_bytes, run_with_memory_limit,
+		spawn_with_program_path, tmpfile_in, worker_event_loop, IdleWorker, SpawnErr, WorkerHandle,
 	},
 	LOG_TARGET,
 };
@@ -36,6 +36,11 @@ use std::{panic, sync::Arc, time::Duration};
 // NOTE: If you change this make sure to fix the buckets of `pvf_preparation_time` metric.
 const COMPILATION_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// The limit of the memory allocated by the preparation of a PVF, in bytes.
+///
+/// Exceeding the limit is a deterministic error, so it must be the same for all validators.
+const PREPARE_MEMORY_LIMIT: u64 = 2 * 1024 * 1024 * 1024;
+
 /// Spawns a new worker with the given program path that acts as the worker and the spawn timeout.
 ///
 /// The program should be able to handle `<program-path> prepare-worker <socket-path>` invocation.
@@ -56,6 +61,10 @@ pub enum Outcome {
 	///
 	/// The worker is no longer usable and should be killed.
 	TimedOut,
+	/// The preparation exceeded the memory limit.
+	///
+	/// The worker is no longer usable and should be killed.
+	MemoryLimitExceeded,
 	/// The execution was interrupted abruptly and the worker is not available anymore.
 	///
 	/// This doesn't return an idle worker instance, thus this worker is no longer usable.
@@ -117,20 +126,29 @@ pub async fn start_work(
 								artifact_path.display(),
 							);
 
-							async_std::fs::rename(&tmp_file, &artifact_path)
-								.await
-								.map(|_| Selected::Done(result))
-								.unwrap_or_else(|err| {
-									gum::warn!(
-										target: LOG_TARGET,
-										worker_pid = %pid,
-										"failed to rename the artifact from {} to {}: {:?}",
-										tmp_file.display(),
-										artifact_path.display(),
-										err,
-									);
-									Selected::IoErr
-								})
+							// The header is promoted first, so that an artifact never appears
+							// in the cache without its header.
+							let promote = async {
+								ArtifactHeader::write_for(&tmp_file).await?;
+								async_std::fs::rename(
+									header_path(&tmp_file),
+									header_path(&artifact_path),
+								)
+								.await?;
+								async_std::fs::rename(&tmp_file, &artifact_path).await
+							};
+
+							promote.await.map(|_| Selected::Done(result)).unwrap_or_else(|err| {
+								gum::warn!(
+									target: LOG_TARGET,
+									worker_pid = %pid,
+									"failed to promote the artifact from {} to {}: {:?}",
+									tmp_file.display(),
+									artifact_path.display(),
+									err,
+								);
+								Selected::IoErr
+							})
 						} else {
 							Selected::Done(result)
 						}
@@ -163,6 +181,7 @@ pub async fn start_work(
 			};
 
 		match selected {
+			Selected::Done(Err(PrepareError::MemoryLimitExceeded)) => Outcome::MemoryLimitExceeded,
 			Selected::Done(result) =>
 				Outcome::Concluded { worker: IdleWorker { stream, pid }, result },
 			Selected::Deadline => Outcome::TimedOut,
@@ -199,19 +218,21 @@ where
 	// The function called above is expected to move `tmp_file` to a new location upon success. However,
 	// the function may as well fail and in that case we should remove the tmp file here.
 	//
-	// In any case, we try to remove the file here so that there are no leftovers. We only report
-	// errors that are different from the `NotFound`.
-	match async_std::fs::remove_file(tmp_file).await {
-		Ok(()) => (),
-		Err(err) if err.kind() == std::io::ErrorKind::NotFound => (),
-		Err(err) => {
-			gum::warn!(
-				target: LOG_TARGET,
-				worker_pid = %pid,
-				"failed to remove the tmp file: {:?}",
-				err,
-			);
-		},
+	// In any case, we try to remove the file and its header here so that there are no leftovers. We
+	// only report errors that are different from the `NotFound`.
+	for condemned in [header_path(&tmp_file), tmp_file] {
+		match async_std::fs::remove_file(condemned).await {
+			Ok(()) => (),
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => (),
+			Err(err) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					worker_pid = %pid,
+					"failed to remove the tmp file: {:?}",
+					err,
+				);
+			},
+		}
 	}
 
 	outcome
@@ -252,7 +273,7 @@ pub fn worker_entrypoint(socket_path: &str) {
 				"worker: preparing artifact",
 			);
 
-			let result = match prepare_artifact(&code) {
+			let result = match prepare_with_memory_limit(code, PREPARE_MEMORY_LIMIT).await? {
 				Err(err) => {
 					// Serialized error will be written into the socket.
 					Err(err)
@@ -278,10 +299,22 @@ pub fn worker_entrypoint(socket_path: &str) {
 			};
 
 			framed_send(&mut stream, result.encode().as_slice()).await?;
+			if let Err(PrepareError::MemoryLimitExceeded) = result {
+				// The preparation might still be running, so the worker must not be used anymore.
+				return Err(io::Error::new(io::ErrorKind::Other, "memory limit exceeded"))
+			}
 		}
 	});
 }
 
+async fn prepare_with_memory_limit(
+	code: Vec<u8>,
+	memory_limit: u64,
+) -> io::Result<Result<CompiledArtifact, PrepareError>> {
+	let result = run_with_memory_limit(memory_limit, move || prepare_artifact(&code)).await?;
+	Ok(result.unwrap_or(Err(PrepareError::MemoryLimitExceeded)))
+}
+
 fn prepare_artifact(code: &[u8]) -> Result<CompiledArtifact, PrepareError> {
 	panic::catch_unwind(|| {
 		let blob = match crate::executor_intf::prevalidate(code) {
@@ -299,3 +332,33 @@ fn prepare_artifact(code: &[u8]) -> Result<CompiledArtifact, PrepareError> {
 	})
 	.and_then(|inner_result| inner_result)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{InvalidCandidate, ValidationError};
+	use assert_matches::assert_matches;
+
+	fn adder_code() -> Vec<u8> {
+		sp_maybe_compressed_blob::decompress(adder::wasm_binary_unwrap(), 10 * 1024 * 1024)
+			.unwrap()
+			.into_owned()
+	}
+
+	#[async_std::test]
+	async fn preparation_within_memory_limit_succeeds() {
+		let result = prepare_with_memory_limit(adder_code(), PREPARE_MEMORY_LIMIT).await.unwrap();
+		assert!(result.is_ok());
+	}
+
+	#[async_std::test]
+	async fn preparation_exceeding_memory_limit_is_invalid() {
+		let result = prepare_with_memory_limit(adder_code(), 1024).await.unwrap();
+		let err = result.map(|_| ()).unwrap_err();
+		assert_matches!(err, PrepareError::MemoryLimitExceeded);
+		assert_matches!(
+			ValidationError::from(err),
+			ValidationError::InvalidCandidate(InvalidCandidate::MemoryLimitExceeded)
+		);
+	}
+}
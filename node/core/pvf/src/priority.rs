@@ -17,10 +17,15 @@
 /// A priority assigned to execution of a PVF.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Priority {
+	/// Background priority for things that need to be done eventually, but should not hold up the
+	/// work with a higher priority.
+	///
+	/// Disputes fall into this category.
+	Background,
 	/// Normal priority for things that do not require immediate response, but still need to be
 	/// done pretty quick.
 	///
-	/// Approvals and disputes fall into this category.
+	/// Approvals fall into this category.
 	Normal,
 	/// This priority is used for requests that are required to be processed as soon as possible.
 	///
@@ -34,3 +39,49 @@ impl Priority {
 		self == Priority::Critical
 	}
 }
+
+/// The share of the execute workers given to the jobs of one [`Priority`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriorityClass {
+	/// The relative frequency with which jobs of this priority are picked while jobs of other
+	/// priorities are waiting as well. Must be non-zero.
+	pub weight: u32,
+	/// The number of execute workers that jobs of a lower priority cannot occupy.
+	///
+	/// Regardless of the reservations, the jobs of every priority can always occupy at least one
+	/// worker.
+	pub reserved_workers: usize,
+}
+
+/// How the execute workers are shared between the priorities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriorityClasses {
+	/// The share of [`Priority::Critical`] jobs.
+	pub critical: PriorityClass,
+	/// The share of [`Priority::Normal`] jobs.
+	pub normal: PriorityClass,
+	/// The share of [`Priority::Background`] jobs.
+	pub background: PriorityClass,
+}
+
+impl PriorityClasses {
+	/// Returns the share of the jobs with the given priority.
+	pub fn get(&self, priority: Priority) -> &PriorityClass {
+		match priority {
+			Priority::Background => &self.background,
+			Priority::Normal => &self.normal,
+			Priority::Critical => &self.critical,
+		}
+	}
+}
+
+impl Default for PriorityClasses {
+	fn default() -> Self {
+		Self {
+			critical: PriorityClass { weight: 4, reserved_workers: 0 },
+			// Keep a worker away from disputes, so that approvals are not stuck behind them.
+			normal: PriorityClass { weight: 2, reserved_workers: 1 },
+			background: PriorityClass { weight: 1, reserved_workers: 0 },
+		}
+	}
+}
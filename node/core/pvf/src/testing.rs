@@ -55,6 +55,10 @@ pub fn validate_candidate(
 #[macro_export]
 macro_rules! decl_puppet_worker_main {
 	() => {
+		#[global_allocator]
+		static ALLOC: $crate::TrackingAllocator<std::alloc::System> =
+			$crate::TrackingAllocator(std::alloc::System);
+
 		fn main() {
 			$crate::sp_tracing::try_init_simple();
 
@@ -16,21 +16,29 @@
 
 //! Common logic for implementation of worker processes.
 
-use crate::LOG_TARGET;
+use crate::{LOG_TARGET, NODE_VERSION};
 use async_std::{
 	io,
 	os::unix::net::{UnixListener, UnixStream},
 	path::{Path, PathBuf},
 };
 use futures::{
-	never::Never, AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _, FutureExt as _,
+	channel::oneshot, never::Never, AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _,
+	FutureExt as _,
 };
 use futures_timer::Delay;
 use pin_project::pin_project;
 use rand::Rng;
 use std::{
+	alloc::{GlobalAlloc, Layout},
+	cell::Cell,
 	fmt, mem,
 	pin::Pin,
+	ptr,
+	sync::{
+		atomic::{AtomicBool, AtomicIsize, Ordering},
+		Arc,
+	},
 	task::{Context, Poll},
 	time::Duration,
 };
@@ -68,18 +76,23 @@ pub async fn spawn_with_program_path(
 					SpawnErr::ProcessSpawn
 				})?;
 
+			let rendezvous = async {
+				let (mut stream, _) = listener.accept().await.map_err(|err| {
+					gum::warn!(
+						target: LOG_TARGET,
+						%debug_id,
+						"cannot accept a worker: {:?}",
+						err,
+					);
+					SpawnErr::Accept
+				})?;
+				check_worker_version(debug_id, &mut stream).await?;
+				Ok::<_, SpawnErr>(stream)
+			};
+
 			futures::select! {
-				accept_result = listener.accept().fuse() => {
-					let (stream, _) = accept_result.map_err(|err| {
-						gum::warn!(
-							target: LOG_TARGET,
-							%debug_id,
-							"cannot accept a worker: {:?}",
-							err,
-						);
-						SpawnErr::Accept
-					})?;
-					Ok((IdleWorker { stream, pid: handle.id() }, handle))
+				stream = rendezvous.fuse() => {
+					Ok((IdleWorker { stream: stream?, pid: handle.id() }, handle))
 				}
 				_ = Delay::new(spawn_timeout).fuse() => {
 					Err(SpawnErr::AcceptTimeout)
@@ -90,6 +103,37 @@ pub async fn spawn_with_program_path(
 	.await
 }
 
+/// Receives the version the worker announces upon connecting and checks that it is the version of
+/// the node. A worker binary built from another commit may compile or execute differently, so it is
+/// never used.
+async fn check_worker_version(
+	debug_id: &'static str,
+	stream: &mut UnixStream,
+) -> Result<(), SpawnErr> {
+	let version = framed_recv(stream).await.map_err(|err| {
+		gum::warn!(
+			target: LOG_TARGET,
+			%debug_id,
+			"cannot receive the version of a worker: {:?}",
+			err,
+		);
+		SpawnErr::Accept
+	})?;
+
+	if version != NODE_VERSION.as_bytes() {
+		gum::error!(
+			target: LOG_TARGET,
+			%debug_id,
+			worker_version = %String::from_utf8_lossy(&version),
+			node_version = %NODE_VERSION,
+			"the worker binary does not match the node version",
+		);
+		return Err(SpawnErr::VersionMismatch)
+	}
+
+	Ok(())
+}
+
 async fn with_transient_socket_path<T, F, Fut>(debug_id: &'static str, f: F) -> Result<T, SpawnErr>
 where
 	F: FnOnce(&Path) -> Fut,
@@ -153,9 +197,11 @@ where
 	Fut: futures::Future<Output = io::Result<Never>>,
 {
 	let err = async_std::task::block_on::<_, io::Result<Never>>(async move {
-		let stream = UnixStream::connect(socket_path).await?;
+		let mut stream = UnixStream::connect(socket_path).await?;
 		let _ = async_std::fs::remove_file(socket_path).await;
 
+		framed_send(&mut stream, NODE_VERSION.as_bytes()).await?;
+
 		event_loop(stream).await
 	})
 	.unwrap_err(); // it's never `Ok` because it's `Ok(Never)`
@@ -169,6 +215,154 @@ where
 	);
 }
 
+/// A global allocator which accounts for the memory allocated by the jobs of a worker.
+///
+/// It must be installed as the `#[global_allocator]` of the program running the workers, otherwise
+/// the memory limit of the jobs is not enforced.
+pub struct TrackingAllocator<A>(pub A);
+
+/// The memory allocated by a job and its limit, in bytes.
+pub struct JobMemory {
+	allocated: AtomicIsize,
+	limit: isize,
+	exceeded: AtomicBool,
+}
+
+thread_local! {
+	// The job on whose behalf the current thread allocates, or null. A raw pointer needs no
+	// destructor, so accessing it from the allocator never allocates.
+	static CURRENT_JOB: Cell<*const JobMemory> = const { Cell::new(ptr::null()) };
+}
+
+/// How often the worker checks whether the running job exceeded its memory limit.
+const MEMORY_LIMIT_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The stack size of the threads running the jobs, which is the usual stack size of the main
+/// thread on Linux that the jobs used to run on.
+const JOB_THREAD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+fn track(delta: isize) {
+	let job = CURRENT_JOB.try_with(Cell::get).unwrap_or(ptr::null());
+	// SAFETY: the job is alive for as long as it is current on any thread, see `track_memory`.
+	if let Some(job) = unsafe { job.as_ref() } {
+		let allocated = job.allocated.fetch_add(delta, Ordering::Relaxed) + delta;
+		if allocated > job.limit {
+			job.exceeded.store(true, Ordering::Relaxed);
+		}
+	}
+}
+
+// SAFETY: all the calls are forwarded to the wrapped allocator, the accounting never allocates.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		track(layout.size() as isize);
+		self.0.alloc(layout)
+	}
+
+	unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+		track(layout.size() as isize);
+		self.0.alloc_zeroed(layout)
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		track(-(layout.size() as isize));
+		self.0.dealloc(ptr, layout)
+	}
+
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		track(new_size as isize - layout.size() as isize);
+		self.0.realloc(ptr, layout, new_size)
+	}
+}
+
+/// Returns the job on whose behalf the current thread allocates, if any.
+pub fn current_job() -> Option<Arc<JobMemory>> {
+	let job = CURRENT_JOB.with(Cell::get);
+	if job.is_null() {
+		return None
+	}
+
+	// SAFETY: the pointer was obtained from an `Arc` which is alive while the job is current.
+	unsafe {
+		Arc::increment_strong_count(job);
+		Some(Arc::from_raw(job))
+	}
+}
+
+/// Accounts the allocations of the current thread to the given job while the closure runs.
+///
+/// The jobs run with [`run_with_memory_limit`] are accounted for already, this is only needed for
+/// the threads they delegate their work to.
+pub fn track_memory<R>(job: Option<Arc<JobMemory>>, f: impl FnOnce() -> R) -> R {
+	struct Restore {
+		previous: *const JobMemory,
+		// Dropped after the previous job is restored, which keeps the job alive while current.
+		_job: Option<Arc<JobMemory>>,
+	}
+
+	impl Drop for Restore {
+		fn drop(&mut self) {
+			CURRENT_JOB.with(|current| current.set(self.previous));
+		}
+	}
+
+	let current = job.as_ref().map_or(ptr::null(), Arc::as_ptr);
+	let _restore = Restore { previous: CURRENT_JOB.with(|job| job.replace(current)), _job: job };
+	f()
+}
+
+/// Runs the job on a dedicated thread, limiting the memory it allocates to `limit` bytes. Returns
+/// `None` if the job exceeded the limit.
+///
+/// Only the memory the job allocates counts towards the limit, so that whether a job exceeds it
+/// depends neither on the configuration of the node nor on the jobs the worker ran before. The job
+/// is not interrupted when it exceeds the limit: the worker must report it and exit instead.
+pub async fn run_with_memory_limit<R: Send + 'static>(
+	limit: u64,
+	job: impl FnOnce() -> R + Send + 'static,
+) -> io::Result<Option<R>> {
+	let memory = Arc::new(JobMemory {
+		allocated: AtomicIsize::new(0),
+		limit: limit.try_into().unwrap_or(isize::MAX),
+		exceeded: AtomicBool::new(false),
+	});
+
+	let (tx, rx) = oneshot::channel();
+	let job_memory = memory.clone();
+	std::thread::Builder::new().stack_size(JOB_THREAD_STACK_SIZE).spawn(move || {
+		let _ = tx.send(track_memory(Some(job_memory), job));
+	})?;
+
+	let mut rx = rx.fuse();
+	let result = loop {
+		futures::select! {
+			result = rx => break result.ok(),
+			_ = Delay::new(MEMORY_LIMIT_CHECK_INTERVAL).fuse() => {
+				if memory.exceeded.load(Ordering::Relaxed) {
+					break None
+				}
+			},
+		}
+	};
+
+	let exceeded = memory.exceeded.load(Ordering::Relaxed);
+	if exceeded {
+		gum::debug!(
+			target: LOG_TARGET,
+			worker_pid = %std::process::id(),
+			%limit,
+			"the job exceeded the memory limit",
+		);
+	}
+
+	match result {
+		Some(result) if !exceeded => Ok(Some(result)),
+		Some(_) => Ok(None),
+		None if exceeded => Ok(None),
+		None => Err(io::Error::new(io::ErrorKind::Other, "the job thread panicked")),
+	}
+}
+
 /// A struct that represents an idle worker.
 ///
 /// This struct is supposed to be used as a token that is passed by move into a subroutine that
@@ -195,6 +389,8 @@ pub enum SpawnErr {
 	ProcessSpawn,
 	/// The deadline allotted for the worker spawning and connecting to the socket has elapsed.
 	AcceptTimeout,
+	/// The worker was built from another version than the node.
+	VersionMismatch,
 }
 
 /// This is a representation of a potentially running worker. Drop it and the process will be killed.
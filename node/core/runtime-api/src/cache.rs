@@ -122,37 +122,112 @@ pub(crate) struct RequestResultCache {
 	>,
 }
 
-impl Default for RequestResultCache {
+/// The memory budgets of the runtime API result caches, in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheConfig {
+	/// Budget for the results of `Authorities` requests.
+	pub authorities: usize,
+	/// Budget for the results of `Validators` requests.
+	pub validators: usize,
+	/// Budget for the results of `ValidatorGroups` requests.
+	pub validator_groups: usize,
+	/// Budget for the results of `AvailabilityCores` requests.
+	pub availability_cores: usize,
+	/// Budget for the results of `PersistedValidationData` requests.
+	pub persisted_validation_data: usize,
+	/// Budget for the results of `AssumedValidationData` requests.
+	pub assumed_validation_data: usize,
+	/// Budget for the results of `CheckValidationOutputs` requests.
+	pub check_validation_outputs: usize,
+	/// Budget for the results of `SessionIndexForChild` requests.
+	pub session_index_for_child: usize,
+	/// Budget for the results of `ValidationCode` requests.
+	pub validation_code: usize,
+	/// Budget for the results of `ValidationCodeByHash` requests.
+	pub validation_code_by_hash: usize,
+	/// Budget for the results of `CandidatePendingAvailability` requests.
+	pub candidate_pending_availability: usize,
+	/// Budget for the results of `CandidateEvents` requests.
+	pub candidate_events: usize,
+	/// Budget for the results of `SessionInfo` requests.
+	pub session_info: usize,
+	/// Budget for the results of `DmqContents` requests.
+	pub dmq_contents: usize,
+	/// Budget for the results of `InboundHrmpChannelsContents` requests.
+	pub inbound_hrmp_channels_contents: usize,
+	/// Budget for the results of `CurrentBabeEpoch` requests.
+	pub current_babe_epoch: usize,
+	/// Budget for the results of `FetchOnChainVotes` requests.
+	pub on_chain_votes: usize,
+	/// Budget for the results of `PvfsRequirePrecheck` requests.
+	pub pvfs_require_precheck: usize,
+	/// Budget for the results of `ValidationCodeHash` requests.
+	pub validation_code_hash: usize,
+	/// Budget for the results of `Version` requests.
+	pub version: usize,
+	/// Budget for the results of `StagingDisputes` requests.
+	pub disputes: usize,
+}
+
+impl Default for CacheConfig {
 	fn default() -> Self {
 		Self {
-			authorities: MemoryLruCache::new(AUTHORITIES_CACHE_SIZE),
-			validators: MemoryLruCache::new(VALIDATORS_CACHE_SIZE),
-			validator_groups: MemoryLruCache::new(VALIDATOR_GROUPS_CACHE_SIZE),
-			availability_cores: MemoryLruCache::new(AVAILABILITY_CORES_CACHE_SIZE),
-			persisted_validation_data: MemoryLruCache::new(PERSISTED_VALIDATION_DATA_CACHE_SIZE),
-			assumed_validation_data: MemoryLruCache::new(ASSUMED_VALIDATION_DATA_CACHE_SIZE),
-			check_validation_outputs: MemoryLruCache::new(CHECK_VALIDATION_OUTPUTS_CACHE_SIZE),
-			session_index_for_child: MemoryLruCache::new(SESSION_INDEX_FOR_CHILD_CACHE_SIZE),
-			validation_code: MemoryLruCache::new(VALIDATION_CODE_CACHE_SIZE),
-			validation_code_by_hash: MemoryLruCache::new(VALIDATION_CODE_CACHE_SIZE),
-			candidate_pending_availability: MemoryLruCache::new(
-				CANDIDATE_PENDING_AVAILABILITY_CACHE_SIZE,
-			),
-			candidate_events: MemoryLruCache::new(CANDIDATE_EVENTS_CACHE_SIZE),
-			session_info: MemoryLruCache::new(SESSION_INFO_CACHE_SIZE),
-			dmq_contents: MemoryLruCache::new(DMQ_CONTENTS_CACHE_SIZE),
-			inbound_hrmp_channels_contents: MemoryLruCache::new(INBOUND_HRMP_CHANNELS_CACHE_SIZE),
-			current_babe_epoch: MemoryLruCache::new(CURRENT_BABE_EPOCH_CACHE_SIZE),
-			on_chain_votes: MemoryLruCache::new(ON_CHAIN_VOTES_CACHE_SIZE),
-			pvfs_require_precheck: MemoryLruCache::new(PVFS_REQUIRE_PRECHECK_SIZE),
-			validation_code_hash: MemoryLruCache::new(VALIDATION_CODE_HASH_CACHE_SIZE),
-			version: MemoryLruCache::new(VERSION_CACHE_SIZE),
-			disputes: MemoryLruCache::new(DISPUTES_CACHE_SIZE),
+			authorities: AUTHORITIES_CACHE_SIZE,
+			validators: VALIDATORS_CACHE_SIZE,
+			validator_groups: VALIDATOR_GROUPS_CACHE_SIZE,
+			availability_cores: AVAILABILITY_CORES_CACHE_SIZE,
+			persisted_validation_data: PERSISTED_VALIDATION_DATA_CACHE_SIZE,
+			assumed_validation_data: ASSUMED_VALIDATION_DATA_CACHE_SIZE,
+			check_validation_outputs: CHECK_VALIDATION_OUTPUTS_CACHE_SIZE,
+			session_index_for_child: SESSION_INDEX_FOR_CHILD_CACHE_SIZE,
+			validation_code: VALIDATION_CODE_CACHE_SIZE,
+			validation_code_by_hash: VALIDATION_CODE_CACHE_SIZE,
+			candidate_pending_availability: CANDIDATE_PENDING_AVAILABILITY_CACHE_SIZE,
+			candidate_events: CANDIDATE_EVENTS_CACHE_SIZE,
+			session_info: SESSION_INFO_CACHE_SIZE,
+			dmq_contents: DMQ_CONTENTS_CACHE_SIZE,
+			inbound_hrmp_channels_contents: INBOUND_HRMP_CHANNELS_CACHE_SIZE,
+			current_babe_epoch: CURRENT_BABE_EPOCH_CACHE_SIZE,
+			on_chain_votes: ON_CHAIN_VOTES_CACHE_SIZE,
+			pvfs_require_precheck: PVFS_REQUIRE_PRECHECK_SIZE,
+			validation_code_hash: VALIDATION_CODE_HASH_CACHE_SIZE,
+			version: VERSION_CACHE_SIZE,
+			disputes: DISPUTES_CACHE_SIZE,
 		}
 	}
 }
 
 impl RequestResultCache {
+	pub(crate) fn new(config: &CacheConfig) -> Self {
+		Self {
+			authorities: MemoryLruCache::new(config.authorities),
+			validators: MemoryLruCache::new(config.validators),
+			validator_groups: MemoryLruCache::new(config.validator_groups),
+			availability_cores: MemoryLruCache::new(config.availability_cores),
+			persisted_validation_data: MemoryLruCache::new(config.persisted_validation_data),
+			assumed_validation_data: MemoryLruCache::new(config.assumed_validation_data),
+			check_validation_outputs: MemoryLruCache::new(config.check_validation_outputs),
+			session_index_for_child: MemoryLruCache::new(config.session_index_for_child),
+			validation_code: MemoryLruCache::new(config.validation_code),
+			validation_code_by_hash: MemoryLruCache::new(config.validation_code_by_hash),
+			candidate_pending_availability: MemoryLruCache::new(
+				config.candidate_pending_availability,
+			),
+			candidate_events: MemoryLruCache::new(config.candidate_events),
+			session_info: MemoryLruCache::new(config.session_info),
+			dmq_contents: MemoryLruCache::new(config.dmq_contents),
+			inbound_hrmp_channels_contents: MemoryLruCache::new(
+				config.inbound_hrmp_channels_contents,
+			),
+			current_babe_epoch: MemoryLruCache::new(config.current_babe_epoch),
+			on_chain_votes: MemoryLruCache::new(config.on_chain_votes),
+			pvfs_require_precheck: MemoryLruCache::new(config.pvfs_require_precheck),
+			validation_code_hash: MemoryLruCache::new(config.validation_code_hash),
+			version: MemoryLruCache::new(config.version),
+			disputes: MemoryLruCache::new(config.disputes),
+		}
+	}
+
 	pub(crate) fn authorities(
 		&mut self,
 		relay_parent: &Hash,
@@ -0,0 +1,131 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracking of runtime API requests which are currently being executed, such that identical
+//! requests arriving in the meantime wait for their result instead of executing again.
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use polkadot_node_subsystem::messages::RuntimeApiRequest as Request;
+use polkadot_primitives::v2::{
+	CandidateCommitments, Hash, Id as ParaId, OccupiedCoreAssumption, SessionIndex,
+	ValidationCodeHash,
+};
+
+/// Identifies a runtime API request by its kind and parameters, without the response sender.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum RequestKey {
+	Version,
+	Authorities,
+	Validators,
+	ValidatorGroups,
+	AvailabilityCores,
+	PersistedValidationData(ParaId, OccupiedCoreAssumption),
+	AssumedValidationData(ParaId, Hash),
+	CheckValidationOutputs(ParaId, CandidateCommitments),
+	SessionIndexForChild,
+	ValidationCode(ParaId, OccupiedCoreAssumption),
+	ValidationCodeByHash(ValidationCodeHash),
+	CandidatePendingAvailability(ParaId),
+	CandidateEvents,
+	SessionInfo(SessionIndex),
+	DmqContents(ParaId),
+	InboundHrmpChannelsContents(ParaId),
+	CurrentBabeEpoch,
+	FetchOnChainVotes,
+	PvfsRequirePrecheck,
+	ValidationCodeHash(ParaId, OccupiedCoreAssumption),
+	StagingDisputes,
+}
+
+impl RequestKey {
+	/// The key of `request`, or `None` if it must never be coalesced with other requests.
+	pub(crate) fn new(request: &Request) -> Option<Self> {
+		let key = match request {
+			Request::Version(_) => Self::Version,
+			Request::Authorities(_) => Self::Authorities,
+			Request::Validators(_) => Self::Validators,
+			Request::ValidatorGroups(_) => Self::ValidatorGroups,
+			Request::AvailabilityCores(_) => Self::AvailabilityCores,
+			Request::PersistedValidationData(para, assumption, _) =>
+				Self::PersistedValidationData(*para, *assumption),
+			Request::AssumedValidationData(para, expected_persisted_validation_data_hash, _) =>
+				Self::AssumedValidationData(*para, *expected_persisted_validation_data_hash),
+			Request::CheckValidationOutputs(para, commitments, _) =>
+				Self::CheckValidationOutputs(*para, commitments.clone()),
+			Request::SessionIndexForChild(_) => Self::SessionIndexForChild,
+			Request::ValidationCode(para, assumption, _) =>
+				Self::ValidationCode(*para, *assumption),
+			Request::ValidationCodeByHash(validation_code_hash, _) =>
+				Self::ValidationCodeByHash(*validation_code_hash),
+			Request::CandidatePendingAvailability(para, _) =>
+				Self::CandidatePendingAvailability(*para),
+			Request::CandidateEvents(_) => Self::CandidateEvents,
+			Request::SessionInfo(index, _) => Self::SessionInfo(*index),
+			Request::DmqContents(para, _) => Self::DmqContents(*para),
+			Request::InboundHrmpChannelsContents(para, _) =>
+				Self::InboundHrmpChannelsContents(*para),
+			Request::CurrentBabeEpoch(_) => Self::CurrentBabeEpoch,
+			Request::FetchOnChainVotes(_) => Self::FetchOnChainVotes,
+			Request::PvfsRequirePrecheck(_) => Self::PvfsRequirePrecheck,
+			// This request is side-effecting, every one of them has to be executed.
+			Request::SubmitPvfCheckStatement(_, _, _) => return None,
+			Request::ValidationCodeHash(para, assumption, _) =>
+				Self::ValidationCodeHash(*para, *assumption),
+			Request::StagingDisputes(_) => Self::StagingDisputes,
+		};
+
+		Some(key)
+	}
+}
+
+/// The requests being executed, together with the identical requests waiting for them.
+#[derive(Default)]
+pub(crate) struct InFlightRequests {
+	waiting: HashMap<(Hash, RequestKey), Vec<Request>>,
+}
+
+impl InFlightRequests {
+	/// Either register `request` as being executed, returning it back together with its key, or
+	/// attach it to an identical request already being executed.
+	pub(crate) fn start_or_attach(
+		&mut self,
+		relay_parent: Hash,
+		request: Request,
+	) -> Option<(Option<RequestKey>, Request)> {
+		let key = match RequestKey::new(&request) {
+			Some(key) => key,
+			None => return Some((None, request)),
+		};
+
+		match self.waiting.entry((relay_parent, key)) {
+			Entry::Occupied(mut entry) => {
+				entry.get_mut().push(request);
+				None
+			},
+			Entry::Vacant(entry) => {
+				let key = entry.key().1.clone();
+				entry.insert(Vec::new());
+				Some((Some(key), request))
+			},
+		}
+	}
+
+	/// Mark the request identified by `key` as finished, returning the requests waiting for it.
+	pub(crate) fn finish(&mut self, relay_parent: Hash, key: RequestKey) -> Vec<Request> {
+		self.waiting.remove(&(relay_parent, key)).unwrap_or_default()
+	}
+}
@@ -31,10 +31,14 @@ use polkadot_node_subsystem_types::RuntimeApiSubsystemClient;
 use polkadot_primitives::v2::Hash;
 
 use cache::{RequestResult, RequestResultCache};
-use futures::{channel::oneshot, prelude::*, select, stream::FuturesUnordered};
+use futures::{channel::oneshot, future::BoxFuture, prelude::*, select, stream::FuturesUnordered};
+use in_flight::{InFlightRequests, RequestKey};
 use std::sync::Arc;
 
 mod cache;
+pub use cache::CacheConfig;
+
+mod in_flight;
 
 mod metrics;
 use self::metrics::Metrics;
@@ -57,24 +61,46 @@ pub struct RuntimeApiSubsystem<Client> {
 	metrics: Metrics,
 	spawn_handle: Box<dyn overseer::gen::Spawner>,
 	/// All the active runtime API requests that are currently being executed.
-	active_requests: FuturesUnordered<oneshot::Receiver<Option<RequestResult>>>,
+	active_requests: FuturesUnordered<BoxFuture<'static, FinishedRequest>>,
+	/// Identical requests waiting for one of the active requests.
+	in_flight: InFlightRequests,
 	/// Requests results cache
 	requests_cache: RequestResultCache,
 }
 
+/// An executed runtime API request.
+struct FinishedRequest {
+	relay_parent: Hash,
+	/// The key of the request, if identical requests could have attached to it.
+	key: Option<RequestKey>,
+	/// The result, if the request succeeded.
+	result: Option<RequestResult>,
+}
+
 impl<Client> RuntimeApiSubsystem<Client> {
 	/// Create a new Runtime API subsystem wrapping the given client and metrics.
 	pub fn new(
 		client: Arc<Client>,
 		metrics: Metrics,
 		spawner: impl overseer::gen::Spawner + 'static,
+	) -> Self {
+		Self::with_config(client, metrics, spawner, CacheConfig::default())
+	}
+
+	/// Create a new Runtime API subsystem with the given cache configuration.
+	pub fn with_config(
+		client: Arc<Client>,
+		metrics: Metrics,
+		spawner: impl overseer::gen::Spawner + 'static,
+		cache_config: CacheConfig,
 	) -> Self {
 		RuntimeApiSubsystem {
 			client,
 			metrics,
 			spawn_handle: Box::new(spawner),
 			active_requests: Default::default(),
-			requests_cache: RequestResultCache::default(),
+			in_flight: Default::default(),
+			requests_cache: RequestResultCache::new(&cache_config),
 		}
 	}
 }
@@ -262,6 +288,8 @@ where
 	}
 
 	/// Spawn a runtime API request.
+	///
+	/// Requests identical to one being executed wait for its result instead.
 	fn spawn_request(&mut self, relay_parent: Hash, request: Request) {
 		let client = self.client.clone();
 		let metrics = self.metrics.clone();
@@ -273,6 +301,14 @@ where
 			None => return,
 		};
 
+		let (key, request) = match self.in_flight.start_or_attach(relay_parent, request) {
+			Some(started) => started,
+			None => {
+				self.metrics.on_coalesced_request();
+				return
+			},
+		};
+
 		let request = async move {
 			let result = make_runtime_api_request(client, metrics, relay_parent, request).await;
 			let _ = sender.send(result);
@@ -281,7 +317,15 @@ where
 
 		self.spawn_handle
 			.spawn_blocking(API_REQUEST_TASK_NAME, Some("runtime-api"), request);
-		self.active_requests.push(receiver);
+		self.active_requests.push(
+			receiver
+				.map(move |result| FinishedRequest {
+					relay_parent,
+					key,
+					result: result.ok().flatten(),
+				})
+				.boxed(),
+		);
 	}
 
 	/// Poll the active runtime API requests.
@@ -292,8 +336,20 @@ where
 		}
 
 		// If there are active requests, this will always resolve to `Some(_)` when a request is finished.
-		if let Some(Ok(Some(result))) = self.active_requests.next().await {
-			self.store_cache(result);
+		if let Some(FinishedRequest { relay_parent, key, result }) =
+			self.active_requests.next().await
+		{
+			if let Some(result) = result {
+				self.store_cache(result);
+			}
+
+			// The waiting requests are answered from the cache. Should the result not have made it
+			// there, e.g. because the request failed, they are executed on their own.
+			if let Some(key) = key {
+				for request in self.in_flight.finish(relay_parent, key) {
+					self.spawn_request(relay_parent, request);
+				}
+			}
 		}
 	}
 
@@ -20,6 +20,7 @@ use polkadot_node_subsystem_util::metrics::{self, prometheus};
 pub(crate) struct MetricsInner {
 	pub(crate) chain_api_requests: prometheus::CounterVec<prometheus::U64>,
 	pub(crate) make_runtime_api_request: prometheus::Histogram,
+	pub(crate) coalesced_requests: prometheus::Counter<prometheus::U64>,
 }
 
 /// Runtime API metrics.
@@ -43,6 +44,13 @@ impl Metrics {
 			.map(|metrics| metrics.chain_api_requests.with_label_values(&["cached"]).inc());
 	}
 
+	/// A request waits for an identical one being executed instead of executing again.
+	pub fn on_coalesced_request(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.coalesced_requests.inc();
+		}
+	}
+
 	/// Provide a timer for `make_runtime_api_request` which observes on drop.
 	pub fn time_make_runtime_api_request(
 		&self,
@@ -71,6 +79,13 @@ impl metrics::Metrics for Metrics {
 				))?,
 				registry,
 			)?,
+			coalesced_requests: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_runtime_api_coalesced_requests_total",
+					"Number of Runtime API requests that waited for an identical request in flight.",
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
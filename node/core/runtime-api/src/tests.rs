@@ -36,7 +36,10 @@ use sp_consensus_babe::BabeApi;
 use sp_core::testing::TaskExecutor;
 use std::{
 	collections::{BTreeMap, HashMap},
-	sync::{Arc, Mutex},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
 };
 
 #[derive(Default, Clone)]
@@ -46,6 +49,7 @@ struct MockRuntimeApi {
 	validator_groups: Vec<Vec<ValidatorIndex>>,
 	availability_cores: Vec<CoreState>,
 	availability_cores_wait: Arc<Mutex<()>>,
+	availability_cores_calls: Arc<AtomicUsize>,
 	validation_data: HashMap<ParaId, PersistedValidationData>,
 	session_index_for_child: SessionIndex,
 	session_info: HashMap<SessionIndex, SessionInfo>,
@@ -90,6 +94,7 @@ sp_api::mock_impl_runtime_apis! {
 
 		fn availability_cores(&self) -> Vec<CoreState> {
 			let _ = self.availability_cores_wait.lock().unwrap();
+			self.availability_cores_calls.fetch_add(1, Ordering::SeqCst);
 			self.availability_cores.clone()
 		}
 
@@ -906,6 +911,105 @@ fn multiple_requests_in_parallel_are_working() {
 	futures::executor::block_on(future::join(subsystem_task, test_task));
 }
 
+#[test]
+fn identical_requests_in_flight_are_coalesced() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+	let runtime_api = Arc::new(MockRuntimeApi::default());
+	let relay_parent = [1; 32].into();
+	let other_relay_parent = [2; 32].into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let mutex = runtime_api.availability_cores_wait.clone();
+	let calls = runtime_api.availability_cores_calls.clone();
+
+	let subsystem =
+		RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), SpawnGlue(spawner));
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		// Keep the first request in flight until all the others arrived.
+		let lock = mutex.lock().unwrap();
+
+		// More than `MAX_PARALLEL_REQUESTS`, as waiting requests don't count towards it.
+		let mut receivers = Vec::new();
+		for _ in 0..MAX_PARALLEL_REQUESTS * 2 {
+			let (tx, rx) = oneshot::channel();
+
+			ctx_handle
+				.send(FromOrchestra::Communication {
+					msg: RuntimeApiMessage::Request(relay_parent, Request::AvailabilityCores(tx)),
+				})
+				.await;
+			receivers.push(rx);
+		}
+
+		// Requests for another relay parent are not coalesced with them.
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOrchestra::Communication {
+				msg: RuntimeApiMessage::Request(other_relay_parent, Request::AvailabilityCores(tx)),
+			})
+			.await;
+		receivers.push(rx);
+
+		// Requests are handled in order, so all of the above arrived once this is answered.
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOrchestra::Communication {
+				msg: RuntimeApiMessage::Request(relay_parent, Request::Validators(tx)),
+			})
+			.await;
+		assert_eq!(rx.await.unwrap().unwrap(), runtime_api.validators);
+
+		drop(lock);
+
+		future::join_all(receivers)
+			.await
+			.into_iter()
+			.for_each(|r| assert_eq!(r.unwrap().unwrap(), runtime_api.availability_cores));
+		assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+		ctx_handle.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn cache_sizes_are_configurable() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+	let runtime_api = Arc::new(MockRuntimeApi::default());
+	let relay_parent = [1; 32].into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let calls = runtime_api.availability_cores_calls.clone();
+
+	// Nothing fits into the cache.
+	let cache_config = CacheConfig { availability_cores: 0, ..Default::default() };
+	let subsystem = RuntimeApiSubsystem::with_config(
+		runtime_api.clone(),
+		Metrics(None),
+		SpawnGlue(spawner),
+		cache_config,
+	);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		for _ in 0..2 {
+			let (tx, rx) = oneshot::channel();
+
+			ctx_handle
+				.send(FromOrchestra::Communication {
+					msg: RuntimeApiMessage::Request(relay_parent, Request::AvailabilityCores(tx)),
+				})
+				.await;
+
+			assert_eq!(rx.await.unwrap().unwrap(), runtime_api.availability_cores);
+		}
+		assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+		ctx_handle.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
 #[test]
 fn requests_babe_epoch() {
 	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
@@ -19,6 +19,7 @@
 use clap::Parser;
 use color_eyre::eyre;
 use polkadot_cli::{Cli, RunCmd};
+use polkadot_node_core_pvf::TrackingAllocator;
 
 pub(crate) mod interceptor;
 pub(crate) mod shared;
@@ -103,6 +104,11 @@ impl MalusCli {
 	}
 }
 
+/// The PVF workers run by this binary account for the memory allocated by their jobs.
+#[global_allocator]
+static ALLOC: TrackingAllocator<tikv_jemallocator::Jemalloc> =
+	TrackingAllocator(tikv_jemallocator::Jemalloc);
+
 fn main() -> eyre::Result<()> {
 	color_eyre::install()?;
 	let cli = MalusCli::parse();
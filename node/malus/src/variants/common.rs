@@ -217,6 +217,7 @@ where
 						candidate_receipt,
 						pov,
 						timeout,
+						exec_kind,
 						sender,
 					),
 			} => {
@@ -232,6 +233,7 @@ where
 									candidate_receipt,
 									pov,
 									timeout,
+									exec_kind,
 									sender,
 								),
 							})
@@ -265,6 +267,7 @@ where
 							candidate_receipt,
 							pov,
 							timeout,
+							exec_kind,
 							sender,
 						),
 					}),
@@ -276,6 +279,7 @@ where
 						candidate_receipt,
 						pov,
 						timeout,
+						exec_kind,
 						response_sender,
 					),
 			} => {
@@ -289,6 +293,7 @@ where
 									candidate_receipt,
 									pov,
 									timeout,
+									exec_kind,
 									response_sender,
 								),
 							})
@@ -320,6 +325,7 @@ where
 							candidate_receipt,
 							pov,
 							timeout,
+							exec_kind,
 							response_sender,
 						),
 					}),
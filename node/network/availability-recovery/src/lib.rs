@@ -26,7 +26,7 @@ use std::{
 
 use futures::{
 	channel::oneshot,
-	future::{FutureExt, RemoteHandle},
+	future::{BoxFuture, FutureExt, RemoteHandle},
 	pin_mut,
 	prelude::*,
 	stream::FuturesUnordered,
@@ -36,7 +36,9 @@ use lru::LruCache;
 use rand::seq::SliceRandom;
 
 use fatality::Nested;
-use polkadot_erasure_coding::{branch_hash, branches, obtain_chunks_v1, recovery_threshold};
+use polkadot_erasure_coding::{
+	branch_hash, branches, obtain_chunks_v1, recovery_threshold, systematic_recovery_threshold,
+};
 #[cfg(not(test))]
 use polkadot_node_network_protocol::request_response::CHUNK_REQUEST_TIMEOUT;
 use polkadot_node_network_protocol::{
@@ -95,9 +97,15 @@ const TIMEOUT_START_NEW_REQUESTS: Duration = CHUNK_REQUEST_TIMEOUT;
 #[cfg(test)]
 const TIMEOUT_START_NEW_REQUESTS: Duration = Duration::from_millis(100);
 
+/// A pending request for a chunk.
+type ChunkRequest =
+	BoxFuture<'static, Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>;
+
 /// The Availability Recovery Subsystem.
 pub struct AvailabilityRecoverySubsystem {
 	fast_path: bool,
+	/// Whether to fetch the systematic chunks before falling back to requesting any chunks.
+	systematic_chunks: bool,
 	/// Receiver for available data requests.
 	req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 	/// Metrics for this subsystem.
@@ -125,6 +133,19 @@ struct RequestChunksFromValidators {
 	requesting_chunks: FuturesUndead<Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>,
 }
 
+/// Request the systematic chunks from the validators holding them.
+///
+/// The systematic chunks contain the encoded data as is, so the data can be reassembled from them
+/// without running the (expensive) erasure decoding. As each systematic chunk is only held by a
+/// single validator, this fails as soon as one of them cannot provide its chunk.
+struct RequestSystematicChunksFromValidators {
+	/// Validators holding systematic chunks we did not request yet.
+	validators: VecDeque<ValidatorIndex>,
+	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
+	/// Pending chunk requests with soft timeout.
+	requesting_chunks: FuturesUndead<Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>,
+}
+
 struct RecoveryParams {
 	/// Discovery ids of `validators`.
 	validator_authority_keys: Vec<AuthorityDiscoveryId>,
@@ -135,6 +156,9 @@ struct RecoveryParams {
 	/// The number of pieces needed.
 	threshold: usize,
 
+	/// The number of systematic chunks, if they should be requested before any other chunks.
+	systematic_threshold: Option<usize>,
+
 	/// A hash of the relevant candidate.
 	candidate_hash: CandidateHash,
 
@@ -150,9 +174,24 @@ struct RecoveryParams {
 /// backers (a.k.a. fast-path), or recover from chunks.
 enum Source {
 	RequestFromBackers(RequestFromBackers),
+	RequestSystematicChunks(RequestSystematicChunksFromValidators),
 	RequestChunks(RequestChunksFromValidators),
 }
 
+impl Source {
+	/// The source to recover the data from chunks, according to the parameters.
+	fn chunks(params: &RecoveryParams) -> Self {
+		match params.systematic_threshold {
+			Some(systematic_threshold) => Source::RequestSystematicChunks(
+				RequestSystematicChunksFromValidators::new(systematic_threshold as _),
+			),
+			None => Source::RequestChunks(RequestChunksFromValidators::new(
+				params.validators.len() as _,
+			)),
+		}
+	}
+}
+
 /// A stateful reconstruction of availability data in reference to
 /// a candidate hash.
 struct RecoveryTask<Sender> {
@@ -328,28 +367,9 @@ impl RequestChunksFromValidators {
 					"Requesting chunk",
 				);
 
-				// Request data.
-				let raw_request = req_res::v1::ChunkFetchingRequest {
-					candidate_hash: params.candidate_hash,
-					index: validator_index,
-				};
-
-				let (req, res) =
-					OutgoingRequest::new(Recipient::Authority(validator), raw_request.clone());
-				requests.push(Requests::ChunkFetchingV1(req));
-
-				params.metrics.on_chunk_request_issued();
-				let timer = params.metrics.time_chunk_request();
-
-				self.requesting_chunks.push(Box::pin(async move {
-					let _timer = timer;
-					match res.await {
-						Ok(req_res::v1::ChunkFetchingResponse::Chunk(chunk)) =>
-							Ok(Some(chunk.recombine_into_chunk(&raw_request))),
-						Ok(req_res::v1::ChunkFetchingResponse::NoSuchChunk) => Ok(None),
-						Err(e) => Err((validator_index, e)),
-					}
-				}));
+				let (req, res) = make_chunk_request(params, validator_index);
+				requests.push(req);
+				self.requesting_chunks.push(res);
 			} else {
 				break
 			}
@@ -376,49 +396,14 @@ impl RequestChunksFromValidators {
 			self.total_received_responses += 1;
 
 			match request_result {
-				Ok(Some(chunk)) => {
-					// Check merkle proofs of any received chunks.
-
-					let validator_index = chunk.index;
-
-					if let Ok(anticipated_hash) =
-						branch_hash(&params.erasure_root, chunk.proof(), chunk.index.0 as usize)
-					{
-						let erasure_chunk_hash = BlakeTwo256::hash(&chunk.chunk);
-
-						if erasure_chunk_hash != anticipated_hash {
-							metrics.on_chunk_request_invalid();
-							self.error_count += 1;
-
-							gum::debug!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								?validator_index,
-								"Merkle proof mismatch",
-							);
-						} else {
-							metrics.on_chunk_request_succeeded();
-
-							gum::trace!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								?validator_index,
-								"Received valid chunk.",
-							);
-							self.received_chunks.insert(validator_index, chunk);
-						}
+				Ok(Some(chunk)) =>
+					if is_chunk_valid(params, &chunk) {
+						metrics.on_chunk_request_succeeded();
+						self.received_chunks.insert(chunk.index, chunk);
 					} else {
 						metrics.on_chunk_request_invalid();
 						self.error_count += 1;
-
-						gum::debug!(
-							target: LOG_TARGET,
-							candidate_hash = ?params.candidate_hash,
-							?validator_index,
-							"Invalid Merkle proof",
-						);
-					}
-				},
+					},
 				Ok(None) => {
 					metrics.on_chunk_request_no_such_chunk();
 					self.error_count += 1;
@@ -493,32 +478,13 @@ impl RequestChunksFromValidators {
 		let metrics = &params.metrics;
 
 		// First query the store for any chunks we've got.
-		{
-			let (tx, rx) = oneshot::channel();
-			sender
-				.send_message(AvailabilityStoreMessage::QueryAllChunks(params.candidate_hash, tx))
-				.await;
-
-			match rx.await {
-				Ok(chunks) => {
-					// This should either be length 1 or 0. If we had the whole data,
-					// we wouldn't have reached this stage.
-					let chunk_indices: Vec<_> = chunks.iter().map(|c| c.index).collect();
-					self.shuffling.retain(|i| !chunk_indices.contains(i));
-
-					for chunk in chunks {
-						self.received_chunks.insert(chunk.index, chunk);
-					}
-				},
-				Err(oneshot::Canceled) => {
-					gum::warn!(
-						target: LOG_TARGET,
-						candidate_hash = ?params.candidate_hash,
-						"Failed to reach the availability store"
-					);
-				},
-			}
+		for chunk in query_local_chunks(params, sender).await {
+			self.received_chunks.insert(chunk.index, chunk);
 		}
+		// Don't request the chunks we already got, either locally or from the systematic chunks
+		// recovery.
+		let received_chunks = &self.received_chunks;
+		self.shuffling.retain(|i| !received_chunks.contains_key(i));
 
 		let _recovery_timer = metrics.time_full_recovery();
 
@@ -600,6 +566,188 @@ impl RequestChunksFromValidators {
 	}
 }
 
+impl RequestSystematicChunksFromValidators {
+	fn new(systematic_threshold: u32) -> Self {
+		RequestSystematicChunksFromValidators {
+			validators: (0..systematic_threshold).map(ValidatorIndex).collect(),
+			received_chunks: HashMap::new(),
+			requesting_chunks: FuturesUndead::new(),
+		}
+	}
+
+	/// Request the chunks of the validators we did not request yet, up to `N_PARALLEL` at a time.
+	async fn launch_parallel_requests<Sender>(
+		&mut self,
+		params: &RecoveryParams,
+		sender: &mut Sender,
+	) where
+		Sender: overseer::AvailabilityRecoverySenderTrait,
+	{
+		let mut requests = Vec::new();
+		while self.requesting_chunks.len() < N_PARALLEL {
+			let validator_index = match self.validators.pop_front() {
+				Some(validator_index) => validator_index,
+				None => break,
+			};
+
+			gum::trace!(
+				target: LOG_TARGET,
+				?validator_index,
+				candidate_hash = ?params.candidate_hash,
+				"Requesting systematic chunk",
+			);
+
+			let (req, res) = make_chunk_request(params, validator_index);
+			requests.push(req);
+			self.requesting_chunks.push(res);
+		}
+
+		if !requests.is_empty() {
+			sender
+				.send_message(NetworkBridgeTxMessage::SendRequests(
+					requests,
+					IfDisconnected::ImmediateError,
+				))
+				.await;
+		}
+	}
+
+	/// Wait for the pending requests to conclude.
+	///
+	/// Returns `false` as soon as a validator fails to provide a valid chunk, as there is no one
+	/// else to get that systematic chunk from.
+	async fn wait_for_chunks(&mut self, params: &RecoveryParams) -> bool {
+		let metrics = &params.metrics;
+
+		while let Some(request_result) =
+			self.requesting_chunks.next_with_timeout(TIMEOUT_START_NEW_REQUESTS).await
+		{
+			match request_result {
+				Ok(Some(chunk)) if is_chunk_valid(params, &chunk) => {
+					metrics.on_chunk_request_succeeded();
+					self.received_chunks.insert(chunk.index, chunk);
+				},
+				Ok(Some(chunk)) => {
+					metrics.on_chunk_request_invalid();
+
+					gum::debug!(
+						target: LOG_TARGET,
+						candidate_hash = ?params.candidate_hash,
+						validator_index = ?chunk.index,
+						"Invalid systematic chunk",
+					);
+					return false
+				},
+				Ok(None) => {
+					metrics.on_chunk_request_no_such_chunk();
+					return false
+				},
+				Err((validator_index, e)) => {
+					match e {
+						RequestError::InvalidResponse(_) => metrics.on_chunk_request_invalid(),
+						RequestError::NetworkError(RequestFailure::Network(
+							OutboundFailure::Timeout,
+						)) => metrics.on_chunk_request_timeout(),
+						RequestError::NetworkError(_) | RequestError::Canceled(_) =>
+							metrics.on_chunk_request_error(),
+					}
+
+					gum::debug!(
+						target: LOG_TARGET,
+						candidate_hash = ?params.candidate_hash,
+						err = ?e,
+						?validator_index,
+						"Failure requesting systematic chunk",
+					);
+					return false
+				},
+			}
+		}
+
+		true
+	}
+
+	async fn run<Sender>(
+		&mut self,
+		params: &RecoveryParams,
+		sender: &mut Sender,
+	) -> Result<AvailableData, RecoveryError>
+	where
+		Sender: overseer::AvailabilityRecoverySenderTrait,
+	{
+		let metrics = &params.metrics;
+
+		// First query the store for any systematic chunks we've got.
+		for chunk in query_local_chunks(params, sender).await {
+			if self.validators.contains(&chunk.index) {
+				self.validators.retain(|i| *i != chunk.index);
+				self.received_chunks.insert(chunk.index, chunk);
+			}
+		}
+
+		let _recovery_timer = metrics.time_full_recovery();
+
+		while !self.validators.is_empty() || self.requesting_chunks.total_len() > 0 {
+			self.launch_parallel_requests(params, sender).await;
+
+			if !self.wait_for_chunks(params).await {
+				gum::debug!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					received = %self.received_chunks.len(),
+					"Systematic chunks recovery is not possible, falling back to regular chunks",
+				);
+				metrics.on_systematic_recovery_fallback();
+
+				return Err(RecoveryError::Unavailable)
+			}
+		}
+
+		let recovery_duration = metrics.time_erasure_recovery();
+
+		let mut chunks: Vec<_> = self.received_chunks.values().collect();
+		chunks.sort_by_key(|c| c.index);
+
+		match polkadot_erasure_coding::reconstruct_from_systematic_v1(
+			params.validators.len(),
+			chunks.iter().map(|c| &c.chunk[..]),
+		) {
+			Ok(data)
+				if reconstructed_data_matches_root(
+					params.validators.len(),
+					&params.erasure_root,
+					&data,
+				) =>
+			{
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					erasure_root = ?params.erasure_root,
+					"Data recovery from systematic chunks complete",
+				);
+				metrics.on_systematic_recovery_succeeded();
+				metrics.on_recovery_succeeded();
+
+				Ok(data)
+			},
+			res => {
+				recovery_duration.map(|rd| rd.stop_and_discard());
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					erasure_root = ?params.erasure_root,
+					err = ?res.err(),
+					"Data recovery from systematic chunks - invalid data",
+				);
+				metrics.on_systematic_recovery_invalid();
+				metrics.on_recovery_invalid();
+
+				Err(RecoveryError::Invalid)
+			},
+		}
+	}
+}
+
 const fn is_unavailable(
 	received_chunks: usize,
 	requesting_chunks: usize,
@@ -609,6 +757,99 @@ const fn is_unavailable(
 	received_chunks + requesting_chunks + unrequested_validators < threshold
 }
 
+/// Create a request for the chunk of the given validator, along with the future resolving to the
+/// response.
+fn make_chunk_request(
+	params: &RecoveryParams,
+	validator_index: ValidatorIndex,
+) -> (Requests, ChunkRequest) {
+	let validator = params.validator_authority_keys[validator_index.0 as usize].clone();
+	let raw_request = req_res::v1::ChunkFetchingRequest {
+		candidate_hash: params.candidate_hash,
+		index: validator_index,
+	};
+
+	let (req, res) = OutgoingRequest::new(Recipient::Authority(validator), raw_request.clone());
+
+	params.metrics.on_chunk_request_issued();
+	let timer = params.metrics.time_chunk_request();
+
+	let res: ChunkRequest = Box::pin(async move {
+		let _timer = timer;
+		match res.await {
+			Ok(req_res::v1::ChunkFetchingResponse::Chunk(chunk)) =>
+				Ok(Some(chunk.recombine_into_chunk(&raw_request))),
+			Ok(req_res::v1::ChunkFetchingResponse::NoSuchChunk) => Ok(None),
+			Err(e) => Err((validator_index, e)),
+		}
+	});
+
+	(Requests::ChunkFetchingV1(req), res)
+}
+
+/// Check the merkle proof of a received chunk.
+fn is_chunk_valid(params: &RecoveryParams, chunk: &ErasureChunk) -> bool {
+	let validator_index = chunk.index;
+
+	match branch_hash(&params.erasure_root, chunk.proof(), chunk.index.0 as usize) {
+		Ok(anticipated_hash) => {
+			let erasure_chunk_hash = BlakeTwo256::hash(&chunk.chunk);
+
+			if erasure_chunk_hash != anticipated_hash {
+				gum::debug!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					?validator_index,
+					"Merkle proof mismatch",
+				);
+				false
+			} else {
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					?validator_index,
+					"Received valid chunk.",
+				);
+				true
+			}
+		},
+		Err(_) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				candidate_hash = ?params.candidate_hash,
+				?validator_index,
+				"Invalid Merkle proof",
+			);
+			false
+		},
+	}
+}
+
+/// Query the availability store for any chunks of the candidate we've got.
+async fn query_local_chunks(
+	params: &RecoveryParams,
+	sender: &mut impl overseer::AvailabilityRecoverySenderTrait,
+) -> Vec<ErasureChunk> {
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(AvailabilityStoreMessage::QueryAllChunks(params.candidate_hash, tx))
+		.await;
+
+	match rx.await {
+		// This should either be length 1 or 0. If we had the whole data,
+		// we wouldn't have reached this stage.
+		Ok(chunks) => chunks,
+		Err(oneshot::Canceled) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				candidate_hash = ?params.candidate_hash,
+				"Failed to reach the availability store"
+			);
+			Vec::new()
+		},
+	}
+}
+
 /// Re-encode the data into erasure chunks in order to verify
 /// the root hash of the provided Merkle tree, which is built
 /// on-top of the encoded chunks.
@@ -685,9 +926,22 @@ where
 						Ok(data) => break Ok(data),
 						Err(RecoveryError::Invalid) => break Err(RecoveryError::Invalid),
 						Err(RecoveryError::Unavailable) =>
-							self.source = Source::RequestChunks(RequestChunksFromValidators::new(
-								self.params.validators.len() as _,
-							)),
+							self.source = Source::chunks(&self.params),
+					}
+				},
+				Source::RequestSystematicChunks(ref mut systematic) => {
+					match systematic.run(&self.params, &mut self.sender).await {
+						Ok(data) => break Ok(data),
+						Err(RecoveryError::Invalid) => break Err(RecoveryError::Invalid),
+						Err(RecoveryError::Unavailable) => {
+							// The systematic chunks we got are just as good for the regular
+							// recovery.
+							let mut from_all =
+								RequestChunksFromValidators::new(self.params.validators.len() as _);
+							from_all.received_chunks =
+								std::mem::take(&mut systematic.received_chunks);
+							self.source = Source::RequestChunks(from_all);
+						},
 					}
 				},
 				Source::RequestChunks(ref mut from_all) =>
@@ -839,15 +1093,23 @@ async fn launch_recovery_task<Context>(
 	session_info: SessionInfo,
 	receipt: CandidateReceipt,
 	backing_group: Option<GroupIndex>,
+	systematic_chunks: bool,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 	metrics: &Metrics,
 ) -> error::Result<()> {
 	let candidate_hash = receipt.hash();
 
+	let systematic_threshold = if systematic_chunks {
+		Some(systematic_recovery_threshold(session_info.validators.len())?)
+	} else {
+		None
+	};
+
 	let params = RecoveryParams {
 		validator_authority_keys: session_info.discovery_keys.clone(),
 		validators: session_info.validators.clone(),
 		threshold: recovery_threshold(session_info.validators.len())?,
+		systematic_threshold,
 		candidate_hash,
 		erasure_root: receipt.descriptor.erasure_root,
 		metrics: metrics.clone(),
@@ -856,9 +1118,7 @@ async fn launch_recovery_task<Context>(
 	let phase = backing_group
 		.and_then(|g| session_info.validator_groups.get(g.0 as usize))
 		.map(|group| Source::RequestFromBackers(RequestFromBackers::new(group.clone())))
-		.unwrap_or_else(|| {
-			Source::RequestChunks(RequestChunksFromValidators::new(params.validators.len() as _))
-		});
+		.unwrap_or_else(|| Source::chunks(&params));
 
 	let recovery_task = RecoveryTask { sender: ctx.sender().clone(), params, source: phase };
 
@@ -889,6 +1149,7 @@ async fn handle_recover<Context>(
 	receipt: CandidateReceipt,
 	session_index: SessionIndex,
 	backing_group: Option<GroupIndex>,
+	systematic_chunks: bool,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
 	metrics: &Metrics,
 ) -> error::Result<()> {
@@ -932,6 +1193,7 @@ async fn handle_recover<Context>(
 				session_info,
 				receipt,
 				backing_group,
+				systematic_chunks,
 				response_sender,
 				metrics,
 			)
@@ -967,7 +1229,7 @@ impl AvailabilityRecoverySubsystem {
 		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 		metrics: Metrics,
 	) -> Self {
-		Self { fast_path: true, req_receiver, metrics }
+		Self { fast_path: true, systematic_chunks: false, req_receiver, metrics }
 	}
 
 	/// Create a new instance of `AvailabilityRecoverySubsystem` which requests only chunks
@@ -975,12 +1237,21 @@ impl AvailabilityRecoverySubsystem {
 		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 		metrics: Metrics,
 	) -> Self {
-		Self { fast_path: false, req_receiver, metrics }
+		Self { fast_path: false, systematic_chunks: false, req_receiver, metrics }
+	}
+
+	/// Create a new instance of `AvailabilityRecoverySubsystem` which requests only chunks, starting
+	/// with the systematic chunks. These allow recovering the data without erasure decoding.
+	pub fn with_systematic_chunks(
+		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
+		metrics: Metrics,
+	) -> Self {
+		Self { fast_path: false, systematic_chunks: true, req_receiver, metrics }
 	}
 
 	async fn run<Context>(self, mut ctx: Context) -> SubsystemResult<()> {
 		let mut state = State::default();
-		let Self { fast_path, mut req_receiver, metrics } = self;
+		let Self { fast_path, systematic_chunks, mut req_receiver, metrics } = self;
 
 		loop {
 			let recv_req = req_receiver.recv(|| vec![COST_INVALID_REQUEST]).fuse();
@@ -1008,6 +1279,7 @@ impl AvailabilityRecoverySubsystem {
 										receipt,
 										session_index,
 										maybe_backing_group.filter(|_| fast_path),
+										systematic_chunks,
 										response_sender,
 										&metrics,
 									).await {
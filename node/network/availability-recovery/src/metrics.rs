@@ -58,6 +58,15 @@ struct MetricsInner {
 	/// Note: Those are only recoveries which could not get served locally already - so in other
 	/// words: Only real recoveries.
 	full_recoveries_started: Counter<U64>,
+
+	/// Number of recoveries from systematic chunks that have been finished.
+	///
+	/// Split by result:
+	/// - `success` ... data was recovered from the systematic chunks.
+	/// - `invalid` ... data was recovered, but was invalid.
+	/// - `fallback` ... not all systematic chunks could be fetched, so we fell back to requesting
+	///   any chunks.
+	systematic_recoveries_finished: CounterVec<U64>,
 }
 
 impl Metrics {
@@ -144,6 +153,27 @@ impl Metrics {
 		}
 	}
 
+	/// A recovery from systematic chunks succeeded.
+	pub fn on_systematic_recovery_succeeded(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.systematic_recoveries_finished.with_label_values(&["success"]).inc()
+		}
+	}
+
+	/// A recovery from systematic chunks failed (data was recovered, but invalid).
+	pub fn on_systematic_recovery_invalid(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.systematic_recoveries_finished.with_label_values(&["invalid"]).inc()
+		}
+	}
+
+	/// A recovery from systematic chunks was not possible, falling back to regular chunks.
+	pub fn on_systematic_recovery_fallback(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.systematic_recoveries_finished.with_label_values(&["fallback"]).inc()
+		}
+	}
+
 	/// A recover was started.
 	pub fn on_recovery_started(&self) {
 		if let Some(metrics) = &self.0 {
@@ -210,6 +240,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			systematic_recoveries_finished: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_availability_recovery_systematic_recoveries_finished",
+						"Total number of recoveries from systematic chunks that finished.",
+					),
+					&["result"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -49,6 +49,9 @@ use self::network::Network;
 mod metrics;
 pub use self::metrics::Metrics;
 
+mod rate_limit;
+pub use self::rate_limit::{NotificationKind, RateLimit, RateLimits};
+
 mod errors;
 pub(crate) use self::errors::Error;
 
@@ -68,6 +71,8 @@ pub(crate) const UNCONNECTED_PEERSET_COST: Rep =
 	Rep::CostMinor("Message sent to un-connected peer-set");
 pub(crate) const MALFORMED_VIEW_COST: Rep = Rep::CostMajor("Malformed view");
 pub(crate) const EMPTY_VIEW_COST: Rep = Rep::CostMajor("Peer sent us an empty view");
+pub(crate) const RATE_LIMITED_COST: Rep =
+	Rep::CostMinor("Peer exceeded the notification rate limit");
 
 /// Messages from and to the network.
 ///
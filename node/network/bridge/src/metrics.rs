@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use super::{PeerSet, ProtocolVersion};
+use super::{NotificationKind, PeerSet, ProtocolVersion, RateLimits};
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
 
 /// Metrics for the network bridge.
@@ -103,6 +103,31 @@ impl Metrics {
 		});
 	}
 
+	pub fn on_notification_dropped(&self, peer_set: PeerSet, kind: NotificationKind) {
+		self.0.as_ref().map(|metrics| {
+			metrics
+				.notifications_dropped
+				.with_label_values(&[peer_set.get_default_protocol_name(), kind.label()])
+				.inc()
+		});
+	}
+
+	pub fn note_rate_limits(&self, limits: &RateLimits) {
+		if let Some(metrics) = self.0.as_ref() {
+			for (peer_set, kind, limit) in limits.iter() {
+				let protocol = peer_set.get_default_protocol_name();
+				metrics
+					.rate_limits
+					.with_label_values(&[protocol, kind.label(), "burst"])
+					.set(limit.burst as u64);
+				metrics
+					.rate_limits
+					.with_label_values(&[protocol, kind.label(), "per_second"])
+					.set(limit.per_second as u64);
+			}
+		}
+	}
+
 	pub fn on_report_event(&self) {
 		if let Some(metrics) = self.0.as_ref() {
 			metrics.report_events.inc()
@@ -123,6 +148,9 @@ pub(crate) struct MetricsInner {
 
 	bytes_received: prometheus::CounterVec<prometheus::U64>,
 	bytes_sent: prometheus::CounterVec<prometheus::U64>,
+
+	notifications_dropped: prometheus::CounterVec<prometheus::U64>,
+	rate_limits: prometheus::GaugeVec<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -217,6 +245,26 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			notifications_dropped: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_notifications_rate_limited_total",
+						"The number of notifications dropped for exceeding the rate limit of their peer",
+					),
+					&["protocol", "kind"]
+				)?,
+				registry,
+			)?,
+			rate_limits: prometheus::register(
+				prometheus::GaugeVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_notification_rate_limit",
+						"The per-peer rate limits of notifications, as burst size and refill rate per second",
+					),
+					&["protocol", "kind", "parameter"]
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -0,0 +1,360 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Per-peer rate limiting of incoming notifications.
+//!
+//! Every connected peer gets a token bucket per peer set and kind of protocol message.
+//! Messages arriving while the bucket is empty are dropped before they reach any subsystem.
+//!
+//! View updates are not limited: dropping one would leave subsystems with an outdated view of
+//! the peer, and they are bounded by `MAX_VIEW_HEADS` and only dispatched when they change.
+
+use std::{collections::HashMap, time::Instant};
+
+use polkadot_node_network_protocol::{peer_set::PeerSet, v1 as protocol_v1, PeerId};
+
+/// The kinds of protocol messages which are rate limited separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationKind {
+	/// Messages of the bitfield distribution subsystem.
+	BitfieldDistribution,
+	/// Messages of the statement distribution subsystem.
+	StatementDistribution,
+	/// Messages of the approval distribution subsystem.
+	ApprovalDistribution,
+	/// Messages of the collator protocol subsystem.
+	CollatorProtocol,
+}
+
+impl NotificationKind {
+	/// All kinds of notifications.
+	pub const ALL: [Self; 4] = [
+		Self::BitfieldDistribution,
+		Self::StatementDistribution,
+		Self::ApprovalDistribution,
+		Self::CollatorProtocol,
+	];
+
+	/// The peer set the kind of notification is sent on.
+	pub fn peer_set(&self) -> PeerSet {
+		match self {
+			Self::BitfieldDistribution |
+			Self::StatementDistribution |
+			Self::ApprovalDistribution => PeerSet::Validation,
+			Self::CollatorProtocol => PeerSet::Collation,
+		}
+	}
+
+	/// The label of the kind in metrics and on the command line.
+	pub fn label(&self) -> &'static str {
+		match self {
+			Self::BitfieldDistribution => "bitfield-distribution",
+			Self::StatementDistribution => "statement-distribution",
+			Self::ApprovalDistribution => "approval-distribution",
+			Self::CollatorProtocol => "collator-protocol",
+		}
+	}
+}
+
+/// Protocol messages which can tell their kind of notification.
+pub(crate) trait OfNotificationKind {
+	/// The kind of notification the message is sent in.
+	fn notification_kind(&self) -> NotificationKind;
+}
+
+impl OfNotificationKind for protocol_v1::ValidationProtocol {
+	fn notification_kind(&self) -> NotificationKind {
+		match self {
+			Self::BitfieldDistribution(_) => NotificationKind::BitfieldDistribution,
+			Self::StatementDistribution(_) => NotificationKind::StatementDistribution,
+			Self::ApprovalDistribution(_) => NotificationKind::ApprovalDistribution,
+		}
+	}
+}
+
+impl OfNotificationKind for protocol_v1::CollationProtocol {
+	fn notification_kind(&self) -> NotificationKind {
+		match self {
+			Self::CollatorProtocol(_) => NotificationKind::CollatorProtocol,
+		}
+	}
+}
+
+/// A token bucket: up to `burst` notifications are let through at once, after which the bucket
+/// refills by `per_second` notifications per second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+	/// The capacity of the bucket.
+	pub burst: u32,
+	/// How many notifications per second the bucket is refilled by.
+	pub per_second: u32,
+}
+
+/// The rate limits per peer set and kind of notification.
+///
+/// Notifications without a limit are never dropped.
+#[derive(Debug, Clone)]
+pub struct RateLimits(HashMap<(PeerSet, NotificationKind), RateLimit>);
+
+impl RateLimits {
+	/// Rate limits which never drop any notification.
+	pub fn unlimited() -> Self {
+		Self(HashMap::new())
+	}
+
+	/// Limit a kind of notification on a peer set, replacing any previous limit.
+	pub fn with_limit(
+		mut self,
+		peer_set: PeerSet,
+		kind: NotificationKind,
+		limit: RateLimit,
+	) -> Self {
+		self.0.insert((peer_set, kind), limit);
+		self
+	}
+
+	/// Stop limiting a kind of notification on a peer set.
+	pub fn without_limit(mut self, peer_set: PeerSet, kind: NotificationKind) -> Self {
+		self.0.remove(&(peer_set, kind));
+		self
+	}
+
+	/// The limit of a kind of notification on a peer set, if any.
+	pub fn get(&self, peer_set: PeerSet, kind: NotificationKind) -> Option<RateLimit> {
+		self.0.get(&(peer_set, kind)).copied()
+	}
+
+	/// All configured limits.
+	pub fn iter(&self) -> impl Iterator<Item = (PeerSet, NotificationKind, RateLimit)> + '_ {
+		self.0.iter().map(|((peer_set, kind), limit)| (*peer_set, *kind, *limit))
+	}
+}
+
+/// The number of validators the default limits of the validation peer set are sized for, well
+/// above the size of the active sets on Polkadot and Kusama.
+const MAX_EXPECTED_VALIDATORS: u32 = 2_000;
+
+/// The number of leaves per block time the default limits of the validation peer set are sized
+/// for, since gossip is repeated for every fork.
+const MAX_EXPECTED_LEAVES: u32 = 4;
+
+/// The block time the default limits of the validation peer set are refilled over.
+const BLOCK_TIME_SECS: u32 = 6;
+
+/// A limit letting through the burst of notifications a peer may relay upon new leaves, given the
+/// number of notifications a single validator sends per leaf. The bucket is refilled within a block
+/// time.
+const fn validation_limit(per_validator_and_leaf: u32) -> RateLimit {
+	let burst = per_validator_and_leaf * MAX_EXPECTED_VALIDATORS * MAX_EXPECTED_LEAVES;
+	RateLimit { burst, per_second: burst / BLOCK_TIME_SECS }
+}
+
+impl Default for RateLimits {
+	/// Collators are expected to send few messages, so these are limited generously.
+	///
+	/// Bitfields, statements and approvals are gossiped in bursts growing with the number of
+	/// validators, as a peer may relay the notifications of every validator for each new leaf. Per
+	/// leaf, a validator sends a single bitfield, a statement for each candidate of its backing
+	/// group, and an assignment and an approval for each of the few candidates it checks. The
+	/// limits are sized for these bursts, so only peers sending far more than gossip requires are
+	/// limited.
+	fn default() -> Self {
+		Self::unlimited()
+			.with_limit(
+				PeerSet::Collation,
+				NotificationKind::CollatorProtocol,
+				RateLimit { burst: 100, per_second: 20 },
+			)
+			.with_limit(
+				PeerSet::Validation,
+				NotificationKind::BitfieldDistribution,
+				validation_limit(1),
+			)
+			.with_limit(
+				PeerSet::Validation,
+				NotificationKind::StatementDistribution,
+				validation_limit(5),
+			)
+			.with_limit(
+				PeerSet::Validation,
+				NotificationKind::ApprovalDistribution,
+				validation_limit(10),
+			)
+	}
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+	tokens: f64,
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	fn full(limit: RateLimit, now: Instant) -> Self {
+		Self { tokens: limit.burst as f64, last_refill: now }
+	}
+
+	fn try_take(&mut self, limit: RateLimit, now: Instant) -> bool {
+		let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+		self.tokens = (self.tokens + elapsed * limit.per_second as f64).min(limit.burst as f64);
+		self.last_refill = now;
+
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			true
+		} else {
+			false
+		}
+	}
+}
+
+/// The token buckets of all connected peers.
+pub(crate) struct RateLimiter {
+	limits: RateLimits,
+	buckets: HashMap<(PeerId, PeerSet, NotificationKind), TokenBucket>,
+}
+
+impl RateLimiter {
+	pub(crate) fn new(limits: RateLimits) -> Self {
+		Self { limits, buckets: HashMap::new() }
+	}
+
+	/// Whether a notification of a peer may pass, taking a token from its bucket if so.
+	pub(crate) fn allow(
+		&mut self,
+		peer: PeerId,
+		peer_set: PeerSet,
+		kind: NotificationKind,
+		now: Instant,
+	) -> bool {
+		let limit = match self.limits.get(peer_set, kind) {
+			Some(limit) => limit,
+			None => return true,
+		};
+
+		self.buckets
+			.entry((peer, peer_set, kind))
+			.or_insert_with(|| TokenBucket::full(limit, now))
+			.try_take(limit, now)
+	}
+
+	/// Forget the buckets of a peer which disconnected from a peer set.
+	pub(crate) fn remove_peer(&mut self, peer: &PeerId, peer_set: PeerSet) {
+		self.buckets.retain(|(p, s, _), _| p != peer || *s != peer_set);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	const LIMIT: RateLimit = RateLimit { burst: 3, per_second: 2 };
+
+	fn limiter() -> RateLimiter {
+		RateLimiter::new(RateLimits::unlimited().with_limit(
+			PeerSet::Validation,
+			NotificationKind::StatementDistribution,
+			LIMIT,
+		))
+	}
+
+	#[test]
+	fn bursts_are_limited_and_refilled() {
+		let mut limiter = limiter();
+		let peer = PeerId::random();
+		let now = Instant::now();
+		let mut allow = |at| {
+			limiter.allow(peer, PeerSet::Validation, NotificationKind::StatementDistribution, at)
+		};
+
+		assert!((0..LIMIT.burst).all(|_| allow(now)));
+		assert!(!allow(now));
+
+		// Half a second refills a single token.
+		let later = now + Duration::from_millis(500);
+		assert!(allow(later));
+		assert!(!allow(later));
+
+		// The bucket never holds more than the burst.
+		let much_later = later + Duration::from_secs(60);
+		assert!((0..LIMIT.burst).all(|_| allow(much_later)));
+		assert!(!allow(much_later));
+	}
+
+	#[test]
+	fn buckets_are_per_peer_peer_set_and_kind() {
+		let mut limiter = limiter();
+		let (peer_a, peer_b) = (PeerId::random(), PeerId::random());
+		let now = Instant::now();
+
+		for _ in 0..LIMIT.burst {
+			assert!(limiter.allow(
+				peer_a,
+				PeerSet::Validation,
+				NotificationKind::StatementDistribution,
+				now
+			));
+		}
+		assert!(!limiter.allow(
+			peer_a,
+			PeerSet::Validation,
+			NotificationKind::StatementDistribution,
+			now
+		));
+
+		assert!(limiter.allow(
+			peer_b,
+			PeerSet::Validation,
+			NotificationKind::StatementDistribution,
+			now
+		));
+		assert!(limiter.allow(
+			peer_a,
+			PeerSet::Validation,
+			NotificationKind::ApprovalDistribution,
+			now
+		));
+		assert!(limiter.allow(
+			peer_a,
+			PeerSet::Collation,
+			NotificationKind::StatementDistribution,
+			now
+		));
+
+		// Reconnecting peers start with a full bucket.
+		limiter.remove_peer(&peer_a, PeerSet::Validation);
+		assert!(limiter.allow(
+			peer_a,
+			PeerSet::Validation,
+			NotificationKind::StatementDistribution,
+			now
+		));
+	}
+
+	#[test]
+	fn validation_notifications_are_limited_by_default() {
+		let mut limiter = RateLimiter::new(RateLimits::default());
+		let peer = PeerId::random();
+		let now = Instant::now();
+
+		for kind in NotificationKind::ALL {
+			let limit = RateLimits::default().get(kind.peer_set(), kind).unwrap();
+			assert!((0..limit.burst).all(|_| limiter.allow(peer, kind.peer_set(), kind, now)));
+			assert!(!limiter.allow(peer, kind.peer_set(), kind, now));
+		}
+	}
+}
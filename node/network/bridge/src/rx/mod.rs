@@ -53,6 +53,7 @@ pub use polkadot_node_network_protocol::peer_set::{peer_sets_info, IsAuthority};
 use std::{
 	collections::{hash_map, HashMap},
 	iter::ExactSizeIterator,
+	time::Instant,
 };
 
 use super::validator_discovery;
@@ -66,6 +67,8 @@ use crate::network::get_peer_id_by_authority_id;
 
 use super::metrics::Metrics;
 
+use crate::rate_limit::{OfNotificationKind, RateLimiter, RateLimits};
+
 #[cfg(test)]
 mod tests;
 
@@ -80,6 +83,7 @@ pub struct NetworkBridgeRx<N, AD> {
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	shared: Shared,
 	metrics: Metrics,
+	rate_limits: RateLimits,
 }
 
 impl<N, AD> NetworkBridgeRx<N, AD> {
@@ -94,7 +98,20 @@ impl<N, AD> NetworkBridgeRx<N, AD> {
 		metrics: Metrics,
 	) -> Self {
 		let shared = Shared::default();
-		Self { network_service, authority_discovery_service, sync_oracle, shared, metrics }
+		Self {
+			network_service,
+			authority_discovery_service,
+			sync_oracle,
+			shared,
+			metrics,
+			rate_limits: RateLimits::default(),
+		}
+	}
+
+	/// Replace the default per-peer rate limits of incoming notifications.
+	pub fn with_rate_limits(mut self, rate_limits: RateLimits) -> Self {
+		self.rate_limits = rate_limits;
+		self
 	}
 }
 
@@ -147,6 +164,7 @@ async fn handle_network_messages<AD>(
 	mut authority_discovery_service: AD,
 	metrics: Metrics,
 	shared: Shared,
+	mut rate_limiter: RateLimiter,
 ) -> Result<(), Error>
 where
 	AD: validator_discovery::AuthorityDiscovery + Send,
@@ -317,6 +335,8 @@ where
 					w
 				};
 
+				rate_limiter.remove_peer(&peer, peer_set);
+
 				if was_connected && version == peer_set.get_default_version() {
 					match peer_set {
 						PeerSet::Validation =>
@@ -428,6 +448,7 @@ where
 								PeerSet::Validation,
 								&mut shared.0.lock().validation_peers,
 								v_messages,
+								&mut rate_limiter,
 								&metrics,
 							)
 						} else {
@@ -459,6 +480,7 @@ where
 								PeerSet::Collation,
 								&mut shared.0.lock().collation_peers,
 								c_messages,
+								&mut rate_limiter,
 								&metrics,
 							)
 						} else {
@@ -619,8 +641,11 @@ where
 		metrics,
 		sync_oracle,
 		shared,
+		rate_limits,
 	} = bridge;
 
+	metrics.note_rate_limits(&rate_limits);
+
 	let (task, network_event_handler) = handle_network_messages(
 		ctx.sender().clone(),
 		network_service.clone(),
@@ -628,6 +653,7 @@ where
 		authority_discovery_service.clone(),
 		metrics.clone(),
 		shared.clone(),
+		RateLimiter::new(rate_limits),
 	)
 	.remote_handle();
 
@@ -724,11 +750,18 @@ fn update_our_view<Net, Context>(
 
 // Handle messages on a specific v1 peer-set. The peer is expected to be connected on that
 // peer-set.
-fn handle_v1_peer_messages<RawMessage: Decode, OutMessage: From<RawMessage>>(
+//
+// Messages exceeding the rate limits of the peer are dropped, and the peer gets reported once
+// per batch of messages.
+fn handle_v1_peer_messages<
+	RawMessage: Decode + OfNotificationKind,
+	OutMessage: From<RawMessage>,
+>(
 	peer: PeerId,
 	peer_set: PeerSet,
 	peers: &mut HashMap<PeerId, PeerData>,
 	messages: Vec<Bytes>,
+	rate_limiter: &mut RateLimiter,
 	metrics: &Metrics,
 ) -> (Vec<NetworkBridgeEvent<OutMessage>>, Vec<Rep>) {
 	let peer_data = match peers.get_mut(&peer) {
@@ -738,6 +771,8 @@ fn handle_v1_peer_messages<RawMessage: Decode, OutMessage: From<RawMessage>>(
 
 	let mut outgoing_events = Vec::with_capacity(messages.len());
 	let mut reports = Vec::new();
+	let mut rate_limited = false;
+	let now = Instant::now();
 
 	for message in messages {
 		metrics.on_notification_received(peer_set, peer_data.version, message.len());
@@ -749,6 +784,19 @@ fn handle_v1_peer_messages<RawMessage: Decode, OutMessage: From<RawMessage>>(
 			Ok(m) => m,
 		};
 
+		// View updates are not limited, such that subsystems always know the latest view.
+		if let WireMessage::ProtocolMessage(message) = &message {
+			let kind = message.notification_kind();
+			if !rate_limiter.allow(peer, peer_set, kind, now) {
+				metrics.on_notification_dropped(peer_set, kind);
+				if !rate_limited {
+					rate_limited = true;
+					reports.push(RATE_LIMITED_COST);
+				}
+				continue
+			}
+		}
+
 		outgoing_events.push(match message {
 			WireMessage::ViewUpdate(new_view) => {
 				if new_view.len() > MAX_VIEW_HEADS ||
@@ -284,6 +284,14 @@ struct TestHarness {
 fn test_harness<T: Future<Output = VirtualOverseer>>(
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	test: impl FnOnce(TestHarness) -> T,
+) {
+	test_harness_with_rate_limits(sync_oracle, RateLimits::default(), test)
+}
+
+fn test_harness_with_rate_limits<T: Future<Output = VirtualOverseer>>(
+	sync_oracle: Box<dyn SyncOracle + Send>,
+	rate_limits: RateLimits,
+	test: impl FnOnce(TestHarness) -> T,
 ) {
 	let pool = sp_core::testing::TaskExecutor::new();
 	let (mut network, network_handle, discovery) = new_test_network();
@@ -291,13 +299,9 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		polkadot_node_subsystem_test_helpers::make_subsystem_context(pool);
 	let network_stream = network.event_stream();
 
-	let bridge = NetworkBridgeRx {
-		network_service: network,
-		authority_discovery_service: discovery,
-		metrics: Metrics(None),
-		sync_oracle,
-		shared: Shared::default(),
-	};
+	// Built the same way as by the service.
+	let bridge = NetworkBridgeRx::new(network, discovery, sync_oracle, Metrics(None))
+		.with_rate_limits(rate_limits);
 
 	let network_bridge = run_network_in(bridge, context, network_stream)
 		.map_err(|_| panic!("subsystem execution failed"))
@@ -1141,3 +1145,92 @@ fn our_view_updates_decreasing_order_and_limited_to_max() {
 		virtual_overseer
 	});
 }
+
+#[test]
+fn notifications_exceeding_rate_limit_are_dropped() {
+	let rate_limits = RateLimits::unlimited().with_limit(
+		PeerSet::Validation,
+		NotificationKind::ApprovalDistribution,
+		RateLimit { burst: 2, per_second: 0 },
+	);
+
+	test_harness_with_rate_limits(done_syncing_oracle(), rate_limits, |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer = PeerId::random();
+
+		network_handle
+			.connect_peer(peer.clone(), PeerSet::Validation, ObservedRole::Full)
+			.await;
+
+		assert_sends_validation_event_to_all(
+			NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, 1, None),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		assert_sends_validation_event_to_all(
+			NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		let approval_distribution_message =
+			protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new());
+		for _ in 0..3 {
+			network_handle
+				.peer_message(
+					peer.clone(),
+					PeerSet::Validation,
+					WireMessage::ProtocolMessage(
+						protocol_v1::ValidationProtocol::ApprovalDistribution(
+							approval_distribution_message.clone(),
+						),
+					)
+					.encode(),
+				)
+				.await;
+		}
+
+		// View updates are never limited, even once the peer got limited.
+		network_handle
+			.peer_message(
+				peer.clone(),
+				PeerSet::Validation,
+				WireMessage::<protocol_v1::ValidationProtocol>::ViewUpdate(view![
+					Hash::repeat_byte(1)
+				])
+				.encode(),
+			)
+			.await;
+
+		for _ in 0..2 {
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::ApprovalDistribution(
+					ApprovalDistributionMessage::NetworkBridgeUpdate(
+						NetworkBridgeEvent::PeerMessage(p, Versioned::V1(m))
+					)
+				) => {
+					assert_eq!(p, peer);
+					assert_eq!(m, approval_distribution_message);
+				}
+			);
+		}
+
+		// The third approval distribution message never reaches the subsystems.
+		assert_sends_validation_event_to_all(
+			NetworkBridgeEvent::PeerViewChange(peer.clone(), view![Hash::repeat_byte(1)]),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		// Our view upon connection, followed by the report.
+		let actions = network_handle.next_network_actions(2).await;
+		assert_network_actions_contains(
+			&actions,
+			&NetworkAction::ReputationChange(peer.clone(), RATE_LIMITED_COST),
+		);
+		virtual_overseer
+	});
+}
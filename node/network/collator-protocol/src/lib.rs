@@ -21,7 +21,11 @@
 #![deny(unused_crate_dependencies)]
 #![recursion_limit = "256"]
 
-use std::time::Duration;
+use std::{
+	collections::{HashMap, HashSet},
+	path::PathBuf,
+	time::Duration,
+};
 
 use futures::{FutureExt, TryFutureExt};
 
@@ -31,7 +35,7 @@ use polkadot_node_network_protocol::{
 	request_response::{v1 as request_v1, IncomingRequestReceiver},
 	PeerId, UnifiedReputationChange as Rep,
 };
-use polkadot_primitives::v2::CollatorPair;
+use polkadot_primitives::v2::{CollatorId, CollatorPair, Id as ParaId};
 
 use polkadot_node_subsystem::{
 	errors::SubsystemError, messages::NetworkBridgeTxMessage, overseer, SpawnedSubsystem,
@@ -62,6 +66,37 @@ impl Default for CollatorEvictionPolicy {
 	}
 }
 
+/// Which collators of a para a validator accepts collations from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollatorFilter {
+	/// Accept only the given collators.
+	Allow(HashSet<CollatorId>),
+	/// Accept all but the given collators.
+	Deny(HashSet<CollatorId>),
+}
+
+impl CollatorFilter {
+	/// Whether collations of the given collator are accepted.
+	pub fn accepts(&self, collator_id: &CollatorId) -> bool {
+		match self {
+			Self::Allow(allowed) => allowed.contains(collator_id),
+			Self::Deny(denied) => !denied.contains(collator_id),
+		}
+	}
+}
+
+/// Which collators a validator accepts collations from and how it keeps track of their behavior.
+#[derive(Debug, Clone, Default)]
+pub struct CollatorPolicy {
+	/// The collators accepted per para. All collators of paras without a filter are accepted.
+	pub filters: HashMap<ParaId, CollatorFilter>,
+	/// The file to keep the reputations of collators in across restarts.
+	///
+	/// Without one, reputations are only kept in memory, though still across reconnects and
+	/// sessions.
+	pub reputation_path: Option<PathBuf>,
+}
+
 /// What side of the collator protocol is being engaged
 pub enum ProtocolSide {
 	/// Validators operate on the relay chain.
@@ -70,6 +105,8 @@ pub enum ProtocolSide {
 		keystore: SyncCryptoStorePtr,
 		/// An eviction policy for inactive peers or validators.
 		eviction_policy: CollatorEvictionPolicy,
+		/// Which collators to accept and where to keep their reputations.
+		collator_policy: CollatorPolicy,
 		/// Prometheus metrics for validators.
 		metrics: validator_side::Metrics,
 	},
@@ -99,8 +136,8 @@ impl CollatorProtocolSubsystem {
 
 	async fn run<Context>(self, ctx: Context) -> std::result::Result<(), error::FatalError> {
 		match self.protocol_side {
-			ProtocolSide::Validator { keystore, eviction_policy, metrics } =>
-				validator_side::run(ctx, keystore, eviction_policy, metrics).await,
+			ProtocolSide::Validator { keystore, eviction_policy, collator_policy, metrics } =>
+				validator_side::run(ctx, keystore, eviction_policy, collator_policy, metrics).await,
 			ProtocolSide::Collator(local_peer_id, collator_pair, req_receiver, metrics) =>
 				collator_side::run(ctx, local_peer_id, collator_pair, req_receiver, metrics).await,
 		}
@@ -57,7 +57,11 @@ use polkadot_primitives::v2::{CandidateReceipt, CollatorId, Hash, Id as ParaId};
 
 use crate::error::Result;
 
-use super::{modify_reputation, LOG_TARGET};
+use super::{modify_reputation, CollatorFilter, CollatorPolicy, LOG_TARGET};
+
+use reputation::Reputations;
+
+mod reputation;
 
 #[cfg(test)]
 mod tests;
@@ -71,6 +75,7 @@ const COST_INVALID_SIGNATURE: Rep = Rep::Malicious("Invalid network message sign
 const COST_REPORT_BAD: Rep = Rep::Malicious("A collator was reported by another subsystem");
 const COST_WRONG_PARA: Rep = Rep::Malicious("A collator provided a collation for the wrong para");
 const COST_UNNEEDED_COLLATOR: Rep = Rep::CostMinor("An unneeded collator connected");
+const COST_BANNED_COLLATOR: Rep = Rep::CostMinor("A collator with a bad reputation connected");
 const BENEFIT_NOTIFY_GOOD: Rep =
 	Rep::BenefitMinor("A collator was noted good by another subsystem");
 
@@ -94,6 +99,9 @@ const ACTIVITY_POLL: Duration = Duration::from_secs(1);
 #[cfg(test)]
 const ACTIVITY_POLL: Duration = Duration::from_millis(10);
 
+// How often to let collator reputations recover and store them.
+const REPUTATION_DECAY_INTERVAL: Duration = Duration::from_secs(60);
+
 // How often to poll collation responses.
 // This is a hack that should be removed in a refactoring.
 // See https://github.com/paritytech/polkadot/issues/4182
@@ -546,9 +554,13 @@ impl CollationsPerRelayParent {
 	///
 	/// Returns `Some(_)` if there is any collation to fetch, the `status` is not `Seconded` and
 	/// the passed in `finished_one` is the currently `waiting_collation`.
+	///
+	/// Collations of the collator with the best reputation are fetched first, the most recently
+	/// advertised one among equals.
 	pub fn get_next_collation_to_fetch(
 		&mut self,
 		finished_one: Option<&CollatorId>,
+		reputations: &Reputations,
 	) -> Option<(PendingCollation, CollatorId)> {
 		// If finished one does not match waiting_collation, then we already dequeued another fetch
 		// to replace it.
@@ -567,7 +579,13 @@ impl CollationsPerRelayParent {
 			// We don't need to fetch any other collation when we already have seconded one.
 			CollationStatus::Seconded => None,
 			CollationStatus::Waiting => {
-				let next = self.unfetched_collations.pop();
+				let best = self
+					.unfetched_collations
+					.iter()
+					.enumerate()
+					.max_by_key(|(_, (_, collator_id))| reputations.score(collator_id))
+					.map(|(index, _)| index);
+				let next = best.map(|index| self.unfetched_collations.remove(index));
 				self.waiting_collation = next.as_ref().map(|(_, collator_id)| collator_id.clone());
 				next
 			},
@@ -617,6 +635,12 @@ struct State {
 
 	/// Keep track of all pending candidate collations
 	pending_candidates: HashMap<Hash, CollationEvent>,
+
+	/// The collators accepted per para.
+	collator_filters: HashMap<ParaId, CollatorFilter>,
+
+	/// Reputations of collators, kept across reconnects.
+	reputations: Reputations,
 }
 
 // O(n) search for collator ID by iterating through the peers map. This should be fast enough
@@ -684,8 +708,10 @@ async fn fetch_collation(
 async fn report_collator(
 	sender: &mut impl overseer::CollatorProtocolSenderTrait,
 	peer_data: &HashMap<PeerId, PeerData>,
+	reputations: &mut Reputations,
 	id: CollatorId,
 ) {
+	reputations.modify(&id, COST_REPORT_BAD);
 	if let Some(peer_id) = collator_peer_id(peer_data, &id) {
 		modify_reputation(sender, peer_id, COST_REPORT_BAD).await;
 	}
@@ -695,8 +721,10 @@ async fn report_collator(
 async fn note_good_collation(
 	sender: &mut impl overseer::CollatorProtocolSenderTrait,
 	peer_data: &HashMap<PeerId, PeerData>,
+	reputations: &mut Reputations,
 	id: CollatorId,
 ) {
+	reputations.modify(&id, BENEFIT_NOTIFY_GOOD);
 	if let Some(peer_id) = collator_peer_id(peer_data, &id) {
 		modify_reputation(sender, peer_id, BENEFIT_NOTIFY_GOOD).await;
 	}
@@ -860,6 +888,35 @@ async fn process_incoming_peer_message<Context>(
 				return
 			}
 
+			if !state.collator_filters.get(&para_id).map_or(true, |f| f.accepts(&collator_id)) {
+				gum::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?collator_id,
+					?para_id,
+					"Declared as collator not accepted for the para",
+				);
+
+				modify_reputation(ctx.sender(), origin.clone(), COST_UNNEEDED_COLLATOR).await;
+				disconnect_peer(ctx.sender(), origin).await;
+				return
+			}
+
+			if state.reputations.is_banned(&collator_id) {
+				gum::debug!(
+					target: LOG_TARGET,
+					peer_id = ?origin,
+					?collator_id,
+					?para_id,
+					reputation = state.reputations.score(&collator_id),
+					"Declared as collator with a bad reputation",
+				);
+
+				modify_reputation(ctx.sender(), origin.clone(), COST_BANNED_COLLATOR).await;
+				disconnect_peer(ctx.sender(), origin).await;
+				return
+			}
+
 			if state.active_paras.is_current(&para_id) {
 				gum::debug!(
 					target: LOG_TARGET,
@@ -968,6 +1025,9 @@ async fn process_incoming_peer_message<Context>(
 						"Invalid advertisement",
 					);
 
+					if let Some(collator_id) = peer_data.collator_id() {
+						state.reputations.modify(collator_id, COST_UNEXPECTED_MESSAGE);
+					}
 					modify_reputation(ctx.sender(), origin, COST_UNEXPECTED_MESSAGE).await;
 				},
 			}
@@ -1113,7 +1173,7 @@ async fn process_msg<Context>(
 			);
 		},
 		ReportCollator(id) => {
-			report_collator(ctx.sender(), &state.peer_data, id).await;
+			report_collator(ctx.sender(), &state.peer_data, &mut state.reputations, id).await;
 		},
 		NetworkBridgeUpdate(event) => {
 			if let Err(e) = handle_network_msg(ctx, state, keystore, event).await {
@@ -1128,7 +1188,13 @@ async fn process_msg<Context>(
 			if let Some(collation_event) = state.pending_candidates.remove(&parent) {
 				let (collator_id, pending_collation) = collation_event;
 				let PendingCollation { relay_parent, peer_id, .. } = pending_collation;
-				note_good_collation(ctx.sender(), &state.peer_data, collator_id).await;
+				note_good_collation(
+					ctx.sender(),
+					&state.peer_data,
+					&mut state.reputations,
+					collator_id,
+				)
+				.await;
 				notify_collation_seconded(ctx.sender(), peer_id, relay_parent, stmt).await;
 
 				if let Some(collations) = state.collations_per_relay_parent.get_mut(&parent) {
@@ -1160,7 +1226,8 @@ async fn process_msg<Context>(
 				Entry::Vacant(_) => return,
 			};
 
-			report_collator(ctx.sender(), &state.peer_data, id.clone()).await;
+			report_collator(ctx.sender(), &state.peer_data, &mut state.reputations, id.clone())
+				.await;
 
 			dequeue_next_collation_and_fetch(ctx, state, parent, id).await;
 		},
@@ -1192,9 +1259,16 @@ pub(crate) async fn run<Context>(
 	mut ctx: Context,
 	keystore: SyncCryptoStorePtr,
 	eviction_policy: crate::CollatorEvictionPolicy,
+	collator_policy: CollatorPolicy,
 	metrics: Metrics,
 ) -> std::result::Result<(), crate::error::FatalError> {
-	let mut state = State { metrics, ..Default::default() };
+	let CollatorPolicy { filters, reputation_path } = collator_policy;
+	let mut state = State {
+		metrics,
+		collator_filters: filters,
+		reputations: Reputations::load(reputation_path),
+		..Default::default()
+	};
 
 	let next_inactivity_stream = infinite_stream(ACTIVITY_POLL);
 	futures::pin_mut!(next_inactivity_stream);
@@ -1202,6 +1276,11 @@ pub(crate) async fn run<Context>(
 	let check_collations_stream = infinite_stream(CHECK_COLLATIONS_POLL);
 	futures::pin_mut!(check_collations_stream);
 
+	let reputation_decay_stream = futures::stream::repeat(())
+		.then(|()| Delay::new(REPUTATION_DECAY_INTERVAL))
+		.fuse();
+	futures::pin_mut!(reputation_decay_stream);
+
 	loop {
 		select! {
 			res = ctx.recv().fuse() => {
@@ -1243,12 +1322,33 @@ pub(crate) async fn run<Context>(
 				).await;
 
 				for (peer_id, rep) in reputation_changes {
+					if let Some(collator_id) = state.peer_data.get(&peer_id).and_then(|p| p.collator_id()) {
+						state.reputations.modify(collator_id, rep);
+					}
 					modify_reputation(ctx.sender(), peer_id, rep).await;
 				}
 			},
+			_ = reputation_decay_stream.next() => {
+				state.reputations.decay();
+				if let Some(snapshot) = state.reputations.snapshot() {
+					let write = async move { snapshot.write() }.boxed();
+					if let Err(error) = ctx.spawn_blocking("collator-reputations", write) {
+						gum::warn!(
+							target: LOG_TARGET,
+							?error,
+							"Failed to spawn storing the collator reputations",
+						);
+					}
+				}
+			},
 		}
 	}
 
+	// Store the reputations right away, the node is about to exit.
+	if let Some(snapshot) = state.reputations.snapshot() {
+		snapshot.write();
+	}
+
 	Ok(())
 }
 
@@ -1288,7 +1388,7 @@ async fn dequeue_next_collation_and_fetch<Context>(
 	if let Some((next, id)) = state
 		.collations_per_relay_parent
 		.get_mut(&relay_parent)
-		.and_then(|c| c.get_next_collation_to_fetch(Some(&previous_fetch)))
+		.and_then(|c| c.get_next_collation_to_fetch(Some(&previous_fetch), &state.reputations))
 	{
 		gum::debug!(
 			target: LOG_TARGET,
@@ -0,0 +1,252 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reputation of collators.
+//!
+//! The reputation the network bridge keeps for a peer is gone once it disconnects, so a collator
+//! reconnecting with a fresh peer id starts over. The reputations here are kept by `CollatorId`
+//! instead, for as long as the subsystem runs and, if a file is given, across restarts.
+
+use parity_scale_codec::{Decode, Encode};
+use std::{collections::HashMap, fs, io, path::PathBuf};
+
+use polkadot_node_network_protocol::UnifiedReputationChange as Rep;
+use polkadot_primitives::v2::CollatorId;
+
+use crate::LOG_TARGET;
+
+/// Collators with a reputation below this are refused.
+///
+/// This is the threshold the peerset bans peers at, so a single malicious act gets a collator
+/// refused until its reputation has recovered a bit.
+const BANNED_THRESHOLD: i32 = 82 * (i32::MIN / 100);
+
+/// The maximum number of collators to keep a reputation for.
+///
+/// Collator ids are cheap to come by, so once full, the collator with the reputation closest to
+/// 0 is forgotten to make room. Banned collators are thus the last to be forgotten.
+const MAX_REPUTATIONS: usize = 10_000;
+
+/// Reputations of collators, by `CollatorId`.
+pub struct Reputations {
+	scores: HashMap<CollatorId, i32>,
+	/// The maximum number of collators in `scores`.
+	capacity: usize,
+	/// Where to store the reputations, if anywhere.
+	path: Option<PathBuf>,
+	/// Whether the reputations changed since they were last stored.
+	dirty: bool,
+}
+
+impl Default for Reputations {
+	fn default() -> Self {
+		Self { scores: HashMap::new(), capacity: MAX_REPUTATIONS, path: None, dirty: false }
+	}
+}
+
+/// The reputations to store, see [`Reputations::snapshot`].
+pub struct Snapshot {
+	path: PathBuf,
+	encoded: Vec<u8>,
+}
+
+impl Snapshot {
+	/// Write the reputations to their file, which blocks.
+	pub fn write(self) {
+		let Snapshot { path, encoded } = self;
+		// Write to a temporary file first, such that a crash can't leave a truncated file behind.
+		let tmp_path = path.with_extension("tmp");
+		if let Err(error) =
+			fs::write(&tmp_path, encoded).and_then(|()| fs::rename(&tmp_path, &path))
+		{
+			gum::warn!(target: LOG_TARGET, ?path, ?error, "Failed to store collator reputations");
+		}
+	}
+}
+
+impl Reputations {
+	/// Load the reputations stored at `path`, if any.
+	///
+	/// A missing or unreadable file results in all collators starting with a neutral reputation.
+	pub fn load(path: Option<PathBuf>) -> Self {
+		let scores = match path.as_ref().map(read_scores).transpose() {
+			Ok(scores) => scores.unwrap_or_default(),
+			Err(error) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					?path,
+					?error,
+					"Failed to load collator reputations, starting afresh",
+				);
+				HashMap::new()
+			},
+		};
+
+		Self { scores, path, ..Default::default() }
+	}
+
+	/// The reputation of a collator, 0 if nothing is known about it.
+	pub fn score(&self, collator_id: &CollatorId) -> i32 {
+		self.scores.get(collator_id).copied().unwrap_or(0)
+	}
+
+	/// Whether a collator behaved so badly that it should be refused.
+	pub fn is_banned(&self, collator_id: &CollatorId) -> bool {
+		self.score(collator_id) < BANNED_THRESHOLD
+	}
+
+	/// Apply a reputation change to a collator.
+	pub fn modify(&mut self, collator_id: &CollatorId, rep: Rep) {
+		if self.scores.len() >= self.capacity && !self.scores.contains_key(collator_id) {
+			let closest_to_neutral = self
+				.scores
+				.iter()
+				.min_by_key(|(_, score)| score.unsigned_abs())
+				.map(|(id, _)| id.clone());
+			if let Some(id) = closest_to_neutral {
+				self.scores.remove(&id);
+			}
+		}
+
+		let score = self.scores.entry(collator_id.clone()).or_default();
+		*score = score.saturating_add(rep.cost_or_benefit());
+		self.dirty = true;
+	}
+
+	/// Let all reputations converge towards 0, by 2% or at least 1, the way the peerset does.
+	///
+	/// Collators whose reputation reached 0 are forgotten.
+	pub fn decay(&mut self) {
+		self.dirty |= !self.scores.is_empty();
+		for score in self.scores.values_mut() {
+			let delta = (*score / 50).saturating_abs().max(1);
+			if *score > 0 {
+				*score -= delta;
+			} else {
+				*score = score.saturating_add(delta).min(0);
+			}
+		}
+		self.scores.retain(|_, score| *score != 0);
+	}
+
+	/// Take a snapshot of the reputations to store, if there is a file to store them in and they
+	/// changed since the last one.
+	pub fn snapshot(&mut self) -> Option<Snapshot> {
+		let path = match &self.path {
+			Some(path) if self.dirty => path.clone(),
+			_ => return None,
+		};
+
+		self.dirty = false;
+		Some(Snapshot { path, encoded: self.scores.iter().collect::<Vec<_>>().encode() })
+	}
+}
+
+fn read_scores(path: &PathBuf) -> io::Result<HashMap<CollatorId, i32>> {
+	let encoded = match fs::read(path) {
+		Ok(encoded) => encoded,
+		Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+		Err(error) => return Err(error),
+	};
+
+	Vec::<(CollatorId, i32)>::decode(&mut &encoded[..])
+		.map(|scores| scores.into_iter().collect())
+		.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_primitives::v2::CollatorPair;
+	use sp_core::crypto::Pair;
+
+	const COST: Rep = Rep::CostMinor("cost");
+	const MALICIOUS: Rep = Rep::Malicious("malicious");
+
+	fn collator() -> CollatorId {
+		CollatorPair::generate().0.public()
+	}
+
+	#[test]
+	fn malicious_collators_get_banned_until_decayed() {
+		let mut reputations = Reputations::default();
+		let (good, bad) = (collator(), collator());
+
+		reputations.modify(&good, COST);
+		reputations.modify(&bad, MALICIOUS);
+		assert!(!reputations.is_banned(&good));
+		assert!(reputations.is_banned(&bad));
+		assert!(reputations.score(&good) > reputations.score(&bad));
+
+		// 2% per decay takes ten rounds to get back above 82% of the minimum.
+		for _ in 0..10 {
+			reputations.decay();
+		}
+		assert!(!reputations.is_banned(&bad));
+	}
+
+	#[test]
+	fn decay_forgets_neutral_collators() {
+		let mut reputations = Reputations::default();
+		let id = collator();
+
+		reputations.modify(&id, Rep::BenefitMinor("benefit"));
+		reputations.modify(&id, Rep::CostMinor("cost"));
+		assert!(reputations.score(&id) < 0);
+
+		while reputations.score(&id) != 0 {
+			reputations.decay();
+		}
+		assert!(reputations.scores.is_empty());
+	}
+
+	#[test]
+	fn banned_collators_are_forgotten_last() {
+		let mut reputations = Reputations { capacity: 3, ..Default::default() };
+		let (bad, first, second) = (collator(), collator(), collator());
+
+		reputations.modify(&bad, MALICIOUS);
+		reputations.modify(&first, COST);
+		reputations.modify(&first, COST);
+		reputations.modify(&second, COST);
+
+		for _ in 0..5 {
+			reputations.modify(&collator(), COST);
+			assert_eq!(reputations.scores.len(), 3);
+		}
+		assert!(reputations.is_banned(&bad));
+		assert_eq!(reputations.score(&first), 2 * COST.cost_or_benefit());
+		assert_eq!(reputations.score(&second), 0);
+	}
+
+	#[test]
+	fn reputations_survive_restarts() {
+		let path =
+			std::env::temp_dir().join(format!("collator-reputations-test-{}", std::process::id()));
+		let id = collator();
+
+		let mut reputations = Reputations::load(Some(path.clone()));
+		assert_eq!(reputations.score(&id), 0);
+		reputations.modify(&id, COST);
+		reputations.snapshot().unwrap().write();
+		assert!(reputations.snapshot().is_none());
+
+		let reloaded = Reputations::load(Some(path.clone()));
+		assert_eq!(reloaded.score(&id), COST.cost_or_benefit());
+
+		let _ = fs::remove_file(path);
+	}
+}
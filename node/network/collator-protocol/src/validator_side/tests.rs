@@ -119,6 +119,13 @@ struct TestHarness {
 }
 
 fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarness) -> T) {
+	test_harness_with_policy(Default::default(), test)
+}
+
+fn test_harness_with_policy<T: Future<Output = VirtualOverseer>>(
+	collator_policy: CollatorPolicy,
+	test: impl FnOnce(TestHarness) -> T,
+) {
 	let _ = env_logger::builder()
 		.is_test(true)
 		.filter(Some("polkadot_collator_protocol"), log::LevelFilter::Trace)
@@ -144,6 +151,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarne
 			inactive_collator: ACTIVITY_TIMEOUT,
 			undeclared: DECLARE_TIMEOUT,
 		},
+		collator_policy,
 		Metrics::default(),
 	);
 
@@ -1067,3 +1075,188 @@ fn view_change_clears_old_collators() {
 		virtual_overseer
 	})
 }
+
+#[test]
+fn collators_not_accepted_by_filter_are_disconnected() {
+	let test_state = TestState::default();
+	let allowed = test_state.collators[1].public();
+	let collator_policy = CollatorPolicy {
+		filters: iter::once((
+			test_state.chain_ids[0],
+			CollatorFilter::Allow(iter::once(allowed).collect()),
+		))
+		.collect(),
+		reputation_path: None,
+	};
+
+	test_harness_with_policy(collator_policy, |test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_UNNEEDED_COLLATOR);
+			}
+		);
+
+		assert_collator_disconnect(&mut virtual_overseer, peer_b).await;
+
+		// The allowed collator gets its collation fetched.
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.collators[1].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		advertise_collation(&mut virtual_overseer, peer_c, test_state.relay_parent).await;
+
+		assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		virtual_overseer
+	})
+}
+
+// A collator reported as malicious is refused, even when reconnecting as a different peer.
+#[test]
+fn reported_collator_is_refused_after_reconnect() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::ReportCollator(test_state.collators[0].public()),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_REPORT_BAD);
+			}
+		);
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerDisconnected(
+				peer_b,
+			)),
+		)
+		.await;
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_c);
+				assert_eq!(rep, COST_BANNED_COLLATOR);
+			}
+		);
+
+		assert_collator_disconnect(&mut virtual_overseer, peer_c).await;
+
+		virtual_overseer
+	})
+}
+
+#[test]
+fn collations_of_reputable_collators_are_fetched_first() {
+	let test_state = TestState::default();
+	let relay_parent = test_state.relay_parent;
+	let para_id = test_state.chain_ids[0];
+
+	let collators: Vec<_> = test_state.collators.iter().map(|pair| pair.public()).collect();
+	let mut reputations = Reputations::default();
+	reputations.modify(&collators[0], BENEFIT_NOTIFY_GOOD);
+	reputations.modify(&collators[2], COST_NETWORK_ERROR);
+
+	let mut collations = CollationsPerRelayParent::default();
+	for collator in &collators[..3] {
+		collations.unfetched_collations.push((
+			PendingCollation::new(relay_parent, &para_id, &PeerId::random()),
+			collator.clone(),
+		));
+	}
+
+	let mut fetch_next = |finished_one: Option<CollatorId>| {
+		collations
+			.get_next_collation_to_fetch(finished_one.as_ref(), &reputations)
+			.map(|(_, collator)| collator)
+	};
+
+	let first = fetch_next(None);
+	assert_eq!(first.as_ref(), Some(&collators[0]));
+	let second = fetch_next(first);
+	assert_eq!(second.as_ref(), Some(&collators[1]));
+	let third = fetch_next(second);
+	assert_eq!(third.as_ref(), Some(&collators[2]));
+	assert_eq!(fetch_next(third), None);
+}
@@ -0,0 +1,338 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline simulation of gossip propagation.
+//!
+//! Simulates how messages spread among fully connected validators under a [`GossipTopology`],
+//! following the routing rules of approval-distribution or statement-distribution, over links
+//! with random latencies and packet loss. Every validator forwards a message once, upon
+//! receiving it for the first time.
+
+#![warn(missing_docs)]
+
+use std::{
+	cmp::Reverse,
+	collections::{BinaryHeap, HashSet},
+	ops::RangeInclusive,
+};
+
+use polkadot_node_network_protocol::{
+	grid_topology::{
+		GossipTopology, RandomRouting, TopologyNeighbors, DEFAULT_RANDOM_CIRCULATION,
+		DEFAULT_RANDOM_SAMPLE_RATE,
+	},
+	MIN_GOSSIP_PEERS,
+};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub mod topologies;
+
+/// The gossip protocols whose propagation can be simulated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+	/// Assignments and approvals are sent along the topology, and to a few random peers.
+	ApprovalDistribution,
+	/// Statements are sent along the topology, topped up with random peers up to
+	/// `MIN_GOSSIP_PEERS`.
+	StatementDistribution,
+}
+
+impl Protocol {
+	/// The name of the subsystem implementing the protocol.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Protocol::ApprovalDistribution => "approval-distribution",
+			Protocol::StatementDistribution => "statement-distribution",
+		}
+	}
+}
+
+/// The simulated network and messages.
+#[derive(Debug, Clone)]
+pub struct Config {
+	/// The number of validators, all connected to each other.
+	pub n_validators: usize,
+	/// The range the latency of every sent message is uniformly sampled from, in milliseconds.
+	pub latency_ms: RangeInclusive<u64>,
+	/// The probability of a sent message getting lost.
+	pub packet_loss: f64,
+	/// The number of peers approval-distribution sends a message to at random.
+	pub random_circulation: usize,
+	/// The sample rate of the random routing of approval-distribution.
+	pub random_sample_rate: usize,
+	/// The number of messages to simulate, each originating from a random validator.
+	pub messages: usize,
+	/// The seed of all randomness of the simulation.
+	pub seed: u64,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Config {
+			n_validators: 1000,
+			latency_ms: 20..=300,
+			packet_loss: 0.0,
+			random_circulation: DEFAULT_RANDOM_CIRCULATION,
+			random_sample_rate: DEFAULT_RANDOM_SAMPLE_RATE,
+			messages: 20,
+			seed: 0,
+		}
+	}
+}
+
+/// The propagation of messages, averaged over all simulated messages.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+	/// The fraction of validators a message reaches, its originator included.
+	pub coverage: f64,
+	/// The number of copies of a message each reached validator receives.
+	pub redundancy: f64,
+	/// The number of hops after which validators first receive a message.
+	pub mean_hops: f64,
+	/// The highest number of hops after which any validator first received any message.
+	pub max_hops: u32,
+	/// The time until the last reached validator first receives a message, in milliseconds.
+	pub mean_completion_ms: f64,
+}
+
+/// Simulate the propagation of `config.messages` messages sent through `protocol`.
+pub fn simulate(config: &Config, protocol: Protocol, topology: &dyn GossipTopology) -> Report {
+	assert!(config.n_validators > 0, "there is no one to simulate");
+
+	let neighbors = (0..config.n_validators)
+		.map(|validator| Neighbors::from(topology.neighbors(validator, config.n_validators)))
+		.collect::<Vec<_>>();
+	let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+
+	let mut report = Report::default();
+	let mut receivers = 0;
+	for _ in 0..config.messages {
+		let origin = rng.gen_range(0..config.n_validators);
+		let outcome = propagate(config, protocol, &neighbors, origin, &mut rng);
+
+		report.coverage += outcome.reached as f64 / config.n_validators as f64;
+		report.redundancy +=
+			outcome.deliveries as f64 / outcome.reached.saturating_sub(1).max(1) as f64;
+		report.mean_hops += outcome.total_hops as f64;
+		report.max_hops = report.max_hops.max(outcome.max_hops);
+		report.mean_completion_ms += outcome.completion_ms as f64;
+		receivers += outcome.reached - 1;
+	}
+
+	let messages = config.messages.max(1) as f64;
+	report.coverage /= messages;
+	report.redundancy /= messages;
+	report.mean_hops /= receivers.max(1) as f64;
+	report.mean_completion_ms /= messages;
+	report
+}
+
+/// The neighbors of a validator, in a form fit for lookups.
+struct Neighbors {
+	x: HashSet<usize>,
+	y: HashSet<usize>,
+}
+
+impl From<TopologyNeighbors> for Neighbors {
+	fn from(neighbors: TopologyNeighbors) -> Self {
+		Neighbors { x: neighbors.x.into_iter().collect(), y: neighbors.y.into_iter().collect() }
+	}
+}
+
+/// The propagation of a single message.
+#[derive(Debug, Default)]
+struct Outcome {
+	/// The number of validators which received the message, its originator included.
+	reached: usize,
+	/// The number of copies received, duplicates included.
+	deliveries: usize,
+	/// The sum of the hops after which validators first received the message.
+	total_hops: u64,
+	max_hops: u32,
+	completion_ms: u64,
+}
+
+fn propagate(
+	config: &Config,
+	protocol: Protocol,
+	neighbors: &[Neighbors],
+	origin: usize,
+	rng: &mut ChaCha8Rng,
+) -> Outcome {
+	let mut reached = vec![false; neighbors.len()];
+	let mut outcome = Outcome { reached: 1, ..Default::default() };
+	// Deliveries by arrival time: `(arrival_ms, recipient, sender, hops)`.
+	let mut queue = BinaryHeap::new();
+
+	reached[origin] = true;
+	for peer in recipients(config, protocol, neighbors, origin, origin, None, rng) {
+		send(config, &mut queue, 0, peer, origin, 1, rng);
+	}
+
+	while let Some(Reverse((arrival_ms, recipient, sender, hops))) = queue.pop() {
+		outcome.deliveries += 1;
+		if reached[recipient] {
+			continue
+		}
+
+		reached[recipient] = true;
+		outcome.reached += 1;
+		outcome.total_hops += hops as u64;
+		outcome.max_hops = outcome.max_hops.max(hops);
+		outcome.completion_ms = arrival_ms;
+
+		for peer in recipients(config, protocol, neighbors, origin, recipient, Some(sender), rng) {
+			send(config, &mut queue, arrival_ms, peer, recipient, hops + 1, rng);
+		}
+	}
+
+	outcome
+}
+
+fn send(
+	config: &Config,
+	queue: &mut BinaryHeap<Reverse<(u64, usize, usize, u32)>>,
+	now_ms: u64,
+	recipient: usize,
+	sender: usize,
+	hops: u32,
+	rng: &mut ChaCha8Rng,
+) {
+	if rng.gen::<f64>() < config.packet_loss {
+		return
+	}
+
+	let latency_ms = rng.gen_range(config.latency_ms.clone());
+	queue.push(Reverse((now_ms + latency_ms, recipient, sender, hops)));
+}
+
+/// The peers `validator` sends a message from `origin` to upon first receiving it from `source`.
+fn recipients(
+	config: &Config,
+	protocol: Protocol,
+	neighbors: &[Neighbors],
+	origin: usize,
+	validator: usize,
+	source: Option<usize>,
+	rng: &mut ChaCha8Rng,
+) -> Vec<usize> {
+	let own = &neighbors[validator];
+
+	// Mirrors `SessionGridTopology::required_routing_by_index`.
+	let (route_x, route_y) = if validator == origin {
+		(true, true)
+	} else {
+		match (own.x.contains(&origin), own.y.contains(&origin)) {
+			(false, false) => (false, false),
+			(true, false) => (false, true),
+			(false, true) => (true, false),
+			(true, true) => (true, true),
+		}
+	};
+	let required =
+		|peer: &usize| (route_x && own.x.contains(peer)) || (route_y && own.y.contains(peer));
+
+	let mut peers = (0..neighbors.len())
+		.filter(|peer| *peer != validator && Some(*peer) != source)
+		.collect::<Vec<_>>();
+
+	match protocol {
+		Protocol::ApprovalDistribution => {
+			// Peers are visited in no particular order, which matters for random routing.
+			peers.shuffle(rng);
+			let n_peers_total = neighbors.len() - 1;
+			let mut random_routing =
+				RandomRouting::new(config.random_circulation, config.random_sample_rate);
+			peers.retain(|peer| {
+				if required(peer) {
+					return true
+				}
+
+				let route_random = random_routing.sample(n_peers_total, rng);
+				if route_random {
+					random_routing.inc_sent();
+				}
+				route_random
+			});
+			peers
+		},
+		Protocol::StatementDistribution => {
+			// Mirrors `choose_random_subset_with_rng` with `MIN_GOSSIP_PEERS`.
+			let (mut chosen, mut others): (Vec<_>, Vec<_>) = peers.into_iter().partition(required);
+			if chosen.len() < MIN_GOSSIP_PEERS {
+				others.shuffle(rng);
+				others.truncate(MIN_GOSSIP_PEERS - chosen.len());
+				chosen.append(&mut others);
+			}
+			chosen
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_network_protocol::grid_topology::GridTopology;
+	use topologies::NoTopology;
+
+	fn config() -> Config {
+		Config { n_validators: 100, messages: 10, ..Default::default() }
+	}
+
+	#[test]
+	fn grid_reaches_everyone_within_two_hops() {
+		// With a constant latency, validators first receive messages along the shortest path.
+		let config = Config { latency_ms: 100..=100, random_circulation: 0, ..config() };
+
+		for protocol in [Protocol::ApprovalDistribution, Protocol::StatementDistribution] {
+			let report = simulate(&config, protocol, &GridTopology);
+			assert_eq!(report.coverage, 1.0, "{:?}", protocol);
+			assert_eq!(report.max_hops, 2, "{:?}", protocol);
+			assert!(report.redundancy >= 1.0, "{:?}", protocol);
+		}
+	}
+
+	#[test]
+	fn lost_messages_reach_no_one() {
+		let config = Config { packet_loss: 1.0, ..config() };
+
+		let report = simulate(&config, Protocol::ApprovalDistribution, &GridTopology);
+		assert!((report.coverage - 1.0 / config.n_validators as f64).abs() < 1e-9);
+		assert_eq!(report.redundancy, 0.0);
+		assert_eq!(report.max_hops, 0);
+		assert_eq!(report.mean_completion_ms, 0.0);
+	}
+
+	#[test]
+	fn random_routing_alone_takes_more_hops() {
+		let config = config();
+
+		let grid = simulate(&config, Protocol::ApprovalDistribution, &GridTopology);
+		let random = simulate(&config, Protocol::ApprovalDistribution, &NoTopology);
+		assert!(random.mean_hops > grid.mean_hops);
+	}
+
+	#[test]
+	fn simulations_are_deterministic() {
+		let config = Config { packet_loss: 0.1, ..config() };
+
+		assert_eq!(
+			simulate(&config, Protocol::StatementDistribution, &GridTopology),
+			simulate(&config, Protocol::StatementDistribution, &GridTopology),
+		);
+	}
+}
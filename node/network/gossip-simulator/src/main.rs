@@ -0,0 +1,158 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compare how gossip topologies propagate messages, without running a network.
+//!
+//! Prints, for every topology and protocol, the fraction of validators reached, the copies each
+//! receives, the hops it takes them to first receive a message and how long it takes to reach
+//! all of them.
+
+use clap::{ArgEnum, Parser};
+use polkadot_gossip_simulator::{
+	simulate,
+	topologies::{NoTopology, RectangularGrid},
+	Config, Protocol,
+};
+use polkadot_node_network_protocol::grid_topology::{
+	GossipTopology, GridTopology, DEFAULT_RANDOM_CIRCULATION, DEFAULT_RANDOM_SAMPLE_RATE,
+};
+
+#[derive(Clone, Copy, Debug, ArgEnum)]
+enum Topology {
+	Grid,
+	RectangularGrid,
+	None,
+}
+
+#[derive(Clone, Copy, Debug, ArgEnum)]
+enum ProtocolArg {
+	ApprovalDistribution,
+	StatementDistribution,
+}
+
+impl From<ProtocolArg> for Protocol {
+	fn from(protocol: ProtocolArg) -> Self {
+		match protocol {
+			ProtocolArg::ApprovalDistribution => Protocol::ApprovalDistribution,
+			ProtocolArg::StatementDistribution => Protocol::StatementDistribution,
+		}
+	}
+}
+
+#[derive(Debug, Parser)]
+struct Opt {
+	/// The topologies to simulate. All of them by default.
+	#[clap(long, arg_enum, multiple_values = true)]
+	topology: Vec<Topology>,
+
+	/// The protocols to simulate. All of them by default.
+	#[clap(long, arg_enum, multiple_values = true)]
+	protocol: Vec<ProtocolArg>,
+
+	/// The number of validators.
+	#[clap(long, default_value = "1000")]
+	validators: usize,
+
+	/// The minimum latency of a sent message, in milliseconds.
+	#[clap(long, default_value = "20")]
+	min_latency_ms: u64,
+
+	/// The maximum latency of a sent message, in milliseconds.
+	#[clap(long, default_value = "300")]
+	max_latency_ms: u64,
+
+	/// The probability of a sent message getting lost.
+	#[clap(long, default_value = "0")]
+	packet_loss: f64,
+
+	/// The number of peers approval-distribution sends a message to at random.
+	#[clap(long, default_value_t = DEFAULT_RANDOM_CIRCULATION)]
+	random_circulation: usize,
+
+	/// The sample rate of the random routing of approval-distribution.
+	#[clap(long, default_value_t = DEFAULT_RANDOM_SAMPLE_RATE)]
+	random_sample_rate: usize,
+
+	/// The number of messages to simulate per topology and protocol.
+	#[clap(long, default_value = "20")]
+	messages: usize,
+
+	/// The seed of the simulation.
+	#[clap(long, default_value = "0")]
+	seed: u64,
+
+	/// The number of columns of the rectangular grid.
+	#[clap(long, default_value = "10")]
+	columns: usize,
+}
+
+fn main() {
+	let opt = Opt::parse();
+	if opt.validators == 0 || opt.min_latency_ms > opt.max_latency_ms {
+		eprintln!("At least one validator and a valid latency range are required");
+		std::process::exit(1);
+	}
+
+	let config = Config {
+		n_validators: opt.validators,
+		latency_ms: opt.min_latency_ms..=opt.max_latency_ms,
+		packet_loss: opt.packet_loss,
+		random_circulation: opt.random_circulation,
+		random_sample_rate: opt.random_sample_rate,
+		messages: opt.messages,
+		seed: opt.seed,
+	};
+	let topologies = if opt.topology.is_empty() {
+		vec![Topology::Grid, Topology::RectangularGrid, Topology::None]
+	} else {
+		opt.topology
+	};
+	let protocols = if opt.protocol.is_empty() {
+		vec![ProtocolArg::ApprovalDistribution, ProtocolArg::StatementDistribution]
+	} else {
+		opt.protocol
+	};
+
+	println!(
+		"{:<24} {:<24} {:>9} {:>10} {:>9} {:>8} {:>13}",
+		"topology", "protocol", "coverage", "redundancy", "mean hops", "max hops", "completion ms",
+	);
+	for topology in topologies {
+		let (name, gossip_topology): (String, Box<dyn GossipTopology>) = match topology {
+			Topology::Grid => ("grid".into(), Box::new(GridTopology)),
+			Topology::RectangularGrid => (
+				format!("rectangular-grid ({})", opt.columns),
+				Box::new(RectangularGrid { columns: opt.columns }),
+			),
+			Topology::None => ("none".into(), Box::new(NoTopology)),
+		};
+
+		for protocol in &protocols {
+			let protocol = Protocol::from(*protocol);
+			let report = simulate(&config, protocol, &*gossip_topology);
+			println!(
+				"{:<24} {:<24} {:>8.2}% {:>10.2} {:>9.2} {:>8} {:>13.0}",
+				name,
+				protocol.name(),
+				report.coverage * 100.0,
+				report.redundancy,
+				report.mean_hops,
+				report.max_hops,
+				report.mean_completion_ms,
+			);
+		}
+	}
+}
@@ -0,0 +1,75 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Alternatives to the [`GridTopology`](polkadot_node_network_protocol::grid_topology::GridTopology)
+//! used by the node.
+
+use polkadot_node_network_protocol::grid_topology::{GossipTopology, TopologyNeighbors};
+
+/// A grid with a fixed number of columns, instead of `sqrt(n_validators)`.
+///
+/// Validators still reach each other in two hops, but have fewer neighbors in one dimension and
+/// more in the other.
+#[derive(Debug, Clone, Copy)]
+pub struct RectangularGrid {
+	/// The number of columns of the grid.
+	pub columns: usize,
+}
+
+impl GossipTopology for RectangularGrid {
+	fn neighbors(&self, position: usize, n_validators: usize) -> TopologyNeighbors {
+		let columns = self.columns.clamp(1, n_validators.max(1));
+		let row_start = position - position % columns;
+		let row = row_start..std::cmp::min(row_start + columns, n_validators);
+		let column = (position % columns..n_validators).step_by(columns);
+
+		TopologyNeighbors {
+			x: row.filter(|i| *i != position).collect(),
+			y: column.filter(|i| *i != position).collect(),
+		}
+	}
+}
+
+/// No neighbors at all, leaving the propagation of messages to random routing alone.
+///
+/// This is the baseline to compare topologies against.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoTopology;
+
+impl GossipTopology for NoTopology {
+	fn neighbors(&self, _position: usize, _n_validators: usize) -> TopologyNeighbors {
+		TopologyNeighbors::default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_network_protocol::grid_topology::GridTopology;
+
+	#[test]
+	fn square_rectangular_grid_is_the_grid() {
+		for n_validators in [1, 9, 10, 11, 100] {
+			let columns = (n_validators as f64).sqrt() as usize;
+			for position in 0..n_validators {
+				assert_eq!(
+					RectangularGrid { columns }.neighbors(position, n_validators),
+					GridTopology.neighbors(position, n_validators),
+				);
+			}
+		}
+	}
+}
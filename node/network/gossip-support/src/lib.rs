@@ -40,13 +40,15 @@ use sp_application_crypto::{AppKey, ByteArray};
 use sp_keystore::{CryptoStore, SyncCryptoStorePtr};
 
 use polkadot_node_network_protocol::{
-	authority_discovery::AuthorityDiscovery, peer_set::PeerSet, GossipSupportNetworkMessage,
-	PeerId, Versioned,
+	authority_discovery::AuthorityDiscovery,
+	grid_topology::{GossipTopology, GridTopology},
+	peer_set::PeerSet,
+	GossipSupportNetworkMessage, PeerId, Versioned,
 };
 use polkadot_node_subsystem::{
 	messages::{
-		GossipSupportMessage, NetworkBridgeEvent, NetworkBridgeRxMessage, NetworkBridgeTxMessage,
-		RuntimeApiMessage, RuntimeApiRequest,
+		AuthorityConnectivity, ConnectivityReport, GossipSupportMessage, NetworkBridgeEvent,
+		NetworkBridgeRxMessage, NetworkBridgeTxMessage, RuntimeApiMessage, RuntimeApiRequest,
 	},
 	overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
 };
@@ -79,6 +81,9 @@ const LOW_CONNECTIVITY_WARN_DELAY: Duration = Duration::from_secs(600);
 /// If connectivity is lower than this in percent, issue warning in logs.
 const LOW_CONNECTIVITY_WARN_THRESHOLD: usize = 90;
 
+/// How often the per-authority connectivity metrics are updated.
+const CONNECTIVITY_METRICS_INTERVAL: Duration = Duration::from_secs(30);
+
 /// The Gossip Support subsystem.
 pub struct GossipSupport<AD> {
 	keystore: SyncCryptoStorePtr,
@@ -96,11 +101,17 @@ pub struct GossipSupport<AD> {
 	/// connectivity.
 	failure_start: Option<Instant>,
 
+	/// The authorities of the last connection request, in the order of the request.
+	requested_authorities: Vec<AuthorityDiscoveryId>,
+
 	/// Successfully resolved connections
 	///
 	/// waiting for actual connection.
 	resolved_authorities: HashMap<AuthorityDiscoveryId, HashSet<Multiaddr>>,
 
+	/// When requested or connected authorities were last seen connected.
+	last_connected: HashMap<AuthorityDiscoveryId, Instant>,
+
 	/// Actually connected authorities.
 	connected_authorities: HashMap<AuthorityDiscoveryId, PeerId>,
 	/// By `PeerId`.
@@ -110,6 +121,9 @@ pub struct GossipSupport<AD> {
 	/// Authority discovery service.
 	authority_discovery: AD,
 
+	/// Construction of the gossip topology of each session.
+	topology: Box<dyn GossipTopology + Send + Sync>,
+
 	/// Subsystem metrics.
 	metrics: Metrics,
 }
@@ -130,19 +144,29 @@ where
 			last_session_index: None,
 			last_failure: None,
 			failure_start: None,
+			requested_authorities: Vec::new(),
 			resolved_authorities: HashMap::new(),
+			last_connected: HashMap::new(),
 			connected_authorities: HashMap::new(),
 			connected_authorities_by_peer_id: HashMap::new(),
 			authority_discovery,
+			topology: Box::new(GridTopology),
 			metrics,
 		}
 	}
 
+	/// Replace the default grid with another gossip topology.
+	pub fn with_topology(mut self, topology: impl GossipTopology + Send + Sync + 'static) -> Self {
+		self.topology = Box::new(topology);
+		self
+	}
+
 	async fn run<Context>(mut self, mut ctx: Context) -> Self {
 		fn get_connectivity_check_delay() -> Delay {
 			Delay::new(LOW_CONNECTIVITY_WARN_DELAY)
 		}
 		let mut next_connectivity_check = get_connectivity_check_delay().fuse();
+		let mut next_metrics_update = Delay::new(CONNECTIVITY_METRICS_INTERVAL).fuse();
 		loop {
 			let message = select!(
 				_ = next_connectivity_check => {
@@ -150,6 +174,11 @@ where
 					next_connectivity_check = get_connectivity_check_delay().fuse();
 					continue
 				}
+				_ = next_metrics_update => {
+					self.metrics.on_connectivity_report(&self.connectivity_report());
+					next_metrics_update = Delay::new(CONNECTIVITY_METRICS_INTERVAL).fuse();
+					continue
+				}
 				result = ctx.recv().fuse() =>
 					match result {
 						Ok(message) => message,
@@ -167,6 +196,11 @@ where
 				FromOrchestra::Communication {
 					msg: GossipSupportMessage::NetworkBridgeUpdate(ev),
 				} => self.handle_connect_disconnect(ev),
+				FromOrchestra::Communication {
+					msg: GossipSupportMessage::ConnectivityReport(tx),
+				} => {
+					let _ = tx.send(self.connectivity_report());
+				},
 				FromOrchestra::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
 					activated,
 					..
@@ -262,6 +296,7 @@ where
 
 					update_gossip_topology(
 						sender,
+						&*self.topology,
 						our_index,
 						session_info.discovery_keys,
 						relay_parent,
@@ -323,6 +358,7 @@ where
 		Sender: overseer::GossipSupportSenderTrait,
 	{
 		let num = authorities.len();
+		self.requested_authorities = authorities.clone();
 		let mut validator_addrs = Vec::with_capacity(authorities.len());
 		let mut failures = 0;
 		let mut resolved = HashMap::with_capacity(authorities.len());
@@ -342,6 +378,12 @@ where
 			}
 		}
 		self.resolved_authorities = resolved;
+		{
+			let requested = self.requested_authorities.iter().collect::<HashSet<_>>();
+			let connected = &self.connected_authorities;
+			self.last_connected
+				.retain(|a, _| requested.contains(a) || connected.contains_key(a));
+		}
 		gum::debug!(target: LOG_TARGET, %num, "Issuing a connection request");
 
 		sender
@@ -385,8 +427,10 @@ where
 		match ev {
 			NetworkBridgeEvent::PeerConnected(peer_id, _, _, o_authority) => {
 				if let Some(authority_ids) = o_authority {
+					let now = Instant::now();
 					authority_ids.iter().for_each(|a| {
 						self.connected_authorities.insert(a.clone(), peer_id);
+						self.last_connected.insert(a.clone(), now);
 					});
 					self.connected_authorities_by_peer_id.insert(peer_id, authority_ids);
 				}
@@ -394,8 +438,10 @@ where
 			NetworkBridgeEvent::PeerDisconnected(peer_id) => {
 				if let Some(authority_ids) = self.connected_authorities_by_peer_id.remove(&peer_id)
 				{
+					let now = Instant::now();
 					authority_ids.into_iter().for_each(|a| {
 						self.connected_authorities.remove(&a);
+						self.last_connected.insert(a, now);
 					});
 				}
 			},
@@ -408,6 +454,39 @@ where
 		}
 	}
 
+	/// Our connectivity to the requested authorities, followed by any other connected ones.
+	fn connectivity_report(&self) -> ConnectivityReport {
+		let now = Instant::now();
+		let connectivity = |authority: &AuthorityDiscoveryId| {
+			let connected_peer = self.connected_authorities.get(authority).copied();
+			let since_last_connected = if connected_peer.is_some() {
+				Some(Duration::ZERO)
+			} else {
+				self.last_connected.get(authority).map(|at| now.saturating_duration_since(*at))
+			};
+
+			AuthorityConnectivity {
+				authority: authority.clone(),
+				connected_peer,
+				since_last_connected,
+				resolved_addresses: self
+					.resolved_authorities
+					.get(authority)
+					.map(|addrs| addrs.iter().cloned().collect()),
+			}
+		};
+
+		let requested = self.requested_authorities.iter().collect::<HashSet<_>>();
+		let authorities = self
+			.requested_authorities
+			.iter()
+			.chain(self.connected_authorities.keys().filter(|a| !requested.contains(a)))
+			.map(connectivity)
+			.collect();
+
+		ConnectivityReport { session: self.last_session_index, authorities }
+	}
+
 	/// Check connectivity and report on it in logs.
 	fn check_connectivity(&mut self) {
 		let absolute_connected = self.connected_authorities.len();
@@ -486,16 +565,17 @@ async fn remove_all_controlled(
 	to_remove.len()
 }
 
-/// We partition the list of all sorted `authorities` into `sqrt(len)` groups of `sqrt(len)` size
-/// and form a matrix where each validator is connected to all validators in its row and column.
+/// We shuffle the list of all sorted `authorities` and let the `topology` pick our neighbors among
+/// them. With the default [`GridTopology`], this forms a matrix where each validator is connected
+/// to all validators in its row and column.
 /// This is similar to `[web3]` research proposed topology, except for the groups are not parachain
 /// groups (because not all validators are parachain validators and the group size is small),
 /// but formed randomly via BABE randomness from two epochs ago.
-/// This limits the amount of gossip peers to 2 * `sqrt(len)` and ensures the diameter of 2.
 ///
 /// [web3]: https://research.web3.foundation/en/latest/polkadot/networking/3-avail-valid.html#topology
 async fn update_gossip_topology(
 	sender: &mut impl overseer::GossipSupportSenderTrait,
+	topology: &(dyn GossipTopology + Send + Sync),
 	our_index: usize,
 	authorities: Vec<AuthorityDiscoveryId>,
 	relay_parent: Hash,
@@ -531,15 +611,17 @@ async fn update_gossip_topology(
 		.position(|i| *i == our_index)
 		.expect("our_index < len; indices contains it; qed");
 
-	let neighbors = matrix_neighbors(our_shuffled_position, len);
+	let neighbors = topology.neighbors(our_shuffled_position, len);
 	let row_neighbors = neighbors
-		.row_neighbors
+		.x
+		.into_iter()
 		.map(|i| indices[i])
 		.map(|i| (authorities[i].clone(), ValidatorIndex::from(i as u32)))
 		.collect();
 
 	let column_neighbors = neighbors
-		.column_neighbors
+		.y
+		.into_iter()
 		.map(|i| indices[i])
 		.map(|i| (authorities[i].clone(), ValidatorIndex::from(i as u32)))
 		.collect();
@@ -555,39 +637,6 @@ async fn update_gossip_topology(
 	Ok(())
 }
 
-struct MatrixNeighbors<R, C> {
-	row_neighbors: R,
-	column_neighbors: C,
-}
-
-/// Compute our row and column neighbors in a matrix
-fn matrix_neighbors(
-	our_index: usize,
-	len: usize,
-) -> MatrixNeighbors<impl Iterator<Item = usize>, impl Iterator<Item = usize>> {
-	assert!(our_index < len, "our_index is computed using `enumerate`; qed");
-
-	// e.g. for size 11 the matrix would be
-	//
-	// 0  1  2
-	// 3  4  5
-	// 6  7  8
-	// 9 10
-	//
-	// and for index 10, the neighbors would be 1, 4, 7, 9
-
-	let sqrt = (len as f64).sqrt() as usize;
-	let our_row = our_index / sqrt;
-	let our_column = our_index % sqrt;
-	let row_neighbors = our_row * sqrt..std::cmp::min(our_row * sqrt + sqrt, len);
-	let column_neighbors = (our_column..len).step_by(sqrt);
-
-	MatrixNeighbors {
-		row_neighbors: row_neighbors.filter(move |i| *i != our_index),
-		column_neighbors: column_neighbors.filter(move |i| *i != our_index),
-	}
-}
-
 #[overseer::subsystem(GossipSupport, error = SubsystemError, prefix = self::overseer)]
 impl<Context, AD> GossipSupport<AD>
 where
@@ -14,11 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use polkadot_node_subsystem::messages::ConnectivityReport;
 use polkadot_node_subsystem_util::{
 	metrics,
 	metrics::{
 		prometheus,
-		prometheus::{Gauge, PrometheusError, Registry, U64},
+		prometheus::{Gauge, GaugeVec, Opts, PrometheusError, Registry, U64},
 	},
 };
 
@@ -32,6 +33,12 @@ struct MetricsInner {
 	is_authority: Gauge<U64>,
 	/// Tracks authority status for parachain approval checking.
 	is_parachain_validator: Gauge<U64>,
+	/// Whether we are connected to an authority.
+	authority_connected: GaugeVec<U64>,
+	/// Whether the addresses of an authority were resolved via authority discovery.
+	authority_resolved: GaugeVec<U64>,
+	/// Seconds since we were last connected to an authority.
+	authority_since_last_connected: GaugeVec<U64>,
 }
 
 impl Metrics {
@@ -68,6 +75,35 @@ impl Metrics {
 			metrics.is_parachain_validator.set(0);
 		}
 	}
+
+	/// Replace the per-authority connectivity metrics with the ones of `report`.
+	pub fn on_connectivity_report(&self, report: &ConnectivityReport) {
+		if let Some(metrics) = &self.0 {
+			// Forget about authorities we no longer care about.
+			metrics.authority_connected.reset();
+			metrics.authority_resolved.reset();
+			metrics.authority_since_last_connected.reset();
+
+			for connectivity in &report.authorities {
+				let authority = connectivity.authority.to_string();
+				let labels = [authority.as_str()];
+				metrics
+					.authority_connected
+					.with_label_values(&labels)
+					.set(connectivity.connected_peer.is_some() as u64);
+				metrics
+					.authority_resolved
+					.with_label_values(&labels)
+					.set(connectivity.resolved_addresses.is_some() as u64);
+				if let Some(since) = connectivity.since_last_connected {
+					metrics
+						.authority_since_last_connected
+						.with_label_values(&labels)
+						.set(since.as_secs());
+				}
+			}
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -85,6 +121,37 @@ impl metrics::Metrics for Metrics {
 				Updates at session boundary.")?,
 				registry,
 			)?,
+			authority_connected: prometheus::register(
+				GaugeVec::new(
+					Opts::new(
+						"polkadot_parachain_gossip_authority_connected",
+						"Whether we are connected to an authority we asked the network to connect to.",
+					),
+					&["authority"],
+				)?,
+				registry,
+			)?,
+			authority_resolved: prometheus::register(
+				GaugeVec::new(
+					Opts::new(
+						"polkadot_parachain_gossip_authority_resolved",
+						"Whether the addresses of an authority were resolved via authority discovery.",
+					),
+					&["authority"],
+				)?,
+				registry,
+			)?,
+			authority_since_last_connected: prometheus::register(
+				GaugeVec::new(
+					Opts::new(
+						"polkadot_parachain_gossip_authority_seconds_since_connected",
+						"Seconds since we were last connected to an authority, 0 while connected. \
+						Absent for authorities never connected to.",
+					),
+					&["authority"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -638,24 +638,83 @@ fn issues_a_connection_request_when_last_request_was_mostly_unresolved() {
 }
 
 #[test]
-fn test_matrix_neighbors() {
-	for (our_index, len, expected_row, expected_column) in vec![
-		(0usize, 1usize, vec![], vec![]),
-		(1, 2, vec![], vec![0usize]),
-		(0, 9, vec![1, 2], vec![3, 6]),
-		(9, 10, vec![], vec![0, 3, 6]),
-		(10, 11, vec![9], vec![1, 4, 7]),
-		(7, 11, vec![6, 8], vec![1, 4, 10]),
-	]
-	.into_iter()
-	{
-		let matrix = matrix_neighbors(our_index, len);
-		let mut row_result: Vec<_> = matrix.row_neighbors.collect();
-		let mut column_result: Vec<_> = matrix.column_neighbors.collect();
-		row_result.sort();
-		column_result.sort();
-
-		assert_eq!(row_result, expected_row);
-		assert_eq!(column_result, expected_column);
-	}
+fn reports_connectivity_of_requested_authorities() {
+	let mut state = make_subsystem();
+	let alice: AuthorityDiscoveryId = Sr25519Keyring::Alice.public().into();
+	let bob: AuthorityDiscoveryId = Sr25519Keyring::Bob.public().into();
+	let charlie: AuthorityDiscoveryId = Sr25519Keyring::Charlie.public().into();
+	let eve: AuthorityDiscoveryId = Sr25519Keyring::Eve.public().into();
+	let (alice_peer, bob_peer, eve_peer) = (PeerId::random(), PeerId::random(), PeerId::random());
+
+	// Charlie could not be resolved, Eve was not requested at all.
+	state.last_session_index = Some(1);
+	state.requested_authorities = vec![alice.clone(), bob.clone(), charlie.clone()];
+	state.resolved_authorities = vec![alice.clone(), bob.clone()]
+		.into_iter()
+		.map(|a| {
+			let addr = Multiaddr::empty().with(Protocol::P2p(PeerId::random().into()));
+			(a, HashSet::from([addr]))
+		})
+		.collect();
+
+	let alice_addrs = state.resolved_authorities[&alice].iter().cloned().collect::<Vec<_>>();
+
+	test_harness(state, |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		for (peer, authority) in [(alice_peer, &alice), (bob_peer, &bob), (eve_peer, &eve)] {
+			overseer
+				.send(FromOrchestra::Communication {
+					msg: GossipSupportMessage::NetworkBridgeUpdate(
+						NetworkBridgeEvent::PeerConnected(
+							peer,
+							polkadot_node_network_protocol::ObservedRole::Authority,
+							1,
+							Some(HashSet::from([authority.clone()])),
+						),
+					),
+				})
+				.await;
+		}
+		overseer
+			.send(FromOrchestra::Communication {
+				msg: GossipSupportMessage::NetworkBridgeUpdate(
+					NetworkBridgeEvent::PeerDisconnected(bob_peer),
+				),
+			})
+			.await;
+
+		let (tx, rx) = oneshot::channel();
+		overseer
+			.send(FromOrchestra::Communication {
+				msg: GossipSupportMessage::ConnectivityReport(tx),
+			})
+			.await;
+		let report = rx.timeout(TIMEOUT).await.expect("report timeout").unwrap();
+
+		assert_eq!(report.session, Some(1));
+		assert_eq!(
+			report.authorities.iter().map(|a| a.authority.clone()).collect::<Vec<_>>(),
+			vec![alice.clone(), bob.clone(), charlie.clone(), eve.clone()],
+		);
+
+		let [alice_report, bob_report, charlie_report, eve_report] =
+			<[AuthorityConnectivity; 4]>::try_from(report.authorities).unwrap();
+
+		assert_eq!(alice_report.connected_peer, Some(alice_peer));
+		assert_eq!(alice_report.since_last_connected, Some(Duration::ZERO));
+		assert_eq!(alice_report.resolved_addresses, Some(alice_addrs));
+
+		assert_eq!(bob_report.connected_peer, None);
+		assert!(bob_report.since_last_connected.is_some());
+		assert!(bob_report.resolved_addresses.is_some());
+
+		assert_eq!(charlie_report.connected_peer, None);
+		assert_eq!(charlie_report.since_last_connected, None);
+		assert_eq!(charlie_report.resolved_addresses, None);
+
+		assert_eq!(eve_report.connected_peer, Some(eve_peer));
+		assert_eq!(eve_report.resolved_addresses, None);
+
+		virtual_overseer
+	});
 }
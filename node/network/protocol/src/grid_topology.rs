@@ -28,6 +28,8 @@
 //! an adversary doesn't know which peers a validator will send to.
 //! This is combined with the property that the adversary doesn't know which validators will elect to check a block.
 //!
+//! Which validators are neighbors in which dimension is decided by a [`GossipTopology`], the grid
+//! being the [`GridTopology`] used by default.
 
 use crate::PeerId;
 use polkadot_primitives::v2::{SessionIndex, ValidatorIndex};
@@ -48,6 +50,62 @@ pub const DEFAULT_RANDOM_SAMPLE_RATE: usize = crate::MIN_GOSSIP_PEERS;
 /// The number of peers to randomly propagate messages to.
 pub const DEFAULT_RANDOM_CIRCULATION: usize = 4;
 
+/// The neighbors of a validator in a gossip topology, as positions in the list of validators of
+/// the session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopologyNeighbors {
+	/// Neighbors in the X dimension.
+	pub x: Vec<usize>,
+	/// Neighbors in the Y dimension.
+	pub y: Vec<usize>,
+}
+
+/// Construction of the gossip topology of a session.
+///
+/// Messages are routed along two dimensions: messages originating from a neighbor in one
+/// dimension are forwarded to the neighbors in the other one, while our own messages go to both
+/// (see [`SessionGridTopology::required_routing_by_index`]). A topology decides which validators
+/// are neighbors in which dimension. The relation must be symmetric: if `a` is an X neighbor of
+/// `b`, then `b` is an X neighbor of `a`.
+pub trait GossipTopology {
+	/// The neighbors of the validator at `position` among `n_validators` validators.
+	fn neighbors(&self, position: usize, n_validators: usize) -> TopologyNeighbors;
+}
+
+/// The 2D grid topology: validators are arranged in a matrix of `sqrt(n_validators)` columns,
+/// their X neighbors being the validators in the same row and their Y neighbors the ones in the
+/// same column.
+///
+/// This limits the amount of gossip peers to 2 * `sqrt(n_validators)` and ensures a diameter of 2.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GridTopology;
+
+impl GossipTopology for GridTopology {
+	fn neighbors(&self, position: usize, n_validators: usize) -> TopologyNeighbors {
+		assert!(position < n_validators, "position is computed using `enumerate`; qed");
+
+		// e.g. for size 11 the matrix would be
+		//
+		// 0  1  2
+		// 3  4  5
+		// 6  7  8
+		// 9 10
+		//
+		// and for index 10, the neighbors would be 1, 4, 7, 9
+
+		let sqrt = (n_validators as f64).sqrt() as usize;
+		let our_row = position / sqrt;
+		let our_column = position % sqrt;
+		let row_neighbors = our_row * sqrt..std::cmp::min(our_row * sqrt + sqrt, n_validators);
+		let column_neighbors = (our_column..n_validators).step_by(sqrt);
+
+		TopologyNeighbors {
+			x: row_neighbors.filter(|i| *i != position).collect(),
+			y: column_neighbors.filter(|i| *i != position).collect(),
+		}
+	}
+}
+
 /// Topology representation
 #[derive(Default, Clone, Debug)]
 pub struct SessionGridTopology {
@@ -248,6 +306,12 @@ impl Default for RandomRouting {
 }
 
 impl RandomRouting {
+	/// Random routing to `target` peers, each peer being picked with a probability of
+	/// `sample_rate / n_peers_total`.
+	pub fn new(target: usize, sample_rate: usize) -> Self {
+		RandomRouting { target, sent: 0, sample_rate }
+	}
+
 	/// Perform random sampling for a specific peer
 	/// Returns `true` for a lucky peer
 	pub fn sample(&self, n_peers_total: usize, rng: &mut (impl CryptoRng + Rng)) -> bool {
@@ -305,6 +369,28 @@ mod tests {
 		rand_chacha::ChaCha12Rng::seed_from_u64(12345)
 	}
 
+	#[test]
+	fn test_grid_neighbors() {
+		for (our_index, len, expected_row, expected_column) in vec![
+			(0usize, 1usize, vec![], vec![]),
+			(1, 2, vec![], vec![0usize]),
+			(0, 9, vec![1, 2], vec![3, 6]),
+			(9, 10, vec![], vec![0, 3, 6]),
+			(10, 11, vec![9], vec![1, 4, 7]),
+			(7, 11, vec![6, 8], vec![1, 4, 10]),
+		]
+		.into_iter()
+		{
+			let TopologyNeighbors { x: mut row_result, y: mut column_result } =
+				GridTopology.neighbors(our_index, len);
+			row_result.sort();
+			column_result.sort();
+
+			assert_eq!(row_result, expected_row);
+			assert_eq!(column_result, expected_column);
+		}
+	}
+
 	#[test]
 	fn test_random_routing_sample() {
 		// This test is fragile as it relies on a specific ChaCha12Rng
@@ -80,6 +80,18 @@ pub(crate) fn impl_builder(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 		})
 		.collect::<Vec<_>>();
 
+	let restart_policy = &info
+		.subsystems()
+		.iter()
+		.filter(|ssf| !ssf.wip)
+		.map(|ssf| match ssf.restart {
+			RestartPolicy::Never => quote! { #support_crate ::RestartPolicy::Never },
+			RestartPolicy::OnFailure { max_restarts } => quote! {
+				#support_crate ::RestartPolicy::OnFailure { max_restarts: #max_restarts }
+			},
+		})
+		.collect::<Vec<_>>();
+
 	// Helpers to use within quote! macros
 	let spawner_where_clause: syn::TypeParam = parse_quote! {
 			S: #support_crate ::Spawner
@@ -113,6 +125,7 @@ pub(crate) fn impl_builder(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 			let impl_subsystem_state_generics = recollect_without_idx(&subsystem_passthrough_state_generics[..], idx);
 
 			let field_name_with = format_ident!("{}_with", field_name);
+			let field_name_factory = format_ident!("{}_factory", field_name);
 			let field_name_replace = format_ident!("replace_{}", field_name);
 
 			// In a setter we replace `Uninit<T>` with `Init<T>` leaving all other
@@ -189,6 +202,31 @@ pub(crate) fn impl_builder(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 							spawner: self.spawner,
 
 
+							channel_capacity: self.channel_capacity,
+							signal_capacity: self.signal_capacity,
+						}
+					}
+					/// Specify a factory for the subsystem, which is used to instantiate it and,
+					/// if its restart policy permits, to instantiate it again once it failed.
+					pub fn #field_name_factory<F>(self, subsystem_factory: F ) ->
+						#builder <InitStateSpawner, #( #post_setter_state_generics, )* #( #baggage_passthrough_state_generics, )*>
+					where
+						F: 'static + Send + FnMut(#handle) ->
+							::std::result::Result<#field_type, #error_ty>,
+					{
+						let boxed_factory = Init::<#field_type>::Factory(
+							Box::new(subsystem_factory) as SubsystemFactoryFn<#field_type>
+						);
+						#builder {
+							#field_name: boxed_factory,
+							#(
+								#to_keep_subsystem_name: self. #to_keep_subsystem_name,
+							)*
+							#(
+								#baggage_name: self. #baggage_name,
+							)*
+							spawner: self.spawner,
+
 							channel_capacity: self.channel_capacity,
 							signal_capacity: self.signal_capacity,
 						}
@@ -217,6 +255,13 @@ pub(crate) fn impl_builder(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 							})),
 							Init::Value(val) =>
 								Init::Value(gen_replacement_fn(val)),
+							// The replacement can only be applied once, so the replaced
+							// subsystem can not be restarted.
+							Init::Factory(mut factory) =>
+								Init::<NEW>::Fn(Box::new(move |handle: #handle| {
+								let orig = factory(handle)?;
+								Ok(gen_replacement_fn(orig))
+							})),
 						};
 						#builder {
 							#field_name: replacement,
@@ -335,6 +380,9 @@ pub(crate) fn impl_builder(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 		/// Convenience alias.
 		type SubsystemInitFn<T> = Box<dyn FnOnce(#handle) -> ::std::result::Result<T, #error_ty> >;
 
+		/// Convenience alias.
+		type SubsystemFactoryFn<T> = Box<dyn FnMut(#handle) -> ::std::result::Result<T, #error_ty> + Send>;
+
 		/// Type for the initialized field of the orchestra builder
 		pub enum Init<T> {
 			/// Defer initialization to a point where the `handle` is available.
@@ -342,6 +390,9 @@ pub(crate) fn impl_builder(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 			/// Directly initialize the subsystem with the given subsystem type `T`.
 			/// Also used for baggage fields
 			Value(T),
+			/// Initialize the subsystem from a factory, which is retained to
+			/// instantiate the subsystem again when it is restarted.
+			Factory(SubsystemFactoryFn<T>),
 		}
 		/// Type marker for the uninitialized field of the orchestra builder.
 		/// `PhantomData` is used for type hinting when creating uninitialized
@@ -595,14 +646,18 @@ pub(crate) fn impl_builder(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 				};
 
 				let mut running_subsystems = #support_crate ::FuturesUnordered::<
-						BoxFuture<'static, ::std::result::Result<(), #error_ty > >
+						BoxFuture<'static, #support_crate ::SubsystemExited>
 					>::new();
 
 				#(
-					let #subsystem_name = match self. #subsystem_name {
-						Init::Fn(func) => func(handle.clone())?,
-						Init::Value(val) => val,
+					let (#subsystem_name, factory) = match self. #subsystem_name {
+						Init::Fn(func) => (func(handle.clone())?, None),
+						Init::Value(val) => (val, None),
+						Init::Factory(mut factory) => (factory(handle.clone())?, Some(factory)),
 					};
+					let restart_policy = #restart_policy;
+					// Only subsystems instantiated by a factory can be restarted.
+					let factory = factory.filter(|_| restart_policy != #support_crate ::RestartPolicy::Never);
 
 					let unbounded_meter = #channel_name_unbounded_rx.meter().clone();
 					// Prefer unbounded channel when selecting
@@ -614,15 +669,31 @@ pub(crate) fn impl_builder(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 						self.signal_capacity.unwrap_or(SIGNAL_CHANNEL_CAPACITY)
 					);
 
-					let ctx = #subsystem_ctx_name::< #consumes >::new(
+					let (signal_rx, message_rx, recycled) = if factory.is_some() {
+						let (signal_rx, recycled_signals) = #support_crate ::Recyclable::recycled(signal_rx);
+						let (message_rx, recycled_messages) = #support_crate ::Recyclable::recycled(message_rx);
+						(signal_rx, message_rx, Some(RecycledChannels {
+							signals: recycled_signals,
+							messages: recycled_messages,
+						}))
+					} else {
+						(
+							#support_crate ::Recyclable::new(signal_rx),
+							#support_crate ::Recyclable::new(message_rx),
+							None,
+						)
+					};
+
+					let ctx = #subsystem_ctx_name::< #consumes >::with_recyclable_channels(
 						signal_rx,
 						message_rx,
 						channels_out.clone(),
 						to_orchestra_tx.clone(),
-						#subsystem_name_str_literal
+						#subsystem_name_str_literal,
+						SignalsReceived::default(),
 					);
 
-					let #subsystem_name: OrchestratedSubsystem< #consumes > =
+					let mut #subsystem_name: OrchestratedSubsystem< #consumes > =
 						spawn::<_,_, #blocking, _, _, _>(
 							&mut spawner,
 							#channel_name_tx,
@@ -633,6 +704,41 @@ pub(crate) fn impl_builder(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 							#subsystem_name_str_literal,
 							&mut running_subsystems,
 						)?;
+
+					if let (Some(mut factory), Some(recycled)) = (factory, recycled) {
+						let handle = handle.clone();
+						let channels_out = channels_out.clone();
+						let to_orchestra_tx = to_orchestra_tx.clone();
+						let start: SubsystemStartFn< #consumes > = Box::new(
+							move |signal_rx, message_rx, signals_received| {
+								let subsystem = factory(handle.clone())?;
+								let (signal_rx, recycled_signals) = #support_crate ::Recyclable::recycled(signal_rx);
+								let (message_rx, recycled_messages) = #support_crate ::Recyclable::recycled(message_rx);
+								let ctx = #subsystem_ctx_name::< #consumes >::with_recyclable_channels(
+									signal_rx,
+									message_rx,
+									channels_out.clone(),
+									to_orchestra_tx.clone(),
+									#subsystem_name_str_literal,
+									SignalsReceived::new(signals_received),
+								);
+								let spawned = <#subsystem_generics as #support_crate ::Subsystem<
+									#subsystem_ctx_name< #consumes >,
+									#error_ty,
+								>>::start(subsystem, ctx);
+								Ok((spawned, RecycledChannels {
+									signals: recycled_signals,
+									messages: recycled_messages,
+								}))
+							}
+						);
+						#subsystem_name.restart = Some(SubsystemRestart {
+							policy: restart_policy,
+							restarts: 0,
+							recycled,
+							start,
+						});
+					}
 				)*
 
 				use #support_crate ::StreamExt;
@@ -705,7 +811,7 @@ pub(crate) fn impl_task_kind(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 			ctx: Ctx,
 			s: SubSys,
 			subsystem_name: &'static str,
-			futures: &mut #support_crate ::FuturesUnordered<BoxFuture<'static, ::std::result::Result<(), #error_ty> >>,
+			futures: &mut #support_crate ::FuturesUnordered<BoxFuture<'static, #support_crate ::SubsystemExited>>,
 		) -> ::std::result::Result<OrchestratedSubsystem<M>, #error_ty >
 		where
 			S: #support_crate ::Spawner,
@@ -715,33 +821,17 @@ pub(crate) fn impl_task_kind(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 			E: ::std::error::Error + Send + Sync + 'static + ::std::convert::From<#support_crate ::OrchestraError>,
 			SubSys: #support_crate ::Subsystem<Ctx, E>,
 		{
-			let #support_crate ::SpawnedSubsystem::<E> { future, name } = s.start(ctx);
+			let spawned = s.start(ctx);
+			let name = spawned.name;
 
-			let (tx, rx) = #support_crate ::oneshot::channel();
-
-			let fut = Box::pin(async move {
-				if let Err(e) = future.await {
-					#support_crate ::tracing::error!(subsystem=name, err = ?e, "subsystem exited with error");
-				} else {
-					#support_crate ::tracing::debug!(subsystem=name, "subsystem exited without an error");
-				}
-				let _ = tx.send(());
-			});
-
-			<TK as TaskKind>::launch_task(spawner, name, subsystem_name, fut);
-
-			futures.push(Box::pin(
-				rx.map(|e| {
-					#support_crate ::tracing::warn!(err = ?e, "dropping error");
-					Ok(())
-				})
-			));
+			launch_subsystem::<S, TK, E>(spawner, spawned, subsystem_name, futures);
 
 			let instance = Some(SubsystemInstance {
 				meters: #support_crate ::SubsystemMeters {
 					unbounded: unbounded_meter,
 					bounded: message_tx.meter().clone(),
 					signals: signal_tx.meter().clone(),
+					restarts: ::std::default::Default::default(),
 				},
 				tx_signal: signal_tx,
 				tx_bounded: message_tx,
@@ -751,8 +841,55 @@ pub(crate) fn impl_task_kind(info: &OrchestraInfo) -> proc_macro2::TokenStream {
 
 			Ok(OrchestratedSubsystem {
 				instance,
+				restart: None,
 			})
 		}
+
+		/// Launch the task of a started subsystem, and track when it ends in `futures`.
+		fn launch_subsystem<S, TK, E>(
+			spawner: &mut S,
+			spawned: #support_crate ::SpawnedSubsystem<E>,
+			subsystem_name: &'static str,
+			futures: &mut #support_crate ::FuturesUnordered<BoxFuture<'static, #support_crate ::SubsystemExited>>,
+		)
+		where
+			S: #support_crate ::Spawner,
+			TK: TaskKind,
+			E: ::std::error::Error + Send + Sync + 'static + ::std::convert::From<#support_crate ::OrchestraError>,
+		{
+			let #support_crate ::SpawnedSubsystem::<E> { future, name } = spawned;
+
+			let (tx, rx) = #support_crate ::oneshot::channel();
+
+			let fut = Box::pin(async move {
+				let reason = match #support_crate ::FutureExt::catch_unwind(
+					::std::panic::AssertUnwindSafe(future)
+				).await {
+					Ok(Ok(())) => {
+						#support_crate ::tracing::debug!(subsystem=name, "subsystem exited without an error");
+						#support_crate ::SubsystemExitReason::Concluded
+					},
+					Ok(Err(e)) => {
+						#support_crate ::tracing::error!(subsystem=name, err = ?e, "subsystem exited with error");
+						#support_crate ::SubsystemExitReason::Failed
+					},
+					Err(_) => {
+						#support_crate ::tracing::error!(subsystem=name, "subsystem panicked");
+						#support_crate ::SubsystemExitReason::Panicked
+					},
+				};
+				let _ = tx.send(reason);
+			});
+
+			<TK as TaskKind>::launch_task(spawner, name, subsystem_name, fut);
+
+			futures.push(Box::pin(
+				rx.map(move |reason| #support_crate ::SubsystemExited {
+					name: subsystem_name,
+					reason: reason.unwrap_or(#support_crate ::SubsystemExitReason::Aborted),
+				})
+			));
+		}
 	};
 
 	ts
@@ -48,6 +48,24 @@ pub(crate) fn impl_orchestra_struct(info: &OrchestraInfo) -> proc_macro2::TokenS
 
 	let event_ty = &info.extern_event_ty;
 
+	// Create the string literals the subsystems are spawned with.
+	let subsystem_name_str_literal = subsystem_name
+		.iter()
+		.map(|ident| proc_macro2::Literal::string(ident.to_string().replace("_", "-").as_str()))
+		.collect::<Vec<_>>();
+	let blocking = &info
+		.subsystems()
+		.iter()
+		.filter(|ssf| !ssf.wip)
+		.map(|ssf| {
+			if ssf.blocking {
+				quote! { Blocking }
+			} else {
+				quote! { Regular }
+			}
+		})
+		.collect::<Vec<_>>();
+
 	let message_channel_capacity = info.message_channel_capacity;
 	let signal_channel_capacity = info.signal_channel_capacity;
 
@@ -81,9 +99,9 @@ pub(crate) fn impl_orchestra_struct(info: &OrchestraInfo) -> proc_macro2::TokenS
 			/// Responsible for driving the subsystem futures.
 			spawner: S,
 
-			/// The set of running subsystems.
+			/// The set of running subsystems, resolving once the task of a subsystem ends.
 			running_subsystems: #support_crate ::FuturesUnordered<
-				BoxFuture<'static, ::std::result::Result<(), #error_ty>>
+				BoxFuture<'static, #support_crate ::SubsystemExited>
 			>,
 
 			/// Gather running subsystems' outbound streams into one.
@@ -157,6 +175,38 @@ pub(crate) fn impl_orchestra_struct(info: &OrchestraInfo) -> proc_macro2::TokenS
 				Ok(())
 			}
 
+			/// Restart a subsystem whose task ended, if its restart policy permits.
+			///
+			/// The `replay` signals are sent to the new instance before anything else,
+			/// e.g. to inform it about the currently active leaves. Returns `false` if the
+			/// subsystem may not be restarted, in which case the orchestra is expected to
+			/// shut down.
+			pub async fn restart_subsystem(
+				&mut self,
+				exited: #support_crate ::SubsystemExited,
+				replay: impl IntoIterator<Item = #signal_ty>,
+			) -> ::std::result::Result<bool, #error_ty > {
+				match exited.name {
+					#(
+						#subsystem_name_str_literal => {
+							let restarted = self. #subsystem_name .restart::<_, #blocking>(
+								exited.name,
+								exited.reason,
+								&mut self.spawner,
+								&mut self.running_subsystems,
+							)?;
+							if restarted {
+								for signal in replay {
+									self. #subsystem_name .send_signal(signal).await?;
+								}
+							}
+							Ok(restarted)
+						},
+					)*
+					_ => Ok(false),
+				}
+			}
+
 			/// Extract information from each subsystem.
 			pub fn map_subsystems<'a, Mapper, Output>(&'a self, mapper: Mapper)
 			-> Vec<Output>
@@ -201,9 +251,125 @@ pub(crate) fn impl_orchestrated_subsystem(info: &OrchestraInfo) -> proc_macro2::
 			pub instance: std::option::Option<
 				#support_crate ::SubsystemInstance<M, #signal>
 			>,
+			/// Everything required to restart the subsystem, if it is restartable.
+			pub restart: std::option::Option<SubsystemRestart<M>>,
+		}
+
+		/// Receivers through which the channels of a subsystem are handed back
+		/// once its context is dropped.
+		pub struct RecycledChannels<M> {
+			/// Receives the signal channel.
+			pub signals: #support_crate ::oneshot::Receiver<
+				#support_crate ::metered::MeteredReceiver< #signal >
+			>,
+			/// Receives the message channels.
+			pub messages: #support_crate ::oneshot::Receiver<SubsystemIncomingMessages<M>>,
+		}
+
+		/// Instantiates and starts a new instance of a subsystem, given the channels of
+		/// the previous instance and the number of signals sent to it so far.
+		pub type SubsystemStartFn<M> = Box<
+			dyn FnMut(
+				#support_crate ::metered::MeteredReceiver< #signal >,
+				SubsystemIncomingMessages<M>,
+				usize,
+			) -> ::std::result::Result<
+				(#support_crate ::SpawnedSubsystem< #error_ty >, RecycledChannels<M>),
+				#error_ty
+			> + Send
+		>;
+
+		/// Everything required to restart a subsystem.
+		pub struct SubsystemRestart<M> {
+			/// The restart policy of the subsystem.
+			pub policy: #support_crate ::RestartPolicy,
+			/// The number of restarts so far.
+			pub restarts: usize,
+			/// The channels of the current instance, once it is gone.
+			pub recycled: RecycledChannels<M>,
+			/// Starts a new instance.
+			pub start: SubsystemStartFn<M>,
 		}
 
 		impl<M> OrchestratedSubsystem<M> {
+			/// Restart the subsystem after its task ended, if its restart policy permits.
+			///
+			/// The new instance takes over the channels of the previous one, including
+			/// all messages not yet processed, but not the pending signals.
+			/// Returns `false` if the subsystem may not be restarted.
+			fn restart<S, TK>(
+				&mut self,
+				subsystem_name: &'static str,
+				reason: #support_crate ::SubsystemExitReason,
+				spawner: &mut S,
+				futures: &mut #support_crate ::FuturesUnordered<
+					BoxFuture<'static, #support_crate ::SubsystemExited>
+				>,
+			) -> ::std::result::Result<bool, #error_ty >
+			where
+				S: #support_crate ::Spawner,
+				TK: TaskKind,
+			{
+				let (instance, restart) = match (self.instance.as_mut(), self.restart.as_mut()) {
+					(Some(instance), Some(restart)) => (instance, restart),
+					_ => return Ok(false),
+				};
+
+				if !reason.is_failure() {
+					return Ok(false)
+				}
+
+				match restart.policy {
+					#support_crate ::RestartPolicy::Never => return Ok(false),
+					#support_crate ::RestartPolicy::OnFailure { max_restarts } if restart.restarts >= max_restarts => {
+						#support_crate ::tracing::error!(
+							target: LOG_TARGET,
+							subsystem = instance.name,
+							max_restarts,
+							"Subsystem failed too often, not restarting it.",
+						);
+						return Ok(false)
+					},
+					#support_crate ::RestartPolicy::OnFailure { .. } => {},
+				}
+
+				let (mut signals, messages) = match (
+					restart.recycled.signals.try_recv(),
+					restart.recycled.messages.try_recv(),
+				) {
+					(Ok(Some(signals)), Ok(Some(messages))) => (signals, messages),
+					_ => {
+						#support_crate ::tracing::error!(
+							target: LOG_TARGET,
+							subsystem = instance.name,
+							"Channels of the subsystem were not handed back, not restarting it.",
+						);
+						return Ok(false)
+					},
+				};
+
+				// Signals the previous instance did not get to are stale, the new instance
+				// starts out as if it received all signals sent so far.
+				while let Ok(Some(_)) = signals.try_next() {}
+
+				let (spawned, recycled) = (restart.start)(signals, messages, instance.signals_received)?;
+				restart.recycled = recycled;
+				restart.restarts += 1;
+				instance.meters.restarts.inc();
+
+				#support_crate ::tracing::warn!(
+					target: LOG_TARGET,
+					subsystem = instance.name,
+					?reason,
+					restarts = restart.restarts,
+					"Restarting subsystem.",
+				);
+
+				launch_subsystem::<S, TK, #error_ty>(spawner, spawned, subsystem_name, futures);
+
+				Ok(true)
+			}
+
 			/// Send a message to the wrapped subsystem.
 			///
 			/// If the inner `instance` is `None`, nothing is happening.
@@ -386,67 +386,11 @@ pub(crate) fn impl_subsystem_context_trait_for(
 			}
 
 			async fn recv(&mut self) -> ::std::result::Result<FromOrchestra<Self::Message, #signal>, #error_ty> {
-				loop {
-					// If we have a message pending an orchestra signal, we only poll for signals
-					// in the meantime.
-					if let Some((needs_signals_received, msg)) = self.pending_incoming.take() {
-						if needs_signals_received <= self.signals_received.load() {
-							return Ok( #support_crate ::FromOrchestra::Communication { msg });
-						} else {
-							self.pending_incoming = Some((needs_signals_received, msg));
-
-							// wait for next signal.
-							let signal = self.signals.next().await
-								.ok_or(#support_crate ::OrchestraError::Context(
-									"Signal channel is terminated and empty."
-									.to_owned()
-								))?;
-
-							self.signals_received.inc();
-							return Ok( #support_crate ::FromOrchestra::Signal(signal))
-						}
-					}
-
-					let mut await_message = self.messages.next().fuse();
-					let mut await_signal = self.signals.next().fuse();
-					let signals_received = self.signals_received.load();
-					let pending_incoming = &mut self.pending_incoming;
-
-					// Otherwise, wait for the next signal or incoming message.
-					let from_orchestra = #support_crate ::futures::select_biased! {
-						signal = await_signal => {
-							let signal = signal
-								.ok_or( #support_crate ::OrchestraError::Context(
-									"Signal channel is terminated and empty."
-									.to_owned()
-								))?;
-
-							#support_crate ::FromOrchestra::Signal(signal)
-						}
-						msg = await_message => {
-							let packet = msg
-								.ok_or( #support_crate ::OrchestraError::Context(
-									"Message channel is terminated and empty."
-									.to_owned()
-								))?;
-
-							if packet.signals_received > signals_received {
-								// wait until we've received enough signals to return this message.
-								*pending_incoming = Some((packet.signals_received, packet.message));
-								continue;
-							} else {
-								// we know enough to return this message.
-								#support_crate ::FromOrchestra::Communication { msg: packet.message}
-							}
-						}
-					};
-
-					if let #support_crate ::FromOrchestra::Signal(_) = from_orchestra {
-						self.signals_received.inc();
-					}
-
-					return Ok(from_orchestra);
-				}
+				let from_orchestra = self.recv_unrecorded().await?;
+				Ok(match self.recorder.as_mut() {
+					Some(recorder) => recorder.record(from_orchestra),
+					None => from_orchestra,
+				})
 			}
 
 			fn sender(&mut self) -> &mut Self::Sender {
@@ -592,6 +536,7 @@ pub(crate) fn impl_subsystem_context(
 	subsystem_ctx_name: &Ident,
 ) -> TokenStream {
 	let signal_ty = &info.extern_signal_ty;
+	let error_ty = &info.extern_error_ty;
 	let support_crate = info.support_crate_name();
 
 	let ts = quote! {
@@ -605,14 +550,15 @@ pub(crate) fn impl_subsystem_context(
 		#[derive(Debug)]
 		#[allow(missing_docs)]
 		pub struct #subsystem_ctx_name<M: AssociateOutgoing + Send + 'static> {
-			signals: #support_crate ::metered::MeteredReceiver< #signal_ty >,
-			messages: SubsystemIncomingMessages< M >,
+			signals: #support_crate ::Recyclable< #support_crate ::metered::MeteredReceiver< #signal_ty > >,
+			messages: #support_crate ::Recyclable< SubsystemIncomingMessages< M > >,
 			to_subsystems: #subsystem_sender_name < <M as AssociateOutgoing>::OutgoingMessages >,
 			to_orchestra: #support_crate ::metered::UnboundedMeteredSender<
 				#support_crate ::ToOrchestra
 				>,
 			signals_received: SignalsReceived,
 			pending_incoming: Option<(usize, M)>,
+			recorder: Option<Box<dyn #support_crate ::MessageRecorder<M, #signal_ty>>>,
 			name: &'static str
 		}
 
@@ -628,7 +574,26 @@ pub(crate) fn impl_subsystem_context(
 				to_orchestra: #support_crate ::metered::UnboundedMeteredSender<#support_crate:: ToOrchestra>,
 				name: &'static str
 			) -> Self {
-				let signals_received = SignalsReceived::default();
+				Self::with_recyclable_channels(
+					#support_crate ::Recyclable::new(signals),
+					#support_crate ::Recyclable::new(messages),
+					to_subsystems,
+					to_orchestra,
+					name,
+					SignalsReceived::default(),
+				)
+			}
+
+			/// Create a new context, whose channels may be handed back once it is dropped,
+			/// starting out with the given number of received signals.
+			fn with_recyclable_channels(
+				signals: #support_crate ::Recyclable< #support_crate ::metered::MeteredReceiver< #signal_ty > >,
+				messages: #support_crate ::Recyclable< SubsystemIncomingMessages< M > >,
+				to_subsystems: ChannelsOut,
+				to_orchestra: #support_crate ::metered::UnboundedMeteredSender<#support_crate:: ToOrchestra>,
+				name: &'static str,
+				signals_received: SignalsReceived,
+			) -> Self {
 				#subsystem_ctx_name :: <M> {
 					signals,
 					messages,
@@ -640,6 +605,7 @@ pub(crate) fn impl_subsystem_context(
 					to_orchestra,
 					signals_received,
 					pending_incoming: None,
+					recorder: None,
 					name
 				}
 			}
@@ -647,6 +613,87 @@ pub(crate) fn impl_subsystem_context(
 			fn name(&self) -> &'static str {
 				self.name
 			}
+
+			/// Receive the next message or signal, respecting the order they were sent in.
+			async fn recv_unrecorded(&mut self)
+				-> ::std::result::Result<#support_crate ::FromOrchestra<M, #signal_ty>, #support_crate ::OrchestraError>
+			{
+				loop {
+					// If we have a message pending an orchestra signal, we only poll for signals
+					// in the meantime.
+					if let Some((needs_signals_received, msg)) = self.pending_incoming.take() {
+						if needs_signals_received <= self.signals_received.load() {
+							return Ok( #support_crate ::FromOrchestra::Communication { msg });
+						} else {
+							self.pending_incoming = Some((needs_signals_received, msg));
+
+							// wait for next signal.
+							let signal = self.signals.next().await
+								.ok_or(#support_crate ::OrchestraError::Context(
+									"Signal channel is terminated and empty."
+									.to_owned()
+								))?;
+
+							self.signals_received.inc();
+							return Ok( #support_crate ::FromOrchestra::Signal(signal))
+						}
+					}
+
+					let mut await_message = self.messages.next().fuse();
+					let mut await_signal = self.signals.next().fuse();
+					let signals_received = self.signals_received.load();
+					let pending_incoming = &mut self.pending_incoming;
+
+					// Otherwise, wait for the next signal or incoming message.
+					let from_orchestra = #support_crate ::futures::select_biased! {
+						signal = await_signal => {
+							let signal = signal
+								.ok_or( #support_crate ::OrchestraError::Context(
+									"Signal channel is terminated and empty."
+									.to_owned()
+								))?;
+
+							#support_crate ::FromOrchestra::Signal(signal)
+						}
+						msg = await_message => {
+							let packet = msg
+								.ok_or( #support_crate ::OrchestraError::Context(
+									"Message channel is terminated and empty."
+									.to_owned()
+								))?;
+
+							if packet.signals_received > signals_received {
+								// wait until we've received enough signals to return this message.
+								*pending_incoming = Some((packet.signals_received, packet.message));
+								continue;
+							} else {
+								// we know enough to return this message.
+								#support_crate ::FromOrchestra::Communication { msg: packet.message}
+							}
+						}
+					};
+
+					if let #support_crate ::FromOrchestra::Signal(_) = from_orchestra {
+						self.signals_received.inc();
+					}
+
+					return Ok(from_orchestra);
+				}
+			}
+		}
+
+		impl<M, S, R> #support_crate ::Subsystem<#subsystem_ctx_name<M>, #error_ty> for #support_crate ::Recorded<S, R>
+		where
+			M: AssociateOutgoing + Send + 'static,
+			#subsystem_ctx_name<M>: #support_crate ::SubsystemContext,
+			S: #support_crate ::Subsystem<#subsystem_ctx_name<M>, #error_ty>,
+			R: #support_crate ::MessageRecorder<M, #signal_ty>,
+		{
+			fn start(self, mut ctx: #subsystem_ctx_name<M>) -> #support_crate ::SpawnedSubsystem<#error_ty> {
+				let (subsystem, recorder) = self.into_inner();
+				ctx.recorder = Some(Box::new(recorder));
+				subsystem.start(ctx)
+			}
 		}
 	};
 
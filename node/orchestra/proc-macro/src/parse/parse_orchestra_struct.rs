@@ -22,8 +22,8 @@ use syn::{
 	punctuated::Punctuated,
 	spanned::Spanned,
 	token::Bracket,
-	AttrStyle, Error, Field, FieldsNamed, GenericParam, Ident, ItemStruct, Path, PathSegment,
-	Result, Token, Type, Visibility,
+	AttrStyle, Error, Field, FieldsNamed, GenericParam, Ident, ItemStruct, LitInt, LitStr, Path,
+	PathSegment, Result, Token, Type, Visibility,
 };
 
 use quote::{quote, ToTokens};
@@ -33,8 +33,13 @@ mod kw {
 	syn::custom_keyword!(blocking);
 	syn::custom_keyword!(consumes);
 	syn::custom_keyword!(sends);
+	syn::custom_keyword!(restart);
+	syn::custom_keyword!(max_restarts);
 }
 
+/// The number of restarts allowed if `restart = "on-failure"` is given without `max_restarts`.
+pub(crate) const DEFAULT_MAX_RESTARTS: usize = 3;
+
 #[derive(Clone, Debug)]
 pub(crate) enum SubSysAttrItem {
 	/// The subsystem is still a work in progress
@@ -47,6 +52,10 @@ pub(crate) enum SubSysAttrItem {
 	Sends(Sends),
 	/// Message to be consumed by this subsystem.
 	Consumes(Consumes),
+	/// Policy for restarting the subsystem once it fails.
+	Restart(Restart),
+	/// Maximum number of restarts of the subsystem.
+	MaxRestarts(MaxRestarts),
 }
 
 impl Parse for SubSysAttrItem {
@@ -58,6 +67,10 @@ impl Parse for SubSysAttrItem {
 			Self::Blocking(input.parse::<kw::blocking>()?)
 		} else if lookahead.peek(kw::sends) {
 			Self::Sends(input.parse::<Sends>()?)
+		} else if lookahead.peek(kw::restart) {
+			Self::Restart(input.parse::<Restart>()?)
+		} else if lookahead.peek(kw::max_restarts) {
+			Self::MaxRestarts(input.parse::<MaxRestarts>()?)
 		} else {
 			Self::Consumes(input.parse::<Consumes>()?)
 		})
@@ -79,6 +92,12 @@ impl ToTokens for SubSysAttrItem {
 			Self::Consumes(_) => {
 				quote! {}
 			},
+			Self::Restart(_) => {
+				quote! {}
+			},
+			Self::MaxRestarts(_) => {
+				quote! {}
+			},
 		};
 		tokens.extend(ts.into_iter());
 	}
@@ -105,6 +124,8 @@ pub(crate) struct SubSysField {
 	/// Avoids dispatching `Wrapper` type messages, but generates the variants.
 	/// Does not require the subsystem to be instantiated with the builder pattern.
 	pub(crate) wip: bool,
+	/// Policy for restarting the subsystem once it fails.
+	pub(crate) restart: RestartPolicy,
 }
 
 // Converts a type enum to a path if this type is a TypePath
@@ -254,6 +275,60 @@ impl Parse for Consumes {
 	}
 }
 
+/// Policy for restarting a subsystem, see `orchestra::RestartPolicy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RestartPolicy {
+	Never,
+	OnFailure { max_restarts: usize },
+}
+
+/// Parses `restart = "on-failure"`.
+#[derive(Debug, Clone)]
+pub(crate) struct Restart {
+	#[allow(dead_code)]
+	pub(crate) keyword_restart: kw::restart,
+	#[allow(dead_code)]
+	pub(crate) eq: Token![=],
+	/// Whether the subsystem is restarted on failure at all.
+	pub(crate) on_failure: bool,
+}
+
+impl Parse for Restart {
+	fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+		let keyword_restart = input.parse()?;
+		let eq = input.parse()?;
+		let policy = input.parse::<LitStr>()?;
+		let on_failure = match policy.value().as_str() {
+			"never" => false,
+			"on-failure" => true,
+			_ =>
+				return Err(Error::new(
+					policy.span(),
+					"Unknown restart policy, expected `never` or `on-failure`.",
+				)),
+		};
+		Ok(Self { keyword_restart, eq, on_failure })
+	}
+}
+
+/// Parses `max_restarts = 5`.
+#[derive(Debug, Clone)]
+pub(crate) struct MaxRestarts {
+	pub(crate) keyword_max_restarts: kw::max_restarts,
+	#[allow(dead_code)]
+	pub(crate) eq: Token![=],
+	pub(crate) max_restarts: usize,
+}
+
+impl Parse for MaxRestarts {
+	fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+		let keyword_max_restarts = input.parse()?;
+		let eq = input.parse()?;
+		let max_restarts = input.parse::<LitInt>()?.base10_parse::<usize>()?;
+		Ok(Self { keyword_max_restarts, eq, max_restarts })
+	}
+}
+
 /// Parses `(Foo, sends = [Bar, Baz])`
 /// including the `(` and `)`.
 #[derive(Debug, Clone)]
@@ -267,6 +342,8 @@ pub(crate) struct SubSystemAttrItems {
 	/// The message type being consumed by the subsystem.
 	pub(crate) consumes: Option<Consumes>,
 	pub(crate) sends: Option<Sends>,
+	/// Policy for restarting the subsystem once it fails.
+	pub(crate) restart: RestartPolicy,
 }
 
 impl Parse for SubSystemAttrItems {
@@ -307,7 +384,22 @@ impl Parse for SubSystemAttrItems {
 		let blocking = extract_variant!(unique, Blocking; default = false);
 		let wip = extract_variant!(unique, Wip; default = false);
 
-		Ok(Self { blocking, wip, sends, consumes })
+		let restart = extract_variant!(unique, Restart take);
+		let max_restarts = extract_variant!(unique, MaxRestarts take);
+		let restart = match (restart, max_restarts) {
+			(Some(Restart { on_failure: true, .. }), max_restarts) => RestartPolicy::OnFailure {
+				max_restarts: max_restarts
+					.map_or(DEFAULT_MAX_RESTARTS, |max_restarts| max_restarts.max_restarts),
+			},
+			(_, Some(max_restarts)) =>
+				return Err(Error::new(
+					max_restarts.keyword_max_restarts.span(),
+					"`max_restarts` requires `restart = \"on-failure\"`.",
+				)),
+			(_, None) => RestartPolicy::Never,
+		};
+
+		Ok(Self { blocking, wip, sends, consumes, restart })
 	}
 }
 
@@ -520,7 +612,8 @@ impl OrchestraGuts {
 				}
 				unique_subsystem_idents.insert(generic.clone());
 
-				let SubSystemAttrItems { wip, blocking, consumes, sends, .. } = subsystem_attrs;
+				let SubSystemAttrItems { wip, blocking, consumes, sends, restart, .. } =
+					subsystem_attrs;
 
 				// messages to be sent
 				let sends = if let Some(sends) = sends {
@@ -542,6 +635,7 @@ impl OrchestraGuts {
 					messages_to_send: sends,
 					wip,
 					blocking,
+					restart,
 				});
 			} else {
 				let flattened = flatten_type(&ty, ident.span())?;
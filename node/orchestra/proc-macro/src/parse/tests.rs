@@ -246,6 +246,60 @@ mod strukt {
 		});
 	}
 
+	#[test]
+	fn parse_subsystem_attributes_works_13_restart() {
+		assert_matches!(
+		syn::parse2::<SubSystemAttrItems>(quote! {
+			(Foo, restart = "on-failure", max_restarts = 5)
+		}), Ok(SubSystemAttrItems { restart, .. }) => {
+			assert_eq!(restart, RestartPolicy::OnFailure { max_restarts: 5 });
+		});
+		assert_matches!(
+		syn::parse2::<SubSystemAttrItems>(quote! {
+			(Foo, restart = "on-failure")
+		}), Ok(SubSystemAttrItems { restart, .. }) => {
+			assert_eq!(restart, RestartPolicy::OnFailure { max_restarts: DEFAULT_MAX_RESTARTS });
+		});
+		assert_matches!(
+		syn::parse2::<SubSystemAttrItems>(quote! {
+			(Foo, restart = "never")
+		}), Ok(SubSystemAttrItems { restart, .. }) => {
+			assert_eq!(restart, RestartPolicy::Never);
+		});
+		assert_matches!(
+		syn::parse2::<SubSystemAttrItems>(quote! {
+			(Foo)
+		}), Ok(SubSystemAttrItems { restart, .. }) => {
+			assert_eq!(restart, RestartPolicy::Never);
+		});
+	}
+
+	#[test]
+	fn parse_subsystem_attributes_works_14_unknown_restart_policy() {
+		assert_matches!(
+		syn::parse2::<SubSystemAttrItems>(quote! {
+			(Foo, restart = "always")
+		}), Err(e) => {
+			dbg!(e)
+		});
+	}
+
+	#[test]
+	fn parse_subsystem_attributes_works_15_max_restarts_without_restart() {
+		assert_matches!(
+		syn::parse2::<SubSystemAttrItems>(quote! {
+			(Foo, max_restarts = 2)
+		}), Err(e) => {
+			dbg!(e)
+		});
+		assert_matches!(
+		syn::parse2::<SubSystemAttrItems>(quote! {
+			(Foo, restart = "never", max_restarts = 2)
+		}), Err(e) => {
+			dbg!(e)
+		});
+	}
+
 	#[test]
 	fn struct_parse_baggage() {
 		let item: OrchestraGuts = parse_quote! {
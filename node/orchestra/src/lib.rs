@@ -235,6 +235,182 @@ impl SignalsReceived {
 	}
 }
 
+impl SignalsReceived {
+	/// Create a watermark starting at the given number of received signals.
+	///
+	/// Used for restarted subsystems, which take over the signal count of their
+	/// previous instance.
+	pub fn new(signals_received: usize) -> Self {
+		Self(Arc::new(AtomicUsize::new(signals_received)))
+	}
+}
+
+/// Policy for restarting a subsystem, as declared with
+/// `#[subsystem(.., restart = "on-failure", max_restarts = N)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+	/// The subsystem is never restarted, the orchestra has to shut down once it exits.
+	Never,
+	/// The subsystem is restarted if it returns an error or panics, at most
+	/// `max_restarts` times over the lifetime of the orchestra.
+	OnFailure {
+		/// The maximum number of restarts.
+		max_restarts: usize,
+	},
+}
+
+/// Why the task of a subsystem ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubsystemExitReason {
+	/// The subsystem returned without an error.
+	Concluded,
+	/// The subsystem returned an error.
+	Failed,
+	/// The subsystem panicked.
+	Panicked,
+	/// The task of the subsystem was dropped before it completed, e.g. by the spawner.
+	Aborted,
+}
+
+impl SubsystemExitReason {
+	/// Whether the subsystem ended due to a failure, which makes it eligible for a restart.
+	pub fn is_failure(&self) -> bool {
+		matches!(self, Self::Failed | Self::Panicked)
+	}
+}
+
+/// Notification that the task of a subsystem ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubsystemExited {
+	/// The name of the subsystem.
+	pub name: &'static str,
+	/// Why the subsystem ended.
+	pub reason: SubsystemExitReason,
+}
+
+impl SubsystemExited {
+	/// The result of the subsystem, as returned by the orchestra once it shuts down
+	/// because the subsystem was not restarted.
+	pub fn result(&self) -> OrchestraResult<()> {
+		match self.reason {
+			SubsystemExitReason::Concluded => Ok(()),
+			reason =>
+				Err(OrchestraError::Context(format!("run subsystem {}: {:?}", self.name, reason))),
+		}
+	}
+}
+
+/// A value which is handed back through a channel once dropped.
+///
+/// Used to retain the receiving ends of the channels of a restartable subsystem,
+/// such that a restarted instance can take over the messages that were sent to the
+/// previous one.
+pub struct Recyclable<T> {
+	value: Option<T>,
+	recycle: Option<oneshot::Sender<T>>,
+}
+
+impl<T> Recyclable<T> {
+	/// Wrap a value which is not handed back.
+	pub fn new(value: T) -> Self {
+		Self { value: Some(value), recycle: None }
+	}
+
+	/// Wrap a value which is handed back through the returned receiver once dropped.
+	pub fn recycled(value: T) -> (Self, oneshot::Receiver<T>) {
+		let (tx, rx) = oneshot::channel();
+		(Self { value: Some(value), recycle: Some(tx) }, rx)
+	}
+}
+
+impl<T> std::ops::Deref for Recyclable<T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		self.value.as_ref().expect("Value is only taken when dropped. qed")
+	}
+}
+
+impl<T> std::ops::DerefMut for Recyclable<T> {
+	fn deref_mut(&mut self) -> &mut T {
+		self.value.as_mut().expect("Value is only taken when dropped. qed")
+	}
+}
+
+impl<T> Drop for Recyclable<T> {
+	fn drop(&mut self) {
+		if let (Some(value), Some(recycle)) = (self.value.take(), self.recycle.take()) {
+			// The orchestra may not be interested anymore, e.g. because it is shutting down.
+			let _ = recycle.send(value);
+		}
+	}
+}
+
+impl<T: fmt::Debug> fmt::Debug for Recyclable<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Recyclable")
+			.field("value", &self.value)
+			.field("recycle", &self.recycle.is_some())
+			.finish()
+	}
+}
+
+/// Counts the restarts of a subsystem.
+#[derive(Debug, Default, Clone)]
+pub struct SubsystemRestarts(Arc<AtomicUsize>);
+
+impl SubsystemRestarts {
+	/// Load the number of restarts so far.
+	pub fn load(&self) -> usize {
+		self.0.load(atomic::Ordering::Acquire)
+	}
+
+	/// Increase the number of restarts by one.
+	pub fn inc(&self) {
+		self.0.fetch_add(1, atomic::Ordering::AcqRel);
+	}
+}
+
+/// Records the messages and signals delivered to a subsystem.
+///
+/// Installed by wrapping the subsystem into [`Recorded`], the context of the subsystem
+/// then hands every message and signal to the recorder right before the subsystem
+/// receives it.
+pub trait MessageRecorder<Message, Signal>: Send + 'static {
+	/// Record a message or signal about to be received by the subsystem.
+	///
+	/// Returns what the subsystem receives instead, which allows replacing the channels
+	/// contained in requests, in order to record the responses too.
+	fn record(&mut self, message: FromOrchestra<Message, Signal>)
+		-> FromOrchestra<Message, Signal>;
+}
+
+impl<Message, Signal> fmt::Debug for dyn MessageRecorder<Message, Signal> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("MessageRecorder")
+	}
+}
+
+/// A subsystem, whose incoming messages and signals are recorded by `R`.
+///
+/// Can be passed to the orchestra builder in place of the wrapped subsystem.
+pub struct Recorded<S, R> {
+	subsystem: S,
+	recorder: R,
+}
+
+impl<S, R> Recorded<S, R> {
+	/// Wrap a subsystem, recording its incoming messages and signals with `recorder`.
+	pub fn new(subsystem: S, recorder: R) -> Self {
+		Self { subsystem, recorder }
+	}
+
+	/// Split into the wrapped subsystem and the recorder.
+	pub fn into_inner(self) -> (S, R) {
+		(self.subsystem, self.recorder)
+	}
+}
+
 /// A trait to support the origin annotation
 /// such that errors across subsystems can be easier tracked.
 pub trait AnnotateErrorOrigin: 'static + Send + Sync + std::error::Error {
@@ -312,6 +488,8 @@ pub struct SubsystemMeters {
 	pub unbounded: metered::Meter,
 	#[allow(missing_docs)]
 	pub signals: metered::Meter,
+	/// The number of times the subsystem has been restarted.
+	pub restarts: SubsystemRestarts,
 }
 
 impl SubsystemMeters {
@@ -321,6 +499,7 @@ impl SubsystemMeters {
 			bounded: self.bounded.read(),
 			unbounded: self.unbounded.read(),
 			signals: self.signals.read(),
+			restarts: self.restarts.load(),
 		}
 	}
 }
@@ -333,6 +512,8 @@ pub struct SubsystemMeterReadouts {
 	pub unbounded: metered::Readout,
 	#[allow(missing_docs)]
 	pub signals: metered::Readout,
+	/// The number of times the subsystem has been restarted.
+	pub restarts: usize,
 }
 
 /// A running instance of some [`Subsystem`].
@@ -24,7 +24,7 @@ use orchestra::async_trait;
 use std::time::Duration;
 
 use ::test_helpers::{dummy_candidate_descriptor, dummy_hash};
-use polkadot_node_primitives::{BlockData, PoV};
+use polkadot_node_primitives::{BlockData, PoV, PvfExecKind};
 use polkadot_node_subsystem_types::messages::CandidateValidationMessage;
 use polkadot_overseer::{
 	self as overseer,
@@ -77,6 +77,7 @@ impl Subsystem1 {
 				candidate_receipt,
 				PoV { block_data: BlockData(Vec::new()) }.into(),
 				Default::default(),
+				PvfExecKind::Backing,
 				tx,
 			);
 			ctx.send_message(msg).await;
@@ -88,11 +88,14 @@ pub use polkadot_node_subsystem_types::{
 	RuntimeApiSubsystemClient,
 };
 
+mod memory_stats;
 pub mod metrics;
 pub use self::metrics::Metrics as OverseerMetrics;
 
 /// A dummy subsystem, mostly useful for placeholders and tests.
 pub mod dummy;
+
+pub mod recorder;
 pub use self::dummy::DummySubsystem;
 
 pub use polkadot_node_metrics::{
@@ -100,14 +103,15 @@ pub use polkadot_node_metrics::{
 	Metronome,
 };
 
-use parity_util_mem::MemoryAllocationTracker;
+use memory_stats::MemoryAllocationTracker;
 
 pub use orchestra as gen;
 pub use orchestra::{
 	contextbounds, orchestra, subsystem, FromOrchestra, MapSubsystem, MessagePacket,
-	OrchestraError as OverseerError, SignalsReceived, Spawner, Subsystem, SubsystemContext,
+	MessageRecorder, OrchestraError as OverseerError, Recorded, Recyclable, RestartPolicy,
+	SignalsReceived, Spawner, Subsystem, SubsystemContext, SubsystemExitReason, SubsystemExited,
 	SubsystemIncomingMessages, SubsystemInstance, SubsystemMeterReadouts, SubsystemMeters,
-	SubsystemSender, TimeoutExt, ToOrchestra,
+	SubsystemRestarts, SubsystemSender, TimeoutExt, ToOrchestra,
 };
 
 /// Store 2 days worth of blocks, not accounting for forks,
@@ -456,7 +460,7 @@ pub struct Overseer<SupportsParachains> {
 	#[subsystem(PvfCheckerMessage, sends: [
 		CandidateValidationMessage,
 		RuntimeApiMessage,
-	])]
+	], restart = "on-failure")]
 	pvf_checker: PvfChecker,
 
 	#[subsystem(CandidateBackingMessage, sends: [
@@ -544,6 +548,7 @@ pub struct Overseer<SupportsParachains> {
 	#[subsystem(CollationGenerationMessage, sends: [
 		RuntimeApiMessage,
 		CollatorProtocolMessage,
+		ChainApiMessage,
 	])]
 	collation_generation: CollationGeneration,
 
@@ -753,14 +758,18 @@ where
 						}
 					}
 				},
-				res = self.running_subsystems.select_next_some() => {
+				exited = self.running_subsystems.select_next_some() => {
+					let replay = self.active_leaves_replay();
+					if self.restart_subsystem(exited, replay).await? {
+						continue
+					}
 					gum::error!(
 						target: LOG_TARGET,
-						subsystem = ?res,
+						subsystem = ?exited,
 						"subsystem finished unexpectedly",
 					);
 					self.stop().await;
-					return res;
+					return exited.result().map_err(Into::into);
 				},
 			}
 		}
@@ -867,6 +876,23 @@ where
 		Some((span, status))
 	}
 
+	/// The signals informing a restarted subsystem about the currently active leaves.
+	fn active_leaves_replay(&self) -> Vec<OverseerSignal> {
+		self.active_leaves
+			.iter()
+			.filter_map(|(hash, number)| {
+				// Only leaves supporting parachains have a span and were announced.
+				let span = self.span_per_active_leaf.get(hash)?.clone();
+				Some(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+					hash: *hash,
+					number: *number,
+					status: LeafStatus::Stale,
+					span,
+				})))
+			})
+			.collect()
+	}
+
 	fn on_head_deactivated(&mut self, hash: &Hash) {
 		self.metrics.on_head_deactivated();
 		self.activation_external_listeners.remove(hash);
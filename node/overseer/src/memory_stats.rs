@@ -0,0 +1,56 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Memory statistics of the jemalloc allocator the node is built with.
+
+use tikv_jemalloc_ctl::{epoch, stats, Error};
+
+/// Reads the memory statistics of jemalloc.
+#[derive(Clone)]
+pub struct MemoryAllocationTracker {
+	epoch: tikv_jemalloc_ctl::epoch_mib,
+	allocated: stats::allocated_mib,
+	resident: stats::resident_mib,
+}
+
+impl MemoryAllocationTracker {
+	/// Creates a new tracker.
+	pub fn new() -> Result<Self, Error> {
+		Ok(Self {
+			epoch: epoch::mib()?,
+			allocated: stats::allocated::mib()?,
+			resident: stats::resident::mib()?,
+		})
+	}
+
+	/// Takes a snapshot of the current memory statistics.
+	pub fn snapshot(&self) -> Result<MemoryAllocationSnapshot, Error> {
+		// The statistics are cached by jemalloc and only refreshed when the epoch advances.
+		self.epoch.advance()?;
+		let allocated = self.allocated.read()? as u64;
+		let resident = self.resident.read()? as u64;
+		Ok(MemoryAllocationSnapshot { allocated, resident })
+	}
+}
+
+/// A snapshot of the memory statistics.
+#[derive(Debug, Clone)]
+pub struct MemoryAllocationSnapshot {
+	/// Bytes allocated by the node.
+	pub allocated: u64,
+	/// Bytes allocated by the node and held in RAM.
+	pub resident: u64,
+}
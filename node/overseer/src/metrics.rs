@@ -19,7 +19,7 @@
 use super::*;
 pub use polkadot_node_metrics::metrics::{self, prometheus, Metrics as MetricsTrait};
 
-use parity_util_mem::MemoryAllocationSnapshot;
+use crate::memory_stats::MemoryAllocationSnapshot;
 
 /// Overseer Prometheus metrics.
 #[derive(Clone)]
@@ -40,6 +40,8 @@ struct MetricsInner {
 	signals_sent: prometheus::GaugeVec<prometheus::U64>,
 	signals_received: prometheus::GaugeVec<prometheus::U64>,
 
+	subsystem_restarts: prometheus::GaugeVec<prometheus::U64>,
+
 	memory_stats_resident: prometheus::Gauge<prometheus::U64>,
 	memory_stats_allocated: prometheus::Gauge<prometheus::U64>,
 }
@@ -117,6 +119,11 @@ impl Metrics {
 						.with_label_values(&[name])
 						.set(readouts.signals.received as u64);
 
+					metrics
+						.subsystem_restarts
+						.with_label_values(&[name])
+						.set(readouts.restarts as u64);
+
 					let hist_bounded = metrics.to_subsystem_bounded_tof.with_label_values(&[name]);
 					for tof in readouts.bounded.tof {
 						hist_bounded.observe(tof.as_f64());
@@ -246,6 +253,16 @@ impl MetricsTrait for Metrics {
 				)?,
 				registry,
 			)?,
+			subsystem_restarts: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_overseer_subsystem_restarts",
+						"Number of times subsystems were restarted after failing",
+					),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
 
 			memory_stats_allocated: prometheus::register(
 				prometheus::Gauge::<prometheus::U64>::new(
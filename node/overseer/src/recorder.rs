@@ -0,0 +1,309 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recording of the messages and signals delivered to a subsystem.
+//!
+//! A subsystem wrapped into [`Recorded`](crate::Recorded) together with a [`StreamRecorder`]
+//! writes everything it receives to a file, which can be replayed into a fresh instance of the
+//! subsystem later on, e.g. to reproduce a bug.
+//!
+//! A recording starts with a [`RecordingHeader`], followed by one entry per delivered message or
+//! signal and one per response of the subsystem to a delivered request, all of them
+//! SCALE-encoded. Only subsystems whose messages implement [`RecordableMessage`] can be recorded.
+
+use std::{
+	fmt,
+	fs::File,
+	io::{self, BufWriter, Write},
+	path::Path,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use futures::{
+	channel::{mpsc, oneshot},
+	future::BoxFuture,
+	prelude::*,
+	select,
+	stream::FuturesUnordered,
+};
+use parity_scale_codec::{Decode, Encode, Error as CodecError};
+use polkadot_node_subsystem_types::recordable::{EncodedResponse, RecordableMessage};
+
+use crate::{FromOrchestra, MessageRecorder, OverseerSignal, LOG_TARGET};
+
+/// The version of the recording format.
+pub const RECORDING_VERSION: u32 = 1;
+
+const SIGNAL: u8 = 0;
+const COMMUNICATION: u8 = 1;
+const RESPONSE: u8 = 2;
+
+/// The header of a recording.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct RecordingHeader {
+	/// The version of the recording format.
+	pub version: u32,
+	/// The name of the recorded subsystem.
+	pub subsystem: String,
+}
+
+/// A message or signal delivered to a subsystem.
+pub struct RecordedMessage<M> {
+	/// When the message was delivered, in microseconds since the UNIX epoch.
+	pub timestamp: u64,
+	/// The message or signal.
+	pub message: FromOrchestra<M, OverseerSignal>,
+	/// The SCALE-encoded response of the subsystem to the request carried by the message, if it
+	/// answered one.
+	pub response: Option<Vec<u8>>,
+	/// Resolves to the SCALE-encoded response of the subsystem the message gets replayed into,
+	/// if the message carries a request.
+	pub replayed_response: Option<EncodedResponse>,
+}
+
+impl<M: fmt::Debug> fmt::Debug for RecordedMessage<M> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("RecordedMessage")
+			.field("timestamp", &self.timestamp)
+			.field("message", &self.message)
+			.field("response", &self.response)
+			.finish()
+	}
+}
+
+/// A recording of the messages and signals delivered to a subsystem.
+#[derive(Debug)]
+pub struct Recording<M> {
+	/// The header of the recording.
+	pub header: RecordingHeader,
+	/// The recorded messages and signals, in the order they were delivered in.
+	pub messages: Vec<RecordedMessage<M>>,
+}
+
+impl<M: RecordableMessage> Recording<M> {
+	/// Decode a recording.
+	pub fn decode(mut input: &[u8]) -> Result<Self, CodecError> {
+		let header = RecordingHeader::decode(&mut input)?;
+		if header.version != RECORDING_VERSION {
+			return Err("Unsupported version of the recording format".into())
+		}
+
+		let mut messages: Vec<RecordedMessage<M>> = Vec::new();
+		while !input.is_empty() {
+			let timestamp = u64::decode(&mut input)?;
+			let (message, replayed_response) = match u8::decode(&mut input)? {
+				SIGNAL => (FromOrchestra::Signal(OverseerSignal::decode(&mut input)?), None),
+				COMMUNICATION => {
+					let (msg, replayed_response) = M::decode_replayed(&mut input)?;
+					(FromOrchestra::Communication { msg }, replayed_response)
+				},
+				RESPONSE => {
+					let index = u64::decode(&mut input)?;
+					let response = Vec::<u8>::decode(&mut input)?;
+					let recorded = usize::try_from(index)
+						.ok()
+						.and_then(|index| messages.get_mut(index))
+						.ok_or_else(|| CodecError::from("Response to an unknown message"))?;
+					recorded.response = Some(response);
+					continue
+				},
+				_ => return Err("Invalid kind of recorded entry".into()),
+			};
+			messages.push(RecordedMessage {
+				timestamp,
+				message,
+				response: None,
+				replayed_response,
+			});
+		}
+
+		Ok(Recording { header, messages })
+	}
+
+	/// Read a recording from a file.
+	pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+		let bytes = std::fs::read(path)?;
+		Self::decode(&bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+	}
+}
+
+fn timestamp() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |elapsed| elapsed.as_micros() as u64)
+}
+
+/// An encoded entry of a recording, along with the entry of the response if it is a request.
+struct Entry {
+	encoded: Vec<u8>,
+	response: Option<BoxFuture<'static, Option<Vec<u8>>>>,
+}
+
+/// Records the messages and signals delivered to a subsystem into a writer.
+///
+/// Writing happens on a dedicated thread, which also forwards the recorded responses to the
+/// requesters. Every entry is flushed right away, such that the recording is complete even if
+/// the node crashes. If writing fails, recording stops.
+pub struct StreamRecorder<W> {
+	entries: mpsc::UnboundedSender<Entry>,
+	written: Option<oneshot::Receiver<Option<W>>>,
+	next_index: u64,
+}
+
+impl<W: Write + Send + 'static> StreamRecorder<W> {
+	/// Start a recording of the given subsystem, by writing the header to `writer`.
+	pub fn new(mut writer: W, subsystem: &str) -> io::Result<Self> {
+		let header = RecordingHeader { version: RECORDING_VERSION, subsystem: subsystem.into() };
+		writer.write_all(&header.encode())?;
+		writer.flush()?;
+
+		let (entries, entries_rx) = mpsc::unbounded();
+		let (written_tx, written) = oneshot::channel();
+		let subsystem = subsystem.to_owned();
+		std::thread::Builder::new()
+			.name(format!("{}-recorder", subsystem))
+			.spawn(move || {
+				let writer =
+					futures::executor::block_on(write_entries(writer, &subsystem, entries_rx));
+				let _ = written_tx.send(writer);
+			})?;
+
+		Ok(StreamRecorder { entries, written: Some(written), next_index: 0 })
+	}
+
+	/// A future resolving to the writer once the recorder was dropped and everything was
+	/// written, or to `None` if writing failed before.
+	///
+	/// Only the first future returned resolves to the writer.
+	pub fn written(&mut self) -> impl Future<Output = Option<W>> {
+		let written = self.written.take();
+		async move { written?.await.ok().flatten() }
+	}
+
+	/// Stop recording and return the writer, unless writing to it failed before.
+	///
+	/// Waits for the responses to the recorded requests which are still pending.
+	pub async fn finish(mut self) -> Option<W> {
+		let written = self.written();
+		drop(self);
+		written.await
+	}
+}
+
+impl StreamRecorder<BufWriter<File>> {
+	/// Start a recording of the given subsystem into a newly created file at `path`.
+	pub fn create(path: impl AsRef<Path>, subsystem: &str) -> io::Result<Self> {
+		Self::new(BufWriter::new(File::create(path)?), subsystem)
+	}
+}
+
+/// Write the entries to `writer`, until all senders are dropped and all responses are in.
+async fn write_entries<W: Write>(
+	writer: W,
+	subsystem: &str,
+	mut entries: mpsc::UnboundedReceiver<Entry>,
+) -> Option<W> {
+	let mut writer = Some(writer);
+	let mut responses = FuturesUnordered::new();
+	loop {
+		let encoded = select! {
+			entry = entries.next() => match entry {
+				Some(Entry { encoded, response }) => {
+					responses.extend(response);
+					encoded
+				},
+				None => break,
+			},
+			response = responses.select_next_some() => match response {
+				Some(encoded) => encoded,
+				None => continue,
+			},
+		};
+		write_entry(&mut writer, subsystem, &encoded);
+	}
+
+	// The requesters still wait for the responses to be forwarded.
+	while let Some(response) = responses.next().await {
+		if let Some(encoded) = response {
+			write_entry(&mut writer, subsystem, &encoded);
+		}
+	}
+
+	writer
+}
+
+fn write_entry<W: Write>(writer: &mut Option<W>, subsystem: &str, encoded: &[u8]) {
+	let result = match writer.as_mut() {
+		Some(writer) => writer.write_all(encoded).and_then(|()| writer.flush()),
+		None => return,
+	};
+
+	if let Err(err) = result {
+		gum::warn!(
+			target: LOG_TARGET,
+			subsystem = %subsystem,
+			?err,
+			"Failed to record message, stopping the recording",
+		);
+		*writer = None;
+	}
+}
+
+impl<M, W> MessageRecorder<M, OverseerSignal> for StreamRecorder<W>
+where
+	M: RecordableMessage + Send + 'static,
+	W: Write + Send + 'static,
+{
+	fn record(
+		&mut self,
+		message: FromOrchestra<M, OverseerSignal>,
+	) -> FromOrchestra<M, OverseerSignal> {
+		let index = self.next_index;
+		self.next_index += 1;
+
+		let mut encoded = Vec::new();
+		timestamp().encode_to(&mut encoded);
+		let (message, response) = match message {
+			FromOrchestra::Signal(signal) => {
+				SIGNAL.encode_to(&mut encoded);
+				signal.encode_to(&mut encoded);
+				(FromOrchestra::Signal(signal), None)
+			},
+			FromOrchestra::Communication { msg } => {
+				COMMUNICATION.encode_to(&mut encoded);
+				let (msg, response) = msg.encode_recorded(&mut encoded);
+				(FromOrchestra::Communication { msg }, response)
+			},
+		};
+
+		let response = response.map(|response| {
+			async move {
+				let response = response.await?;
+				let mut encoded = Vec::new();
+				timestamp().encode_to(&mut encoded);
+				RESPONSE.encode_to(&mut encoded);
+				index.encode_to(&mut encoded);
+				response.encode_to(&mut encoded);
+				Some(encoded)
+			}
+			.boxed()
+		});
+
+		// The writing thread only stops once the recorder is dropped.
+		let _ = self.entries.unbounded_send(Entry { encoded, response });
+		message
+	}
+}
@@ -15,14 +15,14 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use async_trait::async_trait;
-use futures::{executor, pending, pin_mut, poll, select, stream, FutureExt};
+use futures::{channel::mpsc, executor, pending, pin_mut, poll, select, stream, FutureExt};
 use std::{collections::HashMap, sync::atomic, task::Poll};
 
 use ::test_helpers::{dummy_candidate_descriptor, dummy_candidate_receipt, dummy_hash};
 use polkadot_node_network_protocol::{PeerId, UnifiedReputationChange};
 use polkadot_node_primitives::{
 	BlockData, CollationGenerationConfig, CollationResult, DisputeMessage, InvalidDisputeVote, PoV,
-	UncheckedDisputeMessage, ValidDisputeVote,
+	PvfExecKind, UncheckedDisputeMessage, ValidDisputeVote,
 };
 use polkadot_node_subsystem_types::{
 	jaeger,
@@ -36,7 +36,7 @@ use polkadot_primitives::v2::{
 
 use crate::{
 	self as overseer,
-	dummy::{dummy_overseer_builder, one_for_all_overseer_builder},
+	dummy::{dummy_overseer_builder, one_for_all_overseer_builder, DummySubsystem},
 	gen::Delay,
 	HeadSupportsParachains,
 };
@@ -115,6 +115,7 @@ where
 							candidate_receipt,
 							PoV { block_data: BlockData(Vec::new()) }.into(),
 							Default::default(),
+							PvfExecKind::Backing,
 							tx,
 						))
 						.await;
@@ -306,6 +307,164 @@ fn overseer_ends_on_subsystem_exit() {
 	})
 }
 
+/// A PVF checker which fails as soon as it was informed about the active leaves.
+struct FailOnActiveLeaves {
+	instance: usize,
+	leaves: mpsc::UnboundedSender<(usize, ActiveLeavesUpdate)>,
+}
+
+impl<C> overseer::Subsystem<C, SubsystemError> for FailOnActiveLeaves
+where
+	C: overseer::SubsystemContext<Message = PvfCheckerMessage, Signal = OverseerSignal>,
+{
+	fn start(self, mut ctx: C) -> SpawnedSubsystem {
+		SpawnedSubsystem {
+			name: "test-subsystem-5",
+			future: Box::pin(async move {
+				loop {
+					match ctx.recv().await? {
+						FromOrchestra::Signal(OverseerSignal::ActiveLeaves(update)) => {
+							let _ = self.leaves.unbounded_send((self.instance, update));
+							return Err(SubsystemError::Context("failing on purpose".into()))
+						},
+						FromOrchestra::Signal(OverseerSignal::Conclude) => return Ok(()),
+						_ => {},
+					}
+				}
+			}),
+		}
+	}
+}
+
+// A failing restartable subsystem is instantiated again from its factory and informed about the
+// active leaves, until it failed too often.
+#[test]
+fn overseer_restarts_failed_subsystem() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let leaf = Hash::repeat_byte(1);
+
+	executor::block_on(async move {
+		let (leaves_tx, mut leaves_rx) = mpsc::unbounded();
+		let mut instances = 0;
+		let (overseer, _handle) = Overseer::builder()
+			.availability_distribution(DummySubsystem)
+			.availability_recovery(DummySubsystem)
+			.availability_store(DummySubsystem)
+			.bitfield_distribution(DummySubsystem)
+			.bitfield_signing(DummySubsystem)
+			.candidate_backing(DummySubsystem)
+			.candidate_validation(DummySubsystem)
+			.pvf_checker_factory(move |_| {
+				let instance = instances;
+				instances += 1;
+				Ok(FailOnActiveLeaves { instance, leaves: leaves_tx.clone() })
+			})
+			.chain_api(DummySubsystem)
+			.collation_generation(DummySubsystem)
+			.collator_protocol(DummySubsystem)
+			.network_bridge_tx(DummySubsystem)
+			.network_bridge_rx(DummySubsystem)
+			.provisioner(DummySubsystem)
+			.runtime_api(DummySubsystem)
+			.statement_distribution(DummySubsystem)
+			.approval_distribution(DummySubsystem)
+			.approval_voting(DummySubsystem)
+			.gossip_support(DummySubsystem)
+			.dispute_coordinator(DummySubsystem)
+			.dispute_distribution(DummySubsystem)
+			.chain_selection(DummySubsystem)
+			.activation_external_listeners(Default::default())
+			.span_per_active_leaf(Default::default())
+			.active_leaves(Default::default())
+			.known_leaves(LruCache::new(KNOWN_LEAVES_CACHE_SIZE))
+			.leaves(vec![(leaf, 1)])
+			.spawner(SpawnGlue(spawner))
+			.metrics(<OverseerMetrics as MetricsTrait>::register(None).unwrap())
+			.supports_parachains(MockSupportsParachains)
+			.build()
+			.unwrap();
+
+		// The PVF checker is restarted up to 3 times, after that the overseer gives up.
+		assert_matches!(overseer.run().await, Err(_));
+
+		for expected_instance in 0..=3 {
+			let (instance, update) = leaves_rx.next().await.unwrap();
+			assert_eq!(instance, expected_instance);
+			assert_eq!(update.activated.as_ref().map(|leaf| leaf.hash), Some(leaf));
+			assert_eq!(update.activated.as_ref().map(|leaf| leaf.number), Some(1));
+			// Restarted instances are informed about the leaf once it is no longer fresh.
+			let expected_status = if instance == 0 { LeafStatus::Fresh } else { LeafStatus::Stale };
+			assert_eq!(update.activated.map(|leaf| leaf.status), Some(expected_status));
+		}
+		assert!(leaves_rx.next().await.is_none());
+	});
+}
+
+#[test]
+fn overseer_records_incoming_signals() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	executor::block_on(async move {
+		let first_block_hash = [1; 32].into();
+		let second_block_hash = [2; 32].into();
+		let second_block =
+			BlockInfo { hash: second_block_hash, parent_hash: first_block_hash, number: 2 };
+
+		let mut recorder = recorder::StreamRecorder::new(Vec::new(), "bitfield-signing").unwrap();
+		let written = recorder.written();
+
+		let (overseer, handle) = dummy_overseer_builder(spawner, MockSupportsParachains, None)
+			.unwrap()
+			.replace_bitfield_signing(move |s| Recorded::new(s, recorder))
+			.leaves(vec![(first_block_hash, 1)])
+			.build()
+			.unwrap();
+		let mut handle = Handle::new(handle);
+
+		let overseer_fut = overseer.run().fuse();
+		pin_mut!(overseer_fut);
+
+		handle.block_imported(second_block).await;
+		handle.stop().await;
+		overseer_fut.await.unwrap();
+
+		// Resolves once the subsystem concluded and dropped the recorder.
+		let buffer = written.await.unwrap();
+		let recording = recorder::Recording::<BitfieldSigningMessage>::decode(&buffer).unwrap();
+		assert_eq!(recording.header.subsystem, "bitfield-signing");
+
+		let signals: Vec<_> = recording
+			.messages
+			.into_iter()
+			.map(|recorded| match recorded.message {
+				FromOrchestra::Signal(signal) => signal,
+				FromOrchestra::Communication { msg } => match msg {},
+			})
+			.collect();
+		assert_eq!(
+			signals,
+			vec![
+				OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+					hash: first_block_hash,
+					number: 1,
+					span: Arc::new(jaeger::Span::Disabled),
+					status: LeafStatus::Fresh,
+				})),
+				OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+					activated: Some(ActivatedLeaf {
+						hash: second_block_hash,
+						number: 2,
+						span: Arc::new(jaeger::Span::Disabled),
+						status: LeafStatus::Fresh,
+					}),
+					deactivated: [first_block_hash].as_ref().into(),
+				}),
+				OverseerSignal::Conclude,
+			],
+		);
+	});
+}
+
 struct TestSubsystem5(metered::MeteredSender<OverseerSignal>);
 
 impl<C> overseer::Subsystem<C, SubsystemError> for TestSubsystem5
@@ -792,6 +951,7 @@ fn test_candidate_validation_msg() -> CandidateValidationMessage {
 		candidate_receipt,
 		pov,
 		Duration::default(),
+		PvfExecKind::Backing,
 		sender,
 	)
 }
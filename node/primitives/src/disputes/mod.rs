@@ -31,6 +31,10 @@ use polkadot_primitives::v2::{
 mod message;
 pub use message::{DisputeMessage, Error as DisputeMessageCheckError, UncheckedDisputeMessage};
 
+/// The status of a dispute as tracked by the dispute coordinator.
+mod status;
+pub use status::{DisputeStatus, Timestamp};
+
 /// A checked dispute statement from an associated validator.
 #[derive(Debug, Clone)]
 pub struct SignedDisputeStatement {
@@ -42,7 +46,7 @@ pub struct SignedDisputeStatement {
 }
 
 /// Tracked votes on candidates, for the purposes of dispute resolution.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Encode, Decode)]
 pub struct CandidateVotes {
 	/// The receipt of the candidate itself.
 	pub candidate_receipt: CandidateReceipt,
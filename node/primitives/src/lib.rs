@@ -46,8 +46,8 @@ pub mod approval;
 /// Disputes related types.
 pub mod disputes;
 pub use disputes::{
-	CandidateVotes, DisputeMessage, DisputeMessageCheckError, InvalidDisputeVote,
-	SignedDisputeStatement, UncheckedDisputeMessage, ValidDisputeVote,
+	CandidateVotes, DisputeMessage, DisputeMessageCheckError, DisputeStatus, InvalidDisputeVote,
+	SignedDisputeStatement, Timestamp, UncheckedDisputeMessage, ValidDisputeVote,
 };
 
 // For a 16-ary Merkle Prefix Trie, we can expect at most 16 32-byte hashes per node
@@ -74,6 +74,19 @@ pub const BACKING_EXECUTION_TIMEOUT: Duration = Duration::from_secs(2);
 /// dispute participants.
 pub const APPROVAL_EXECUTION_TIMEOUT: Duration = Duration::from_secs(6);
 
+/// The kind of work a candidate is validated for.
+///
+/// It determines the priority with which the execution of the PVF is scheduled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PvfExecKind {
+	/// Backing, which is on the critical path of block production.
+	Backing,
+	/// Approval checking.
+	Approval,
+	/// Participation in a dispute.
+	Dispute,
+}
+
 /// Linked to `MAX_FINALITY_LAG` in relay chain selection,
 /// `MAX_HEADS_LOOK_BACK` in `approval-voting` and
 /// `MAX_BATCH_SCRAPE_ANCESTORS` in `dispute-coordinator`
@@ -322,6 +335,54 @@ pub struct CollationSecondedSignal {
 	pub statement: SignedFullStatement,
 }
 
+/// A step in the life of a candidate built by this collator, as recorded by the relay chain.
+///
+/// Every event names the relay chain block it was recorded in. As the relay chain may fork, the
+/// same event can be reported for several blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CandidateLifecycleEvent {
+	/// The candidate got backed and now occupies a core.
+	Backed {
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+		/// The relay chain block the candidate got backed in.
+		relay_block: Hash,
+	},
+	/// The candidate became available and got included.
+	Included {
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+		/// The relay chain block the candidate got included in.
+		relay_block: Hash,
+	},
+	/// The candidate did not become available in time and got dropped from its core.
+	TimedOut {
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+		/// The relay chain block the candidate timed out in.
+		relay_block: Hash,
+	},
+	/// Votes of a dispute about the backed candidate were recorded on chain for the first time.
+	Disputed {
+		/// The hash of the candidate.
+		candidate_hash: CandidateHash,
+		/// The relay chain block the dispute votes were recorded in.
+		relay_block: Hash,
+	},
+}
+
+impl CandidateLifecycleEvent {
+	/// The hash of the candidate the event is about.
+	pub fn candidate_hash(&self) -> CandidateHash {
+		match self {
+			Self::Backed { candidate_hash, .. } |
+			Self::Included { candidate_hash, .. } |
+			Self::TimedOut { candidate_hash, .. } |
+			Self::Disputed { candidate_hash, .. } => *candidate_hash,
+		}
+	}
+}
+
 /// Result of the [`CollatorFn`] invocation.
 #[cfg(not(target_os = "unknown"))]
 pub struct CollationResult {
@@ -27,7 +27,9 @@ mod relay_chain_selection;
 pub mod overseer;
 
 #[cfg(feature = "full-node")]
-pub use self::overseer::{OverseerGen, OverseerGenArgs, RealOverseerGen};
+pub use self::overseer::{
+	OverseerGen, OverseerGenArgs, RealOverseerGen, RecordedSubsystem, RecordingOverseerGen,
+};
 
 #[cfg(test)]
 mod tests;
@@ -47,7 +49,9 @@ use {
 	},
 	polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig,
 	polkadot_node_network_protocol::request_response::ReqProtocolNames,
+	polkadot_node_primitives::AvailableData,
 	polkadot_overseer::BlockInfo,
+	polkadot_primitives::v2::CandidateHash,
 	sc_client_api::{BlockBackend, ExecutorProvider},
 	sp_core::traits::SpawnNamed,
 	sp_trie::PrefixedMemoryDB,
@@ -57,6 +61,11 @@ use polkadot_node_subsystem_util::database::Database;
 
 #[cfg(feature = "full-node")]
 pub use {
+	parachains_db::inspect::{Dump as ParachainsDbDump, Filter as ParachainsDbFilter},
+	polkadot_collator_protocol::{CollatorFilter, CollatorPolicy},
+	polkadot_network_bridge::{NotificationKind, RateLimit, RateLimits},
+	polkadot_node_core_av_store::PruningConfig as AvailabilityPruningConfig,
+	polkadot_node_core_runtime_api::CacheConfig as RuntimeApiCacheConfig,
 	polkadot_overseer::{Handle, Overseer, OverseerConnector, OverseerHandle},
 	polkadot_primitives::runtime_api::ParachainHost,
 	relay_chain_selection::SelectRelayChain,
@@ -236,6 +245,10 @@ pub enum Error {
 	#[error("Creating a custom database is required for validators")]
 	DatabasePathRequired,
 
+	#[cfg(feature = "full-node")]
+	#[error("The parachains database does not support a custom database source")]
+	CustomDatabaseUnsupported,
+
 	#[cfg(feature = "full-node")]
 	#[error("Expected at least one of polkadot, kusama, westend or rococo runtime feature")]
 	NoRuntime,
@@ -319,6 +332,93 @@ fn open_database(db_source: &DatabaseSource) -> Result<Arc<dyn Database>, Error>
 	Ok(parachains_db)
 }
 
+/// Open the existing parachains database read-only, without creating or upgrading it.
+#[cfg(feature = "full-node")]
+fn open_existing_database(db_source: &DatabaseSource) -> Result<Arc<dyn Database>, Error> {
+	let parachains_db = match db_source {
+		DatabaseSource::RocksDb { path, .. } => parachains_db::open_existing_rocksdb(path.clone())?,
+		DatabaseSource::ParityDb { path, .. } => parachains_db::open_existing_paritydb(
+			path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+		)?,
+		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
+			if paritydb_path.is_dir() && paritydb_path.exists() {
+				parachains_db::open_existing_paritydb(
+					paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+				)?
+			} else {
+				parachains_db::open_existing_rocksdb(rocksdb_path.clone())?
+			},
+		DatabaseSource::Custom { .. } => return Err(Error::CustomDatabaseUnsupported),
+	};
+	Ok(parachains_db)
+}
+
+/// Decode the entries of the parachains database matching `filter`, e.g. to dump them as JSON.
+///
+/// Must not be used while a node is running on the same database.
+#[cfg(feature = "full-node")]
+pub fn dump_parachains_db(
+	db_source: &DatabaseSource,
+	filter: &ParachainsDbFilter,
+) -> Result<ParachainsDbDump, Error> {
+	let parachains_db = open_existing_database(db_source)?;
+
+	parachains_db::inspect::dump(parachains_db, filter)
+}
+
+/// Downgrade the parachains database to the given version, e.g. to run an older release on it.
+///
+/// Refuses to change anything if the database can not be downgraded to that version. Must not be
+/// used while a node is running on the same database.
+#[cfg(feature = "full-node")]
+pub fn downgrade_parachains_db(
+	db_source: &DatabaseSource,
+	target_version: u32,
+) -> Result<(), Error> {
+	match db_source {
+		DatabaseSource::RocksDb { path, .. } =>
+			parachains_db::downgrade_rocksdb(path.clone(), target_version)?,
+		DatabaseSource::ParityDb { path, .. } => parachains_db::downgrade_paritydb(
+			path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+			target_version,
+		)?,
+		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
+			if paritydb_path.is_dir() && paritydb_path.exists() {
+				parachains_db::downgrade_paritydb(
+					paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+					target_version,
+				)?
+			} else {
+				parachains_db::downgrade_rocksdb(rocksdb_path.clone(), target_version)?
+			},
+		DatabaseSource::Custom { .. } => return Err(Error::CustomDatabaseUnsupported),
+	}
+	Ok(())
+}
+
+/// Read the `AvailableData` of a candidate from the availability store of the parachains database.
+///
+/// Must not be used while a node is running on the same database.
+#[cfg(feature = "full-node")]
+pub fn read_available_data(
+	db_source: &DatabaseSource,
+	candidate_hash: &CandidateHash,
+) -> Result<Option<AvailableData>, Error> {
+	let parachains_db = open_existing_database(db_source)?;
+	let availability_config = AvailabilityConfig {
+		col_data: parachains_db::REAL_COLUMNS.col_availability_data,
+		col_meta: parachains_db::REAL_COLUMNS.col_availability_meta,
+		pruning: AvailabilityPruningConfig::default(),
+	};
+
+	polkadot_node_core_av_store::read_available_data(
+		&parachains_db,
+		&availability_config,
+		candidate_hash,
+	)
+	.map_err(Into::into)
+}
+
 /// Initialize the `Jeager` collector. The destination must listen
 /// on the given address and port for `UDP` packets.
 #[cfg(any(test, feature = "full-node"))]
@@ -446,6 +546,7 @@ fn new_partial<RuntimeApi, ExecutorDispatch, ChainSelection>(
 		ExecutorDispatch,
 	>,
 	select_chain: ChainSelection,
+	overseer_handle: Option<Handle>,
 ) -> Result<
 	service::PartialComponents<
 		FullClient<RuntimeApi, ExecutorDispatch>,
@@ -595,6 +696,7 @@ where
 					beefy_best_block_stream: beefy_rpc_links.from_voter_best_beefy_stream.clone(),
 					subscription_executor,
 				},
+				overseer_handle: overseer_handle.clone(),
 			};
 
 			polkadot_rpc::create_full(deps, backend.clone()).map_err(Into::into)
@@ -737,6 +839,10 @@ pub fn new_full<RuntimeApi, ExecutorDispatch, OverseerGenerator>(
 	overseer_enable_anyways: bool,
 	overseer_gen: OverseerGenerator,
 	overseer_message_channel_capacity_override: Option<usize>,
+	availability_pruning_config: AvailabilityPruningConfig,
+	runtime_api_cache_config: RuntimeApiCacheConfig,
+	collator_policy: CollatorPolicy,
+	network_rate_limits: RateLimits,
 	_malus_finality_delay: Option<u32>,
 	hwbench: Option<sc_sysinfo::HwBench>,
 ) -> Result<NewFull<Arc<FullClient<RuntimeApi, ExecutorDispatch>>>, Error>
@@ -827,6 +933,7 @@ where
 		&mut config,
 		basics,
 		select_chain,
+		Some(overseer_handle.clone()),
 	)?;
 
 	let shared_voter_state = rpc_setup;
@@ -922,6 +1029,7 @@ where
 	let availability_config = AvailabilityConfig {
 		col_data: parachains_db::REAL_COLUMNS.col_availability_data,
 		col_meta: parachains_db::REAL_COLUMNS.col_availability_meta,
+		pruning: availability_pruning_config,
 	};
 
 	let approval_voting_config = ApprovalVotingConfig {
@@ -1058,11 +1166,14 @@ where
 					approval_voting_config,
 					availability_config,
 					candidate_validation_config,
+					runtime_api_cache_config,
 					chain_selection_config,
 					dispute_coordinator_config,
 					pvf_checker_enabled,
 					overseer_message_channel_capacity_override,
 					req_protocol_names,
+					collator_policy,
+					network_rate_limits,
 				},
 			)
 			.map_err(|e| {
@@ -1299,6 +1410,7 @@ macro_rules! chain_ops {
 				&mut config,
 				basics,
 				chain_selection,
+				None,
 			)?;
 		Ok((Arc::new(Client::$variant(client)), backend, import_queue, task_manager))
 	}};
@@ -1367,6 +1479,10 @@ pub fn build_full(
 	overseer_enable_anyways: bool,
 	overseer_gen: impl OverseerGen,
 	overseer_message_channel_override: Option<usize>,
+	availability_pruning_config: AvailabilityPruningConfig,
+	runtime_api_cache_config: RuntimeApiCacheConfig,
+	collator_policy: CollatorPolicy,
+	network_rate_limits: RateLimits,
 	malus_finality_delay: Option<u32>,
 	hwbench: Option<sc_sysinfo::HwBench>,
 ) -> Result<NewFull<Client>, Error> {
@@ -1386,6 +1502,10 @@ pub fn build_full(
 			overseer_enable_anyways,
 			overseer_gen,
 			overseer_message_channel_override,
+			availability_pruning_config,
+			runtime_api_cache_config,
+			collator_policy,
+			network_rate_limits,
 			malus_finality_delay,
 			hwbench,
 		)
@@ -1405,6 +1525,10 @@ pub fn build_full(
 			overseer_enable_anyways,
 			overseer_gen,
 			overseer_message_channel_override,
+			availability_pruning_config,
+			runtime_api_cache_config,
+			collator_policy,
+			network_rate_limits,
 			malus_finality_delay,
 			hwbench,
 		)
@@ -1424,6 +1548,10 @@ pub fn build_full(
 			overseer_enable_anyways,
 			overseer_gen,
 			overseer_message_channel_override,
+			availability_pruning_config,
+			runtime_api_cache_config,
+			collator_policy,
+			network_rate_limits,
 			malus_finality_delay,
 			hwbench,
 		)
@@ -1446,6 +1574,10 @@ pub fn build_full(
 				gum::warn!("Channel capacity should _never_ be tampered with on polkadot!");
 				capacity
 			}),
+			availability_pruning_config,
+			runtime_api_cache_config,
+			collator_policy,
+			network_rate_limits,
 			malus_finality_delay,
 			hwbench,
 		)
@@ -24,6 +24,7 @@ use polkadot_node_core_av_store::Config as AvailabilityConfig;
 use polkadot_node_core_candidate_validation::Config as CandidateValidationConfig;
 use polkadot_node_core_chain_selection::Config as ChainSelectionConfig;
 use polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig;
+use polkadot_node_core_runtime_api::CacheConfig as RuntimeApiCacheConfig;
 use polkadot_node_network_protocol::request_response::{
 	v1 as request_v1, IncomingRequestReceiver, ReqProtocolNames,
 };
@@ -33,8 +34,9 @@ pub use polkadot_overseer::{
 	HeadSupportsParachains,
 };
 use polkadot_overseer::{
-	metrics::Metrics as OverseerMetrics, BlockInfo, InitializedOverseerBuilder, MetricsTrait,
-	Overseer, OverseerConnector, OverseerHandle, SpawnGlue,
+	metrics::Metrics as OverseerMetrics, recorder::StreamRecorder, BlockInfo,
+	InitializedOverseerBuilder, MetricsTrait, Overseer, OverseerConnector, OverseerHandle,
+	Recorded, SpawnGlue,
 };
 
 use polkadot_primitives::runtime_api::ParachainHost;
@@ -45,18 +47,18 @@ use sc_network_common::service::NetworkStateInfo;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_consensus_babe::BabeApi;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 pub use polkadot_approval_distribution::ApprovalDistribution as ApprovalDistributionSubsystem;
 pub use polkadot_availability_bitfield_distribution::BitfieldDistribution as BitfieldDistributionSubsystem;
 pub use polkadot_availability_distribution::AvailabilityDistributionSubsystem;
 pub use polkadot_availability_recovery::AvailabilityRecoverySubsystem;
-pub use polkadot_collator_protocol::{CollatorProtocolSubsystem, ProtocolSide};
+pub use polkadot_collator_protocol::{CollatorPolicy, CollatorProtocolSubsystem, ProtocolSide};
 pub use polkadot_dispute_distribution::DisputeDistributionSubsystem;
 pub use polkadot_gossip_support::GossipSupport as GossipSupportSubsystem;
 pub use polkadot_network_bridge::{
 	Metrics as NetworkBridgeMetrics, NetworkBridgeRx as NetworkBridgeRxSubsystem,
-	NetworkBridgeTx as NetworkBridgeTxSubsystem,
+	NetworkBridgeTx as NetworkBridgeTxSubsystem, RateLimits,
 };
 pub use polkadot_node_collation_generation::CollationGenerationSubsystem;
 pub use polkadot_node_core_approval_voting::ApprovalVotingSubsystem;
@@ -68,7 +70,7 @@ pub use polkadot_node_core_chain_api::ChainApiSubsystem;
 pub use polkadot_node_core_chain_selection::ChainSelectionSubsystem;
 pub use polkadot_node_core_dispute_coordinator::DisputeCoordinatorSubsystem;
 pub use polkadot_node_core_provisioner::ProvisionerSubsystem;
-pub use polkadot_node_core_pvf_checker::PvfCheckerSubsystem;
+pub use polkadot_node_core_pvf_checker::{Metrics as PvfCheckerMetrics, PvfCheckerSubsystem};
 pub use polkadot_node_core_runtime_api::RuntimeApiSubsystem;
 use polkadot_node_subsystem_util::rand::{self, SeedableRng};
 pub use polkadot_statement_distribution::StatementDistributionSubsystem;
@@ -112,6 +114,8 @@ where
 	pub availability_config: AvailabilityConfig,
 	/// Configuration for the candidate validation subsystem.
 	pub candidate_validation_config: CandidateValidationConfig,
+	/// Configuration for the runtime API subsystem's caches.
+	pub runtime_api_cache_config: RuntimeApiCacheConfig,
 	/// Configuration for the chain selection subsystem.
 	pub chain_selection_config: ChainSelectionConfig,
 	/// Configuration for the dispute coordinator subsystem.
@@ -122,6 +126,10 @@ where
 	pub overseer_message_channel_capacity_override: Option<usize>,
 	/// Request-response protocol names source.
 	pub req_protocol_names: ReqProtocolNames,
+	/// Which collators to accept collations from, when not a collator.
+	pub collator_policy: CollatorPolicy,
+	/// Per-peer rate limits of incoming notifications.
+	pub network_rate_limits: RateLimits,
 }
 
 /// Obtain a prepared `OverseerBuilder`, that is initialized
@@ -146,11 +154,14 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		approval_voting_config,
 		availability_config,
 		candidate_validation_config,
+		runtime_api_cache_config,
 		chain_selection_config,
 		dispute_coordinator_config,
 		pvf_checker_enabled,
 		overseer_message_channel_capacity_override,
 		req_protocol_names,
+		collator_policy,
+		network_rate_limits,
 	}: OverseerGenArgs<'a, Spawner, RuntimeClient>,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -207,18 +218,21 @@ where
 			network_bridge_metrics.clone(),
 			req_protocol_names,
 		))
-		.network_bridge_rx(NetworkBridgeRxSubsystem::new(
-			network_service.clone(),
-			authority_discovery_service.clone(),
-			Box::new(network_service.clone()),
-			network_bridge_metrics,
-		))
+		.network_bridge_rx(
+			NetworkBridgeRxSubsystem::new(
+				network_service.clone(),
+				authority_discovery_service.clone(),
+				Box::new(network_service.clone()),
+				network_bridge_metrics,
+			)
+			.with_rate_limits(network_rate_limits),
+		)
 		.availability_distribution(AvailabilityDistributionSubsystem::new(
 			keystore.clone(),
 			IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
 			Metrics::register(registry)?,
 		))
-		.availability_recovery(AvailabilityRecoverySubsystem::with_chunks_only(
+		.availability_recovery(AvailabilityRecoverySubsystem::with_systematic_chunks(
 			available_data_req_receiver,
 			Metrics::register(registry)?,
 		))
@@ -241,11 +255,13 @@ where
 			Metrics::register(registry)?, // candidate-validation metrics
 			Metrics::register(registry)?, // validation host metrics
 		))
-		.pvf_checker(PvfCheckerSubsystem::new(
-			pvf_checker_enabled,
-			keystore.clone(),
-			Metrics::register(registry)?,
-		))
+		.pvf_checker_factory({
+			let keystore = keystore.clone();
+			let metrics: PvfCheckerMetrics = Metrics::register(registry)?;
+			move |_| {
+				Ok(PvfCheckerSubsystem::new(pvf_checker_enabled, keystore.clone(), metrics.clone()))
+			}
+		})
 		.chain_api(ChainApiSubsystem::new(runtime_client.clone(), Metrics::register(registry)?))
 		.collation_generation(CollationGenerationSubsystem::new(Metrics::register(registry)?))
 		.collator_protocol({
@@ -259,16 +275,18 @@ where
 				IsCollator::No => ProtocolSide::Validator {
 					keystore: keystore.clone(),
 					eviction_policy: Default::default(),
+					collator_policy,
 					metrics: Metrics::register(registry)?,
 				},
 			};
 			CollatorProtocolSubsystem::new(side)
 		})
 		.provisioner(ProvisionerSubsystem::new(Metrics::register(registry)?))
-		.runtime_api(RuntimeApiSubsystem::new(
+		.runtime_api(RuntimeApiSubsystem::with_config(
 			runtime_client.clone(),
 			Metrics::register(registry)?,
 			spawner.clone(),
+			runtime_api_cache_config,
 		))
 		.statement_distribution(StatementDistributionSubsystem::new(
 			keystore.clone(),
@@ -367,3 +385,62 @@ impl OverseerGen for RealOverseerGen {
 			.map_err(|e| e.into())
 	}
 }
+
+/// A subsystem whose incoming messages and signals can be recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordedSubsystem {
+	/// The chain API subsystem.
+	ChainApi,
+	/// The dispute coordinator subsystem.
+	DisputeCoordinator,
+	/// The approval voting subsystem.
+	ApprovalVoting,
+}
+
+impl RecordedSubsystem {
+	/// The name of the subsystem, as stored in the header of its recordings.
+	pub fn name(&self) -> &'static str {
+		match self {
+			RecordedSubsystem::ChainApi => "chain-api",
+			RecordedSubsystem::DisputeCoordinator => "dispute-coordinator",
+			RecordedSubsystem::ApprovalVoting => "approval-voting",
+		}
+	}
+}
+
+/// The regular set of subsystems, with the incoming messages and signals of one of them
+/// recorded into a file.
+pub struct RecordingOverseerGen {
+	/// The subsystem to record.
+	pub subsystem: RecordedSubsystem,
+	/// The file to record into, which is created or truncated.
+	pub path: PathBuf,
+}
+
+impl OverseerGen for RecordingOverseerGen {
+	fn generate<'a, Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'a, Spawner, RuntimeClient>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		let recorder = StreamRecorder::create(&self.path, self.subsystem.name())?;
+		let builder = prepared_overseer_builder(args)?;
+		match self.subsystem {
+			RecordedSubsystem::ChainApi => builder
+				.replace_chain_api(move |subsystem| Recorded::new(subsystem, recorder))
+				.build_with_connector(connector),
+			RecordedSubsystem::DisputeCoordinator => builder
+				.replace_dispute_coordinator(move |subsystem| Recorded::new(subsystem, recorder))
+				.build_with_connector(connector),
+			RecordedSubsystem::ApprovalVoting => builder
+				.replace_approval_voting(move |subsystem| Recorded::new(subsystem, recorder))
+				.build_with_connector(connector),
+		}
+		.map_err(|e| e.into())
+	}
+}
@@ -0,0 +1,405 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline inspection of the parachains DB.
+//!
+//! Decodes the entries of the subsystems storing data in the parachains DB into a [`Dump`], which
+//! serializes to JSON.
+
+use polkadot_node_core_approval_voting as approval_voting;
+use polkadot_node_core_av_store as av_store;
+use polkadot_node_core_chain_selection as chain_selection;
+use polkadot_node_core_dispute_coordinator as dispute_coordinator;
+use polkadot_node_primitives::DisputeStatus;
+use polkadot_node_subsystem_util::database::Database;
+use polkadot_primitives::v2::{BlockNumber, CandidateHash, Hash, SessionIndex};
+use serde::Serialize;
+use std::sync::Arc;
+
+use super::{other_io_error, REAL_COLUMNS};
+use crate::Error;
+
+/// Restricts the entries included in a [`Dump`].
+///
+/// Entries which do not record the information a filter restricts are excluded, e.g. no entries
+/// of the availability store are included when filtering by session, as it does not record
+/// sessions.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+	/// Only include entries concerning this block.
+	pub block_hash: Option<Hash>,
+	/// Only include entries concerning this candidate.
+	pub candidate_hash: Option<CandidateHash>,
+	/// Only include entries of this session.
+	pub session: Option<SessionIndex>,
+}
+
+impl Filter {
+	fn block(&self, block_hash: &Hash) -> bool {
+		self.block_hash.map_or(true, |h| &h == block_hash)
+	}
+
+	fn candidate(&self, candidate_hash: &CandidateHash) -> bool {
+		self.candidate_hash.map_or(true, |h| &h == candidate_hash)
+	}
+
+	fn session(&self, session: SessionIndex) -> bool {
+		self.session.map_or(true, |s| s == session)
+	}
+}
+
+/// The decoded entries of the parachains DB.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dump {
+	/// The meta information of the availability store about candidates.
+	pub candidate_meta: Vec<CandidateMeta>,
+	/// The blocks tracked by approval voting.
+	pub approval_blocks: Vec<ApprovalBlock>,
+	/// The recent disputes of the dispute coordinator.
+	pub disputes: Vec<Dispute>,
+	/// The leaves of the block-tree of chain selection.
+	pub chain_selection_leaves: Vec<Leaf>,
+}
+
+impl Dump {
+	/// The dump as pretty-printed JSON.
+	pub fn to_json(&self) -> String {
+		serde_json::to_string_pretty(self)
+			.expect("The dump contains no maps with non-string keys; qed")
+	}
+}
+
+/// A block referenced by its number and hash.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockRef {
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// The hash of the block.
+	pub block_hash: Hash,
+}
+
+/// The state of a candidate in the availability store. Times are seconds since the unix epoch.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum CandidateState {
+	/// Not available in any block.
+	#[serde(rename_all = "camelCase")]
+	Unavailable { since: u64 },
+	/// Included in unfinalized blocks.
+	#[serde(rename_all = "camelCase")]
+	Unfinalized { since: u64, blocks: Vec<BlockRef> },
+	/// Included in a finalized block.
+	#[serde(rename_all = "camelCase")]
+	Finalized { since: u64 },
+}
+
+/// The meta information the availability store keeps about a candidate.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateMeta {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The state of the candidate.
+	pub state: CandidateState,
+	/// Whether the full available data is stored.
+	pub data_available: bool,
+	/// The validator indices of the stored chunks.
+	pub chunks_stored: Vec<u32>,
+}
+
+/// Assignments received for a delay tranche.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tranche {
+	/// The delay tranche.
+	pub tranche: u32,
+	/// The assigned validators, with the tick their assignment was received at.
+	pub assignments: Vec<(u32, u64)>,
+}
+
+/// A candidate included in a block tracked by approval voting.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalCandidate {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The para the candidate is for.
+	pub para_id: u32,
+	/// The core the candidate occupied.
+	pub core_index: u32,
+	/// Whether the candidate is approved in the context of the block.
+	pub approved: bool,
+	/// The assignments received for the candidate in the context of the block.
+	pub tranches: Vec<Tranche>,
+	/// The validators which approved the candidate.
+	pub approvals: Vec<u32>,
+}
+
+/// A block tracked by approval voting.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalBlock {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// The hash of the parent of the block.
+	pub parent_hash: Hash,
+	/// The session the block is in.
+	pub session: SessionIndex,
+	/// The slot of the block.
+	pub slot: u64,
+	/// Whether all candidates included in the block are approved.
+	pub approved: bool,
+	/// The candidates included in the block.
+	pub candidates: Vec<ApprovalCandidate>,
+}
+
+/// The status of a dispute. Times are seconds since the unix epoch.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum Status {
+	/// The dispute is not confirmed and not concluded.
+	Active,
+	/// The dispute is confirmed but not concluded.
+	Confirmed,
+	/// The dispute concluded in favor of the candidate.
+	#[serde(rename_all = "camelCase")]
+	ConcludedFor { concluded_at: u64 },
+	/// The dispute concluded against the candidate.
+	#[serde(rename_all = "camelCase")]
+	ConcludedAgainst { concluded_at: u64 },
+}
+
+impl From<DisputeStatus> for Status {
+	fn from(status: DisputeStatus) -> Self {
+		match status {
+			DisputeStatus::Active => Status::Active,
+			DisputeStatus::Confirmed => Status::Confirmed,
+			DisputeStatus::ConcludedFor(concluded_at) => Status::ConcludedFor { concluded_at },
+			DisputeStatus::ConcludedAgainst(concluded_at) =>
+				Status::ConcludedAgainst { concluded_at },
+		}
+	}
+}
+
+/// A dispute recorded by the dispute coordinator.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dispute {
+	/// The session the disputed candidate was included in.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The status of the dispute.
+	pub status: Status,
+	/// The para the disputed candidate is for, if its votes are still stored.
+	pub para_id: Option<u32>,
+	/// The validators which voted for the candidate.
+	pub valid: Vec<u32>,
+	/// The validators which voted against the candidate.
+	pub invalid: Vec<u32>,
+}
+
+/// A leaf of the block-tree of chain selection.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaf {
+	/// The hash of the leaf.
+	pub block_hash: Hash,
+	/// The number of the leaf.
+	pub block_number: BlockNumber,
+	/// The weight of the chain ending in the leaf.
+	pub weight: u32,
+}
+
+/// Decode the entries of the parachains DB matching `filter`.
+pub fn dump(db: Arc<dyn Database>, filter: &Filter) -> Result<Dump, Error> {
+	Ok(Dump {
+		candidate_meta: candidate_meta(&db, filter)?,
+		approval_blocks: approval_blocks(db.clone(), filter)?,
+		disputes: disputes(db.clone(), filter)?,
+		chain_selection_leaves: chain_selection_leaves(db, filter)?,
+	})
+}
+
+fn candidate_meta(db: &Arc<dyn Database>, filter: &Filter) -> Result<Vec<CandidateMeta>, Error> {
+	// The availability store does not record sessions.
+	if filter.session.is_some() {
+		return Ok(Vec::new())
+	}
+
+	let config = av_store::Config {
+		col_data: REAL_COLUMNS.col_availability_data,
+		col_meta: REAL_COLUMNS.col_availability_meta,
+		pruning: Default::default(),
+	};
+
+	let meta = av_store::read_candidate_meta(db, &config)?
+		.into_iter()
+		.filter(|meta| filter.candidate(&meta.candidate_hash))
+		.filter_map(|meta| {
+			let state = match meta.state {
+				av_store::CandidateState::Unavailable(since) =>
+					CandidateState::Unavailable { since: since.as_secs() },
+				av_store::CandidateState::Unfinalized(since, blocks) =>
+					CandidateState::Unfinalized {
+						since: since.as_secs(),
+						blocks: blocks
+							.into_iter()
+							.map(|(block_number, block_hash)| BlockRef { block_number, block_hash })
+							.collect(),
+					},
+				av_store::CandidateState::Finalized(since) =>
+					CandidateState::Finalized { since: since.as_secs() },
+			};
+
+			// Only unfinalized candidates record the blocks they are included in.
+			if filter.block_hash.is_some() {
+				match &state {
+					CandidateState::Unfinalized { blocks, .. }
+						if blocks.iter().any(|b| filter.block(&b.block_hash)) => {},
+					_ => return None,
+				}
+			}
+
+			Some(CandidateMeta {
+				candidate_hash: meta.candidate_hash.0,
+				state,
+				data_available: meta.data_available,
+				chunks_stored: meta.chunks_stored.into_iter().map(|v| v.0).collect(),
+			})
+		})
+		.collect();
+
+	Ok(meta)
+}
+
+fn approval_blocks(db: Arc<dyn Database>, filter: &Filter) -> Result<Vec<ApprovalBlock>, Error> {
+	let config = approval_voting::Config {
+		col_data: REAL_COLUMNS.col_approval_data,
+		slot_duration_millis: Default::default(),
+	};
+
+	let blocks = approval_voting::read_block_entries(db, &config)?
+		.into_iter()
+		.filter(|block| filter.block(&block.block_hash) && filter.session(block.session))
+		.filter_map(|block| {
+			let candidates: Vec<_> = block
+				.candidates
+				.into_iter()
+				.filter(|candidate| filter.candidate(&candidate.candidate_hash))
+				.map(|candidate| ApprovalCandidate {
+					candidate_hash: candidate.candidate_hash.0,
+					para_id: candidate.para_id.into(),
+					core_index: candidate.core_index.0,
+					approved: candidate.approved,
+					tranches: candidate
+						.tranches
+						.into_iter()
+						.map(|tranche| Tranche {
+							tranche: tranche.tranche,
+							assignments: tranche
+								.assignments
+								.into_iter()
+								.map(|(validator, tick)| (validator.0, tick))
+								.collect(),
+						})
+						.collect(),
+					approvals: candidate.approvals.into_iter().map(|v| v.0).collect(),
+				})
+				.collect();
+
+			if filter.candidate_hash.is_some() && candidates.is_empty() {
+				return None
+			}
+
+			Some(ApprovalBlock {
+				block_hash: block.block_hash,
+				block_number: block.block_number,
+				parent_hash: block.parent_hash,
+				session: block.session,
+				slot: block.slot.into(),
+				approved: block.approved,
+				candidates,
+			})
+		})
+		.collect();
+
+	Ok(blocks)
+}
+
+fn disputes(db: Arc<dyn Database>, filter: &Filter) -> Result<Vec<Dispute>, Error> {
+	let config =
+		dispute_coordinator::Config { col_data: REAL_COLUMNS.col_dispute_coordinator_data };
+
+	let disputes = dispute_coordinator::read_disputes(db, &config)?
+		.into_iter()
+		.filter(|dispute| {
+			filter.session(dispute.session) && filter.candidate(&dispute.candidate_hash)
+		})
+		.map(|dispute| {
+			let (para_id, valid, invalid) = match dispute.votes {
+				None => (None, Vec::new(), Vec::new()),
+				Some(votes) => (
+					Some(votes.candidate_receipt.descriptor.para_id.into()),
+					votes.valid.keys().map(|v| v.0).collect(),
+					votes.invalid.keys().map(|v| v.0).collect(),
+				),
+			};
+
+			Dispute {
+				session: dispute.session,
+				candidate_hash: dispute.candidate_hash.0,
+				status: dispute.status.into(),
+				para_id,
+				valid,
+				invalid,
+			}
+		})
+		.collect();
+
+	Ok(disputes)
+}
+
+fn chain_selection_leaves(db: Arc<dyn Database>, filter: &Filter) -> Result<Vec<Leaf>, Error> {
+	// Leaves record neither sessions nor candidates.
+	if filter.session.is_some() || filter.candidate_hash.is_some() {
+		return Ok(Vec::new())
+	}
+
+	let config = chain_selection::Config {
+		col_data: REAL_COLUMNS.col_chain_selection_data,
+		stagnant_check_interval: chain_selection::StagnantCheckInterval::never(),
+		stagnant_check_mode: chain_selection::StagnantCheckMode::PruneOnly,
+	};
+
+	let leaves = chain_selection::ChainSelectionSubsystem::new(config, db)
+		.leaves()
+		.map_err(|err| other_io_error(err.to_string()))?
+		.into_iter()
+		.filter(|leaf| filter.block(&leaf.block_hash))
+		.map(|leaf| Leaf {
+			block_hash: leaf.block_hash,
+			block_number: leaf.block_number,
+			weight: leaf.weight,
+		})
+		.collect();
+
+	Ok(leaves)
+}
@@ -18,6 +18,8 @@ use {
 	polkadot_node_subsystem_util::database::Database, std::io, std::path::PathBuf, std::sync::Arc,
 };
 
+#[cfg(feature = "full-node")]
+pub mod inspect;
 #[cfg(feature = "full-node")]
 mod upgrade;
 
@@ -156,3 +158,86 @@ pub fn open_creating_paritydb(
 	);
 	Ok(Arc::new(db))
 }
+
+/// Open an existing database on disk read-only, without creating or upgrading it.
+///
+/// The database is opened as a secondary instance, which keeps its logs in a temporary directory
+/// instead of the database directory.
+#[cfg(feature = "full-node")]
+pub fn open_existing_rocksdb(root: PathBuf) -> io::Result<Arc<dyn Database>> {
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let path = root.join("parachains").join("db");
+	let path_str = path
+		.to_str()
+		.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", path)))?;
+
+	if !path.is_dir() {
+		return Err(other_io_error(format!("No parachains database at {:?}", path)))
+	}
+	upgrade::ensure_current_version(&path)?;
+
+	let secondary_path = std::env::temp_dir().join("polkadot-parachains-db-inspect");
+	let secondary_path_str = secondary_path
+		.to_str()
+		.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", secondary_path)))?;
+	std::fs::create_dir_all(&secondary_path)?;
+
+	let mut db_config = DatabaseConfig::with_columns(columns::v1::NUM_COLUMNS);
+	// Secondary instances need to keep all files open.
+	db_config.max_open_files = -1;
+	db_config.secondary = Some(secondary_path_str.into());
+	let db = Database::open(&db_config, &path_str)?;
+	let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(
+		db,
+		columns::v1::ORDERED_COL,
+	);
+
+	Ok(Arc::new(db))
+}
+
+/// Open an existing parity db database read-only, without creating or upgrading it.
+#[cfg(feature = "full-node")]
+pub fn open_existing_paritydb(root: PathBuf) -> io::Result<Arc<dyn Database>> {
+	let path = root.join("parachains");
+
+	if !path.is_dir() {
+		return Err(other_io_error(format!("No parachains database at {:?}", path)))
+	}
+	upgrade::ensure_current_version(&path)?;
+
+	let db = parity_db::Db::open_read_only(&upgrade::paritydb_version_1_config(&path))
+		.map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))?;
+
+	let db = polkadot_node_subsystem_util::database::paritydb_impl::DbAdapter::new(
+		db,
+		columns::v1::ORDERED_COL,
+	);
+	Ok(Arc::new(db))
+}
+
+/// Downgrade the database on disk to the given version, e.g. to run an older release on it.
+///
+/// It must not be in use by a running node.
+#[cfg(feature = "full-node")]
+pub fn downgrade_rocksdb(root: PathBuf, target_version: u32) -> io::Result<()> {
+	let path = root.join("parachains").join("db");
+
+	if !path.is_dir() {
+		return Err(other_io_error(format!("No parachains database at {:?}", path)))
+	}
+	upgrade::try_downgrade_db(&path, DatabaseKind::RocksDB, target_version).map_err(Into::into)
+}
+
+/// Downgrade the parity db database to the given version, e.g. to run an older release on it.
+///
+/// It must not be in use by a running node.
+#[cfg(feature = "full-node")]
+pub fn downgrade_paritydb(root: PathBuf, target_version: u32) -> io::Result<()> {
+	let path = root.join("parachains");
+
+	if !path.is_dir() {
+		return Err(other_io_error(format!("No parachains database at {:?}", path)))
+	}
+	upgrade::try_downgrade_db(&path, DatabaseKind::ParityDB, target_version).map_err(Into::into)
+}
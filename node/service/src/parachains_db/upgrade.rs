@@ -16,6 +16,7 @@
 #![cfg(feature = "full-node")]
 
 use super::{columns, other_io_error, DatabaseKind, LOG_TARGET};
+use polkadot_node_subsystem_util::database::{kvdb_impl, paritydb_impl, DBTransaction, Database};
 use std::{
 	fs, io,
 	path::{Path, PathBuf},
@@ -28,7 +29,15 @@ type Version = u32;
 const VERSION_FILE_NAME: &'static str = "parachain_db_version";
 
 /// Current db version.
-const CURRENT_VERSION: Version = 1;
+///
+/// Version 2 extended the chain selection block entries, which older versions can't decode.
+const CURRENT_VERSION: Version = 2;
+
+/// The oldest version the db can be downgraded to.
+///
+/// Version 0 either lacks the chain selection and dispute coordinator columns (`RocksDB`) or
+/// uses a different layout for the latter (`ParityDB`), so their data could not be kept.
+const OLDEST_DOWNGRADE_VERSION: Version = 1;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -38,6 +47,14 @@ pub enum Error {
 	CorruptedVersionFile,
 	#[error("Future version (expected {current:?}, found {got:?})")]
 	FutureVersion { current: Version, got: Version },
+	#[error("Outdated version (expected {current:?}, found {got:?})")]
+	OutdatedVersion { current: Version, got: Option<Version> },
+	#[error("The version file is missing, the version of the database is unknown")]
+	MissingVersionFile,
+	#[error("Version {target:?} is not older than the current version {current:?}")]
+	NotADowngrade { current: Version, target: Version },
+	#[error("Downgrading to version {target:?} is not supported, the oldest supported version is {oldest:?}")]
+	UnsupportedDowngrade { target: Version, oldest: Version },
 }
 
 impl From<Error> for io::Error {
@@ -54,8 +71,10 @@ pub(crate) fn try_upgrade_db(db_path: &Path, db_kind: DatabaseKind) -> Result<()
 	let is_empty = db_path.read_dir().map_or(true, |mut d| d.next().is_none());
 	if !is_empty {
 		match get_db_version(db_path)? {
-			// 0 -> 1 migration
+			// 0 -> 1 migration, nothing to migrate from 1 to 2.
 			Some(0) => migrate_from_version_0_to_1(db_path, db_kind)?,
+			// Version 2 decodes version 1 data, nothing to migrate.
+			Some(1) => (),
 			// Already at current version, do nothing.
 			Some(CURRENT_VERSION) => (),
 			// This is an arbitrary future version, we don't handle it.
@@ -73,6 +92,50 @@ pub(crate) fn try_upgrade_db(db_path: &Path, db_kind: DatabaseKind) -> Result<()
 	update_version(db_path)
 }
 
+/// Downgrade parachain's database to the given version, e.g. to run an older release on it.
+///
+/// Refuses to do anything if any of the downgrades down to `target` is not supported.
+pub(crate) fn try_downgrade_db(
+	db_path: &Path,
+	db_kind: DatabaseKind,
+	target: Version,
+) -> Result<(), Error> {
+	let current = get_db_version(db_path)?.ok_or(Error::MissingVersionFile)?;
+	if current > CURRENT_VERSION {
+		return Err(Error::FutureVersion { current: CURRENT_VERSION, got: current })
+	}
+	if target >= current {
+		return Err(Error::NotADowngrade { current, target })
+	}
+	if target < OLDEST_DOWNGRADE_VERSION {
+		return Err(Error::UnsupportedDowngrade { target, oldest: OLDEST_DOWNGRADE_VERSION })
+	}
+
+	for version in (target..current).rev() {
+		match version {
+			// 2 -> 1 migration
+			1 => migrate_from_version_2_to_1(db_path, db_kind)?,
+			_ => unreachable!(
+				"versions older than `OLDEST_DOWNGRADE_VERSION` are refused above; qed"
+			),
+		}
+		// Record every step, such that an interrupted downgrade can be resumed.
+		write_version(db_path, version)?;
+	}
+
+	Ok(())
+}
+
+/// Ensure the database is at the current version, without upgrading it.
+pub(crate) fn ensure_current_version(db_path: &Path) -> Result<(), Error> {
+	match get_db_version(db_path)? {
+		Some(CURRENT_VERSION) => Ok(()),
+		Some(v) if v > CURRENT_VERSION =>
+			Err(Error::FutureVersion { current: CURRENT_VERSION, got: v }),
+		got => Err(Error::OutdatedVersion { current: CURRENT_VERSION, got }),
+	}
+}
+
 /// Reads current database version from the file at given path.
 /// If the file does not exist returns `None`, otherwise the version stored in the file.
 fn get_db_version(path: &Path) -> Result<Option<Version>, Error> {
@@ -89,7 +152,12 @@ fn get_db_version(path: &Path) -> Result<Option<Version>, Error> {
 /// Creates a new file if the version file does not exist yet.
 fn update_version(path: &Path) -> Result<(), Error> {
 	fs::create_dir_all(path)?;
-	fs::write(version_file_path(path), CURRENT_VERSION.to_string()).map_err(Into::into)
+	write_version(path, CURRENT_VERSION)
+}
+
+/// Writes the given database version to the file.
+fn write_version(path: &Path, version: Version) -> Result<(), Error> {
+	fs::write(version_file_path(path), version.to_string()).map_err(Into::into)
 }
 
 /// Returns the version file path.
@@ -129,6 +197,59 @@ fn rocksdb_migrate_from_version_0_to_1(path: &Path) -> Result<(), Error> {
 	Ok(())
 }
 
+fn migrate_from_version_2_to_1(path: &Path, db_kind: DatabaseKind) -> Result<(), Error> {
+	gum::info!(target: LOG_TARGET, "Migrating parachains db from version 2 to version 1 ...");
+
+	match db_kind {
+		DatabaseKind::ParityDB => paritydb_migrate_from_version_2_to_1(path),
+		DatabaseKind::RocksDB => rocksdb_migrate_from_version_2_to_1(path),
+	}
+	.and_then(|result| {
+		gum::info!(target: LOG_TARGET, "Migration complete! ");
+		Ok(result)
+	})
+}
+
+/// Migration from version 2 to version 1:
+/// * the participation queue of the dispute coordinator is dropped;
+/// * chain selection block entries drop the reverting block and the time they became stagnant at.
+fn migrate_from_version_2_to_1_in(db: &dyn Database) -> Result<(), Error> {
+	let mut tx = DBTransaction::new();
+	let participations = polkadot_node_core_dispute_coordinator::participation_queue_downgrade(
+		db,
+		columns::v1::COL_DISPUTE_COORDINATOR_DATA,
+	);
+	for key in participations {
+		tx.delete(columns::v1::COL_DISPUTE_COORDINATOR_DATA, &key);
+	}
+
+	let overwrites = polkadot_node_core_chain_selection::viability_details_downgrade(
+		db,
+		columns::v1::COL_CHAIN_SELECTION_DATA,
+	)
+	.map_err(|e| other_io_error(format!("Error reading chain selection data {:?}", e)))?;
+	for (key, value) in overwrites {
+		tx.put_vec(columns::v1::COL_CHAIN_SELECTION_DATA, &key, value);
+	}
+
+	db.write(tx)?;
+
+	Ok(())
+}
+
+fn rocksdb_migrate_from_version_2_to_1(path: &Path) -> Result<(), Error> {
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let db_path = path
+		.to_str()
+		.ok_or_else(|| super::other_io_error("Invalid database path".into()))?;
+	let db_cfg = DatabaseConfig::with_columns(super::columns::v1::NUM_COLUMNS);
+	let db = Database::open(&db_cfg, db_path)?;
+	let db = kvdb_impl::DbAdapter::new(db, columns::v1::ORDERED_COL);
+
+	migrate_from_version_2_to_1_in(&db)
+}
+
 // This currently clears columns which had their configs altered between versions.
 // The columns to be changed are constrained by the `allowed_columns` vector.
 fn paritydb_fix_columns(
@@ -224,6 +345,14 @@ fn paritydb_migrate_from_version_0_to_1(path: &Path) -> Result<(), Error> {
 	Ok(())
 }
 
+fn paritydb_migrate_from_version_2_to_1(path: &Path) -> Result<(), Error> {
+	let db = parity_db::Db::open(&paritydb_version_1_config(path))
+		.map_err(|e| other_io_error(format!("Error opening database {:?}", e)))?;
+	let db = paritydb_impl::DbAdapter::new(db, columns::v1::ORDERED_COL);
+
+	migrate_from_version_2_to_1_in(&db)
+}
+
 #[cfg(test)]
 mod tests {
 	#[test]
@@ -246,13 +375,160 @@ mod tests {
 		try_upgrade_db(&path, DatabaseKind::ParityDB).unwrap();
 
 		let db = Db::open(&paritydb_version_1_config(&path)).unwrap();
-		assert_eq!(
-			db.get(super::columns::v1::COL_DISPUTE_COORDINATOR_DATA as u8, b"1234").unwrap(),
-			None
-		);
+		assert_eq!(db.get(COL_DISPUTE_COORDINATOR_DATA as u8, b"1234").unwrap(), None);
 		assert_eq!(
 			db.get(super::columns::v1::COL_AVAILABILITY_META as u8, b"5678").unwrap(),
 			Some("somevalue".as_bytes().to_vec())
 		);
 	}
+
+	#[test]
+	fn test_paritydb_migrate_1_2() {
+		use super::{columns::v1::*, *};
+		use parity_db::Db;
+
+		let db_dir = tempfile::tempdir().unwrap();
+		let path = db_dir.path();
+		{
+			let db = Db::open_or_create(&paritydb_version_1_config(&path)).unwrap();
+
+			db.commit(vec![(
+				COL_DISPUTE_COORDINATOR_DATA as u8,
+				b"1234".to_vec(),
+				Some(b"somevalue".to_vec()),
+			)])
+			.unwrap();
+		}
+		fs::write(version_file_path(path), "1").unwrap();
+
+		try_upgrade_db(&path, DatabaseKind::ParityDB).unwrap();
+		assert_eq!(get_db_version(path).unwrap(), Some(CURRENT_VERSION));
+
+		let db = Db::open(&paritydb_version_1_config(&path)).unwrap();
+		// Existing data is kept:
+		assert_eq!(
+			db.get(COL_DISPUTE_COORDINATOR_DATA as u8, b"1234").unwrap(),
+			Some(b"somevalue".to_vec())
+		);
+	}
+
+	#[test]
+	fn test_paritydb_downgrade_2_1() {
+		use super::{columns::v1::*, *};
+		use parity_db::Db;
+
+		let db_dir = tempfile::tempdir().unwrap();
+		let path = db_dir.path();
+		{
+			let db = Db::open_or_create(&paritydb_version_1_config(&path)).unwrap();
+
+			db.commit(vec![
+				(COL_DISPUTE_COORDINATOR_DATA as u8, b"1234".to_vec(), Some(b"somevalue".to_vec())),
+				// A queued participation:
+				(
+					COL_DISPUTE_COORDINATOR_DATA as u8,
+					b"priority-participation5678".to_vec(),
+					Some(b"somevalue".to_vec()),
+				),
+			])
+			.unwrap();
+		}
+		fs::write(version_file_path(path), "2").unwrap();
+
+		try_downgrade_db(&path, DatabaseKind::ParityDB, 1).unwrap();
+		assert_eq!(get_db_version(path).unwrap(), Some(1));
+
+		{
+			let db = Db::open(&paritydb_version_1_config(&path)).unwrap();
+			// Existing data is kept:
+			assert_eq!(
+				db.get(COL_DISPUTE_COORDINATOR_DATA as u8, b"1234").unwrap(),
+				Some(b"somevalue".to_vec())
+			);
+			assert_eq!(
+				db.get(COL_DISPUTE_COORDINATOR_DATA as u8, b"priority-participation5678")
+					.unwrap(),
+				None
+			);
+		}
+
+		// And it can be upgraded again.
+		try_upgrade_db(&path, DatabaseKind::ParityDB).unwrap();
+		assert_eq!(get_db_version(path).unwrap(), Some(CURRENT_VERSION));
+	}
+
+	#[test]
+	fn test_rocksdb_downgrade_2_1() {
+		use super::{columns::v1::*, *};
+		use kvdb::KeyValueDB;
+		use kvdb_rocksdb::{Database, DatabaseConfig};
+
+		let db_dir = tempfile::tempdir().unwrap();
+		let path = db_dir.path();
+		let db_path = path.to_str().unwrap();
+		let db_cfg = DatabaseConfig::with_columns(NUM_COLUMNS);
+		{
+			let db = Database::open(&db_cfg, db_path).unwrap();
+
+			let mut tx = DBTransaction::new();
+			tx.put(COL_DISPUTE_COORDINATOR_DATA, b"1234", b"somevalue");
+			// A queued participation:
+			tx.put(COL_DISPUTE_COORDINATOR_DATA, b"priority-participation5678", b"somevalue");
+			db.write(tx).unwrap();
+		}
+		fs::write(version_file_path(path), "2").unwrap();
+
+		try_downgrade_db(&path, DatabaseKind::RocksDB, 1).unwrap();
+		assert_eq!(get_db_version(path).unwrap(), Some(1));
+
+		{
+			let db = Database::open(&db_cfg, db_path).unwrap();
+			// Existing data is kept:
+			assert_eq!(
+				db.get(COL_DISPUTE_COORDINATOR_DATA, b"1234").unwrap(),
+				Some(b"somevalue".to_vec())
+			);
+			assert_eq!(
+				db.get(COL_DISPUTE_COORDINATOR_DATA, b"priority-participation5678").unwrap(),
+				None
+			);
+		}
+
+		// And it can be upgraded again.
+		try_upgrade_db(&path, DatabaseKind::RocksDB).unwrap();
+		assert_eq!(get_db_version(path).unwrap(), Some(CURRENT_VERSION));
+	}
+
+	#[test]
+	fn test_unsupported_downgrades_are_refused() {
+		use super::*;
+		use parity_db::Db;
+
+		let db_dir = tempfile::tempdir().unwrap();
+		let path = db_dir.path();
+		drop(Db::open_or_create(&paritydb_version_1_config(&path)).unwrap());
+
+		// Without a version file, the version is unknown.
+		assert!(matches!(
+			try_downgrade_db(&path, DatabaseKind::ParityDB, 1),
+			Err(Error::MissingVersionFile)
+		));
+
+		fs::write(version_file_path(path), "2").unwrap();
+		assert!(matches!(
+			try_downgrade_db(&path, DatabaseKind::ParityDB, 0),
+			Err(Error::UnsupportedDowngrade { target: 0, oldest: 1 })
+		));
+		assert!(matches!(
+			try_downgrade_db(&path, DatabaseKind::ParityDB, 2),
+			Err(Error::NotADowngrade { current: 2, target: 2 })
+		));
+		assert!(matches!(
+			try_downgrade_db(&path, DatabaseKind::ParityDB, 3),
+			Err(Error::NotADowngrade { current: 2, target: 3 })
+		));
+
+		// Nothing was changed.
+		assert_eq!(get_db_version(path).unwrap(), Some(2));
+	}
 }
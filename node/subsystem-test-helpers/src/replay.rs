@@ -0,0 +1,281 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Replaying recorded subsystem traffic.
+//!
+//! Feeds the messages and signals of a [`Recording`], as written by a
+//! [`StreamRecorder`](polkadot_node_subsystem::overseer::recorder::StreamRecorder), into a fresh
+//! instance of a subsystem and captures everything it sends and its responses to the recorded
+//! requests, such that the outcome of a failing run can be compared with the one of a good run or
+//! with the recording itself.
+
+use polkadot_node_subsystem::{
+	messages::AllMessages,
+	overseer::{
+		self,
+		recorder::{RecordedMessage, Recording},
+	},
+	FromOrchestra, OverseerSignal, SpawnGlue, SpawnedSubsystem, SubsystemError, SubsystemResult,
+};
+use polkadot_node_subsystem_util::TimeoutExt;
+
+use futures::{future::Either, prelude::*, select};
+use sp_core::testing::TaskExecutor;
+
+use crate::{make_subsystem_context, TestSubsystemContext, TestSubsystemContextHandle};
+
+/// The outcome of replaying a recording into a subsystem.
+#[derive(Debug)]
+pub struct ReplayOutcome {
+	/// The messages sent by the subsystem in their `Debug` representation, in the order they
+	/// were sent in.
+	pub outgoing: Vec<String>,
+	/// The responses of the subsystem to the recorded requests, in the order of the requests.
+	pub responses: Vec<ReplayedResponse>,
+	/// What the subsystem returned.
+	pub result: SubsystemResult<()>,
+}
+
+/// The response of the subsystem to a recorded request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayedResponse {
+	/// The index of the request among the recorded messages.
+	pub index: usize,
+	/// The SCALE-encoded response in the recording, if the request was answered.
+	pub recorded: Option<Vec<u8>>,
+	/// The SCALE-encoded response during the replay, if the request was answered.
+	pub replayed: Option<Vec<u8>>,
+}
+
+impl ReplayOutcome {
+	/// The index of the first outgoing message differing from the ones of `expected`, or `None`
+	/// if both sent the same messages.
+	pub fn first_divergence(&self, expected: &ReplayOutcome) -> Option<usize> {
+		let common = self.outgoing.len().min(expected.outgoing.len());
+		(0..common)
+			.find(|i| self.outgoing[*i] != expected.outgoing[*i])
+			.or_else(|| (self.outgoing.len() != expected.outgoing.len()).then(|| common))
+	}
+
+	/// The responses to recorded requests which differ from the recorded ones.
+	pub fn diverging_responses(&self) -> impl Iterator<Item = &ReplayedResponse> {
+		self.responses.iter().filter(|response| response.recorded != response.replayed)
+	}
+}
+
+/// Replay a recording into `subsystem`.
+///
+/// The recorded messages and signals are delivered one after another, each once the subsystem
+/// received the previous one, followed by `Conclude` unless the recording ends with it already.
+/// Every message sent by the subsystem is handed to `respond`, which may answer requests
+/// contained in it. Requests not answered are dropped. The responses of the subsystem to the
+/// recorded requests are collected once it returned, requests answered later on count as not
+/// answered.
+///
+/// Times out after [`TestSubsystemContextHandle::TIMEOUT`].
+pub fn replay_recording<M, S>(
+	subsystem: S,
+	recording: Recording<M>,
+	mut respond: impl FnMut(AllMessages),
+) -> ReplayOutcome
+where
+	M: overseer::AssociateOutgoing + std::fmt::Debug + Send + 'static,
+	AllMessages: From<<M as overseer::AssociateOutgoing>::OutgoingMessages>,
+	AllMessages: From<M>,
+	S: overseer::Subsystem<TestSubsystemContext<M, SpawnGlue<TaskExecutor>>, SubsystemError>,
+{
+	let (context, handle) = make_subsystem_context(TaskExecutor::new());
+	let TestSubsystemContextHandle { mut tx, mut rx } = handle;
+	let SpawnedSubsystem { future, .. } = subsystem.start(context);
+
+	let concluded = matches!(
+		recording.messages.last(),
+		Some(RecordedMessage { message: FromOrchestra::Signal(OverseerSignal::Conclude), .. })
+	);
+	let mut messages = Vec::with_capacity(recording.messages.len() + 1);
+	let mut pending_responses = Vec::new();
+	for (index, recorded) in recording.messages.into_iter().enumerate() {
+		if let Some(replayed) = recorded.replayed_response {
+			pending_responses.push((index, recorded.response, replayed));
+		}
+		messages.push(recorded.message);
+	}
+	if !concluded {
+		messages.push(FromOrchestra::Signal(OverseerSignal::Conclude));
+	}
+
+	let feed = async move {
+		for message in messages {
+			// Resolves once the subsystem received the message.
+			let _ = tx.send(message).await;
+		}
+	};
+
+	// The subsystem may exit before all messages were delivered.
+	let run = async move {
+		match future::select(future, feed.boxed()).await {
+			Either::Left((result, _)) => result,
+			Either::Right(((), future)) => future.await,
+		}
+	};
+
+	let replay = async move {
+		let mut run = run.boxed().fuse();
+		let mut outgoing = Vec::new();
+		let result = loop {
+			select! {
+				result = run => {
+					// Spawned tasks may still hold on to a sender, don't wait for them.
+					while let Ok(Some(msg)) = rx.try_next() {
+						outgoing.push(format!("{:?}", msg));
+						respond(msg);
+					}
+					break result
+				},
+				msg = rx.select_next_some() => {
+					outgoing.push(format!("{:?}", msg));
+					respond(msg);
+				},
+			}
+		};
+		(outgoing, result)
+	};
+
+	let (outgoing, result) =
+		futures::executor::block_on(replay.timeout(TestSubsystemContextHandle::<M>::TIMEOUT))
+			.expect("replay timed out");
+	let responses = pending_responses
+		.into_iter()
+		.map(|(index, recorded, replayed)| ReplayedResponse {
+			index,
+			recorded,
+			replayed: replayed.now_or_never().flatten(),
+		})
+		.collect();
+
+	ReplayOutcome { outgoing, responses, result }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::channel::oneshot;
+	use polkadot_node_subsystem::{
+		jaeger,
+		messages::{BitfieldSigningMessage, RuntimeApiMessage, RuntimeApiRequest},
+		overseer::{recorder::StreamRecorder, MessageRecorder},
+		ActivatedLeaf, ActiveLeavesUpdate, LeafStatus,
+	};
+	use polkadot_primitives::v2::Hash;
+	use std::sync::Arc;
+
+	/// Requests the validators and then the session index for every activated leaf.
+	struct LeafRequester;
+
+	#[overseer::subsystem(BitfieldSigning, error = SubsystemError, prefix = self::overseer)]
+	impl<Context> LeafRequester {
+		fn start(self, mut ctx: Context) -> SpawnedSubsystem {
+			let future = Box::pin(async move {
+				loop {
+					match ctx.recv().await? {
+						FromOrchestra::Signal(OverseerSignal::ActiveLeaves(update)) => {
+							let hash = match update.activated {
+								Some(leaf) => leaf.hash,
+								None => continue,
+							};
+							let (tx, rx) = oneshot::channel();
+							ctx.send_message(RuntimeApiMessage::Request(
+								hash,
+								RuntimeApiRequest::Validators(tx),
+							))
+							.await;
+							if rx.await.is_err() {
+								continue
+							}
+							let (tx, _rx) = oneshot::channel();
+							ctx.send_message(RuntimeApiMessage::Request(
+								hash,
+								RuntimeApiRequest::SessionIndexForChild(tx),
+							))
+							.await;
+						},
+						FromOrchestra::Signal(OverseerSignal::Conclude) => return Ok(()),
+						_ => {},
+					}
+				}
+			});
+
+			SpawnedSubsystem { name: "leaf-requester", future }
+		}
+	}
+
+	fn record_leaves(leaves: &[Hash]) -> Recording<BitfieldSigningMessage> {
+		let mut recorder = StreamRecorder::new(Vec::new(), "bitfield-signing").unwrap();
+		for (number, hash) in leaves.iter().enumerate() {
+			let signal =
+				OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+					hash: *hash,
+					number: number as _,
+					status: LeafStatus::Fresh,
+					span: Arc::new(jaeger::Span::Disabled),
+				}));
+			MessageRecorder::<BitfieldSigningMessage, _>::record(
+				&mut recorder,
+				FromOrchestra::Signal(signal),
+			);
+		}
+		Recording::decode(&futures::executor::block_on(recorder.finish()).unwrap()).unwrap()
+	}
+
+	fn answer_validators(msg: AllMessages) {
+		if let AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			_,
+			RuntimeApiRequest::Validators(tx),
+		)) = msg
+		{
+			let _ = tx.send(Ok(Vec::new()));
+		}
+	}
+
+	#[test]
+	fn replay_delivers_recorded_signals() {
+		let leaves = [Hash::repeat_byte(1), Hash::repeat_byte(2)];
+		let recording = record_leaves(&leaves);
+		assert_eq!(recording.header.subsystem, "bitfield-signing");
+		assert_eq!(recording.messages.len(), 2);
+
+		let outcome = replay_recording(LeafRequester, recording, answer_validators);
+
+		assert!(outcome.result.is_ok());
+		assert_eq!(outcome.outgoing.len(), 4);
+		assert!(outcome.outgoing[0].contains("Validators"));
+		assert!(outcome.outgoing[1].contains("SessionIndexForChild"));
+	}
+
+	#[test]
+	fn replay_outcomes_can_be_compared() {
+		let leaves = [Hash::repeat_byte(1), Hash::repeat_byte(2)];
+
+		let expected = replay_recording(LeafRequester, record_leaves(&leaves), answer_validators);
+		let same = replay_recording(LeafRequester, record_leaves(&leaves), answer_validators);
+		assert_eq!(same.first_divergence(&expected), None);
+
+		// Without answers, the session index is never requested.
+		let unanswered = replay_recording(LeafRequester, record_leaves(&leaves), |_| {});
+		assert_eq!(unanswered.outgoing.len(), 2);
+		assert_eq!(unanswered.first_divergence(&expected), Some(1));
+	}
+}
@@ -0,0 +1,306 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+#![deny(unused_crate_dependencies)]
+#![deny(missing_docs)]
+#![deny(clippy::dbg_macro)]
+
+//! Derive `RecordableMessage` for subsystem messages. See
+//! `polkadot_node_subsystem_types::recordable` for usage documentation.
+
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+	parse2, spanned::Spanned, Data, DeriveInput, Error, Fields, GenericArgument, Lit, Meta,
+	NestedMeta, Path, PathArguments, Result, Type,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Derive `RecordableMessage` for an enum of subsystem messages.
+///
+/// Variants are encoded by their index, followed by their fields in order. Fields of type
+/// `oneshot::Sender<T>` or `Option<oneshot::Sender<T>>` carry the response to a request, all
+/// other fields must implement `Encode` and `Decode`.
+///
+/// Fields accept the following attributes:
+/// - `#[recordable(response)]` marks the field as a response channel, for aliases of
+///   `oneshot::Sender<T>`.
+/// - `#[recordable(with = "path")]` encodes the field with `path::encode(&field, dest)` and
+///   decodes it with `path::decode(input)`, for types not implementing `Encode` and `Decode`.
+/// - `#[recordable(encode_response_with = "path")]` encodes the response sent on the channel
+///   with `path(&response)`, for responses not implementing `Encode`.
+#[proc_macro_derive(RecordableMessage, attributes(recordable))]
+pub fn derive_recordable_message(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	impl_recordable_message(item.into())
+		.unwrap_or_else(|err| err.to_compile_error())
+		.into()
+}
+
+/// How a field of a message is recorded.
+enum FieldKind {
+	/// The field is encoded, with the given module if any.
+	Encoded { with: Option<Path> },
+	/// The field is a channel to send the response of a request on, which is optional.
+	Response { optional: bool, encode_with: Option<Path> },
+}
+
+struct Field {
+	binding: Ident,
+	kind: FieldKind,
+}
+
+pub(crate) fn impl_recordable_message(item: TokenStream) -> Result<TokenStream> {
+	let input: DeriveInput = parse2(item)?;
+	let data = match &input.data {
+		Data::Enum(data) => data,
+		_ =>
+			return Err(Error::new(
+				input.span(),
+				"`RecordableMessage` can only be derived for enums",
+			)),
+	};
+	if data.variants.len() > u8::MAX as usize + 1 {
+		return Err(Error::new(input.span(), "Too many variants to be recorded"))
+	}
+
+	let support_crate = support_crate();
+	let codec = quote! { #support_crate::recordable::codec };
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let mut encode_arms = Vec::new();
+	let mut decode_arms = Vec::new();
+	for (index, variant) in data.variants.iter().enumerate() {
+		let index = Literal::u8_suffixed(index as u8);
+		let variant_name = &variant.ident;
+		let fields = variant
+			.fields
+			.iter()
+			.enumerate()
+			.map(|(i, field)| {
+				let binding = field.ident.clone().unwrap_or_else(|| format_ident!("field_{}", i));
+				Ok(Field { binding, kind: field_kind(field)? })
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		if fields.iter().filter(|f| matches!(f.kind, FieldKind::Response { .. })).count() > 1 {
+			return Err(Error::new(
+				variant.span(),
+				"A recorded message can carry at most a single response channel",
+			))
+		}
+
+		let bindings = fields.iter().map(|f| &f.binding);
+		let constructor = match &variant.fields {
+			Fields::Named(_) => quote! { #name::#variant_name { #(#bindings),* } },
+			Fields::Unnamed(_) => quote! { #name::#variant_name ( #(#bindings),* ) },
+			Fields::Unit => quote! { #name::#variant_name },
+		};
+
+		let mut encode = vec![quote! { #codec::Encode::encode_to(&#index, dest); }];
+		let mut decode = Vec::new();
+		let mut response = quote! { None };
+		for Field { binding, kind } in &fields {
+			match kind {
+				FieldKind::Encoded { with: None } => {
+					encode.push(quote! { #codec::Encode::encode_to(&#binding, dest); });
+					decode.push(quote! { let #binding = #codec::Decode::decode(input)?; });
+				},
+				FieldKind::Encoded { with: Some(with) } => {
+					encode.push(quote! { #with::encode(&#binding, dest); });
+					decode.push(quote! { let #binding = #with::decode(input)?; });
+				},
+				FieldKind::Response { optional, encode_with } => {
+					let (intercept, replay) = match encode_with {
+						None => (
+							quote! { #support_crate::recordable::intercept_response(tx) },
+							quote! { #support_crate::recordable::replay_response() },
+						),
+						Some(encode_with) => (
+							quote! {
+								#support_crate::recordable::intercept_response_with(tx, #encode_with)
+							},
+							quote! {
+								#support_crate::recordable::replay_response_with(#encode_with)
+							},
+						),
+					};
+
+					if *optional {
+						encode.push(quote! {
+							#codec::Encode::encode_to(&#binding.is_some(), dest);
+							let (#binding, response) = match #binding {
+								Some(tx) => {
+									let (tx, response) = #intercept;
+									(Some(tx), Some(response))
+								},
+								None => (None, None),
+							};
+						});
+						decode.push(quote! {
+							let (#binding, response) =
+								if <bool as #codec::Decode>::decode(input)? {
+									let (tx, response) = #replay;
+									(Some(tx), Some(response))
+								} else {
+									(None, None)
+								};
+						});
+						response = quote! { response };
+					} else {
+						encode.push(quote! {
+							let tx = #binding;
+							let (#binding, response) = #intercept;
+						});
+						decode.push(quote! { let (#binding, response) = #replay; });
+						response = quote! { Some(response) };
+					}
+				},
+			}
+		}
+
+		encode_arms.push(quote! {
+			#constructor => {
+				#(#encode)*
+				(#constructor, #response)
+			}
+		});
+		decode_arms.push(quote! {
+			#index => {
+				#(#decode)*
+				Ok((#constructor, #response))
+			}
+		});
+	}
+
+	let invalid_variant = format!("Invalid variant of `{}`", name);
+	let decode_body = if data.variants.is_empty() {
+		let no_variants = format!("`{}` has no variants", name);
+		quote! { Err(#no_variants.into()) }
+	} else {
+		quote! {
+			match <u8 as #codec::Decode>::decode(input)? {
+				#(#decode_arms)*
+				_ => Err(#invalid_variant.into()),
+			}
+		}
+	};
+
+	Ok(quote! {
+		impl #impl_generics #support_crate::recordable::RecordableMessage for #name #ty_generics
+			#where_clause
+		{
+			fn encode_recorded(
+				self,
+				dest: &mut Vec<u8>,
+			) -> (Self, Option<#support_crate::recordable::EncodedResponse>) {
+				match self {
+					#(#encode_arms)*
+				}
+			}
+
+			fn decode_replayed<I: #codec::Input>(
+				input: &mut I,
+			) -> Result<
+				(Self, Option<#support_crate::recordable::EncodedResponse>),
+				#codec::Error,
+			> {
+				#decode_body
+			}
+		}
+	})
+}
+
+fn field_kind(field: &syn::Field) -> Result<FieldKind> {
+	let mut with = None;
+	let mut encode_with = None;
+	let mut response = false;
+	for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("recordable")) {
+		let list = match attr.parse_meta()? {
+			Meta::List(list) => list,
+			meta => return Err(Error::new(meta.span(), "Expected `#[recordable(..)]`")),
+		};
+		for nested in list.nested {
+			match nested {
+				NestedMeta::Meta(Meta::NameValue(name_value)) => {
+					let path = match &name_value.lit {
+						Lit::Str(path) => path.parse::<Path>()?,
+						lit => return Err(Error::new(lit.span(), "Expected a path as a string")),
+					};
+					if name_value.path.is_ident("with") {
+						with = Some(path);
+					} else if name_value.path.is_ident("encode_response_with") {
+						encode_with = Some(path);
+					} else {
+						return Err(Error::new(name_value.path.span(), "Unknown attribute"))
+					}
+				},
+				NestedMeta::Meta(Meta::Path(path)) if path.is_ident("response") => response = true,
+				nested => return Err(Error::new(nested.span(), "Unknown attribute")),
+			}
+		}
+	}
+
+	let channel = if response { Some(false) } else { response_channel(&field.ty) };
+	match channel {
+		Some(optional) if with.is_none() => Ok(FieldKind::Response { optional, encode_with }),
+		None if encode_with.is_none() => Ok(FieldKind::Encoded { with }),
+		Some(_) => Err(Error::new(field.span(), "`with` does not apply to response channels")),
+		None => Err(Error::new(
+			field.span(),
+			"`encode_response_with` only applies to response channels",
+		)),
+	}
+}
+
+/// Whether the type is a response channel, and if so whether it is optional.
+fn response_channel(ty: &Type) -> Option<bool> {
+	let last_segment = match ty {
+		Type::Path(path) => path.path.segments.last()?,
+		_ => return None,
+	};
+
+	if last_segment.ident == "Sender" {
+		return Some(false)
+	}
+
+	match &last_segment.arguments {
+		PathArguments::AngleBracketed(args) if last_segment.ident == "Option" =>
+			match args.args.first()? {
+				GenericArgument::Type(inner) if response_channel(inner) == Some(false) =>
+					Some(true),
+				_ => None,
+			},
+		_ => None,
+	}
+}
+
+fn support_crate() -> TokenStream {
+	if cfg!(test) {
+		quote! {crate}
+	} else {
+		use proc_macro_crate::{crate_name, FoundCrate};
+		let crate_name = crate_name("polkadot-node-subsystem-types").expect(
+			"Support crate `polkadot-node-subsystem-types` is present in `Cargo.toml`. qed",
+		);
+		match crate_name {
+			FoundCrate::Itself => quote! {crate},
+			FoundCrate::Name(name) => Ident::new(&name, Span::call_site()).to_token_stream(),
+		}
+	}
+}
@@ -0,0 +1,61 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+
+use assert_matches::assert_matches;
+use quote::quote;
+
+#[test]
+fn smoke() {
+	assert_matches!(
+		impl_recordable_message(quote! {
+			enum Message {
+				Unit,
+				Aliased(#[recordable(response)] ResponseChannel<u32>),
+				Request(Hash, #[recordable(with = "usize_as_u64")] usize, oneshot::Sender<u32>),
+				OptionalRequest {
+					hash: Hash,
+					#[recordable(encode_response_with = "encode_map")]
+					tx: Option<oneshot::Sender<HashMap<u32, u32>>>,
+				},
+			}
+		}),
+		Ok(_)
+	);
+}
+
+#[test]
+fn empty_enum() {
+	assert_matches!(impl_recordable_message(quote! { enum Message {} }), Ok(_));
+}
+
+#[test]
+fn multiple_response_channels_are_rejected() {
+	assert_matches!(
+		impl_recordable_message(quote! {
+			enum Message {
+				Request(oneshot::Sender<u32>, oneshot::Sender<u32>),
+			}
+		}),
+		Err(_)
+	);
+}
+
+#[test]
+fn structs_are_rejected() {
+	assert_matches!(impl_recordable_message(quote! { struct Message(u32); }), Err(_));
+}
@@ -18,6 +18,7 @@
 
 use crate::JaegerError;
 use ::orchestra::OrchestraError as OverseerError;
+use parity_scale_codec::{Decode, Encode};
 
 /// A description of an error causing the runtime API request to be unservable.
 #[derive(thiserror::Error, Debug, Clone)]
@@ -42,7 +43,7 @@ pub enum RuntimeApiError {
 }
 
 /// A description of an error causing the chain API request to be unservable.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Encode, Decode)]
 pub struct ChainApiError {
 	msg: String,
 }
@@ -24,11 +24,13 @@
 
 use std::{fmt, sync::Arc};
 
+use parity_scale_codec::{Decode, Encode, Error as CodecError, Input, Output};
 pub use polkadot_primitives::v2::{BlockNumber, Hash};
 use smallvec::SmallVec;
 
 pub mod errors;
 pub mod messages;
+pub mod recordable;
 
 mod runtime_client;
 pub use runtime_client::RuntimeApiSubsystemClient;
@@ -43,7 +45,7 @@ pub use polkadot_node_jaeger as jaeger;
 const ACTIVE_LEAVES_SMALLVEC_CAPACITY: usize = 8;
 
 /// The status of an activated leaf.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
 pub enum LeafStatus {
 	/// A leaf is fresh when it's the first time the leaf has been encountered.
 	/// Most leaves should be fresh.
@@ -87,6 +89,27 @@ pub struct ActivatedLeaf {
 	pub span: Arc<jaeger::Span>,
 }
 
+/// Encodes the leaf without its span, e.g. for recording the signals sent to a subsystem.
+impl Encode for ActivatedLeaf {
+	fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+		self.hash.encode_to(dest);
+		self.number.encode_to(dest);
+		self.status.encode_to(dest);
+	}
+}
+
+/// Decodes a leaf with a disabled span.
+impl Decode for ActivatedLeaf {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+		Ok(ActivatedLeaf {
+			hash: Decode::decode(input)?,
+			number: Decode::decode(input)?,
+			status: Decode::decode(input)?,
+			span: Arc::new(jaeger::Span::Disabled),
+		})
+	}
+}
+
 /// Changes in the set of active leaves: the parachain heads which we care to work on.
 ///
 /// Note that the activated and deactivated fields indicate deltas, not complete sets.
@@ -115,6 +138,21 @@ impl ActiveLeavesUpdate {
 	}
 }
 
+impl Encode for ActiveLeavesUpdate {
+	fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+		self.activated.encode_to(dest);
+		self.deactivated.as_slice().encode_to(dest);
+	}
+}
+
+impl Decode for ActiveLeavesUpdate {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+		let activated = Decode::decode(input)?;
+		let deactivated = Vec::<Hash>::decode(input)?;
+		Ok(ActiveLeavesUpdate { activated, deactivated: deactivated.into() })
+	}
+}
+
 impl PartialEq for ActiveLeavesUpdate {
 	/// Equality for `ActiveLeavesUpdate` doesn't imply bitwise equality.
 	///
@@ -136,7 +174,7 @@ impl fmt::Debug for ActiveLeavesUpdate {
 }
 
 /// Signals sent by an overseer to a subsystem.
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Encode, Decode)]
 pub enum OverseerSignal {
 	/// Subsystems should adjust their jobs to start and stop work on appropriate block hashes.
 	ActiveLeaves(ActiveLeavesUpdate),
@@ -22,7 +22,8 @@
 //!
 //! Subsystems' APIs are defined separately from their implementation, leading to easier mocking.
 
-use futures::channel::oneshot;
+use futures::channel::{mpsc, oneshot};
+use parity_scale_codec::{Decode, Encode, Output};
 use sc_network::Multiaddr;
 use thiserror::Error;
 
@@ -33,14 +34,17 @@ use polkadot_node_network_protocol::{
 	UnifiedReputationChange,
 };
 use polkadot_node_primitives::{
-	approval::{BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote},
-	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
-	CollationSecondedSignal, DisputeMessage, ErasureChunk, PoV, SignedDisputeStatement,
-	SignedFullStatement, ValidationResult,
+	approval::{
+		BlockApprovalMeta, DelayTranche, IndirectAssignmentCert, IndirectSignedApprovalVote,
+	},
+	AvailableData, BabeEpoch, BlockWeight, CandidateLifecycleEvent, CandidateVotes,
+	CollationGenerationConfig, CollationSecondedSignal, DisputeMessage, DisputeStatus,
+	ErasureChunk, PoV, PvfExecKind, SignedDisputeStatement, SignedFullStatement, Timestamp,
+	ValidationResult,
 };
 use polkadot_primitives::v2::{
 	AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateEvent, CandidateHash,
-	CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreState,
+	CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreIndex, CoreState,
 	DisputeState, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader, Id as ParaId,
 	InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet, OccupiedCoreAssumption,
 	PersistedValidationData, PvfCheckStatement, SessionIndex, SessionInfo,
@@ -54,6 +58,8 @@ use std::{
 	time::Duration,
 };
 
+use crate::recordable::RecordableMessage;
+
 /// Network events as transmitted to other subsystems, wrapped in their message types.
 pub mod network_bridge_event;
 pub use network_bridge_event::NetworkBridgeEvent;
@@ -137,6 +143,8 @@ pub enum CandidateValidationMessage {
 		Arc<PoV>,
 		/// Execution timeout
 		Duration,
+		/// What the candidate is validated for
+		PvfExecKind,
 		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
 	),
 	/// Validate a candidate with provided, exhaustive parameters for validation.
@@ -155,6 +163,8 @@ pub enum CandidateValidationMessage {
 		Arc<PoV>,
 		/// Execution timeout
 		Duration,
+		/// What the candidate is validated for
+		PvfExecKind,
 		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
 	),
 	/// Try to compile the given validation code and send back
@@ -174,8 +184,8 @@ impl CandidateValidationMessage {
 	/// If the current variant contains the relay parent hash, return it.
 	pub fn relay_parent(&self) -> Option<Hash> {
 		match self {
-			Self::ValidateFromChainState(_, _, _, _) => None,
-			Self::ValidateFromExhaustive(_, _, _, _, _, _) => None,
+			Self::ValidateFromChainState(_, _, _, _, _) => None,
+			Self::ValidateFromExhaustive(_, _, _, _, _, _, _) => None,
 			Self::PreCheck(relay_parent, _, _) => Some(*relay_parent),
 		}
 	}
@@ -228,7 +238,7 @@ impl BoundToRelayParent for CollatorProtocolMessage {
 ///
 /// NOTE: Any response oneshots might get cancelled if the `DisputeCoordinator` was not yet
 /// properly initialized for some reason.
-#[derive(Debug)]
+#[derive(Debug, RecordableMessage)]
 pub enum DisputeCoordinatorMessage {
 	/// Import statements by validators about a candidate.
 	///
@@ -251,6 +261,7 @@ pub enum DisputeCoordinatorMessage {
 		///
 		/// The validator index passed alongside each statement should correspond to the index
 		/// of the validator in the set.
+		#[recordable(with = "crate::recordable::dispute_statements")]
 		statements: Vec<(SignedDisputeStatement, ValidatorIndex)>,
 		/// Inform the requester once we finished importing (if a sender was provided).
 		///
@@ -268,10 +279,10 @@ pub enum DisputeCoordinatorMessage {
 		///		- or the imported statements are backing/approval votes, which are always accepted.
 		pending_confirmation: Option<oneshot::Sender<ImportStatementsResult>>,
 	},
-	/// Fetch a list of all recent disputes the co-ordinator is aware of.
+	/// Fetch a list of all recent disputes the co-ordinator is aware of, along with their status.
 	/// These are disputes which have occurred any time in recent sessions,
 	/// and which may have already concluded.
-	RecentDisputes(oneshot::Sender<Vec<(SessionIndex, CandidateHash)>>),
+	RecentDisputes(oneshot::Sender<Vec<(SessionIndex, CandidateHash, DisputeStatus)>>),
 	/// Fetch a list of all active disputes that the coordinator is aware of.
 	/// These disputes are either not yet concluded or recently concluded.
 	ActiveDisputes(oneshot::Sender<Vec<(SessionIndex, CandidateHash)>>),
@@ -300,7 +311,7 @@ pub enum DisputeCoordinatorMessage {
 }
 
 /// The result of `DisputeCoordinatorMessage::ImportStatements`.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Encode)]
 pub enum ImportStatementsResult {
 	/// Import was invalid (candidate was not available)  and the sending peer should get banned.
 	InvalidImport,
@@ -477,7 +488,7 @@ impl BitfieldDistributionMessage {
 /// Bitfield signing message.
 ///
 /// Currently non-instantiable.
-#[derive(Debug)]
+#[derive(Debug, RecordableMessage)]
 pub enum BitfieldSigningMessage {}
 
 impl BoundToRelayParent for BitfieldSigningMessage {
@@ -552,14 +563,14 @@ impl AvailabilityStoreMessage {
 pub type ChainApiResponseChannel<T> = oneshot::Sender<Result<T, crate::errors::ChainApiError>>;
 
 /// Chain API request subsystem message.
-#[derive(Debug)]
+#[derive(Debug, RecordableMessage)]
 pub enum ChainApiMessage {
 	/// Request the block number by hash.
 	/// Returns `None` if a block with the given hash is not present in the db.
-	BlockNumber(Hash, ChainApiResponseChannel<Option<BlockNumber>>),
+	BlockNumber(Hash, #[recordable(response)] ChainApiResponseChannel<Option<BlockNumber>>),
 	/// Request the block header by hash.
 	/// Returns `None` if a block with the given hash is not present in the db.
-	BlockHeader(Hash, ChainApiResponseChannel<Option<BlockHeader>>),
+	BlockHeader(Hash, #[recordable(response)] ChainApiResponseChannel<Option<BlockHeader>>),
 	/// Get the cumulative weight of the given block, by hash.
 	/// If the block or weight is unknown, this returns `None`.
 	///
@@ -567,14 +578,14 @@ pub enum ChainApiMessage {
 	/// not the high-level one implemented in the chain-selection subsystem.
 	///
 	/// Weight is used for comparing blocks in a fork-choice rule.
-	BlockWeight(Hash, ChainApiResponseChannel<Option<BlockWeight>>),
+	BlockWeight(Hash, #[recordable(response)] ChainApiResponseChannel<Option<BlockWeight>>),
 	/// Request the finalized block hash by number.
 	/// Returns `None` if a block with the given number is not present in the db.
 	/// Note: the caller must ensure the block is finalized.
-	FinalizedBlockHash(BlockNumber, ChainApiResponseChannel<Option<Hash>>),
+	FinalizedBlockHash(BlockNumber, #[recordable(response)] ChainApiResponseChannel<Option<Hash>>),
 	/// Request the last finalized block number.
 	/// This request always succeeds.
-	FinalizedBlockNumber(ChainApiResponseChannel<BlockNumber>),
+	FinalizedBlockNumber(#[recordable(response)] ChainApiResponseChannel<BlockNumber>),
 	/// Request the `k` ancestors block hashes of a block with the given hash.
 	/// The response channel may return a `Vec` of size up to `k`
 	/// filled with ancestors hashes with the following order:
@@ -584,8 +595,10 @@ pub enum ChainApiMessage {
 		/// The hash of the block in question.
 		hash: Hash,
 		/// The number of ancestors to request.
+		#[recordable(with = "crate::recordable::usize_as_u64")]
 		k: usize,
 		/// The response channel.
+		#[recordable(response)]
 		response_channel: ChainApiResponseChannel<Vec<Hash>>,
 	},
 }
@@ -607,6 +620,70 @@ pub enum ChainSelectionMessage {
 	/// Request the best leaf containing the given block in its ancestry. Return `None` if
 	/// there is no such leaf.
 	BestLeafContaining(Hash, oneshot::Sender<Option<Hash>>),
+	/// Request an explanation of the viability of the given block, along with the current
+	/// set of viable leaves.
+	Viability(Hash, oneshot::Sender<ViabilityReport>),
+}
+
+/// The approval state of a block, as tracked by the chain selection subsystem.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockApprovalState {
+	/// The block has been approved.
+	Approved,
+	/// The block has not been approved yet, but is not stagnant.
+	Unapproved,
+	/// The block has not been approved in time and is stagnant.
+	Stagnant {
+		/// The UNIX timestamp, in seconds, at which the block became stagnant. `None` for blocks
+		/// marked stagnant before the time was recorded.
+		since: Option<Timestamp>,
+	},
+}
+
+/// The viability of an unfinalized block, as determined by the chain selection subsystem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockViability {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// Whether the block can be built upon.
+	pub viable: bool,
+	/// The approval state of the block itself.
+	pub approval: BlockApprovalState,
+	/// Whether the block was explicitly reverted, because a dispute concluded against a
+	/// candidate included in it.
+	pub explicitly_reverted: bool,
+	/// The block carrying the revert digest, if the block was explicitly reverted and the
+	/// reverting block is known.
+	pub reverted_by: Option<Hash>,
+	/// The earliest ancestor which is reverted or stagnant and makes this block unviable.
+	/// Always `None` for the returned ancestor itself.
+	pub earliest_unviable_ancestor: Option<Box<BlockViability>>,
+	/// The earliest unfinalized ancestor which has not been approved yet, among the ancestors
+	/// inspected. Only a bounded number of the closest ancestors is inspected.
+	pub earliest_unapproved_ancestor: Option<Hash>,
+}
+
+/// A viable leaf of the chain selection subsystem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViableLeaf {
+	/// The hash of the leaf.
+	pub block_hash: Hash,
+	/// The number of the leaf.
+	pub block_number: BlockNumber,
+	/// The weight of the leaf, which determines its score.
+	pub weight: BlockWeight,
+}
+
+/// Response to `ChainSelectionMessage::Viability`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViabilityReport {
+	/// The viability of the requested block, or `None` if the block is unknown or finalized.
+	pub block: Option<BlockViability>,
+	/// The viable leaves, in descending order by score. Empty if the finalized block is the
+	/// only viable leaf.
+	pub leaves: Vec<ViableLeaf>,
 }
 
 impl ChainSelectionMessage {
@@ -619,6 +696,7 @@ impl ChainSelectionMessage {
 			ChainSelectionMessage::Approved(_) => None,
 			ChainSelectionMessage::Leaves(_) => None,
 			ChainSelectionMessage::BestLeafContaining(..) => None,
+			ChainSelectionMessage::Viability(..) => None,
 		}
 	}
 }
@@ -786,6 +864,11 @@ impl BoundToRelayParent for ProvisionerMessage {
 pub enum CollationGenerationMessage {
 	/// Initialize the collation generation subsystem
 	Initialize(CollationGenerationConfig),
+	/// Subscribe to the lifecycle events of the candidates built by this collator.
+	///
+	/// Only events recorded in relay chain blocks activated after subscribing are reported. The
+	/// subscription ends once the receiver is dropped.
+	SubscribeCandidateLifecycle(mpsc::UnboundedSender<CandidateLifecycleEvent>),
 }
 
 impl CollationGenerationMessage {
@@ -796,7 +879,7 @@ impl CollationGenerationMessage {
 }
 
 /// The result type of [`ApprovalVotingMessage::CheckAndImportAssignment`] request.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode)]
 pub enum AssignmentCheckResult {
 	/// The vote was accepted and should be propagated onwards.
 	Accepted,
@@ -809,7 +892,7 @@ pub enum AssignmentCheckResult {
 }
 
 /// The error result type of [`ApprovalVotingMessage::CheckAndImportAssignment`] request.
-#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq, Encode)]
 #[allow(missing_docs)]
 pub enum AssignmentCheckError {
 	#[error("Unknown block: {0:?}")]
@@ -827,7 +910,7 @@ pub enum AssignmentCheckError {
 }
 
 /// The result type of [`ApprovalVotingMessage::CheckAndImportApproval`] request.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Encode)]
 pub enum ApprovalCheckResult {
 	/// The vote was accepted and should be propagated onwards.
 	Accepted,
@@ -836,7 +919,7 @@ pub enum ApprovalCheckResult {
 }
 
 /// The error result type of [`ApprovalVotingMessage::CheckAndImportApproval`] request.
-#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq, Encode)]
 #[allow(missing_docs)]
 pub enum ApprovalCheckError {
 	#[error("Unknown block: {0:?}")]
@@ -859,7 +942,7 @@ pub enum ApprovalCheckError {
 
 /// Describes a relay-chain block by the para-chain candidates
 /// it includes.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Encode, Decode)]
 pub struct BlockDescription {
 	/// The relay-chain block hash.
 	pub block_hash: Hash,
@@ -870,7 +953,7 @@ pub struct BlockDescription {
 }
 
 /// Response type to `ApprovalVotingMessage::ApprovedAncestor`.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Encode)]
 pub struct HighestApprovedAncestorBlock {
 	/// The block hash of the highest viable ancestor.
 	pub hash: Hash,
@@ -884,7 +967,7 @@ pub struct HighestApprovedAncestorBlock {
 }
 
 /// Message to the Approval Voting subsystem.
-#[derive(Debug)]
+#[derive(Debug, RecordableMessage)]
 pub enum ApprovalVotingMessage {
 	/// Check if the assignment is valid and can be accepted by our view of the protocol.
 	/// Should not be sent unless the block hash is known.
@@ -913,8 +996,126 @@ pub enum ApprovalVotingMessage {
 	/// requires calling into `approval-distribution`: Calls should be infrequent and bounded.
 	GetApprovalSignaturesForCandidate(
 		CandidateHash,
+		#[recordable(encode_response_with = "crate::recordable::encode_map")]
 		oneshot::Sender<HashMap<ValidatorIndex, ValidatorSignature>>,
 	),
+
+	/// Retrieve the approval state of every candidate included in the given block, as computed
+	/// at the current tick. Returns `None` if the block is unknown.
+	InspectBlock(Hash, oneshot::Sender<Option<BlockApprovalStatus>>),
+}
+
+/// The required tranches of assignments needed to determine whether a candidate is approved.
+///
+/// Ticks are half-second intervals since the Unix epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequiredTranches {
+	/// All validators appear to be required, based on tranches already taken and remaining
+	/// no-shows.
+	All,
+	/// More tranches required - We're awaiting more assignments.
+	Pending {
+		/// The highest considered delay tranche when counting assignments.
+		considered: DelayTranche,
+		/// The tick at which the next no-show, of the assignments counted, would occur.
+		next_no_show: Option<u64>,
+		/// The highest tranche to consider when looking to broadcast own assignment.
+		maximum_broadcast: DelayTranche,
+		/// The clock drift, in ticks, applied to the local clock to cover no-shows.
+		clock_drift: u64,
+	},
+	/// An exact number of required tranches and a number of no-shows.
+	Exact {
+		/// The tranche to inspect up to.
+		needed: DelayTranche,
+		/// The amount of missing votes that should be tolerated.
+		tolerated_missing: usize,
+		/// When the next no-show would be, if any.
+		next_no_show: Option<u64>,
+		/// The last tick at which a needed assignment was received.
+		last_assignment_tick: Option<u64>,
+	},
+}
+
+/// Encodes `tolerated_missing` as a `u64`, as `usize` has no encoding.
+impl Encode for RequiredTranches {
+	fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+		match self {
+			RequiredTranches::All => 0u8.encode_to(dest),
+			RequiredTranches::Pending {
+				considered,
+				next_no_show,
+				maximum_broadcast,
+				clock_drift,
+			} => {
+				1u8.encode_to(dest);
+				considered.encode_to(dest);
+				next_no_show.encode_to(dest);
+				maximum_broadcast.encode_to(dest);
+				clock_drift.encode_to(dest);
+			},
+			RequiredTranches::Exact {
+				needed,
+				tolerated_missing,
+				next_no_show,
+				last_assignment_tick,
+			} => {
+				2u8.encode_to(dest);
+				needed.encode_to(dest);
+				(*tolerated_missing as u64).encode_to(dest);
+				next_no_show.encode_to(dest);
+				last_assignment_tick.encode_to(dest);
+			},
+		}
+	}
+}
+
+/// The assignments received for a delay tranche.
+#[derive(Debug, Clone, PartialEq, Encode)]
+pub struct TrancheAssignments {
+	/// The delay tranche.
+	pub tranche: DelayTranche,
+	/// The assigned validators, along with the tick the assignment was received at.
+	pub assignments: Vec<(ValidatorIndex, u64)>,
+}
+
+/// The approval state of a candidate under a block.
+#[derive(Debug, Clone, PartialEq, Encode)]
+pub struct CandidateApprovalStatus {
+	/// The hash of the candidate.
+	pub candidate_hash: CandidateHash,
+	/// The para the candidate is for.
+	pub para_id: ParaId,
+	/// The core the candidate occupies.
+	pub core_index: CoreIndex,
+	/// Whether the candidate is approved under the block.
+	pub approved: bool,
+	/// The required tranches computed at the current tick.
+	pub required_tranches: RequiredTranches,
+	/// The assignments received for the candidate under the block, by tranche.
+	pub tranches: Vec<TrancheAssignments>,
+	/// The validators which approved the candidate.
+	pub approvals: Vec<ValidatorIndex>,
+	/// The assigned validators in the considered tranches which have not approved in time, with
+	/// the clock drift of covering no-shows applied.
+	pub no_shows: Vec<ValidatorIndex>,
+}
+
+/// Response type to `ApprovalVotingMessage::InspectBlock`.
+#[derive(Debug, Clone, PartialEq, Encode)]
+pub struct BlockApprovalStatus {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// The session of the block.
+	pub session: SessionIndex,
+	/// The current delay tranche of the block.
+	pub tranche_now: DelayTranche,
+	/// Whether all candidates included in the block are approved.
+	pub approved: bool,
+	/// The candidates included in the block.
+	pub candidates: Vec<CandidateApprovalStatus>,
 }
 
 /// Message to the Approval Distribution subsystem.
@@ -947,6 +1148,35 @@ pub enum GossipSupportMessage {
 	/// Dummy constructor, so we can receive networking events.
 	#[from]
 	NetworkBridgeUpdate(NetworkBridgeEvent<net_protocol::GossipSupportNetworkMessage>),
+	/// Request a report on our connectivity to the authorities we asked the network to connect
+	/// to.
+	ConnectivityReport(oneshot::Sender<ConnectivityReport>),
+}
+
+/// Our connectivity to a single authority, as tracked by the gossip support subsystem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorityConnectivity {
+	/// The authority discovery key of the authority.
+	pub authority: AuthorityDiscoveryId,
+	/// The peer the authority is connected as, `None` if it is not connected.
+	pub connected_peer: Option<PeerId>,
+	/// The time elapsed since the authority was last connected. Zero while it is connected and
+	/// `None` if it was never connected since the node started.
+	pub since_last_connected: Option<Duration>,
+	/// The addresses of the authority, as resolved through authority discovery on the last
+	/// connection request. `None` if they could not be resolved or the authority was not part
+	/// of that request.
+	pub resolved_addresses: Option<Vec<Multiaddr>>,
+}
+
+/// Response to `GossipSupportMessage::ConnectivityReport`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectivityReport {
+	/// The current session, if known yet.
+	pub session: Option<SessionIndex>,
+	/// The authorities we asked to connect to, in the order of the request, followed by any
+	/// other authorities we are connected to.
+	pub authorities: Vec<AuthorityConnectivity>,
 }
 
 /// PVF checker message.
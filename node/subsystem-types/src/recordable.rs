@@ -0,0 +1,172 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Encoding of subsystem messages for recordings.
+//!
+//! Requests carry the channel to respond on, which can't be encoded. Instead, recording a
+//! request puts a new channel in its place and records the response on its way to the
+//! requester. Replaying it puts a new channel in place as well, such that the response of the
+//! replayed subsystem can be compared with the recorded one.
+//!
+//! Message types implement [`RecordableMessage`] with the derive macro of the same name. The
+//! variants are encoded by their index, so recordings can only be replayed by a node built with
+//! the same message types.
+
+use futures::{channel::oneshot, future::BoxFuture, FutureExt};
+use parity_scale_codec::{Decode, Encode, Error as CodecError, Input};
+use std::collections::HashMap;
+
+pub use polkadot_node_subsystem_types_proc_macro::RecordableMessage;
+
+#[doc(hidden)]
+pub use parity_scale_codec as codec;
+
+/// Resolves to the SCALE-encoded response to a request, or `None` if it was never answered.
+pub type EncodedResponse = BoxFuture<'static, Option<Vec<u8>>>;
+
+/// A message which can be recorded and replayed.
+pub trait RecordableMessage: Sized {
+	/// Encode the message into `dest`.
+	///
+	/// Returns the message to deliver in place of this one, along with its response if it
+	/// carries a request.
+	fn encode_recorded(self, dest: &mut Vec<u8>) -> (Self, Option<EncodedResponse>);
+
+	/// Decode a recorded message.
+	///
+	/// Returns the message along with the response of the subsystem it gets delivered to, if it
+	/// carries a request.
+	fn decode_replayed<I: Input>(
+		input: &mut I,
+	) -> Result<(Self, Option<EncodedResponse>), CodecError>;
+}
+
+/// Create a channel to put in place of `tx`, whose response is encoded and forwarded to `tx`.
+///
+/// The response is only forwarded while the returned future is polled.
+pub fn intercept_response<T: Encode + Send + 'static>(
+	tx: oneshot::Sender<T>,
+) -> (oneshot::Sender<T>, EncodedResponse) {
+	intercept_response_with(tx, T::encode)
+}
+
+/// The same as [`intercept_response`], but encodes the response with `encode`.
+pub fn intercept_response_with<T: Send + 'static>(
+	tx: oneshot::Sender<T>,
+	encode: fn(&T) -> Vec<u8>,
+) -> (oneshot::Sender<T>, EncodedResponse) {
+	let (interceptor, rx) = oneshot::channel();
+	let response = async move {
+		let response = rx.await.ok()?;
+		let encoded = encode(&response);
+		let _ = tx.send(response);
+		Some(encoded)
+	};
+	(interceptor, response.boxed())
+}
+
+/// Create a channel for a replayed request, along with its encoded response.
+pub fn replay_response<T: Encode + Send + 'static>() -> (oneshot::Sender<T>, EncodedResponse) {
+	replay_response_with(T::encode)
+}
+
+/// The same as [`replay_response`], but encodes the response with `encode`.
+pub fn replay_response_with<T: Send + 'static>(
+	encode: fn(&T) -> Vec<u8>,
+) -> (oneshot::Sender<T>, EncodedResponse) {
+	let (tx, rx) = oneshot::channel();
+	(tx, rx.map(move |response| response.ok().map(|response| encode(&response))).boxed())
+}
+
+/// Encode a map sorted by its keys, such that the encoding does not depend on the order of
+/// iteration.
+pub fn encode_map<K: Encode + Ord, V: Encode>(map: &HashMap<K, V>) -> Vec<u8> {
+	let mut entries: Vec<_> = map.iter().collect();
+	entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+	entries.encode()
+}
+
+/// Record a `usize` as a `u64`.
+pub mod usize_as_u64 {
+	use super::*;
+
+	/// Encode the `usize` as a `u64`.
+	pub fn encode(value: &usize, dest: &mut Vec<u8>) {
+		(*value as u64).encode_to(dest)
+	}
+
+	/// Decode a `u64` into a `usize`.
+	pub fn decode<I: Input>(input: &mut I) -> Result<usize, CodecError> {
+		usize::try_from(u64::decode(input)?).map_err(|_| "`usize` out of range".into())
+	}
+}
+
+/// Record dispute statements along with the index of their validator.
+///
+/// The statements were checked before they got recorded, so they are trusted when replayed.
+pub mod dispute_statements {
+	use super::*;
+	use polkadot_node_primitives::SignedDisputeStatement;
+	use polkadot_primitives::v2::{
+		CandidateHash, DisputeStatement, SessionIndex, ValidatorId, ValidatorIndex,
+		ValidatorSignature,
+	};
+
+	type Encoded = (
+		(DisputeStatement, CandidateHash, ValidatorId, ValidatorSignature, SessionIndex),
+		ValidatorIndex,
+	);
+
+	/// Encode the statements along with their validator index.
+	pub fn encode(statements: &[(SignedDisputeStatement, ValidatorIndex)], dest: &mut Vec<u8>) {
+		let encoded: Vec<Encoded> = statements
+			.iter()
+			.map(|(statement, index)| {
+				(
+					(
+						statement.statement().clone(),
+						*statement.candidate_hash(),
+						statement.validator_public().clone(),
+						statement.validator_signature().clone(),
+						statement.session_index(),
+					),
+					*index,
+				)
+			})
+			.collect();
+		encoded.encode_to(dest)
+	}
+
+	/// Decode the statements along with their validator index.
+	pub fn decode<I: Input>(
+		input: &mut I,
+	) -> Result<Vec<(SignedDisputeStatement, ValidatorIndex)>, CodecError> {
+		let encoded = Vec::<Encoded>::decode(input)?;
+		Ok(encoded
+			.into_iter()
+			.map(|((statement, candidate_hash, public, signature, session), index)| {
+				let statement = SignedDisputeStatement::new_unchecked_from_trusted_source(
+					statement,
+					candidate_hash,
+					session,
+					public,
+					signature,
+				);
+				(statement, index)
+			})
+			.collect())
+	}
+}
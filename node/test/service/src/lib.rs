@@ -102,6 +102,10 @@ pub fn new_full(
 		false,
 		polkadot_service::RealOverseerGen,
 		None,
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		Default::default(),
 		None,
 		None,
 	)
@@ -66,6 +66,10 @@ fn main() -> Result<()> {
 					false,
 					polkadot_service::RealOverseerGen,
 					None,
+					Default::default(),
+					Default::default(),
+					Default::default(),
+					Default::default(),
 					None,
 					None,
 				)
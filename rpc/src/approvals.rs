@@ -0,0 +1,251 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPCs exposing the approval voting state of a block, to find out which validators are holding
+//! back finality.
+//!
+//! These methods are unsafe, as they leak the local view on the approval process.
+
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::{error::CallError, ErrorObject},
+};
+use polkadot_node_subsystem_types::messages::{self, ApprovalVotingMessage};
+use polkadot_overseer::Handle;
+use polkadot_primitives::v2::{BlockNumber, Hash, SessionIndex};
+use sc_rpc::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+
+use crate::subsystem;
+
+/// Error code returned when the approval voting subsystem could not answer a request.
+const APPROVAL_VOTING_ERROR: i32 = 4;
+
+/// The required tranches of assignments needed to determine whether a candidate is approved.
+///
+/// Ticks are half-second intervals since the Unix epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum RequiredTranches {
+	/// All validators appear to be required.
+	All,
+	/// More assignments are awaited.
+	#[serde(rename_all = "camelCase")]
+	Pending {
+		/// The highest considered delay tranche.
+		considered: u32,
+		/// The tick at which the next no-show would occur.
+		next_no_show: Option<u64>,
+		/// The highest tranche to consider when broadcasting an own assignment.
+		maximum_broadcast: u32,
+		/// The clock drift, in ticks, applied to cover no-shows.
+		clock_drift: u64,
+	},
+	/// Enough assignments are known and all no-shows are covered.
+	#[serde(rename_all = "camelCase")]
+	Exact {
+		/// The tranche to inspect up to.
+		needed: u32,
+		/// The amount of missing votes tolerated.
+		tolerated_missing: u32,
+		/// The tick at which the next no-show would occur.
+		next_no_show: Option<u64>,
+		/// The last tick at which a needed assignment was received.
+		last_assignment_tick: Option<u64>,
+	},
+}
+
+impl From<messages::RequiredTranches> for RequiredTranches {
+	fn from(required: messages::RequiredTranches) -> Self {
+		match required {
+			messages::RequiredTranches::All => RequiredTranches::All,
+			messages::RequiredTranches::Pending {
+				considered,
+				next_no_show,
+				maximum_broadcast,
+				clock_drift,
+			} => RequiredTranches::Pending {
+				considered,
+				next_no_show,
+				maximum_broadcast,
+				clock_drift,
+			},
+			messages::RequiredTranches::Exact {
+				needed,
+				tolerated_missing,
+				next_no_show,
+				last_assignment_tick,
+			} => RequiredTranches::Exact {
+				needed,
+				tolerated_missing: tolerated_missing as u32,
+				next_no_show,
+				last_assignment_tick,
+			},
+		}
+	}
+}
+
+/// An assignment of a validator to check a candidate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Assignment {
+	/// The index of the validator in the session.
+	pub validator_index: u32,
+	/// The tick at which the assignment was received.
+	pub tick: u64,
+}
+
+/// The assignments of a delay tranche.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tranche {
+	/// The delay tranche.
+	pub tranche: u32,
+	/// The assignments received for the tranche.
+	pub assignments: Vec<Assignment>,
+}
+
+/// The approval state of a candidate included in a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateApproval {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The para the candidate is for.
+	pub para_id: u32,
+	/// The core the candidate occupies.
+	pub core_index: u32,
+	/// Whether the candidate is approved under the block.
+	pub approved: bool,
+	/// The required tranches, computed at the current tick.
+	pub required_tranches: RequiredTranches,
+	/// The assignments received, by tranche.
+	pub tranches: Vec<Tranche>,
+	/// The indices of the validators which approved the candidate.
+	pub approvals: Vec<u32>,
+	/// The indices of the assigned validators which have not approved in time, as considered by
+	/// approval checking.
+	pub no_shows: Vec<u32>,
+}
+
+/// The approval state of a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockApproval {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The number of the block.
+	pub number: BlockNumber,
+	/// The session of the block.
+	pub session: SessionIndex,
+	/// The current delay tranche of the block.
+	pub tranche_now: u32,
+	/// Whether all candidates included in the block are approved.
+	pub approved: bool,
+	/// The candidates included in the block.
+	pub candidates: Vec<CandidateApproval>,
+}
+
+impl From<messages::BlockApprovalStatus> for BlockApproval {
+	fn from(status: messages::BlockApprovalStatus) -> Self {
+		let candidates = status
+			.candidates
+			.into_iter()
+			.map(|candidate| CandidateApproval {
+				candidate_hash: candidate.candidate_hash.0,
+				para_id: candidate.para_id.into(),
+				core_index: candidate.core_index.0,
+				approved: candidate.approved,
+				required_tranches: candidate.required_tranches.into(),
+				tranches: candidate
+					.tranches
+					.into_iter()
+					.map(|tranche| Tranche {
+						tranche: tranche.tranche,
+						assignments: tranche
+							.assignments
+							.into_iter()
+							.map(|(validator, tick)| Assignment {
+								validator_index: validator.0,
+								tick,
+							})
+							.collect(),
+					})
+					.collect(),
+				approvals: candidate.approvals.into_iter().map(|v| v.0).collect(),
+				no_shows: candidate.no_shows.into_iter().map(|v| v.0).collect(),
+			})
+			.collect();
+
+		BlockApproval {
+			hash: status.block_hash,
+			number: status.block_number,
+			session: status.session,
+			tranche_now: status.tranche_now,
+			approved: status.approved,
+			candidates,
+		}
+	}
+}
+
+/// Approval voting inspection RPC methods.
+#[rpc(server, namespace = "parachain")]
+pub trait ApprovalsApi {
+	/// Returns the assignments, approvals and no-shows of every candidate included in the given
+	/// block, along with the required tranches at the current tick. Returns `None` if the block
+	/// is unknown to approval voting, e.g. because it is finalized already.
+	///
+	/// This method is unsafe.
+	#[method(name = "blockApprovalStatus")]
+	async fn block_approval_status(&self, hash: Hash) -> RpcResult<Option<BlockApproval>>;
+}
+
+/// Implements the [`ApprovalsApiServer`] RPC trait on top of the overseer.
+pub struct Approvals {
+	overseer: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Approvals {
+	/// Create a new `Approvals` RPC handler.
+	pub fn new(overseer: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer, deny_unsafe }
+	}
+}
+
+fn approval_voting_error(reason: &str) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		APPROVAL_VOTING_ERROR,
+		"Approval voting request failed",
+		Some(reason),
+	))
+	.into()
+}
+
+#[async_trait]
+impl ApprovalsApiServer for Approvals {
+	async fn block_approval_status(&self, hash: Hash) -> RpcResult<Option<BlockApproval>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let status =
+			subsystem::request(&self.overseer, |tx| ApprovalVotingMessage::InspectBlock(hash, tx))
+				.await
+				.map_err(approval_voting_error)?;
+
+		Ok(status.map(Into::into))
+	}
+}
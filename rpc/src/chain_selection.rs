@@ -0,0 +1,240 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPCs explaining why the chain selection subsystem considers a block viable or not.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::{error::CallError, ErrorObject},
+};
+use polkadot_node_subsystem_types::messages::{self, ChainSelectionMessage};
+use polkadot_overseer::Handle;
+use polkadot_primitives::{
+	runtime_api::ParachainHost,
+	v2::{Block, BlockNumber, DisputeStatement, Hash, SessionIndex},
+};
+use sc_rpc::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_runtime::generic::BlockId;
+
+use crate::subsystem;
+
+/// Error code returned when the chain selection subsystem could not answer a request.
+const CHAIN_SELECTION_ERROR: i32 = 3;
+
+/// The approval state of a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "state")]
+pub enum ApprovalState {
+	/// The block has been approved.
+	Approved,
+	/// The block has not been approved yet, but is not stagnant.
+	Unapproved,
+	/// The block has not been approved in time.
+	Stagnant {
+		/// The UNIX timestamp, in seconds, at which the block became stagnant, if known.
+		since: Option<u64>,
+	},
+}
+
+impl From<messages::BlockApprovalState> for ApprovalState {
+	fn from(state: messages::BlockApprovalState) -> Self {
+		match state {
+			messages::BlockApprovalState::Approved => ApprovalState::Approved,
+			messages::BlockApprovalState::Unapproved => ApprovalState::Unapproved,
+			messages::BlockApprovalState::Stagnant { since } => ApprovalState::Stagnant { since },
+		}
+	}
+}
+
+/// A dispute which might have caused the reversion of a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertingDispute {
+	/// The session the disputed candidate was included in.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+}
+
+/// The viability of an unfinalized block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockViability {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The number of the block.
+	pub number: BlockNumber,
+	/// Whether the block can be built upon.
+	pub viable: bool,
+	/// The approval state of the block itself.
+	pub approval: ApprovalState,
+	/// Whether the block was reverted, because a dispute concluded against a candidate included
+	/// in it.
+	pub reverted: bool,
+	/// The block carrying the revert digest, if known.
+	pub reverted_by: Option<Hash>,
+	/// The disputes included in the reverting block with votes against their candidate. One of
+	/// them concluded against its candidate and caused the reversion. Empty if the state of the
+	/// reverting block is not available anymore.
+	pub reverting_disputes: Vec<RevertingDispute>,
+	/// The earliest reverted or stagnant ancestor, which makes this block unviable.
+	pub unviable_ancestor: Option<Box<BlockViability>>,
+	/// The earliest unfinalized ancestor which has not been approved yet, among the closest
+	/// ancestors.
+	pub unapproved_ancestor: Option<Hash>,
+}
+
+/// A leaf which can be built upon.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViableLeaf {
+	/// The hash of the leaf.
+	pub hash: Hash,
+	/// The number of the leaf.
+	pub number: BlockNumber,
+	/// The weight of the leaf, which determines its score.
+	pub weight: u32,
+}
+
+/// The viability of a block along with the current viable leaves.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViabilityReport {
+	/// The viability of the requested block, `None` if the block is unknown or finalized.
+	pub block: Option<BlockViability>,
+	/// The viable leaves in descending order by score. Empty if the finalized block is the only
+	/// viable leaf.
+	pub leaves: Vec<ViableLeaf>,
+}
+
+impl From<messages::ViableLeaf> for ViableLeaf {
+	fn from(leaf: messages::ViableLeaf) -> Self {
+		ViableLeaf { hash: leaf.block_hash, number: leaf.block_number, weight: leaf.weight }
+	}
+}
+
+/// Chain selection inspection RPC methods.
+#[rpc(server, namespace = "parachain")]
+pub trait ChainSelectionApi {
+	/// Returns why the given block is viable or not for building upon, along with the current
+	/// viable leaves.
+	///
+	/// This method is unsafe.
+	#[method(name = "blockViability")]
+	async fn block_viability(&self, hash: Hash) -> RpcResult<ViabilityReport>;
+}
+
+/// Implements the [`ChainSelectionApiServer`] RPC trait on top of the overseer.
+///
+/// The disputes which caused a reversion are looked up in the runtime here rather than by the
+/// chain selection subsystem, which must not be held up by inspection requests.
+pub struct ChainSelection<C> {
+	client: Arc<C>,
+	overseer: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C> ChainSelection<C> {
+	/// Create a new `ChainSelection` RPC handler.
+	pub fn new(client: Arc<C>, overseer: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { client, overseer, deny_unsafe }
+	}
+}
+
+impl<C> ChainSelection<C>
+where
+	C: ProvideRuntimeApi<Block>,
+	C::Api: ParachainHost<Block>,
+{
+	fn block_viability(&self, block: messages::BlockViability) -> BlockViability {
+		let reverting_disputes =
+			block.reverted_by.map_or_else(Vec::new, |hash| self.reverting_disputes(hash));
+
+		BlockViability {
+			hash: block.block_hash,
+			number: block.block_number,
+			viable: block.viable,
+			approval: block.approval.into(),
+			reverted: block.explicitly_reverted,
+			reverted_by: block.reverted_by,
+			reverting_disputes,
+			unviable_ancestor: block
+				.earliest_unviable_ancestor
+				.map(|ancestor| Box::new(self.block_viability(*ancestor))),
+			unapproved_ancestor: block.earliest_unapproved_ancestor,
+		}
+	}
+
+	// The disputes included in the reverting block which have votes against their candidate.
+	// The runtime reverts a block when one of its disputes concludes against its candidate.
+	fn reverting_disputes(&self, reverted_by: Hash) -> Vec<RevertingDispute> {
+		// The state of the reverting block might have been pruned already.
+		let votes = match self.client.runtime_api().on_chain_votes(&BlockId::Hash(reverted_by)) {
+			Ok(votes) => votes,
+			Err(_) => return Vec::new(),
+		};
+
+		votes
+			.into_iter()
+			.flat_map(|votes| votes.disputes)
+			.filter(|dispute| {
+				dispute
+					.statements
+					.iter()
+					.any(|(statement, _, _)| matches!(statement, DisputeStatement::Invalid(_)))
+			})
+			.map(|dispute| RevertingDispute {
+				session: dispute.session,
+				candidate_hash: dispute.candidate_hash.0,
+			})
+			.collect()
+	}
+}
+
+fn chain_selection_error(reason: &str) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		CHAIN_SELECTION_ERROR,
+		"Chain selection request failed",
+		Some(reason),
+	))
+	.into()
+}
+
+#[async_trait]
+impl<C> ChainSelectionApiServer for ChainSelection<C>
+where
+	C: ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	C::Api: ParachainHost<Block>,
+{
+	async fn block_viability(&self, hash: Hash) -> RpcResult<ViabilityReport> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let report =
+			subsystem::request(&self.overseer, |tx| ChainSelectionMessage::Viability(hash, tx))
+				.await
+				.map_err(chain_selection_error)?;
+
+		Ok(ViabilityReport {
+			block: report.block.map(|block| self.block_viability(block)),
+			leaves: report.leaves.into_iter().map(Into::into).collect(),
+		})
+	}
+}
@@ -0,0 +1,145 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPCs reporting which authorities the node can reach, as tracked by the gossip support
+//! subsystem. They are unsafe, as they leak the network addresses of authorities.
+
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::{error::CallError, ErrorObject},
+};
+use polkadot_node_subsystem_types::messages::{self, GossipSupportMessage};
+use polkadot_overseer::Handle;
+use polkadot_primitives::v2::SessionIndex;
+use sc_rpc::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+
+use crate::subsystem;
+
+/// Error code returned when the gossip support subsystem could not answer a request.
+const GOSSIP_SUPPORT_ERROR: i32 = 5;
+
+/// Our connectivity to a single authority.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorityConnectivity {
+	/// The SS58 encoded authority discovery key of the authority.
+	pub authority: String,
+	/// Whether we are currently connected to the authority.
+	pub connected: bool,
+	/// The peer the authority is connected as, if connected.
+	pub peer_id: Option<String>,
+	/// The seconds elapsed since the authority was last connected, 0 while it is connected.
+	/// `None` if it was never connected since the node started.
+	pub seconds_since_connected: Option<u64>,
+	/// Whether the addresses of the authority were resolved via authority discovery.
+	pub resolved: bool,
+	/// The resolved addresses of the authority.
+	pub addresses: Vec<String>,
+}
+
+impl From<messages::AuthorityConnectivity> for AuthorityConnectivity {
+	fn from(connectivity: messages::AuthorityConnectivity) -> Self {
+		AuthorityConnectivity {
+			authority: connectivity.authority.to_string(),
+			connected: connectivity.connected_peer.is_some(),
+			peer_id: connectivity.connected_peer.map(|peer| peer.to_string()),
+			seconds_since_connected: connectivity.since_last_connected.map(|since| since.as_secs()),
+			resolved: connectivity.resolved_addresses.is_some(),
+			addresses: connectivity
+				.resolved_addresses
+				.unwrap_or_default()
+				.iter()
+				.map(ToString::to_string)
+				.collect(),
+		}
+	}
+}
+
+/// Our connectivity to the authorities of the past, present and future sessions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityReport {
+	/// The current session, if known yet.
+	pub session: Option<SessionIndex>,
+	/// The number of authorities we are connected to.
+	pub connected: u32,
+	/// The number of authorities whose addresses were resolved.
+	pub resolved: u32,
+	/// The authorities we asked to connect to, followed by any other connected authorities.
+	pub authorities: Vec<AuthorityConnectivity>,
+}
+
+impl From<messages::ConnectivityReport> for ConnectivityReport {
+	fn from(report: messages::ConnectivityReport) -> Self {
+		let authorities: Vec<AuthorityConnectivity> =
+			report.authorities.into_iter().map(Into::into).collect();
+
+		ConnectivityReport {
+			session: report.session,
+			connected: authorities.iter().filter(|a| a.connected).count() as u32,
+			resolved: authorities.iter().filter(|a| a.resolved).count() as u32,
+			authorities,
+		}
+	}
+}
+
+/// Authority connectivity RPC methods.
+#[rpc(server, namespace = "parachain")]
+pub trait ConnectivityApi {
+	/// Returns the connection state of every authority the node asked the network to connect
+	/// to, when each was last connected and whether its addresses were resolved.
+	///
+	/// This method is unsafe.
+	#[method(name = "authorityConnectivity")]
+	async fn authority_connectivity(&self) -> RpcResult<ConnectivityReport>;
+}
+
+/// Implements the [`ConnectivityApiServer`] RPC trait on top of the overseer.
+pub struct Connectivity {
+	overseer: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Connectivity {
+	/// Create a new `Connectivity` RPC handler.
+	pub fn new(overseer: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer, deny_unsafe }
+	}
+}
+
+fn gossip_support_error(reason: &str) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		GOSSIP_SUPPORT_ERROR,
+		"Gossip support request failed",
+		Some(reason),
+	))
+	.into()
+}
+
+#[async_trait]
+impl ConnectivityApiServer for Connectivity {
+	async fn authority_connectivity(&self) -> RpcResult<ConnectivityReport> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let report = subsystem::request(&self.overseer, GossipSupportMessage::ConnectivityReport)
+			.await
+			.map_err(gossip_support_error)?;
+
+		Ok(report.into())
+	}
+}
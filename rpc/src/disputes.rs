@@ -0,0 +1,293 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPCs exposing what the local dispute coordinator knows about recent disputes.
+//!
+//! Unlike the `parachain_disputes` method, which returns the disputes recorded on-chain, these
+//! methods query the node's own dispute coordinator through the overseer. They are unsafe, as
+//! they leak how the node voted.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use futures::channel::oneshot;
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::{error::CallError, ErrorObject},
+};
+use polkadot_node_primitives::{CandidateVotes, DisputeStatus, Timestamp};
+use polkadot_node_subsystem_types::messages::DisputeCoordinatorMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::{
+	runtime_api::ParachainHost,
+	v2::{Block, CandidateHash, Hash, SessionIndex, ValidatorId, ValidatorIndex},
+};
+use sc_rpc::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_application_crypto::AppKey;
+use sp_blockchain::HeaderBackend;
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+use sp_runtime::generic::BlockId;
+
+use crate::{parachain::fetch_session_info, subsystem};
+
+/// Error code returned when the dispute coordinator could not answer a request.
+const DISPUTE_COORDINATOR_ERROR: i32 = 2;
+
+/// The status of a dispute, as tracked by the local dispute coordinator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum LocalDisputeStatus {
+	/// The dispute is active and unconcluded.
+	Active,
+	/// The dispute has been confirmed, but not concluded yet.
+	Confirmed,
+	/// The dispute concluded in favor of the candidate.
+	#[serde(rename_all = "camelCase")]
+	ConcludedFor {
+		/// The UNIX timestamp, in seconds, at which the dispute concluded.
+		concluded_at: Timestamp,
+	},
+	/// The dispute concluded against the candidate.
+	#[serde(rename_all = "camelCase")]
+	ConcludedAgainst {
+		/// The UNIX timestamp, in seconds, at which the dispute concluded.
+		concluded_at: Timestamp,
+	},
+}
+
+impl From<DisputeStatus> for LocalDisputeStatus {
+	fn from(status: DisputeStatus) -> Self {
+		match status {
+			DisputeStatus::Active => LocalDisputeStatus::Active,
+			DisputeStatus::Confirmed => LocalDisputeStatus::Confirmed,
+			DisputeStatus::ConcludedFor(concluded_at) =>
+				LocalDisputeStatus::ConcludedFor { concluded_at },
+			DisputeStatus::ConcludedAgainst(concluded_at) =>
+				LocalDisputeStatus::ConcludedAgainst { concluded_at },
+		}
+	}
+}
+
+/// A vote cast by one of the validator keys of this node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnVote {
+	/// The index of our validator in the session.
+	pub validator_index: u32,
+	/// Whether we voted for the validity of the candidate.
+	pub valid: bool,
+	/// The kind of statement the vote stems from, e.g. backing, approval or explicit.
+	pub kind: String,
+}
+
+/// How this node took part in a dispute.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Participation {
+	/// None of the keys of this node are parachain validators in the session of the dispute.
+	NotAValidator,
+	/// We have not voted yet, but the dispute is still open.
+	Pending,
+	/// We have cast a vote.
+	Voted,
+	/// The dispute concluded without our vote.
+	Missed,
+}
+
+/// A dispute as seen by the local dispute coordinator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalDispute {
+	/// The session the disputed candidate was included in.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The para the disputed candidate is for, if the candidate is known.
+	pub para_id: Option<u32>,
+	/// The status of the dispute.
+	pub status: LocalDisputeStatus,
+	/// The number of votes for the validity of the candidate.
+	pub valid_votes: u32,
+	/// The number of votes against the validity of the candidate.
+	pub invalid_votes: u32,
+	/// The votes cast by this node.
+	pub own_votes: Vec<OwnVote>,
+	/// How this node took part in the dispute.
+	pub participation: Participation,
+}
+
+/// Dispute coordinator inspection RPC methods.
+#[rpc(server, namespace = "parachain")]
+pub trait DisputesApi {
+	/// Returns all recent disputes known to the local dispute coordinator, along with their
+	/// status, the vote counts and the votes of this node.
+	///
+	/// This method is unsafe.
+	#[method(name = "localDisputes")]
+	async fn local_disputes(&self) -> RpcResult<Vec<LocalDispute>>;
+}
+
+/// Implements the [`DisputesApiServer`] RPC trait on top of the overseer.
+pub struct Disputes<C> {
+	client: Arc<C>,
+	overseer: Handle,
+	keystore: SyncCryptoStorePtr,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C> Disputes<C> {
+	/// Create a new `Disputes` RPC handler.
+	pub fn new(
+		client: Arc<C>,
+		overseer: Handle,
+		keystore: SyncCryptoStorePtr,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self { client, overseer, keystore, deny_unsafe }
+	}
+
+	/// Send a request to the dispute coordinator and wait for the response.
+	async fn request<T>(
+		&self,
+		make_msg: impl FnOnce(oneshot::Sender<T>) -> DisputeCoordinatorMessage,
+	) -> RpcResult<T> {
+		subsystem::request(&self.overseer, make_msg)
+			.await
+			.map_err(dispute_coordinator_error)
+	}
+}
+
+impl<C> Disputes<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: ParachainHost<Block>,
+{
+	/// Returns the indices of the validators of the given session we hold the keys of, or `None`
+	/// if the session is unknown to the runtime.
+	fn own_validator_indices(
+		&self,
+		session: SessionIndex,
+	) -> RpcResult<Option<Vec<ValidatorIndex>>> {
+		let at = BlockId::Hash(self.client.info().best_hash);
+		let info = match fetch_session_info(&*self.client, &at, session)? {
+			Some(info) => info,
+			None => return Ok(None),
+		};
+		Ok(Some(
+			info.validators
+				.iter()
+				.enumerate()
+				.filter(|(_, validator)| {
+					SyncCryptoStore::has_keys(
+						&*self.keystore,
+						&[(AsRef::<[u8]>::as_ref(*validator).to_vec(), ValidatorId::ID)],
+					)
+				})
+				.map(|(index, _)| ValidatorIndex(index as u32))
+				.collect(),
+		))
+	}
+}
+
+fn dispute_coordinator_error(reason: &str) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		DISPUTE_COORDINATOR_ERROR,
+		"Dispute coordinator request failed",
+		Some(reason),
+	))
+	.into()
+}
+
+fn own_votes(votes: &CandidateVotes, own_indices: &[ValidatorIndex]) -> Vec<OwnVote> {
+	let mut own_votes = Vec::new();
+	for index in own_indices {
+		if let Some((kind, _)) = votes.valid.get(index) {
+			own_votes.push(OwnVote {
+				validator_index: index.0,
+				valid: true,
+				kind: format!("{:?}", kind),
+			});
+		}
+		if let Some((kind, _)) = votes.invalid.get(index) {
+			own_votes.push(OwnVote {
+				validator_index: index.0,
+				valid: false,
+				kind: format!("{:?}", kind),
+			});
+		}
+	}
+	own_votes
+}
+
+#[async_trait]
+impl<C> DisputesApiServer for Disputes<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ParachainHost<Block>,
+{
+	async fn local_disputes(&self) -> RpcResult<Vec<LocalDispute>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let recent = self.request(DisputeCoordinatorMessage::RecentDisputes).await?;
+		let query: Vec<_> = recent
+			.iter()
+			.map(|(session, candidate_hash, _)| (*session, *candidate_hash))
+			.collect();
+		let mut votes: BTreeMap<(SessionIndex, CandidateHash), CandidateVotes> = self
+			.request(|tx| DisputeCoordinatorMessage::QueryCandidateVotes(query, tx))
+			.await?
+			.into_iter()
+			.map(|(session, candidate_hash, votes)| ((session, candidate_hash), votes))
+			.collect();
+
+		let mut own_indices_per_session = BTreeMap::new();
+		let mut disputes = Vec::with_capacity(recent.len());
+		for (session, candidate_hash, status) in recent {
+			if !own_indices_per_session.contains_key(&session) {
+				let indices = self.own_validator_indices(session)?.unwrap_or_default();
+				own_indices_per_session.insert(session, indices);
+			}
+			let own_indices = &own_indices_per_session[&session];
+			let votes = votes.remove(&(session, candidate_hash));
+			let own_votes = votes.as_ref().map_or_else(Vec::new, |v| own_votes(v, own_indices));
+
+			let participation = if own_indices.is_empty() {
+				Participation::NotAValidator
+			} else if !own_votes.is_empty() {
+				Participation::Voted
+			} else if status.concluded_at().is_some() {
+				Participation::Missed
+			} else {
+				Participation::Pending
+			};
+
+			disputes.push(LocalDispute {
+				session,
+				candidate_hash: candidate_hash.0,
+				para_id: votes.as_ref().map(|v| v.candidate_receipt.descriptor.para_id.into()),
+				status: status.into(),
+				valid_votes: votes.as_ref().map_or(0, |v| v.valid.len() as u32),
+				invalid_votes: votes.as_ref().map_or(0, |v| v.invalid.len() as u32),
+				own_votes,
+				participation,
+			});
+		}
+
+		Ok(disputes)
+	}
+}
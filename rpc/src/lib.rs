@@ -22,7 +22,7 @@ use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
 use polkadot_primitives::v2::{AccountId, Balance, Block, BlockNumber, Hash, Nonce};
-use sc_client_api::AuxStore;
+use sc_client_api::{AuxStore, BlockchainEvents};
 use sc_consensus_babe::Epoch;
 use sc_finality_grandpa::FinalityProofProvider;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
@@ -34,6 +34,13 @@ use sp_consensus_babe::BabeApi;
 use sp_keystore::SyncCryptoStorePtr;
 use txpool_api::TransactionPool;
 
+pub mod approvals;
+pub mod chain_selection;
+pub mod connectivity;
+pub mod disputes;
+pub mod parachain;
+mod subsystem;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = RpcModule<()>;
 
@@ -90,6 +97,9 @@ pub struct FullDeps<C, P, SC, B> {
 	pub grandpa: GrandpaDeps<B>,
 	/// BEEFY specific dependencies.
 	pub beefy: BeefyDeps,
+	/// A handle to the overseer, used by the RPCs querying the node's subsystems. These RPCs are
+	/// not available if `None`.
+	pub overseer_handle: Option<polkadot_overseer::Handle>,
 }
 
 /// Instantiate all RPC extensions.
@@ -101,6 +111,7 @@ where
 	C: ProvideRuntimeApi<Block>
 		+ HeaderBackend<Block>
 		+ AuxStore
+		+ BlockchainEvents<Block>
 		+ HeaderMetadata<Block, Error = BlockChainError>
 		+ Send
 		+ Sync
@@ -110,23 +121,38 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: polkadot_primitives::runtime_api::ParachainHost<Block>,
 	P: TransactionPool + Sync + Send + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
+	use approvals::{Approvals, ApprovalsApiServer};
 	use beefy_gadget_rpc::{Beefy, BeefyApiServer};
+	use chain_selection::{ChainSelection, ChainSelectionApiServer};
+	use connectivity::{Connectivity, ConnectivityApiServer};
+	use disputes::{Disputes, DisputesApiServer};
 	use frame_rpc_system::{System, SystemApiServer};
 	use pallet_mmr_rpc::{Mmr, MmrApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
+	use parachain::{Parachain, ParachainApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
 	use sc_finality_grandpa_rpc::{Grandpa, GrandpaApiServer};
 	use sc_sync_state_rpc::{SyncState, SyncStateApiServer};
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
 
 	let mut io = RpcModule::new(());
-	let FullDeps { client, pool, select_chain, chain_spec, deny_unsafe, babe, grandpa, beefy } =
-		deps;
+	let FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		deny_unsafe,
+		babe,
+		grandpa,
+		beefy,
+		overseer_handle,
+	} = deps;
 	let BabeDeps { keystore, babe_config, shared_epoch_changes } = babe;
 	let GrandpaDeps {
 		shared_voter_state,
@@ -140,6 +166,20 @@ where
 	io.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
 	io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 	io.merge(Mmr::new(client.clone()).into_rpc())?;
+	io.merge(
+		Parachain::new(client.clone(), subscription_executor.clone(), deny_unsafe).into_rpc(),
+	)?;
+	if let Some(overseer_handle) = overseer_handle {
+		io.merge(Approvals::new(overseer_handle.clone(), deny_unsafe).into_rpc())?;
+		io.merge(
+			ChainSelection::new(client.clone(), overseer_handle.clone(), deny_unsafe).into_rpc(),
+		)?;
+		io.merge(Connectivity::new(overseer_handle.clone(), deny_unsafe).into_rpc())?;
+		io.merge(
+			Disputes::new(client.clone(), overseer_handle, keystore.clone(), deny_unsafe)
+				.into_rpc(),
+		)?;
+	}
 	io.merge(
 		Babe::new(
 			client.clone(),
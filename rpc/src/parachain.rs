@@ -0,0 +1,626 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPCs for inspecting the parachains state of the relay chain.
+//!
+//! All of the data is fetched through the `ParachainHost` runtime API at the requested block, or
+//! at the best block if none is given, and is returned in a JSON-friendly form.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use futures::{future, FutureExt, StreamExt};
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::{error::CallError, ErrorObject, SubscriptionResult},
+	SubscriptionSink,
+};
+use polkadot_primitives::{
+	runtime_api::ParachainHost,
+	v2::{self as primitives, Block, BlockNumber, CoreState, Hash, SessionIndex},
+};
+use sc_client_api::BlockchainEvents;
+use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::generic::BlockId;
+
+/// Error code returned when a `ParachainHost` runtime API call fails.
+const RUNTIME_API_ERROR: i32 = 1;
+
+/// Error code returned when the runtime API needed by a method is not available.
+const UNSUPPORTED_ERROR: i32 = 6;
+
+/// The state of an availability core.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "state")]
+pub enum CoreInfo {
+	/// The core is occupied by a candidate pending availability.
+	#[serde(rename_all = "camelCase")]
+	Occupied {
+		/// The para occupying the core.
+		para_id: u32,
+		/// The hash of the candidate occupying the core.
+		candidate_hash: Hash,
+		/// The group assigned to distribute availability pieces of the candidate.
+		group_responsible: u32,
+		/// The relay-chain block number at which the core got occupied.
+		occupied_since: BlockNumber,
+		/// The relay-chain block number at which the candidate times out.
+		time_out_at: BlockNumber,
+		/// The number of validators which have made the candidate available.
+		availability_votes: u32,
+		/// The para scheduled next if the candidate becomes available.
+		next_up_on_available: Option<u32>,
+		/// The para scheduled next if the candidate times out.
+		next_up_on_time_out: Option<u32>,
+	},
+	/// A para is scheduled on the core.
+	#[serde(rename_all = "camelCase")]
+	Scheduled {
+		/// The scheduled para.
+		para_id: u32,
+	},
+	/// The core is unassigned.
+	Free,
+}
+
+impl From<CoreState> for CoreInfo {
+	fn from(core: CoreState) -> Self {
+		match core {
+			CoreState::Occupied(core) => CoreInfo::Occupied {
+				para_id: core.para_id().into(),
+				candidate_hash: core.candidate_hash.0,
+				group_responsible: core.group_responsible.0,
+				occupied_since: core.occupied_since,
+				time_out_at: core.time_out_at,
+				availability_votes: core.availability.count_ones() as u32,
+				next_up_on_available: core.next_up_on_available.map(|c| c.para_id.into()),
+				next_up_on_time_out: core.next_up_on_time_out.map(|c| c.para_id.into()),
+			},
+			CoreState::Scheduled(core) => CoreInfo::Scheduled { para_id: core.para_id.into() },
+			CoreState::Free => CoreInfo::Free,
+		}
+	}
+}
+
+/// The validator groups along with the rotation info of the child of a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorGroups {
+	/// The validator indices making up each group.
+	pub groups: Vec<Vec<u32>>,
+	/// The block number at which the session started.
+	pub session_start_block: BlockNumber,
+	/// How often groups rotate. 0 means never.
+	pub group_rotation_frequency: BlockNumber,
+	/// The current block number.
+	pub now: BlockNumber,
+}
+
+/// A candidate pending availability.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingCandidate {
+	/// The para the candidate is for.
+	pub para_id: u32,
+	/// The core occupied by the candidate.
+	pub core_index: u32,
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The relay-chain block the candidate is built on.
+	pub relay_parent: Hash,
+	/// The hash of the candidate's PoV.
+	pub pov_hash: Hash,
+	/// The root of the erasure-coded chunks of the candidate's available data.
+	pub erasure_root: Hash,
+	/// The hash of the para head produced by the candidate.
+	pub para_head: Hash,
+	/// The hash of the validation code the candidate was validated with.
+	pub validation_code_hash: Hash,
+	/// The number of upward messages sent by the candidate.
+	pub upward_messages: u32,
+	/// The number of horizontal messages sent by the candidate.
+	pub horizontal_messages: u32,
+	/// The number of downward messages processed by the candidate.
+	pub processed_downward_messages: u32,
+	/// The mark which specifies the block number up to which all inbound HRMP messages are
+	/// processed.
+	pub hrmp_watermark: BlockNumber,
+	/// Whether the candidate schedules a validation code upgrade.
+	pub new_validation_code: bool,
+}
+
+/// A message in the downward or an inbound HRMP message queue of a para.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundMessage {
+	/// The relay-chain block number at which the message was sent.
+	pub sent_at: BlockNumber,
+	/// The message payload.
+	pub data: Bytes,
+}
+
+/// Information about a session, as recorded on-chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+	/// The index of the session.
+	pub session_index: SessionIndex,
+	/// The indices of the active validators in the session's full validator set.
+	pub active_validator_indices: Vec<u32>,
+	/// The public keys of the parachain validators.
+	pub validators: Vec<Bytes>,
+	/// The validator indices making up each group.
+	pub validator_groups: Vec<Vec<u32>>,
+	/// The number of availability cores.
+	pub n_cores: u32,
+	/// The zeroth delay tranche width.
+	pub zeroth_delay_tranche_width: u32,
+	/// The number of samples to do of the `RelayVRFModulo` approval assignment criterion.
+	pub relay_vrf_modulo_samples: u32,
+	/// The number of delay tranches in total.
+	pub n_delay_tranches: u32,
+	/// How many slots must pass before a no-show is considered.
+	pub no_show_slots: u32,
+	/// The number of validators needed to approve a block.
+	pub needed_approvals: u32,
+	/// How many sessions disputes remain open for.
+	pub dispute_period: SessionIndex,
+}
+
+impl SessionInfo {
+	fn new(session_index: SessionIndex, info: primitives::SessionInfo) -> Self {
+		SessionInfo {
+			session_index,
+			active_validator_indices: info
+				.active_validator_indices
+				.into_iter()
+				.map(|v| v.0)
+				.collect(),
+			validators: info
+				.validators
+				.iter()
+				.map(|v| Bytes(AsRef::<[u8]>::as_ref(v).to_vec()))
+				.collect(),
+			validator_groups: info
+				.validator_groups
+				.into_iter()
+				.map(|g| g.into_iter().map(|v| v.0).collect())
+				.collect(),
+			n_cores: info.n_cores,
+			zeroth_delay_tranche_width: info.zeroth_delay_tranche_width,
+			relay_vrf_modulo_samples: info.relay_vrf_modulo_samples,
+			n_delay_tranches: info.n_delay_tranches,
+			no_show_slots: info.no_show_slots,
+			needed_approvals: info.needed_approvals,
+			dispute_period: info.dispute_period,
+		}
+	}
+}
+
+/// A dispute recorded on-chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnChainDispute {
+	/// The session the disputed candidate was included in.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The number of validators which voted for the validity of the candidate.
+	pub validators_for: u32,
+	/// The number of validators which voted against the validity of the candidate.
+	pub validators_against: u32,
+	/// The relay-chain block number at which the dispute started.
+	pub start: BlockNumber,
+	/// The relay-chain block number at which the dispute concluded, if it did.
+	pub concluded_at: Option<BlockNumber>,
+}
+
+/// An event concerning a candidate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum CandidateEvent {
+	/// The candidate was backed.
+	#[serde(rename_all = "camelCase")]
+	Backed {
+		/// The hash of the candidate.
+		candidate_hash: Hash,
+		/// The para the candidate is for.
+		para_id: u32,
+		/// The core the candidate occupies.
+		core_index: u32,
+		/// The group which backed the candidate.
+		group_index: u32,
+	},
+	/// The candidate was included.
+	#[serde(rename_all = "camelCase")]
+	Included {
+		/// The hash of the candidate.
+		candidate_hash: Hash,
+		/// The para the candidate is for.
+		para_id: u32,
+		/// The core the candidate occupied.
+		core_index: u32,
+		/// The group which backed the candidate.
+		group_index: u32,
+	},
+	/// The candidate timed out while pending availability.
+	#[serde(rename_all = "camelCase")]
+	TimedOut {
+		/// The hash of the candidate.
+		candidate_hash: Hash,
+		/// The para the candidate is for.
+		para_id: u32,
+		/// The core the candidate occupied.
+		core_index: u32,
+	},
+}
+
+impl From<primitives::CandidateEvent> for CandidateEvent {
+	fn from(event: primitives::CandidateEvent) -> Self {
+		match event {
+			primitives::CandidateEvent::CandidateBacked(receipt, _, core, group) =>
+				CandidateEvent::Backed {
+					candidate_hash: receipt.hash().0,
+					para_id: receipt.descriptor.para_id.into(),
+					core_index: core.0,
+					group_index: group.0,
+				},
+			primitives::CandidateEvent::CandidateIncluded(receipt, _, core, group) =>
+				CandidateEvent::Included {
+					candidate_hash: receipt.hash().0,
+					para_id: receipt.descriptor.para_id.into(),
+					core_index: core.0,
+					group_index: group.0,
+				},
+			primitives::CandidateEvent::CandidateTimedOut(receipt, _, core) =>
+				CandidateEvent::TimedOut {
+					candidate_hash: receipt.hash().0,
+					para_id: receipt.descriptor.para_id.into(),
+					core_index: core.0,
+				},
+		}
+	}
+}
+
+/// The candidate events of an imported block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockCandidateEvents {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// Whether the block is the new best block.
+	pub is_new_best: bool,
+	/// The candidate events of the block.
+	pub events: Vec<CandidateEvent>,
+}
+
+/// Parachain inspection RPC methods.
+#[rpc(server, namespace = "parachain")]
+pub trait ParachainApi {
+	/// Returns the state of all availability cores as of the child of the given block.
+	#[method(name = "availabilityCores")]
+	fn availability_cores(&self, at: Option<Hash>) -> RpcResult<Vec<CoreInfo>>;
+
+	/// Returns the validator groups and the group rotation info as of the child of the given
+	/// block.
+	#[method(name = "validatorGroups")]
+	fn validator_groups(&self, at: Option<Hash>) -> RpcResult<ValidatorGroups>;
+
+	/// Returns all candidates pending availability.
+	#[method(name = "pendingCandidates")]
+	fn pending_candidates(&self, at: Option<Hash>) -> RpcResult<Vec<PendingCandidate>>;
+
+	/// Returns the pending messages in the downward message queue of a para.
+	#[method(name = "dmqContents")]
+	fn dmq_contents(&self, para_id: u32, at: Option<Hash>) -> RpcResult<Vec<InboundMessage>>;
+
+	/// Returns the contents of all HRMP channels addressed to a para, keyed by sender.
+	#[method(name = "inboundHrmpChannelsContents")]
+	fn inbound_hrmp_channels_contents(
+		&self,
+		para_id: u32,
+		at: Option<Hash>,
+	) -> RpcResult<BTreeMap<u32, Vec<InboundMessage>>>;
+
+	/// Returns the information about the given session, or about the session of the child of the
+	/// given block if no session is given.
+	#[method(name = "sessionInfo")]
+	fn session_info(
+		&self,
+		session_index: Option<SessionIndex>,
+		at: Option<Hash>,
+	) -> RpcResult<Option<SessionInfo>>;
+
+	/// Returns all disputes currently recorded on-chain.
+	///
+	/// This relies on the staging runtime API, so it is only available on nodes built with the
+	/// `staging-client` feature.
+	#[method(name = "disputes")]
+	fn disputes(&self, at: Option<Hash>) -> RpcResult<Vec<OnChainDispute>>;
+
+	/// Subscribes to the candidate events of every imported block.
+	///
+	/// This method is unsafe.
+	#[subscription(
+		name = "subscribeCandidateEvents" => "candidateEvents",
+		unsubscribe = "unsubscribeCandidateEvents",
+		item = BlockCandidateEvents
+	)]
+	fn subscribe_candidate_events(&self);
+}
+
+/// Implements the [`ParachainApiServer`] RPC trait for inspecting the parachains state.
+pub struct Parachain<C> {
+	client: Arc<C>,
+	executor: SubscriptionTaskExecutor,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C> Parachain<C> {
+	/// Create a new `Parachain` RPC handler.
+	pub fn new(
+		client: Arc<C>,
+		executor: SubscriptionTaskExecutor,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self { client, executor, deny_unsafe }
+	}
+}
+
+impl<C> Parachain<C>
+where
+	C: HeaderBackend<Block>,
+{
+	fn block_id(&self, at: Option<Hash>) -> BlockId<Block> {
+		BlockId::Hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+}
+
+pub(crate) fn runtime_error(err: impl std::fmt::Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		RUNTIME_API_ERROR,
+		"ParachainHost runtime API call failed",
+		Some(format!("{:?}", err)),
+	))
+	.into()
+}
+
+fn unsupported_error(err: impl std::fmt::Display) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		UNSUPPORTED_ERROR,
+		"ParachainHost runtime API not supported",
+		Some(err.to_string()),
+	))
+	.into()
+}
+
+/// Fetches the info of the given session, taking the version of the runtime API into account.
+pub(crate) fn fetch_session_info<C>(
+	client: &C,
+	at: &BlockId<Block>,
+	session_index: SessionIndex,
+) -> RpcResult<Option<primitives::SessionInfo>>
+where
+	C: ProvideRuntimeApi<Block>,
+	C::Api: ParachainHost<Block>,
+{
+	let api = client.runtime_api();
+	let api_version = api
+		.api_version::<dyn ParachainHost<Block>>(at)
+		.map_err(runtime_error)?
+		.unwrap_or_default();
+	if api_version >= 2 {
+		api.session_info(at, session_index).map_err(runtime_error)
+	} else {
+		#[allow(deprecated)]
+		api.session_info_before_version_2(at, session_index)
+			.map(|info| info.map(Into::into))
+			.map_err(runtime_error)
+	}
+}
+
+fn candidate_events<C>(client: &C, at: &BlockId<Block>) -> RpcResult<Vec<CandidateEvent>>
+where
+	C: ProvideRuntimeApi<Block>,
+	C::Api: ParachainHost<Block>,
+{
+	let events = client.runtime_api().candidate_events(at).map_err(runtime_error)?;
+	Ok(events.into_iter().map(Into::into).collect())
+}
+
+impl<C> ParachainApiServer for Parachain<C>
+where
+	C: ProvideRuntimeApi<Block>
+		+ HeaderBackend<Block>
+		+ BlockchainEvents<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: ParachainHost<Block>,
+{
+	fn availability_cores(&self, at: Option<Hash>) -> RpcResult<Vec<CoreInfo>> {
+		let at = self.block_id(at);
+		let cores = self.client.runtime_api().availability_cores(&at).map_err(runtime_error)?;
+		Ok(cores.into_iter().map(Into::into).collect())
+	}
+
+	fn validator_groups(&self, at: Option<Hash>) -> RpcResult<ValidatorGroups> {
+		let at = self.block_id(at);
+		let (groups, rotation_info) =
+			self.client.runtime_api().validator_groups(&at).map_err(runtime_error)?;
+		Ok(ValidatorGroups {
+			groups: groups.into_iter().map(|g| g.into_iter().map(|v| v.0).collect()).collect(),
+			session_start_block: rotation_info.session_start_block,
+			group_rotation_frequency: rotation_info.group_rotation_frequency,
+			now: rotation_info.now,
+		})
+	}
+
+	fn pending_candidates(&self, at: Option<Hash>) -> RpcResult<Vec<PendingCandidate>> {
+		let at = self.block_id(at);
+		let api = self.client.runtime_api();
+		let cores = api.availability_cores(&at).map_err(runtime_error)?;
+
+		let mut candidates = Vec::new();
+		for (core_index, core) in cores.into_iter().enumerate() {
+			let core = match core {
+				CoreState::Occupied(core) => core,
+				_ => continue,
+			};
+			let receipt = match api
+				.candidate_pending_availability(&at, core.para_id())
+				.map_err(runtime_error)?
+			{
+				Some(receipt) => receipt,
+				None => continue,
+			};
+			let descriptor = &receipt.descriptor;
+			let commitments = &receipt.commitments;
+			candidates.push(PendingCandidate {
+				para_id: descriptor.para_id.into(),
+				core_index: core_index as u32,
+				candidate_hash: core.candidate_hash.0,
+				relay_parent: descriptor.relay_parent,
+				pov_hash: descriptor.pov_hash,
+				erasure_root: descriptor.erasure_root,
+				para_head: descriptor.para_head,
+				validation_code_hash: Hash::from_slice(descriptor.validation_code_hash.as_ref()),
+				upward_messages: commitments.upward_messages.len() as u32,
+				horizontal_messages: commitments.horizontal_messages.len() as u32,
+				processed_downward_messages: commitments.processed_downward_messages,
+				hrmp_watermark: commitments.hrmp_watermark,
+				new_validation_code: commitments.new_validation_code.is_some(),
+			});
+		}
+		Ok(candidates)
+	}
+
+	fn dmq_contents(&self, para_id: u32, at: Option<Hash>) -> RpcResult<Vec<InboundMessage>> {
+		let at = self.block_id(at);
+		let messages = self
+			.client
+			.runtime_api()
+			.dmq_contents(&at, para_id.into())
+			.map_err(runtime_error)?;
+		Ok(messages
+			.into_iter()
+			.map(|m| InboundMessage { sent_at: m.sent_at, data: Bytes(m.msg) })
+			.collect())
+	}
+
+	fn inbound_hrmp_channels_contents(
+		&self,
+		para_id: u32,
+		at: Option<Hash>,
+	) -> RpcResult<BTreeMap<u32, Vec<InboundMessage>>> {
+		let at = self.block_id(at);
+		let channels = self
+			.client
+			.runtime_api()
+			.inbound_hrmp_channels_contents(&at, para_id.into())
+			.map_err(runtime_error)?;
+		Ok(channels
+			.into_iter()
+			.map(|(sender, messages)| {
+				let messages = messages
+					.into_iter()
+					.map(|m| InboundMessage { sent_at: m.sent_at, data: Bytes(m.data) })
+					.collect();
+				(sender.into(), messages)
+			})
+			.collect())
+	}
+
+	fn session_info(
+		&self,
+		session_index: Option<SessionIndex>,
+		at: Option<Hash>,
+	) -> RpcResult<Option<SessionInfo>> {
+		let at = self.block_id(at);
+		let session_index = match session_index {
+			Some(session_index) => session_index,
+			None =>
+				self.client.runtime_api().session_index_for_child(&at).map_err(runtime_error)?,
+		};
+
+		let info = fetch_session_info(&*self.client, &at, session_index)?;
+		Ok(info.map(|info| SessionInfo::new(session_index, info)))
+	}
+
+	fn disputes(&self, at: Option<Hash>) -> RpcResult<Vec<OnChainDispute>> {
+		let at = self.block_id(at);
+		let api = self.client.runtime_api();
+		// `staging_get_disputes` is not meant to be called on production runtimes, which only
+		// stub it out. Just like the provisioner, only call it from staging clients.
+		if !cfg!(feature = "staging-client") {
+			return Err(unsupported_error("on-chain disputes require the staging-client feature"))
+		}
+		let api_version = api
+			.api_version::<dyn ParachainHost<Block>>(&at)
+			.map_err(runtime_error)?
+			.unwrap_or_default();
+		if api_version < 2 {
+			return Err(unsupported_error(format!(
+				"on-chain disputes require ParachainHost version 2, the runtime has {}",
+				api_version
+			)))
+		}
+
+		let disputes = api.staging_get_disputes(&at).map_err(runtime_error)?;
+		Ok(disputes
+			.into_iter()
+			.map(|(session, candidate_hash, state)| OnChainDispute {
+				session,
+				candidate_hash: candidate_hash.0,
+				validators_for: state.validators_for.count_ones() as u32,
+				validators_against: state.validators_against.count_ones() as u32,
+				start: state.start,
+				concluded_at: state.concluded_at,
+			})
+			.collect())
+	}
+
+	fn subscribe_candidate_events(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+		// Every subscriber costs a runtime call per imported block.
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			let _ = sink.reject(JsonRpseeError::from(err));
+			return Ok(())
+		}
+
+		let client = self.client.clone();
+		let stream = self.client.import_notification_stream().filter_map(move |notification| {
+			let events = candidate_events(&*client, &BlockId::Hash(notification.hash))
+				.map(|events| BlockCandidateEvents {
+					block_hash: notification.hash,
+					is_new_best: notification.is_new_best,
+					events,
+				})
+				.ok();
+			future::ready(events)
+		});
+
+		let fut = async move {
+			sink.pipe_from_stream(stream).await;
+		};
+
+		self.executor.spawn("polkadot-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+}
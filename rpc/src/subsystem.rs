@@ -0,0 +1,56 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Requests to the node's subsystems, sent through the overseer.
+
+use std::time::Duration;
+
+use futures::{
+	channel::oneshot,
+	future::{self, Either},
+	pin_mut,
+};
+use futures_timer::Delay;
+use polkadot_overseer::{AllMessages, Handle};
+
+/// How long to wait for a subsystem to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Send a request to a subsystem and wait for the response.
+///
+/// Fails with the reason if the request was dropped or timed out.
+pub(crate) async fn request<M, T>(
+	overseer: &Handle,
+	make_msg: impl FnOnce(oneshot::Sender<T>) -> M,
+) -> Result<T, &'static str>
+where
+	M: Into<AllMessages>,
+{
+	let (tx, rx) = oneshot::channel();
+	let mut overseer = overseer.clone();
+	let request = async move {
+		overseer.send_msg(make_msg(tx), "polkadot-rpc").await;
+		rx.await
+	};
+	let timeout = Delay::new(REQUEST_TIMEOUT);
+	pin_mut!(request);
+
+	match future::select(request, timeout).await {
+		Either::Left((Ok(response), _)) => Ok(response),
+		Either::Left((Err(oneshot::Canceled), _)) => Err("request dropped by the subsystem"),
+		Either::Right(_) => Err("request timed out"),
+	}
+}
@@ -0,0 +1,391 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An implementation of the `PunishValidators` trait used by `disputes` that reports the
+//! validators on the losing side of a dispute as offenders, e.g. to `pallet-offences`, which
+//! in turn passes them on to `pallet-staking` to be slashed.
+//!
+//! Disputes may conclude a while after the candidate was included, so the offenders need to be
+//! identified as of the session the dispute is about rather than the current one. Offences are
+//! not reported at all for sessions which are no longer known, either because the account keys of
+//! the session have already been pruned or because the session is past the history kept by the
+//! identification source. Staking additionally ignores offences older than the bonding duration.
+//!
+//! Validators taking part in disputes which don't conclude are not punished at all: they might
+//! well be honest, and even an offence without slash would get them chilled by staking.
+//!
+//! Offences are reported while processing disputes, so the weight of reporting them, including
+//! the weight of slashing the offenders, is registered with `frame_system` on the spot.
+
+use crate::{
+	disputes::PunishValidators,
+	session_info::{self, AccountId, IdentificationTuple},
+};
+use frame_support::{
+	traits::{Get, ValidatorSetWithIdentification},
+	weights::{constants::WEIGHT_PER_MICROS, DispatchClass, Weight},
+};
+use parity_scale_codec::{Decode, Encode};
+use primitives::v2::{CandidateHash, SessionIndex, ValidatorIndex};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Convert, Perbill, RuntimeDebug};
+use sp_staking::offence::{Kind, Offence, OffenceError, ReportOffence};
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &str = "runtime::disputes::slashing";
+
+/// The full identification of a validator, as used by the validator set of the session info
+/// pallet.
+pub type Identification<T> =
+	<<T as session_info::Config>::ValidatorSet as ValidatorSetWithIdentification<
+		<T as frame_system::Config>::AccountId,
+	>>::Identification;
+
+/// The time slot of a dispute offence.
+///
+/// Validators can only commit one offence of each kind per disputed candidate, so the candidate
+/// together with the session it was disputed in is used to tell offences apart.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub struct DisputesTimeSlot {
+	/// The session the dispute is about.
+	pub session_index: SessionIndex,
+	/// The disputed candidate.
+	pub candidate_hash: CandidateHash,
+}
+
+/// A kind of offence committed in a dispute.
+pub trait DisputeOffenceKind {
+	/// The identifier of the offence, as reported to `pallet-offences`.
+	const ID: Kind;
+	/// The fraction of the stake of each offender to slash.
+	const SLASH_FRACTION: Perbill;
+}
+
+/// Voting for an invalid candidate, e.g. by backing it.
+pub struct ForInvalid;
+
+impl DisputeOffenceKind for ForInvalid {
+	const ID: Kind = *b"disputes:for-inv";
+	const SLASH_FRACTION: Perbill = Perbill::from_percent(100);
+}
+
+/// Voting against a valid candidate.
+pub struct AgainstValid;
+
+impl DisputeOffenceKind for AgainstValid {
+	const ID: Kind = *b"disputes:agn-val";
+	const SLASH_FRACTION: Perbill = Perbill::from_percent(1);
+}
+
+/// An offence committed by the validators on the losing side of a dispute.
+#[derive(RuntimeDebug)]
+pub struct DisputeOffence<Offender, K> {
+	/// The session and candidate of the dispute.
+	pub time_slot: DisputesTimeSlot,
+	/// The number of validators in the session.
+	pub validator_set_count: u32,
+	/// The validators on the losing side of the dispute.
+	pub offenders: Vec<Offender>,
+	_kind: PhantomData<K>,
+}
+
+impl<Offender, K> DisputeOffence<Offender, K> {
+	/// Create a new dispute offence.
+	pub fn new(
+		session_index: SessionIndex,
+		candidate_hash: CandidateHash,
+		validator_set_count: u32,
+		offenders: Vec<Offender>,
+	) -> Self {
+		Self {
+			time_slot: DisputesTimeSlot { session_index, candidate_hash },
+			validator_set_count,
+			offenders,
+			_kind: PhantomData,
+		}
+	}
+}
+
+impl<Offender: Clone, K: DisputeOffenceKind> Offence<Offender> for DisputeOffence<Offender, K> {
+	const ID: Kind = K::ID;
+	type TimeSlot = DisputesTimeSlot;
+
+	fn offenders(&self) -> Vec<Offender> {
+		self.offenders.clone()
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.time_slot.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.time_slot.clone()
+	}
+
+	fn slash_fraction(_offenders_count: u32, _validator_set_count: u32) -> Perbill {
+		K::SLASH_FRACTION
+	}
+}
+
+/// Identifies validators as of a past session, so they can be reported as offenders.
+pub trait IdentifyValidators<ValidatorId, Identification> {
+	/// Returns the full identifications of the given validators as of the given session, or
+	/// `None` if the session is too old for them to be known.
+	fn identify(
+		session_index: SessionIndex,
+		validators: Vec<ValidatorId>,
+	) -> Option<Vec<(ValidatorId, Identification)>>;
+
+	/// Returns the weight of identifying and then slashing the given offenders.
+	///
+	/// This is called without any offenders if the validators could not be identified.
+	fn weight(offenders: &[(ValidatorId, Identification)]) -> Weight;
+}
+
+/// The weight of reporting a single offender to `pallet-offences` and of `pallet-staking`
+/// slashing them and the given number of nominators backing them.
+///
+/// This follows the estimate `pallet-grandpa` uses for reporting equivocations.
+fn slash_weight<T: frame_system::Config>(nominators: u32) -> Weight {
+	let nominators = nominators as Weight;
+	(110 * WEIGHT_PER_MICROS)
+		.saturating_add((25 * WEIGHT_PER_MICROS).saturating_mul(nominators))
+		.saturating_add(T::DbWeight::get().reads(14 + 3 * nominators))
+		.saturating_add(T::DbWeight::get().writes(10 + 3 * nominators))
+}
+
+/// Identifies validators by their staking exposure in the era the session belongs to.
+///
+/// Sessions of eras beyond the staking history depth can't be identified.
+pub struct StakingExposures<T>(PhantomData<T>);
+
+impl<T: pallet_staking::Config>
+	IdentifyValidators<
+		T::AccountId,
+		pallet_staking::Exposure<T::AccountId, pallet_staking::BalanceOf<T>>,
+	> for StakingExposures<T>
+{
+	fn identify(
+		session_index: SessionIndex,
+		validators: Vec<T::AccountId>,
+	) -> Option<
+		Vec<(T::AccountId, pallet_staking::Exposure<T::AccountId, pallet_staking::BalanceOf<T>>)>,
+	> {
+		let active_era = pallet_staking::Pallet::<T>::active_era()?.index;
+		let oldest_era = active_era.saturating_sub(pallet_staking::Pallet::<T>::history_depth());
+
+		// The start sessions of eras are only kept for the history depth, so if the session is
+		// older than the oldest known era start, the era it belongs to is unknown.
+		let era = (oldest_era..=active_era).rev().find(|era| {
+			pallet_staking::Pallet::<T>::eras_start_session_index(era)
+				.map_or(false, |start| start <= session_index)
+		})?;
+
+		Some(
+			validators
+				.into_iter()
+				.map(|validator| {
+					let exposure = pallet_staking::Pallet::<T>::eras_stakers(era, &validator);
+					(validator, exposure)
+				})
+				.collect(),
+		)
+	}
+
+	fn weight(
+		offenders: &[(
+			T::AccountId,
+			pallet_staking::Exposure<T::AccountId, pallet_staking::BalanceOf<T>>,
+		)],
+	) -> Weight {
+		// The active era, the history depth and the start sessions of all eras in the history.
+		let history_depth = pallet_staking::Pallet::<T>::history_depth() as Weight;
+		let lookup = T::DbWeight::get().reads(3 + history_depth);
+
+		offenders.iter().fold(lookup, |weight, (_, exposure)| {
+			weight
+				.saturating_add(T::DbWeight::get().reads(1))
+				.saturating_add(slash_weight::<T>(exposure.others.len() as u32))
+		})
+	}
+}
+
+/// Identifies validators through the `IdentificationOf` conversion of the validator set.
+///
+/// This ignores the session, so it's only suitable for validator sets whose identification does
+/// not change over time, e.g. on chains without staking.
+pub struct CurrentIdentification<T>(PhantomData<T>);
+
+impl<T: session_info::Config> IdentifyValidators<AccountId<T>, Identification<T>>
+	for CurrentIdentification<T>
+{
+	fn identify(
+		_session_index: SessionIndex,
+		validators: Vec<AccountId<T>>,
+	) -> Option<Vec<(AccountId<T>, Identification<T>)>> {
+		Some(
+			validators
+				.into_iter()
+				.filter_map(|validator| {
+					let identification = <T::ValidatorSet as ValidatorSetWithIdentification<
+						T::AccountId,
+					>>::IdentificationOf::convert(validator.clone())?;
+					Some((validator, identification))
+				})
+				.collect(),
+		)
+	}
+
+	fn weight(offenders: &[(AccountId<T>, Identification<T>)]) -> Weight {
+		// Without staking, there are no nominators to slash.
+		(offenders.len() as Weight)
+			.saturating_mul(T::DbWeight::get().reads(1).saturating_add(slash_weight::<T>(0)))
+	}
+}
+
+/// Punishes validators on the losing side of disputes by reporting them as offenders through `R`,
+/// identified through `I`.
+pub struct SlashValidatorsForDisputes<C, R, I>(PhantomData<(C, R, I)>);
+
+impl<C, R, I> SlashValidatorsForDisputes<C, R, I>
+where
+	C: session_info::Config,
+	I: IdentifyValidators<AccountId<C>, Identification<C>>,
+{
+	fn report<K: DisputeOffenceKind>(
+		session_index: SessionIndex,
+		candidate_hash: CandidateHash,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) where
+		R: ReportOffence<
+			C::AccountId,
+			IdentificationTuple<C>,
+			DisputeOffence<IdentificationTuple<C>, K>,
+		>,
+	{
+		let validators: Vec<_> = validators.into_iter().collect();
+		if validators.is_empty() {
+			return
+		}
+
+		frame_system::Pallet::<C>::register_extra_weight_unchecked(
+			C::DbWeight::get().reads(1),
+			DispatchClass::Mandatory,
+		);
+		let account_keys = match session_info::Pallet::<C>::account_keys(session_index) {
+			Some(account_keys) => account_keys,
+			None => {
+				// The session is outside of the dispute window, or before `AccountKeys` were
+				// introduced via runtime upgrade.
+				log::debug!(
+					target: LOG_TARGET,
+					"Account keys of session {} are unknown, not reporting dispute offence",
+					session_index,
+				);
+				return
+			},
+		};
+
+		let offenders = validators
+			.into_iter()
+			.filter_map(|i| account_keys.get(i.0 as usize).cloned())
+			.collect();
+		let offenders = I::identify(session_index, offenders);
+		frame_system::Pallet::<C>::register_extra_weight_unchecked(
+			I::weight(offenders.as_deref().unwrap_or_default()),
+			DispatchClass::Mandatory,
+		);
+		let offenders = match offenders {
+			Some(offenders) => offenders,
+			None => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Validators of session {} can no longer be identified, not reporting dispute offence",
+					session_index,
+				);
+				return
+			},
+		};
+		if offenders.is_empty() {
+			return
+		}
+
+		let offence = DisputeOffence::<_, K>::new(
+			session_index,
+			candidate_hash,
+			account_keys.len() as u32,
+			offenders,
+		);
+		match R::report_offence(Vec::new(), offence) {
+			// The same validators were already reported for this dispute.
+			Ok(()) | Err(OffenceError::DuplicateReport) => {},
+			Err(e) => {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to report dispute offence for candidate {:?} in session {}: {:?}",
+					candidate_hash,
+					session_index,
+					e,
+				);
+			},
+		}
+	}
+}
+
+impl<C, R, I> PunishValidators for SlashValidatorsForDisputes<C, R, I>
+where
+	C: session_info::Config,
+	I: IdentifyValidators<AccountId<C>, Identification<C>>,
+	R: ReportOffence<
+			C::AccountId,
+			IdentificationTuple<C>,
+			DisputeOffence<IdentificationTuple<C>, ForInvalid>,
+		> + ReportOffence<
+			C::AccountId,
+			IdentificationTuple<C>,
+			DisputeOffence<IdentificationTuple<C>, AgainstValid>,
+		>,
+{
+	fn punish_for_invalid(
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::report::<ForInvalid>(session, candidate_hash, validators)
+	}
+
+	fn punish_against_valid(
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+		validators: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+		Self::report::<AgainstValid>(session, candidate_hash, validators)
+	}
+
+	fn punish_inconclusive(
+		_: SessionIndex,
+		_: CandidateHash,
+		_: impl IntoIterator<Item = ValidatorIndex>,
+	) {
+	}
+}
@@ -0,0 +1,275 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::mock::{new_test_ext, AccountId, MockGenesisConfig, Test};
+use sp_core::H256;
+use std::cell::RefCell;
+
+type Offender = IdentificationTuple<Test>;
+
+/// Sessions before this one can't be identified by [`TestIdentification`].
+const OLDEST_IDENTIFIABLE_SESSION: SessionIndex = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Report {
+	id: Kind,
+	time_slot: DisputesTimeSlot,
+	validator_set_count: u32,
+	offenders: Vec<Offender>,
+	slash_fraction: Perbill,
+}
+
+thread_local! {
+	static REPORTS: RefCell<Vec<Report>> = RefCell::new(Vec::new());
+}
+
+fn reports() -> Vec<Report> {
+	REPORTS.with(|r| r.borrow().clone())
+}
+
+struct TestReporter;
+
+impl<O> ReportOffence<AccountId, Offender, O> for TestReporter
+where
+	O: Offence<Offender, TimeSlot = DisputesTimeSlot>,
+{
+	fn report_offence(_reporters: Vec<AccountId>, offence: O) -> Result<(), OffenceError> {
+		let offenders = offence.offenders();
+		let time_slot = offence.time_slot();
+
+		// Mirror `pallet-offences`, which rejects reports without any new offenders.
+		if Self::is_known_offence(&offenders, &time_slot) {
+			return Err(OffenceError::DuplicateReport)
+		}
+
+		REPORTS.with(|r| {
+			r.borrow_mut().push(Report {
+				id: O::ID,
+				time_slot,
+				validator_set_count: offence.validator_set_count(),
+				slash_fraction: O::slash_fraction(
+					offenders.len() as u32,
+					offence.validator_set_count(),
+				),
+				offenders,
+			})
+		});
+		Ok(())
+	}
+
+	fn is_known_offence(offenders: &[Offender], time_slot: &DisputesTimeSlot) -> bool {
+		REPORTS.with(|r| {
+			let r = r.borrow();
+			offenders.iter().all(|offender| {
+				r.iter().any(|report| {
+					report.id == O::ID &&
+						&report.time_slot == time_slot &&
+						report.offenders.contains(offender)
+				})
+			})
+		})
+	}
+}
+
+struct TestIdentification;
+
+impl IdentifyValidators<AccountId, ()> for TestIdentification {
+	fn identify(
+		session_index: SessionIndex,
+		validators: Vec<AccountId>,
+	) -> Option<Vec<(AccountId, ())>> {
+		if session_index < OLDEST_IDENTIFIABLE_SESSION {
+			return None
+		}
+		Some(validators.into_iter().map(|v| (v, ())).collect())
+	}
+
+	fn weight(offenders: &[(AccountId, ())]) -> Weight {
+		IDENTIFICATION_WEIGHT + offenders.len() as Weight * OFFENDER_WEIGHT
+	}
+}
+
+const IDENTIFICATION_WEIGHT: Weight = 1_000;
+const OFFENDER_WEIGHT: Weight = 100;
+
+type Slash = SlashValidatorsForDisputes<Test, TestReporter, TestIdentification>;
+
+const SESSION: SessionIndex = 3;
+
+fn candidate_hash() -> CandidateHash {
+	CandidateHash(H256::repeat_byte(1))
+}
+
+fn time_slot(session_index: SessionIndex) -> DisputesTimeSlot {
+	DisputesTimeSlot { session_index, candidate_hash: candidate_hash() }
+}
+
+fn set_account_keys(session_index: SessionIndex) {
+	session_info::AccountKeys::<Test>::insert(session_index, vec![10, 11, 12, 13]);
+}
+
+#[test]
+fn reports_offences_of_each_kind() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		set_account_keys(SESSION);
+
+		Slash::punish_for_invalid(
+			SESSION,
+			candidate_hash(),
+			vec![ValidatorIndex(0), ValidatorIndex(2)],
+		);
+		Slash::punish_against_valid(SESSION, candidate_hash(), vec![ValidatorIndex(1)]);
+
+		assert_eq!(
+			reports(),
+			vec![
+				Report {
+					id: ForInvalid::ID,
+					time_slot: time_slot(SESSION),
+					validator_set_count: 4,
+					offenders: vec![(10, ()), (12, ())],
+					slash_fraction: Perbill::from_percent(100),
+				},
+				Report {
+					id: AgainstValid::ID,
+					time_slot: time_slot(SESSION),
+					validator_set_count: 4,
+					offenders: vec![(11, ())],
+					slash_fraction: Perbill::from_percent(1),
+				},
+			],
+		);
+	});
+}
+
+#[test]
+fn inconclusive_disputes_are_not_punished() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		set_account_keys(SESSION);
+
+		Slash::punish_inconclusive(
+			SESSION,
+			candidate_hash(),
+			vec![ValidatorIndex(0), ValidatorIndex(3)],
+		);
+
+		assert!(reports().is_empty());
+	});
+}
+
+#[test]
+fn weight_of_reports_is_registered() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		let mandatory_weight =
+			|| *frame_system::Pallet::<Test>::block_weight().get(DispatchClass::Mandatory);
+		let read = <Test as frame_system::Config>::DbWeight::get().reads(1);
+		set_account_keys(SESSION);
+
+		Slash::punish_for_invalid(
+			SESSION,
+			candidate_hash(),
+			vec![ValidatorIndex(0), ValidatorIndex(2)],
+		);
+		assert_eq!(mandatory_weight(), read + IDENTIFICATION_WEIGHT + 2 * OFFENDER_WEIGHT);
+
+		// Failing to identify the validators is charged for as well.
+		let before = mandatory_weight();
+		let session = OLDEST_IDENTIFIABLE_SESSION - 1;
+		set_account_keys(session);
+		Slash::punish_for_invalid(session, candidate_hash(), vec![ValidatorIndex(0)]);
+		assert_eq!(reports().len(), 1);
+		assert_eq!(mandatory_weight(), before + read + IDENTIFICATION_WEIGHT);
+	});
+}
+
+#[test]
+fn unknown_validator_indices_are_ignored() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		set_account_keys(SESSION);
+
+		Slash::punish_for_invalid(
+			SESSION,
+			candidate_hash(),
+			vec![ValidatorIndex(1), ValidatorIndex(7)],
+		);
+		assert_eq!(reports().len(), 1);
+		assert_eq!(reports()[0].offenders, vec![(11, ())]);
+
+		Slash::punish_against_valid(SESSION, candidate_hash(), vec![ValidatorIndex(4)]);
+		assert_eq!(reports().len(), 1);
+	});
+}
+
+#[test]
+fn nothing_reported_without_offenders() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		set_account_keys(SESSION);
+
+		Slash::punish_for_invalid(SESSION, candidate_hash(), Vec::new());
+		Slash::punish_against_valid(SESSION, candidate_hash(), Vec::new());
+
+		assert!(reports().is_empty());
+	});
+}
+
+#[test]
+fn nothing_reported_for_pruned_sessions() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		// The account keys of the session have been pruned.
+		Slash::punish_for_invalid(SESSION, candidate_hash(), vec![ValidatorIndex(0)]);
+
+		assert!(reports().is_empty());
+	});
+}
+
+#[test]
+fn nothing_reported_for_sessions_past_the_identification_history() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		let session = OLDEST_IDENTIFIABLE_SESSION - 1;
+		set_account_keys(session);
+
+		Slash::punish_for_invalid(session, candidate_hash(), vec![ValidatorIndex(0)]);
+		assert!(reports().is_empty());
+
+		set_account_keys(OLDEST_IDENTIFIABLE_SESSION);
+		Slash::punish_for_invalid(
+			OLDEST_IDENTIFIABLE_SESSION,
+			candidate_hash(),
+			vec![ValidatorIndex(0)],
+		);
+		assert_eq!(reports().len(), 1);
+	});
+}
+
+#[test]
+fn duplicate_reports_are_ignored() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		set_account_keys(SESSION);
+
+		Slash::punish_for_invalid(SESSION, candidate_hash(), vec![ValidatorIndex(0)]);
+		Slash::punish_for_invalid(SESSION, candidate_hash(), vec![ValidatorIndex(0)]);
+		assert_eq!(reports().len(), 1);
+
+		// The same validator can still be reported for another dispute.
+		Slash::punish_for_invalid(
+			SESSION,
+			CandidateHash(H256::repeat_byte(2)),
+			vec![ValidatorIndex(0)],
+		);
+		assert_eq!(reports().len(), 2);
+	});
+}
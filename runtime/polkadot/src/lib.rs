@@ -1305,7 +1305,11 @@ impl parachains_initializer::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	type PunishValidators = parachains_disputes::slashing::SlashValidatorsForDisputes<
+		Runtime,
+		Offences,
+		parachains_disputes::slashing::StakingExposures<Runtime>,
+	>;
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -149,6 +149,8 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = XcmPallet;
 	type AssetClaims = XcmPallet;
 	type SubscriptionService = XcmPallet;
+	type AssetExchanger = ();
+	type HrmpChannelHandler = ();
 }
 
 parameter_types! {
@@ -355,7 +355,11 @@ impl pallet_session::historical::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	type PunishValidators = parachains_disputes::slashing::SlashValidatorsForDisputes<
+		Runtime,
+		Offences,
+		parachains_disputes::slashing::CurrentIdentification<Runtime>,
+	>;
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -134,6 +134,8 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = XcmPallet;
 	type AssetClaims = XcmPallet;
 	type SubscriptionService = XcmPallet;
+	type AssetExchanger = ();
+	type HrmpChannelHandler = ();
 }
 
 parameter_types! {
@@ -486,7 +486,11 @@ impl parachains_inclusion::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	type PunishValidators = parachains_disputes::slashing::SlashValidatorsForDisputes<
+		Runtime,
+		Offences,
+		parachains_disputes::slashing::StakingExposures<Runtime>,
+	>;
 	type WeightInfo = parachains_disputes::TestWeightInfo;
 }
 
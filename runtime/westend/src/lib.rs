@@ -942,7 +942,11 @@ impl assigned_slots::Config for Runtime {
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
-	type PunishValidators = ();
+	type PunishValidators = parachains_disputes::slashing::SlashValidatorsForDisputes<
+		Runtime,
+		Offences,
+		parachains_disputes::slashing::StakingExposures<Runtime>,
+	>;
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -69,3 +69,100 @@ fn sanity_check_teleport_assets_weight() {
 
 	assert!(weight * 50 < BlockWeights::get().max_block);
 }
+
+mod dispute_offenders {
+	use super::*;
+	use pallet_staking::{ActiveEra, ActiveEraInfo, ErasStakers, ErasStartSessionIndex, Exposure};
+	use parachains_disputes::slashing::{IdentifyValidators, StakingExposures};
+
+	type Identify = StakingExposures<Runtime>;
+
+	fn validator() -> AccountId {
+		keyring::Sr25519Keyring::Alice.to_account_id()
+	}
+
+	fn exposure(total: Balance) -> Exposure<AccountId, Balance> {
+		Exposure { total, own: total, others: Vec::new() }
+	}
+
+	/// Starts the active era `era`, with era `e` having started at session `e * 6`.
+	fn start_eras(era: u32) {
+		ActiveEra::<Runtime>::put(ActiveEraInfo { index: era, start: None });
+		for e in 0..=era {
+			ErasStartSessionIndex::<Runtime>::insert(e, e * 6);
+		}
+	}
+
+	#[test]
+	fn offenders_are_identified_by_the_era_of_the_session() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			start_eras(10);
+			ErasStakers::<Runtime>::insert(8, validator(), exposure(100));
+			ErasStakers::<Runtime>::insert(9, validator(), exposure(200));
+			ErasStakers::<Runtime>::insert(10, validator(), exposure(300));
+
+			let identify = |session| Identify::identify(session, vec![validator()]);
+
+			// The last session of era 8 and the first of era 9.
+			assert_eq!(identify(53), Some(vec![(validator(), exposure(100))]));
+			assert_eq!(identify(54), Some(vec![(validator(), exposure(200))]));
+			// Sessions of the active era.
+			assert_eq!(identify(60), Some(vec![(validator(), exposure(300))]));
+			assert_eq!(identify(70), Some(vec![(validator(), exposure(300))]));
+		});
+	}
+
+	#[test]
+	fn offenders_are_not_identified_without_an_active_era() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			ErasStartSessionIndex::<Runtime>::insert(0, 0);
+			ErasStakers::<Runtime>::insert(0, validator(), exposure(100));
+
+			assert_eq!(Identify::identify(0, vec![validator()]), None);
+		});
+	}
+
+	#[test]
+	fn offenders_are_not_identified_past_the_history_depth() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let history_depth = pallet_staking::Pallet::<Runtime>::history_depth();
+			let active_era = history_depth + 5;
+			let oldest_era = active_era - history_depth;
+			start_eras(active_era);
+			ErasStakers::<Runtime>::insert(oldest_era - 1, validator(), exposure(100));
+			ErasStakers::<Runtime>::insert(oldest_era, validator(), exposure(200));
+
+			// The start of the era before the oldest one is still known, but not trusted.
+			assert_eq!(Identify::identify((oldest_era - 1) * 6, vec![validator()]), None);
+			assert_eq!(Identify::identify(oldest_era * 6 - 1, vec![validator()]), None);
+			assert_eq!(
+				Identify::identify(oldest_era * 6, vec![validator()]),
+				Some(vec![(validator(), exposure(200))]),
+			);
+		});
+	}
+
+	#[test]
+	fn weight_accounts_for_the_nominators_of_offenders() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			let nominated = Exposure {
+				total: 300,
+				own: 100,
+				others: vec![
+					pallet_staking::IndividualExposure { who: validator(), value: 100 },
+					pallet_staking::IndividualExposure { who: validator(), value: 100 },
+				],
+			};
+
+			let none = Identify::weight(&[]);
+			let one = Identify::weight(&[(validator(), exposure(100))]);
+			let two =
+				Identify::weight(&[(validator(), exposure(100)), (validator(), exposure(100))]);
+			let one_nominated = Identify::weight(&[(validator(), nominated)]);
+
+			assert!(none > 0);
+			assert_eq!(two - one, one - none);
+			assert!(one_nominated > one);
+		});
+	}
+}
@@ -121,6 +121,8 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = XcmPallet;
 	type AssetClaims = XcmPallet;
 	type SubscriptionService = XcmPallet;
+	type AssetExchanger = ();
+	type HrmpChannelHandler = ();
 }
 
 /// Type to convert an `Origin` type value into a `MultiLocation` value which represents an interior location
@@ -19,6 +19,12 @@
 #![warn(missing_docs)]
 
 use color_eyre::eyre;
+use polkadot_node_core_pvf::TrackingAllocator;
+
+/// The PVF workers run by this binary account for the memory allocated by their jobs.
+#[global_allocator]
+static ALLOC: TrackingAllocator<tikv_jemallocator::Jemalloc> =
+	TrackingAllocator(tikv_jemallocator::Jemalloc);
 
 fn main() -> eyre::Result<()> {
 	color_eyre::install()?;
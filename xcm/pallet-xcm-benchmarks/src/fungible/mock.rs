@@ -142,6 +142,8 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = ();
 	type AssetClaims = ();
 	type SubscriptionService = ();
+	type AssetExchanger = ();
+	type HrmpChannelHandler = ();
 }
 
 impl crate::Config for Test {
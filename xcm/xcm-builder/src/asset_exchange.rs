@@ -0,0 +1,93 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use frame_support::traits::Get;
+use sp_std::{marker::PhantomData, prelude::*, result::Result};
+use xcm::latest::prelude::*;
+use xcm_executor::{
+	traits::{AssetExchange, TransactAsset},
+	Assets,
+};
+
+/// An `AssetExchange` implementation which swaps one fungible asset for another at a fixed rate,
+/// using the liquidity held by the `Pool` location.
+///
+/// `Rates` is a list of `(give, receive)` pairs: for every `give.fun` units of the `give.id` asset
+/// paid into the pool, `receive.fun` units of the `receive.id` asset are withdrawn from it. Any
+/// remainder of the given amount which does not buy a whole unit is kept by the pool. Both the
+/// given and the wanted assets must consist of a single fungible asset.
+pub struct FixedRateAssetExchange<Transactor, Pool, Rates>(PhantomData<(Transactor, Pool, Rates)>);
+impl<
+		Transactor: TransactAsset,
+		Pool: Get<MultiLocation>,
+		Rates: Get<Vec<(MultiAsset, MultiAsset)>>,
+	> AssetExchange for FixedRateAssetExchange<Transactor, Pool, Rates>
+{
+	fn exchange_asset(
+		origin: Option<&MultiLocation>,
+		give: Assets,
+		want: &MultiAssets,
+	) -> Result<Assets, (Assets, XcmError)> {
+		log::trace!(
+			target: "xcm::exchange_asset",
+			"FixedRateAssetExchange origin: {:?}, give: {:?}, want: {:?}",
+			origin, give, want,
+		);
+		let (give_id, give_amount) = match (give.len(), give.fungible.iter().next()) {
+			(1, Some((id, amount))) => (id.clone(), *amount),
+			_ => return Err((give, XcmError::Unimplemented)),
+		};
+		let (want_id, want_amount) = match want.inner().as_slice() {
+			[MultiAsset { id, fun: Fungible(amount) }] => (id.clone(), *amount),
+			_ => return Err((give, XcmError::Unimplemented)),
+		};
+		let rate = Rates::get().into_iter().find_map(|(give_unit, receive_unit)| {
+			match (give_unit, receive_unit) {
+				(
+					MultiAsset { id: g, fun: Fungible(give_unit) },
+					MultiAsset { id: r, fun: Fungible(receive_unit) },
+				) if g == give_id && r == want_id && give_unit > 0 => Some((give_unit, receive_unit)),
+				_ => None,
+			}
+		});
+		let (give_unit, receive_unit) = match rate {
+			Some(rate) => rate,
+			None => return Err((give, XcmError::Unimplemented)),
+		};
+		let receive_amount = match give_amount.checked_mul(receive_unit) {
+			Some(amount) => amount / give_unit,
+			None => return Err((give, XcmError::Overflow)),
+		};
+		if receive_amount < want_amount {
+			return Err((give, XcmError::TooExpensive))
+		}
+
+		let pool = Pool::get();
+		let received = match Transactor::withdraw_asset(&(want_id, receive_amount).into(), &pool) {
+			Ok(received) => received,
+			Err(error) => return Err((give, error)),
+		};
+		let given: MultiAsset = (give_id, give_amount).into();
+		if let Err(error) = Transactor::deposit_asset(&given, &pool) {
+			// Put the liquidity we just withdrew back into the pool.
+			for asset in received.into_assets_iter() {
+				let _ = Transactor::deposit_asset(&asset, &pool);
+			}
+			return Err((give, error))
+		}
+		Ok(received)
+	}
+}
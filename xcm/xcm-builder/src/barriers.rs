@@ -168,3 +168,28 @@ impl<T: Contains<MultiLocation>> ShouldExecute for AllowSubscriptionsFrom<T> {
 		}
 	}
 }
+
+/// Allows execution from the parent (i.e. the relay chain) if the message is just a straight
+/// `HrmpNewChannelOpenRequest`, `HrmpChannelAccepted` or `HrmpChannelClosing` notification.
+pub struct AllowHrmpNotificationsFromRelayChain;
+impl ShouldExecute for AllowHrmpNotificationsFromRelayChain {
+	fn should_execute<Call>(
+		origin: &MultiLocation,
+		message: &mut Xcm<Call>,
+		_max_weight: Weight,
+		_weight_credit: &mut Weight,
+	) -> Result<(), ()> {
+		log::trace!(
+			target: "xcm::barriers",
+			"AllowHrmpNotificationsFromRelayChain origin: {:?}, message: {:?}, max_weight: {:?}, weight_credit: {:?}",
+			origin, message, _max_weight, _weight_credit,
+		);
+		ensure!(*origin == MultiLocation::parent(), ());
+		match (message.0.len(), message.0.first()) {
+			(1, Some(HrmpNewChannelOpenRequest { .. })) |
+			(1, Some(HrmpChannelAccepted { .. })) |
+			(1, Some(HrmpChannelClosing { .. })) => Ok(()),
+			_ => Err(()),
+		}
+	}
+}
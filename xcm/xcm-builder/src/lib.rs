@@ -44,10 +44,14 @@ pub use origin_conversion::{
 
 mod barriers;
 pub use barriers::{
-	AllowKnownQueryResponses, AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom,
-	AllowUnpaidExecutionFrom, IsChildSystemParachain, TakeWeightCredit,
+	AllowHrmpNotificationsFromRelayChain, AllowKnownQueryResponses, AllowSubscriptionsFrom,
+	AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom, IsChildSystemParachain,
+	TakeWeightCredit,
 };
 
+mod asset_exchange;
+pub use asset_exchange::FixedRateAssetExchange;
+
 mod currency_adapter;
 pub use currency_adapter::CurrencyAdapter;
 
@@ -16,8 +16,9 @@
 
 use crate::{barriers::AllowSubscriptionsFrom, test_utils::*};
 pub use crate::{
-	AllowKnownQueryResponses, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom,
-	FixedRateOfFungible, FixedWeightBounds, LocationInverter, TakeWeightCredit,
+	AllowHrmpNotificationsFromRelayChain, AllowKnownQueryResponses, AllowTopLevelPaidExecutionFrom,
+	AllowUnpaidExecutionFrom, FixedRateAssetExchange, FixedRateOfFungible, FixedWeightBounds,
+	LocationInverter, TakeWeightCredit,
 };
 pub use frame_support::{
 	dispatch::{
@@ -37,7 +38,10 @@ pub use sp_std::{
 };
 pub use xcm::latest::prelude::*;
 pub use xcm_executor::{
-	traits::{ConvertOrigin, FilterAssetLocation, InvertLocation, OnResponse, TransactAsset},
+	traits::{
+		ConvertOrigin, FilterAssetLocation, HrmpChannelHandler, InvertLocation, OnResponse,
+		TransactAsset,
+	},
 	Assets, Config,
 };
 
@@ -262,6 +266,9 @@ parameter_types! {
 	// 1_000_000_000_000 => 1 unit of asset for 1 unit of Weight.
 	pub static WeightPrice: (AssetId, u128) = (From::from(Here), 1_000_000_000_000);
 	pub static MaxInstructions: u32 = 100;
+	// Nothing can be exchanged by default.
+	pub static ExchangeRates: Vec<(MultiAsset, MultiAsset)> = vec![];
+	pub ExchangePool: MultiLocation = X1(AccountIndex64 { index: 4000, network: Any }).into();
 }
 
 pub type TestBarrier = (
@@ -270,8 +277,38 @@ pub type TestBarrier = (
 	AllowTopLevelPaidExecutionFrom<IsInVec<AllowPaidFrom>>,
 	AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,
 	AllowSubscriptionsFrom<IsInVec<AllowSubsFrom>>,
+	AllowHrmpNotificationsFromRelayChain,
 );
 
+parameter_types! {
+	pub static HrmpNotifications: Vec<Instruction<()>> = vec![];
+}
+pub struct TestHrmpChannelHandler;
+impl HrmpChannelHandler for TestHrmpChannelHandler {
+	fn handle_new_channel_open_request(
+		sender: u32,
+		max_message_size: u32,
+		max_capacity: u32,
+	) -> XcmResult {
+		let mut n = HrmpNotifications::get();
+		n.push(HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity });
+		HrmpNotifications::set(n);
+		Ok(())
+	}
+	fn handle_channel_accepted(recipient: u32) -> XcmResult {
+		let mut n = HrmpNotifications::get();
+		n.push(HrmpChannelAccepted { recipient });
+		HrmpNotifications::set(n);
+		Ok(())
+	}
+	fn handle_channel_closing(initiator: u32, sender: u32, recipient: u32) -> XcmResult {
+		let mut n = HrmpNotifications::get();
+		n.push(HrmpChannelClosing { initiator, sender, recipient });
+		HrmpNotifications::set(n);
+		Ok(())
+	}
+}
+
 pub struct TestConfig;
 impl Config for TestConfig {
 	type Call = TestCall;
@@ -288,4 +325,6 @@ impl Config for TestConfig {
 	type AssetTrap = TestAssetTrap;
 	type AssetClaims = TestAssetTrap;
 	type SubscriptionService = TestSubscriptionService;
+	type AssetExchanger = FixedRateAssetExchange<TestAssetTransactor, ExchangePool, ExchangeRates>;
+	type HrmpChannelHandler = TestHrmpChannelHandler;
 }
@@ -183,6 +183,71 @@ fn transfer_should_work() {
 	assert_eq!(sent_xcm(), vec![]);
 }
 
+#[test]
+fn exchange_asset_should_work() {
+	AllowUnpaidFrom::set(vec![X1(Parachain(1)).into()]);
+	// Two units of our native asset buy one unit of the relay-chain asset.
+	ExchangeRates::set(vec![((Here, 2).into(), (Parent, 1).into())]);
+	// The exchange pool holds some of the relay-chain asset.
+	add_asset(4000, (Parent, 1000));
+	// Child parachain #1 owns 1000 tokens held by us in reserve.
+	add_asset(1001, (Here, 1000));
+	// They want to exchange 100 of them for at least 40 of the relay-chain asset.
+	let r = XcmExecutor::<TestConfig>::execute_xcm(
+		Parachain(1),
+		Xcm(vec![
+			WithdrawAsset((Here, 100).into()),
+			ExchangeAsset { give: Wild(All), receive: (Parent, 40).into() },
+			DepositAsset {
+				assets: Wild(All),
+				max_assets: 1,
+				beneficiary: X1(AccountIndex64 { index: 3, network: Any }).into(),
+			},
+		]),
+		50,
+	);
+	assert_eq!(r, Outcome::Complete(30));
+	assert_eq!(assets(3), vec![(Parent, 50).into()]);
+	assert_eq!(assets(1001), vec![(Here, 900).into()]);
+	assert_eq!(assets(4000), vec![(Here, 100).into(), (Parent, 950).into()]);
+}
+
+#[test]
+fn exchange_asset_should_fail_without_touching_the_pool() {
+	AllowUnpaidFrom::set(vec![X1(Parachain(1)).into()]);
+	ExchangeRates::set(vec![((Here, 2).into(), (Parent, 1).into())]);
+	add_asset(4000, (Parent, 1000));
+	add_asset(1001, (Here, 1000));
+
+	// 100 of our native asset are not enough to buy 60 of the relay-chain asset.
+	let message = |receive: MultiAssets| {
+		Xcm(vec![
+			WithdrawAsset((Here, 100).into()),
+			ExchangeAsset { give: Wild(All), receive },
+			DepositAsset {
+				assets: Wild(All),
+				max_assets: 1,
+				beneficiary: X1(AccountIndex64 { index: 3, network: Any }).into(),
+			},
+		])
+	};
+	let r = XcmExecutor::<TestConfig>::execute_xcm(Parachain(1), message((Parent, 60).into()), 50);
+	assert_eq!(r, Outcome::Incomplete(25, XcmError::TooExpensive));
+
+	// There is no rate for exchanging into this asset at all.
+	let r = XcmExecutor::<TestConfig>::execute_xcm(
+		Parachain(1),
+		message((Parachain(1000), 1).into()),
+		50,
+	);
+	assert_eq!(r, Outcome::Incomplete(25, XcmError::Unimplemented));
+
+	// The given assets ended up in the asset trap and the pool is unchanged.
+	assert_eq!(assets(3), vec![]);
+	assert_eq!(assets(1001), vec![(Here, 800).into()]);
+	assert_eq!(assets(4000), vec![(Parent, 1000).into()]);
+}
+
 #[test]
 fn basic_asset_trap_should_work() {
 	// we'll let them have message execution for free.
@@ -461,6 +526,67 @@ fn reserve_transfer_should_work() {
 	);
 }
 
+#[test]
+fn hrmp_notifications_should_only_be_allowed_from_the_relay_chain() {
+	let notification =
+		Xcm::<TestCall>(vec![HrmpChannelClosing { initiator: 1, sender: 1, recipient: 2 }]);
+
+	let r = XcmExecutor::<TestConfig>::execute_xcm(Parachain(1), notification.clone(), 10);
+	assert_eq!(r, Outcome::Error(XcmError::Barrier));
+
+	let message = Xcm::<TestCall>(vec![
+		ClearOrigin,
+		HrmpChannelClosing { initiator: 1, sender: 1, recipient: 2 },
+	]);
+	let r = XcmExecutor::<TestConfig>::execute_xcm(Parent, message, 20);
+	assert_eq!(r, Outcome::Error(XcmError::Barrier));
+
+	let r = XcmExecutor::<TestConfig>::execute_xcm(Parent, notification, 10);
+	assert_eq!(r, Outcome::Complete(10));
+	assert_eq!(
+		HrmpNotifications::get(),
+		vec![HrmpChannelClosing { initiator: 1, sender: 1, recipient: 2 }]
+	);
+}
+
+#[test]
+fn hrmp_notifications_should_be_rejected_from_other_origins() {
+	// Even with a barrier letting everything through, the notifications must come from the relay
+	// chain itself.
+	AllowUnpaidFrom::set(vec![Parent.into(), Parachain(1).into()]);
+	let notifications = vec![
+		HrmpNewChannelOpenRequest { sender: 1, max_message_size: 1024, max_capacity: 8 },
+		HrmpChannelAccepted { recipient: 2 },
+		HrmpChannelClosing { initiator: 1, sender: 1, recipient: 2 },
+	];
+
+	for notification in notifications {
+		let message = Xcm::<TestCall>(vec![notification.clone().into()]);
+		let r = XcmExecutor::<TestConfig>::execute_xcm(Parachain(1), message, 10);
+		assert_eq!(r, Outcome::Incomplete(10, XcmError::BadOrigin));
+
+		let message = Xcm::<TestCall>(vec![ClearOrigin, notification.into()]);
+		let r = XcmExecutor::<TestConfig>::execute_xcm(Parent, message, 20);
+		assert_eq!(r, Outcome::Incomplete(20, XcmError::BadOrigin));
+	}
+	assert_eq!(HrmpNotifications::get(), vec![]);
+}
+
+#[test]
+fn hrmp_notifications_should_be_handled() {
+	let messages = vec![
+		HrmpNewChannelOpenRequest { sender: 1, max_message_size: 1024, max_capacity: 8 },
+		HrmpChannelAccepted { recipient: 2 },
+		HrmpChannelClosing { initiator: 2, sender: 1, recipient: 2 },
+	];
+	for message in messages.clone() {
+		let message = Xcm::<TestCall>(vec![message.into()]);
+		let r = XcmExecutor::<TestConfig>::execute_xcm(Parent, message, 10);
+		assert_eq!(r, Outcome::Complete(10));
+	}
+	assert_eq!(HrmpNotifications::get(), messages);
+}
+
 #[test]
 fn simple_version_subscriptions_should_work() {
 	AllowSubsFrom::set(vec![Parent.into()]);
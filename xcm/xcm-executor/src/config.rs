@@ -15,8 +15,9 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::traits::{
-	ClaimAssets, ConvertOrigin, DropAssets, FilterAssetLocation, InvertLocation, OnResponse,
-	ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds, WeightTrader,
+	AssetExchange, ClaimAssets, ConvertOrigin, DropAssets, FilterAssetLocation, HrmpChannelHandler,
+	InvertLocation, OnResponse, ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds,
+	WeightTrader,
 };
 use frame_support::{
 	dispatch::{Dispatchable, Parameter},
@@ -68,4 +69,12 @@ pub trait Config {
 
 	/// How we handle version subscription requests.
 	type SubscriptionService: VersionChangeNotifier;
+
+	/// How we handle requests to exchange assets held in the Holding Register, via the
+	/// `ExchangeAsset` instruction. Use `()` to reject all such requests.
+	type AssetExchanger: AssetExchange;
+
+	/// How we handle the HRMP channel notifications sent to us by the relay chain. Use `()` to
+	/// ignore them.
+	type HrmpChannelHandler: HrmpChannelHandler;
 }
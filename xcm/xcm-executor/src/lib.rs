@@ -31,8 +31,9 @@ use xcm::latest::{
 
 pub mod traits;
 use traits::{
-	ClaimAssets, ConvertOrigin, DropAssets, FilterAssetLocation, InvertLocation, OnResponse,
-	ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds, WeightTrader,
+	AssetExchange, ClaimAssets, ConvertOrigin, DropAssets, FilterAssetLocation, HrmpChannelHandler,
+	InvertLocation, OnResponse, ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds,
+	WeightTrader,
 };
 
 mod assets;
@@ -501,10 +502,36 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				ensure!(&self.original_origin == origin, XcmError::BadOrigin);
 				Config::SubscriptionService::stop(origin)
 			},
-			ExchangeAsset { .. } => Err(XcmError::Unimplemented),
-			HrmpNewChannelOpenRequest { .. } => Err(XcmError::Unimplemented),
-			HrmpChannelAccepted { .. } => Err(XcmError::Unimplemented),
-			HrmpChannelClosing { .. } => Err(XcmError::Unimplemented),
+			ExchangeAsset { give, receive } => {
+				let give = self.holding.saturating_take(give);
+				match Config::AssetExchanger::exchange_asset(self.origin.as_ref(), give, &receive) {
+					Ok(received) => {
+						self.holding.subsume_assets(received);
+						Ok(())
+					},
+					Err((give, error)) => {
+						self.holding.subsume_assets(give);
+						Err(error)
+					},
+				}
+			},
+			HrmpNewChannelOpenRequest { sender, max_message_size, max_capacity } => {
+				// HRMP notifications are only ever sent by the relay chain.
+				ensure!(self.origin == Some(MultiLocation::parent()), XcmError::BadOrigin);
+				Config::HrmpChannelHandler::handle_new_channel_open_request(
+					sender,
+					max_message_size,
+					max_capacity,
+				)
+			},
+			HrmpChannelAccepted { recipient } => {
+				ensure!(self.origin == Some(MultiLocation::parent()), XcmError::BadOrigin);
+				Config::HrmpChannelHandler::handle_channel_accepted(recipient)
+			},
+			HrmpChannelClosing { initiator, sender, recipient } => {
+				ensure!(self.origin == Some(MultiLocation::parent()), XcmError::BadOrigin);
+				Config::HrmpChannelHandler::handle_channel_closing(initiator, sender, recipient)
+			},
 		}
 	}
 
@@ -0,0 +1,62 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::Assets;
+use xcm::latest::{Error as XcmError, MultiAssets, MultiLocation};
+
+/// A service for exchanging assets, used by the `ExchangeAsset` instruction.
+pub trait AssetExchange {
+	/// Exchange the `give` assets for at least the `want` assets on behalf of `origin`.
+	///
+	/// On success, the assets received in exchange are returned; they must contain at least
+	/// `want`. On failure, `give` is returned unchanged together with the reason, so that it can be
+	/// placed back into the Holding Register.
+	///
+	/// `XcmError::Unimplemented` should be returned by implementations which do not handle the
+	/// given exchange at all; other errors mean the exchange was recognized but failed.
+	fn exchange_asset(
+		origin: Option<&MultiLocation>,
+		give: Assets,
+		want: &MultiAssets,
+	) -> Result<Assets, (Assets, XcmError)>;
+}
+
+impl AssetExchange for () {
+	fn exchange_asset(
+		_origin: Option<&MultiLocation>,
+		give: Assets,
+		_want: &MultiAssets,
+	) -> Result<Assets, (Assets, XcmError)> {
+		Err((give, XcmError::Unimplemented))
+	}
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(1, 30)]
+impl AssetExchange for Tuple {
+	fn exchange_asset(
+		origin: Option<&MultiLocation>,
+		give: Assets,
+		want: &MultiAssets,
+	) -> Result<Assets, (Assets, XcmError)> {
+		for_tuples!( #(
+			let give = match Tuple::exchange_asset(origin, give, want) {
+				Err((give, XcmError::Unimplemented)) => give,
+				result => return result,
+			};
+		)* );
+		Err((give, XcmError::Unimplemented))
+	}
+}
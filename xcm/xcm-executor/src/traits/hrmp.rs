@@ -0,0 +1,81 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use xcm::latest::Result as XcmResult;
+
+/// Define handlers for the HRMP notification instructions which the relay chain sends to
+/// parachains: `HrmpNewChannelOpenRequest`, `HrmpChannelAccepted` and `HrmpChannelClosing`.
+///
+/// The executor only dispatches the notifications whose origin is the relay chain, any other origin
+/// fails with `BadOrigin`.
+pub trait HrmpChannelHandler {
+	/// A new channel from `sender` to this chain has been requested, with the given maximum
+	/// message size and capacity.
+	fn handle_new_channel_open_request(
+		sender: u32,
+		max_message_size: u32,
+		max_capacity: u32,
+	) -> XcmResult;
+
+	/// The channel from this chain to `recipient` has been accepted by the recipient.
+	fn handle_channel_accepted(recipient: u32) -> XcmResult;
+
+	/// The channel from `sender` to `recipient` is being closed by `initiator`.
+	fn handle_channel_closing(initiator: u32, sender: u32, recipient: u32) -> XcmResult;
+}
+
+/// Ignore all HRMP notifications.
+impl HrmpChannelHandler for () {
+	fn handle_new_channel_open_request(
+		_sender: u32,
+		_max_message_size: u32,
+		_max_capacity: u32,
+	) -> XcmResult {
+		Ok(())
+	}
+
+	fn handle_channel_accepted(_recipient: u32) -> XcmResult {
+		Ok(())
+	}
+
+	fn handle_channel_closing(_initiator: u32, _sender: u32, _recipient: u32) -> XcmResult {
+		Ok(())
+	}
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(1, 30)]
+impl HrmpChannelHandler for Tuple {
+	fn handle_new_channel_open_request(
+		sender: u32,
+		max_message_size: u32,
+		max_capacity: u32,
+	) -> XcmResult {
+		for_tuples!( #(
+			Tuple::handle_new_channel_open_request(sender, max_message_size, max_capacity)?;
+		)* );
+		Ok(())
+	}
+
+	fn handle_channel_accepted(recipient: u32) -> XcmResult {
+		for_tuples!( #( Tuple::handle_channel_accepted(recipient)?; )* );
+		Ok(())
+	}
+
+	fn handle_channel_closing(initiator: u32, sender: u32, recipient: u32) -> XcmResult {
+		for_tuples!( #( Tuple::handle_channel_closing(initiator, sender, recipient)?; )* );
+		Ok(())
+	}
+}
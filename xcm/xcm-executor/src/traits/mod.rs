@@ -16,12 +16,16 @@
 
 //! Various traits used in configuring the executor.
 
+mod asset_exchange;
+pub use asset_exchange::AssetExchange;
 mod conversion;
 pub use conversion::{Convert, ConvertOrigin, Decoded, Encoded, Identity, InvertLocation, JustTry};
 mod drop_assets;
 pub use drop_assets::{ClaimAssets, DropAssets};
 mod filter_asset_location;
 pub use filter_asset_location::FilterAssetLocation;
+mod hrmp;
+pub use hrmp::HrmpChannelHandler;
 mod matches_fungible;
 pub use matches_fungible::MatchesFungible;
 mod matches_fungibles;
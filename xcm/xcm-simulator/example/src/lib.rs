@@ -72,6 +72,11 @@ pub fn para_ext(para_id: u32) -> sp_io::TestExternalities {
 	pallet_balances::GenesisConfig::<Runtime> { balances: vec![(ALICE, INITIAL_BALANCE)] }
 		.assimilate_storage(&mut t)
 		.unwrap();
+	pallet_balances::GenesisConfig::<Runtime, pallet_balances::Instance2> {
+		balances: vec![(parachain::EXCHANGE_POOL, INITIAL_BALANCE)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
 
 	let mut ext = sp_io::TestExternalities::new(t);
 	ext.execute_with(|| {
@@ -106,7 +111,7 @@ mod tests {
 
 	use codec::Encode;
 	use frame_support::assert_ok;
-	use xcm::latest::prelude::*;
+	use xcm::{latest::prelude::*, VersionedXcm};
 	use xcm_simulator::TestExt;
 
 	// Helper function for forming buy execution message
@@ -259,6 +264,89 @@ mod tests {
 		});
 	}
 
+	/// Scenario:
+	/// A parachain asks the relay chain, which has no asset exchanger configured, to exchange some
+	/// of its assets and deposit the result to another parachain. It sets an error handler which
+	/// deposits whatever is in holding to a third parachain.
+	///
+	/// Asserts that the exchange is rejected and the given assets are handed to the error handler
+	/// rather than deposited to the intended beneficiary.
+	#[test]
+	fn exchange_asset_without_exchanger_runs_error_handler() {
+		MockNet::reset();
+
+		let send_amount = 10;
+
+		ParaA::execute_with(|| {
+			let message = Xcm(vec![
+				WithdrawAsset((Here, send_amount).into()),
+				buy_execution((Here, send_amount)),
+				SetErrorHandler(Xcm(vec![DepositAsset {
+					assets: All.into(),
+					max_assets: 1,
+					beneficiary: Parachain(3).into(),
+				}])),
+				ExchangeAsset { give: All.into(), receive: (Here, send_amount).into() },
+				DepositAsset {
+					assets: All.into(),
+					max_assets: 1,
+					beneficiary: Parachain(2).into(),
+				},
+			]);
+			assert_ok!(ParachainPalletXcm::send_xcm(Here, Parent, message.clone()));
+		});
+
+		Relay::execute_with(|| {
+			assert_eq!(
+				relay_chain::Balances::free_balance(para_account_id(1)),
+				INITIAL_BALANCE - send_amount
+			);
+			assert_eq!(relay_chain::Balances::free_balance(para_account_id(2)), 0);
+			assert_eq!(relay_chain::Balances::free_balance(para_account_id(3)), send_amount);
+		});
+	}
+
+	/// Scenario:
+	/// ALICE exchanges some of her relay chain tokens for the local tokens of a parachain, at the
+	/// fixed rate offered by the exchange pool of the parachain.
+	///
+	/// Asserts that the given and the received assets are moved between ALICE and the pool.
+	#[test]
+	fn exchange_asset_at_fixed_rate() {
+		MockNet::reset();
+
+		let give_amount = 100;
+		let receive_amount = 2 * give_amount;
+
+		ParaA::execute_with(|| {
+			let message = Xcm(vec![
+				WithdrawAsset((Parent, give_amount).into()),
+				ExchangeAsset {
+					give: All.into(),
+					receive: (parachain::TokensLocation::get(), receive_amount).into(),
+				},
+				DepositAsset {
+					assets: All.into(),
+					max_assets: 1,
+					beneficiary: AccountId32 { network: Any, id: ALICE.into() }.into(),
+				},
+			]);
+			assert_ok!(ParachainPalletXcm::execute(
+				parachain::Origin::signed(ALICE),
+				Box::new(VersionedXcm::V2(message)),
+				1_000_000,
+			));
+
+			assert_eq!(parachain::Balances::free_balance(&ALICE), INITIAL_BALANCE - give_amount);
+			assert_eq!(parachain::Tokens::free_balance(&ALICE), receive_amount);
+			assert_eq!(parachain::Balances::free_balance(&parachain::EXCHANGE_POOL), give_amount);
+			assert_eq!(
+				parachain::Tokens::free_balance(&parachain::EXCHANGE_POOL),
+				INITIAL_BALANCE - receive_amount
+			);
+		});
+	}
+
 	/// Scenario:
 	/// A parachain wants to be notified that a transfer worked correctly.
 	/// It sends a `QueryHolding` after the deposit to get notified on success.
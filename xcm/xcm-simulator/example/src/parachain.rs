@@ -19,7 +19,7 @@
 use codec::{Decode, Encode};
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{Everything, Nothing},
+	traits::{Everything, Nothing, PalletInfoAccess, StorageMapShim},
 	weights::{constants::WEIGHT_PER_SECOND, Weight},
 };
 use sp_core::H256;
@@ -38,15 +38,18 @@ use polkadot_parachain::primitives::{
 use xcm::{latest::prelude::*, VersionedXcm};
 use xcm_builder::{
 	AccountId32Aliases, AllowUnpaidExecutionFrom, CurrencyAdapter as XcmCurrencyAdapter,
-	EnsureXcmOrigin, FixedRateOfFungible, FixedWeightBounds, IsConcrete, LocationInverter,
-	NativeAsset, ParentIsPreset, SiblingParachainConvertsVia, SignedAccountId32AsNative,
-	SignedToAccountId32, SovereignSignedViaLocation,
+	EnsureXcmOrigin, FixedRateAssetExchange, FixedRateOfFungible, FixedWeightBounds, IsConcrete,
+	LocationInverter, NativeAsset, ParentIsPreset, SiblingParachainConvertsVia,
+	SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation,
 };
 use xcm_executor::{Config, XcmExecutor};
 
 pub type AccountId = AccountId32;
 pub type Balance = u128;
 
+/// The account holding the liquidity of the asset exchange.
+pub const EXCHANGE_POOL: AccountId = AccountId32::new([1u8; 32]);
+
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 }
@@ -96,6 +99,24 @@ impl pallet_balances::Config for Runtime {
 	type ReserveIdentifier = [u8; 8];
 }
 
+// The local tokens of the parachain, which can be exchanged for the relay chain tokens.
+impl pallet_balances::Config<pallet_balances::Instance2> for Runtime {
+	type MaxLocks = MaxLocks;
+	type Balance = Balance;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = StorageMapShim<
+		pallet_balances::Account<Runtime, pallet_balances::Instance2>,
+		frame_system::Provider<Runtime>,
+		AccountId,
+		pallet_balances::AccountData<Balance>,
+	>;
+	type WeightInfo = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+}
+
 parameter_types! {
 	pub const ReservedXcmpWeight: Weight = WEIGHT_PER_SECOND / 4;
 	pub const ReservedDmpWeight: Weight = WEIGHT_PER_SECOND / 4;
@@ -128,6 +149,20 @@ parameter_types! {
 pub type LocalAssetTransactor =
 	XcmCurrencyAdapter<Balances, IsConcrete<KsmLocation>, LocationToAccountId, AccountId, ()>;
 
+parameter_types! {
+	pub TokensLocation: MultiLocation = PalletInstance(Tokens::index() as u8).into();
+	pub ExchangePool: MultiLocation =
+		Junction::AccountId32 { network: NetworkId::Any, id: EXCHANGE_POOL.into() }.into();
+	/// Two local tokens for every relay chain token.
+	pub ExchangeRates: Vec<(MultiAsset, MultiAsset)> =
+		vec![((Parent, 1).into(), (TokensLocation::get(), 2).into())];
+}
+
+pub type TokensTransactor =
+	XcmCurrencyAdapter<Tokens, IsConcrete<TokensLocation>, LocationToAccountId, AccountId, ()>;
+
+pub type AssetTransactors = (LocalAssetTransactor, TokensTransactor);
+
 pub type XcmRouter = super::ParachainXcmRouter<MsgQueue>;
 pub type Barrier = AllowUnpaidExecutionFrom<Everything>;
 
@@ -135,7 +170,7 @@ pub struct XcmConfig;
 impl Config for XcmConfig {
 	type Call = Call;
 	type XcmSender = XcmRouter;
-	type AssetTransactor = LocalAssetTransactor;
+	type AssetTransactor = AssetTransactors;
 	type OriginConverter = XcmOriginToCallOrigin;
 	type IsReserve = NativeAsset;
 	type IsTeleporter = ();
@@ -147,6 +182,8 @@ impl Config for XcmConfig {
 	type AssetTrap = ();
 	type AssetClaims = ();
 	type SubscriptionService = ();
+	type AssetExchanger = FixedRateAssetExchange<AssetTransactors, ExchangePool, ExchangeRates>;
+	type HrmpChannelHandler = ();
 }
 
 #[frame_support::pallet]
@@ -325,5 +362,6 @@ construct_runtime!(
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		MsgQueue: mock_msg_queue::{Pallet, Storage, Event<T>},
 		PolkadotXcm: pallet_xcm::{Pallet, Call, Event<T>, Origin},
+		Tokens: pallet_balances::<Instance2>::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
@@ -147,6 +147,8 @@ impl Config for XcmConfig {
 	type AssetTrap = ();
 	type AssetClaims = ();
 	type SubscriptionService = ();
+	type AssetExchanger = ();
+	type HrmpChannelHandler = ();
 }
 
 #[frame_support::pallet]